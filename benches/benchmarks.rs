@@ -5,6 +5,7 @@ use std::path::PathBuf;
 use std::time::{Duration, Instant};
 
 use chainsaw::cache::ParseCache;
+use chainsaw::graph::{EdgeKind, ModuleGraph};
 use chainsaw::lang::LanguageSupport;
 use chainsaw::lang::python::PythonSupport;
 use chainsaw::lang::typescript::TypeScriptSupport;
@@ -404,6 +405,7 @@ fn register_benchmarks() -> Vec<Benchmark> {
             result.unresolved_specifiers,
             unresolvable_count,
             result.unresolvable_dynamic,
+            result.resolution,
         );
         benches.push(Benchmark {
             name: "cache_load_validate_ts",
@@ -457,6 +459,28 @@ fn register_benchmarks() -> Vec<Benchmark> {
         });
     }
 
+    // graph_wide_fanout: one generated index re-exporting 5000 modules, each
+    // edge added twice so dedup runs against the full out-degree
+    {
+        let paths: Vec<PathBuf> = (0..5000)
+            .map(|i| PathBuf::from(format!("m{i}.ts")))
+            .collect();
+        benches.push(Benchmark {
+            name: "graph_wide_fanout",
+            run: Box::new(move || {
+                let mut graph = ModuleGraph::new();
+                let index = graph.add_module(PathBuf::from("index.ts"), 0, None);
+                for _ in 0..2 {
+                    for path in &paths {
+                        let to = graph.add_module(path.clone(), 0, None);
+                        graph.add_edge(index, to, EdgeKind::Static, "./m");
+                    }
+                }
+                black_box(graph);
+            }),
+        });
+    }
+
     // query_trace_ts
     if ts_entry_path.exists() {
         let lang = TypeScriptSupport::new(&ts);
@@ -465,7 +489,7 @@ fn register_benchmarks() -> Vec<Benchmark> {
         let result =
             chainsaw::walker::build_graph(&entry, &ts, &lang, &mut cache, &chainsaw::vfs::OsVfs);
         let graph = result.graph;
-        let entry_id = graph.path_to_id[&entry];
+        let entry_id = graph.module_id(&entry).unwrap();
         let opts = query::TraceOptions::default();
         benches.push(Benchmark {
             name: "query_trace_ts",
@@ -487,7 +511,7 @@ fn register_benchmarks() -> Vec<Benchmark> {
         let result =
             chainsaw::walker::build_graph(&entry, &py, &lang, &mut cache, &chainsaw::vfs::OsVfs);
         let graph = result.graph;
-        let entry_id = graph.path_to_id[&entry];
+        let entry_id = graph.module_id(&entry).unwrap();
         let opts = query::TraceOptions::default();
         benches.push(Benchmark {
             name: "query_trace_py",
@@ -4,11 +4,15 @@
 //! Tier 1.5 incrementally re-parses only changed files when imports are stable.
 //! Tier 2 caches per-file parse and resolve results so single-file edits skip
 //! the resolver entirely.
+//!
+//! Gzip sizes for `--weight-source gzip` live in a separate sidecar file
+//! (see [`CompressedSizeCache`]) so the default mode never pays for them.
 
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
+use std::io::Write as _;
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::thread;
@@ -16,9 +20,11 @@ use std::time::SystemTime;
 
 use crate::graph::ModuleGraph;
 use crate::lang::ParseResult;
+use crate::vfs::Vfs;
+use crate::walker::ResolutionStats;
 
 const CACHE_FILE: &str = ".chainsaw.cache";
-const CACHE_VERSION: u32 = 8;
+const CACHE_VERSION: u32 = 36;
 // 16-byte header: magic (4) + version (4) + graph_len (8)
 const CACHE_MAGIC: u32 = 0x4348_5357; // "CHSW"
 const HEADER_SIZE: usize = 16;
@@ -27,6 +33,65 @@ pub fn cache_path(root: &Path) -> PathBuf {
     root.join(CACHE_FILE)
 }
 
+/// Where the on-disk cache lives.
+///
+/// Defaults to `.chainsaw.cache` in the project root. `File` pins an exact
+/// path; `Dir` keeps one cache per project root inside a shared directory,
+/// named by a hash of the root so several projects can share it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum CacheLocation {
+    #[default]
+    ProjectRoot,
+    File(PathBuf),
+    Dir(PathBuf),
+}
+
+impl CacheLocation {
+    /// The cache file to use for the project at `root`.
+    pub fn path_for(&self, root: &Path) -> PathBuf {
+        match self {
+            Self::ProjectRoot => cache_path(root),
+            Self::File(path) => path.clone(),
+            Self::Dir(dir) => dir.join(format!("{:016x}{CACHE_FILE}", fnv1a(root))),
+        }
+    }
+}
+
+/// How the cache decides whether a file changed since it was cached.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CacheMode {
+    /// Compare mtime and size. Any touch counts as a change.
+    #[default]
+    Mtime,
+    /// Fall back to a content hash when the mtime differs, so touched but
+    /// unchanged files still hit, and reuse parse results for identical
+    /// content found at other paths (e.g. pnpm copies of one package).
+    Hash,
+}
+
+/// FNV-1a over the path's bytes. Stable across runs and platforms, unlike
+/// `DefaultHasher`, so a shared cache directory keeps its file names.
+fn fnv1a(path: &Path) -> u64 {
+    fnv1a_extend(0xcbf2_9ce4_8422_2325, path.as_os_str().as_encoded_bytes())
+}
+
+pub(crate) fn fnv1a_extend(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, &b| {
+        (hash ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Key for reusing a parse result under [`CacheMode::Hash`]. The extension
+/// is mixed in because the same bytes parse differently as `.ts` and `.tsx`.
+pub fn content_hash(path: &Path, content: &[u8]) -> u64 {
+    let ext = path.extension().map_or(&[][..], |e| e.as_encoded_bytes());
+    fnv1a_extend(fnv1a_extend(0xcbf2_9ce4_8422_2325, ext), content)
+}
+
+fn hash_file(path: &Path) -> Option<u64> {
+    fs::read(path).ok().map(|bytes| content_hash(path, &bytes))
+}
+
 fn mtime_of(meta: &fs::Metadata) -> Option<u128> {
     meta.modified()
         .ok()
@@ -42,6 +107,8 @@ struct CachedParse {
     size: u64,
     result: ParseResult,
     resolved_paths: Vec<Option<PathBuf>>,
+    /// Set under [`CacheMode::Hash`].
+    content_hash: Option<u64>,
 }
 
 // --- Whole-graph cache (tier 1) ---
@@ -50,10 +117,17 @@ struct CachedParse {
 struct CachedMtime {
     mtime_nanos: u128,
     size: u64,
+    /// Set under [`CacheMode::Hash`].
+    content_hash: Option<u64>,
+    /// Stamps of the directories the file's `import.meta.glob()` calls
+    /// listed. A file added to or removed from one re-parses the importer.
+    glob_dirs: Vec<(PathBuf, Option<u128>)>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 struct CachedGraph {
+    /// Project root the paths below were recorded under.
+    root: PathBuf,
     entry: PathBuf,
     graph: ModuleGraph,
     file_mtimes: HashMap<PathBuf, CachedMtime>,
@@ -61,8 +135,111 @@ struct CachedGraph {
     unresolvable_dynamic: usize,
     /// Per-file counts of unresolvable dynamic imports.
     unresolvable_dynamic_files: Vec<(PathBuf, usize)>,
-    /// Lockfile mtimes — if unchanged, skip re-resolving unresolved specifiers.
+    resolution: ResolutionStats,
+    /// Lockfile stamps (mtimes, or content hashes under [`CacheMode::Hash`]
+    /// so a cache restored on another machine still matches) — if
+    /// unchanged, skip re-resolving unresolved specifiers.
     dep_sentinels: Vec<(PathBuf, u128)>,
+    /// `--define` values the parse results were computed under.
+    defines: Vec<(String, String)>,
+    /// Whether package imports were resolved to the published entry.
+    prefer_published_entry: bool,
+    /// Whether `@/` and `~/` were left unaliased.
+    no_default_aliases: bool,
+    /// Whether gitignored project files were traced (`--no-gitignore`).
+    no_gitignore: bool,
+}
+
+/// The parse section of the cache file.
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedParses {
+    /// Project root the paths below were recorded under.
+    root: PathBuf,
+    entries: HashMap<PathBuf, CachedParse>,
+}
+
+/// Where `path`, recorded under the project root `from`, lies under `to`:
+/// the same path relative to the root, `..` steps included, so hoisted
+/// workspace packages and lockfiles above the root move along. A path with
+/// nothing in common with `from` (another drive) is kept as is.
+fn rebase(path: &Path, from: &Path, to: &Path) -> PathBuf {
+    let shared = path
+        .components()
+        .zip(from.components())
+        .take_while(|(a, b)| a == b)
+        .count();
+    if shared == 0 {
+        return path.to_path_buf();
+    }
+    let mut rebased = to.to_path_buf();
+    for _ in shared..from.components().count() {
+        if !rebased.pop() {
+            return path.to_path_buf();
+        }
+    }
+    rebased.extend(path.components().skip(shared));
+    rebased
+}
+
+impl CachedGraph {
+    /// Move every recorded path onto `root`, for a cache restored into a
+    /// checkout at another path.
+    fn rebase(&mut self, root: &Path) {
+        let from = std::mem::replace(&mut self.root, root.to_path_buf());
+        let moved = |path: &Path| rebase(path, &from, root);
+        self.entry = moved(&self.entry);
+        for module in &mut self.graph.modules {
+            module.path = moved(&module.path);
+        }
+        let path_to_id = self
+            .graph
+            .modules
+            .iter()
+            .map(|m| (self.graph.path_key(&m.path).into_owned(), m.id))
+            .collect();
+        self.graph.path_to_id = path_to_id;
+        self.file_mtimes = std::mem::take(&mut self.file_mtimes)
+            .into_iter()
+            .map(|(path, mut saved)| {
+                for (dir, _) in &mut saved.glob_dirs {
+                    *dir = moved(dir);
+                }
+                (moved(&path), saved)
+            })
+            .collect();
+        for (path, _) in &mut self.unresolvable_dynamic_files {
+            *path = moved(path);
+        }
+        for (path, _) in &mut self.dep_sentinels {
+            *path = moved(path);
+        }
+    }
+}
+
+impl CachedParses {
+    /// The entries with every recorded path moved onto `root`.
+    fn rebased_onto(self, root: &Path) -> HashMap<PathBuf, CachedParse> {
+        let Self {
+            root: from,
+            entries,
+        } = self;
+        if from == root {
+            return entries;
+        }
+        let moved = |path: &Path| rebase(path, &from, root);
+        entries
+            .into_iter()
+            .map(|(path, mut entry)| {
+                for resolved in entry.resolved_paths.iter_mut().flatten() {
+                    *resolved = moved(resolved);
+                }
+                for dir in &mut entry.result.glob_dirs {
+                    *dir = moved(dir);
+                }
+                (moved(&path), entry)
+            })
+            .collect()
+    }
 }
 
 const LOCKFILES: &[&str] = &[
@@ -79,16 +256,15 @@ const LOCKFILES: &[&str] = &[
 /// Find lockfile sentinels by walking up from `root` until a directory
 /// containing a lockfile is found. This handles workspace layouts where
 /// the lockfile lives at the workspace root, not the package root.
-fn find_dep_sentinels(root: &Path) -> Vec<(PathBuf, u128)> {
+fn find_dep_sentinels(root: &Path, mode: CacheMode) -> Vec<(PathBuf, u128)> {
     let mut dir = root.to_path_buf();
     loop {
         let sentinels: Vec<(PathBuf, u128)> = LOCKFILES
             .iter()
             .filter_map(|name| {
                 let path = dir.join(name);
-                let meta = fs::metadata(&path).ok()?;
-                let mtime = mtime_of(&meta)?;
-                Some((path, mtime))
+                let stamp = sentinel_stamp(&path, mode)?;
+                Some((path, stamp))
             })
             .collect();
         if !sentinels.is_empty() {
@@ -100,6 +276,39 @@ fn find_dep_sentinels(root: &Path) -> Vec<(PathBuf, u128)> {
     }
 }
 
+/// A lockfile's mtime, or its content hash under [`CacheMode::Hash`].
+fn sentinel_stamp(path: &Path, mode: CacheMode) -> Option<u128> {
+    match mode {
+        CacheMode::Mtime => mtime_of(&fs::metadata(path).ok()?),
+        CacheMode::Hash => hash_file(path).map(u128::from),
+    }
+}
+
+/// A directory's mtime, or a hash of its entry names under
+/// [`CacheMode::Hash`]. `None` when it doesn't exist.
+fn dir_stamp(path: &Path, mode: CacheMode) -> Option<u128> {
+    match mode {
+        CacheMode::Mtime => mtime_of(&fs::metadata(path).ok()?),
+        CacheMode::Hash => {
+            let mut names: Vec<_> = fs::read_dir(path)
+                .ok()?
+                .filter_map(|e| Some(e.ok()?.file_name()))
+                .collect();
+            names.sort();
+            let hash = names.iter().fold(0xcbf2_9ce4_8422_2325, |hash, name| {
+                fnv1a_extend(fnv1a_extend(hash, name.as_encoded_bytes()), b"/")
+            });
+            Some(u128::from(hash))
+        }
+    }
+}
+
+fn glob_stamps(dirs: &[PathBuf], mode: CacheMode) -> Vec<(PathBuf, Option<u128>)> {
+    dirs.iter()
+        .map(|dir| (dir.clone(), dir_stamp(dir, mode)))
+        .collect()
+}
+
 #[derive(Debug)]
 #[non_exhaustive]
 pub enum GraphCacheResult {
@@ -109,6 +318,7 @@ pub enum GraphCacheResult {
         unresolvable_dynamic: usize,
         unresolvable_dynamic_files: Vec<(PathBuf, usize)>,
         unresolved_specifiers: Vec<String>,
+        resolution: ResolutionStats,
         /// True if the graph is valid but sentinel mtimes need updating.
         needs_resave: bool,
     },
@@ -117,6 +327,7 @@ pub enum GraphCacheResult {
         graph: ModuleGraph,
         unresolvable_dynamic: usize,
         unresolvable_dynamic_files: Vec<(PathBuf, usize)>,
+        resolution: ResolutionStats,
         changed_files: Vec<PathBuf>,
     },
     /// Cache miss — wrong entry, no cache, file deleted, or new imports resolve.
@@ -160,6 +371,18 @@ pub struct ParseCache {
     /// Preserved from Stale result for incremental save.
     stale_file_mtimes: Option<HashMap<PathBuf, CachedMtime>>,
     stale_unresolved: Option<Vec<String>>,
+    /// Cache file this cache was loaded from and saves back to. `None`
+    /// means the default location in the project root.
+    path: Option<PathBuf>,
+    /// Project root of this run. Paths cached under another root (a cache
+    /// restored into a checkout elsewhere) are rebased onto it on load.
+    root: Option<PathBuf>,
+    /// `--define` values for the current run, stored with the next save.
+    defines: Vec<(String, String)>,
+    prefer_published_entry: bool,
+    no_default_aliases: bool,
+    no_gitignore: bool,
+    mode: CacheMode,
 }
 
 impl Default for ParseCache {
@@ -176,33 +399,57 @@ impl ParseCache {
             cached_graph: None,
             stale_file_mtimes: None,
             stale_unresolved: None,
+            path: None,
+            root: None,
+            defines: Vec::new(),
+            prefer_published_entry: false,
+            no_default_aliases: false,
+            no_gitignore: false,
+            mode: CacheMode::Mtime,
         }
     }
 
-    /// Load cache from disk. The graph cache is deserialized immediately;
-    /// parse entries are deferred until first access (saves ~2.5ms on cache hit).
-    #[allow(clippy::cast_possible_truncation)]
+    /// An empty cache that saves to `path` instead of the project root.
+    pub fn new_at(path: &Path) -> Self {
+        Self {
+            path: Some(path.to_path_buf()),
+            ..Self::new()
+        }
+    }
+
+    /// Load cache from the default location in the project root.
     pub fn load(root: &Path) -> Self {
-        let path = cache_path(root);
-        let Ok(data) = fs::read(&path) else {
-            return Self::new();
+        Self::load_from(&cache_path(root), root)
+    }
+
+    /// Load cache from `path` for the project at `root`; later saves write
+    /// back to the same file. The graph cache is deserialized immediately;
+    /// parse entries are deferred until first access (saves ~2.5ms on cache
+    /// hit). A cache written under another root is rebased onto `root`.
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn load_from(path: &Path, root: &Path) -> Self {
+        let Ok(data) = fs::read(path) else {
+            return Self::new_at(path);
         };
         if data.len() < HEADER_SIZE {
-            return Self::new();
+            return Self::new_at(path);
         }
         let magic = u32::from_le_bytes(data[0..4].try_into().unwrap());
         let version = u32::from_le_bytes(data[4..8].try_into().unwrap());
         if magic != CACHE_MAGIC || version != CACHE_VERSION {
-            return Self::new();
+            return Self::new_at(path);
         }
         let graph_len = u64::from_le_bytes(data[8..16].try_into().unwrap()) as usize;
         let graph_end = HEADER_SIZE + graph_len;
         if data.len() < graph_end {
-            return Self::new();
+            return Self::new_at(path);
         }
 
-        let cached_graph: Option<CachedGraph> =
+        let mut cached_graph: Option<CachedGraph> =
             bitcode::deserialize(&data[HEADER_SIZE..graph_end]).ok();
+        if let Some(graph) = cached_graph.as_mut().filter(|g| g.root != root) {
+            graph.rebase(root);
+        }
 
         let deferred = if data.len() > graph_end {
             Some(data[graph_end..].to_vec())
@@ -216,12 +463,121 @@ impl ParseCache {
             cached_graph,
             stale_file_mtimes: None,
             stale_unresolved: None,
+            path: Some(path.to_path_buf()),
+            root: Some(root.to_path_buf()),
+            defines: Vec::new(),
+            prefer_published_entry: false,
+            no_default_aliases: false,
+            no_gitignore: false,
+            mode: CacheMode::Mtime,
+        }
+    }
+
+    /// Set the `--define` values for this run. Cached parse results and
+    /// graph built under different defines are discarded, since dead-branch
+    /// pruning changes which imports a file has.
+    pub fn set_defines(&mut self, defines: &[(String, String)]) {
+        let built_under = self
+            .cached_graph
+            .as_ref()
+            .map_or(&[][..], |g| &g.defines[..]);
+        if built_under != defines {
+            self.entries.clear();
+            self.deferred_parse_data = None;
+            self.cached_graph = None;
+        }
+        self.defines = defines.to_vec();
+    }
+
+    /// Record whether package imports resolve to the published entry this
+    /// run. Resolved paths cached under the other setting are discarded.
+    pub fn set_prefer_published_entry(&mut self, yes: bool) {
+        let built_under = self
+            .cached_graph
+            .as_ref()
+            .is_some_and(|g| g.prefer_published_entry);
+        if built_under != yes {
+            self.entries.clear();
+            self.deferred_parse_data = None;
+            self.cached_graph = None;
+        }
+        self.prefer_published_entry = yes;
+    }
+
+    /// Record whether default `@/` and `~/` aliases are off this run.
+    /// Resolved paths cached under the other setting are discarded.
+    pub fn set_no_default_aliases(&mut self, yes: bool) {
+        let built_under = self
+            .cached_graph
+            .as_ref()
+            .is_some_and(|g| g.no_default_aliases);
+        if built_under != yes {
+            self.entries.clear();
+            self.deferred_parse_data = None;
+            self.cached_graph = None;
+        }
+        self.no_default_aliases = yes;
+    }
+
+    /// Record whether gitignored project files are traced this run. Only
+    /// the graph depends on it (parse results keep every resolved path),
+    /// so a graph built under the other setting is discarded.
+    pub fn set_no_gitignore(&mut self, yes: bool) {
+        if self
+            .cached_graph
+            .as_ref()
+            .is_some_and(|g| g.no_gitignore != yes)
+        {
+            self.cached_graph = None;
         }
+        self.no_gitignore = yes;
+    }
+
+    /// Record whether paths are matched case-insensitively this run. A
+    /// graph keyed the other way is discarded.
+    pub fn set_case_insensitive_paths(&mut self, yes: bool) {
+        if self
+            .cached_graph
+            .as_ref()
+            .is_some_and(|g| g.graph.fold_case != yes)
+        {
+            self.cached_graph = None;
+        }
+    }
+
+    /// Choose how changed files are detected. Under [`CacheMode::Hash`],
+    /// saves also record each file's content hash.
+    pub fn set_mode(&mut self, mode: CacheMode) {
+        self.mode = mode;
+    }
+
+    pub fn mode(&self) -> CacheMode {
+        self.mode
+    }
+
+    /// Cached parse results keyed by content hash, for reuse across paths
+    /// and entries. Empty unless in [`CacheMode::Hash`].
+    pub fn parses_by_content(&mut self) -> HashMap<u64, &ParseResult> {
+        if self.mode != CacheMode::Hash {
+            return HashMap::new();
+        }
+        self.ensure_entries();
+        // A glob's matches depend on more than the file's bytes
+        self.entries
+            .values()
+            .filter(|e| e.result.glob_dirs.is_empty())
+            .filter_map(|e| Some((e.content_hash?, &e.result)))
+            .collect()
     }
 
     fn ensure_entries(&mut self) {
         if let Some(bytes) = self.deferred_parse_data.take() {
-            self.entries = bitcode::deserialize(&bytes).unwrap_or_default();
+            self.entries = bitcode::deserialize::<CachedParses>(&bytes)
+                .map(|parses| match &self.root {
+                    Some(root) => parses.rebased_onto(root),
+                    None => parses.entries,
+                })
+                .unwrap_or_default();
         }
     }
 
@@ -241,18 +597,37 @@ impl ParseCache {
         };
 
         // Check all file mtimes in parallel — collect changed files.
-        // If any file is missing (deleted), return Miss.
+        // If any file is missing (deleted), return Miss. Under hash mode a
+        // touched file whose content is unchanged is not a change, but its
+        // new mtime is saved so the next run takes the stat-only path.
         let any_missing = AtomicBool::new(false);
+        let any_touched = AtomicBool::new(false);
+        let mode = self.mode;
+        let hash_mode = mode == CacheMode::Hash;
         let changed_files: Vec<PathBuf> = cached
             .file_mtimes
             .par_iter()
             .filter_map(|(path, saved)| {
+                let globs_changed = saved
+                    .glob_dirs
+                    .iter()
+                    .any(|(dir, stamp)| dir_stamp(dir, mode) != *stamp);
                 if let Ok(meta) = fs::metadata(path) {
+                    if globs_changed {
+                        return Some(path.clone());
+                    }
                     let mtime = mtime_of(&meta)?;
-                    if mtime != saved.mtime_nanos || meta.len() != saved.size {
-                        Some(path.clone())
-                    } else {
+                    if mtime == saved.mtime_nanos && meta.len() == saved.size {
+                        None
+                    } else if hash_mode
+                        && meta.len() == saved.size
+                        && saved.content_hash.is_some()
+                        && hash_file(path) == saved.content_hash
+                    {
+                        any_touched.store(true, Ordering::Relaxed);
                         None
+                    } else {
+                        Some(path.clone())
                     }
                 } else {
                     any_missing.store(true, Ordering::Relaxed);
@@ -270,12 +645,10 @@ impl ParseCache {
         // expensive re-resolution check. A new `npm install` / `pip install` would
         // modify the lockfile, triggering the full check.
         let sentinels_unchanged = !cached.dep_sentinels.is_empty()
-            && cached.dep_sentinels.iter().all(|(path, saved_mtime)| {
-                fs::metadata(path)
-                    .ok()
-                    .and_then(|m| mtime_of(&m))
-                    .is_some_and(|t| t == *saved_mtime)
-            });
+            && cached
+                .dep_sentinels
+                .iter()
+                .all(|(path, saved)| sentinel_stamp(path, self.mode) == Some(*saved));
 
         if !sentinels_unchanged {
             let any_resolves = cached
@@ -294,7 +667,8 @@ impl ParseCache {
                 unresolvable_dynamic: cached.unresolvable_dynamic,
                 unresolvable_dynamic_files: cached.unresolvable_dynamic_files,
                 unresolved_specifiers: cached.unresolved_specifiers,
-                needs_resave: !sentinels_unchanged,
+                resolution: cached.resolution,
+                needs_resave: !sentinels_unchanged || any_touched.into_inner(),
             };
         }
 
@@ -306,10 +680,20 @@ impl ParseCache {
             graph: cached.graph,
             unresolvable_dynamic: cached.unresolvable_dynamic,
             unresolvable_dynamic_files: cached.unresolvable_dynamic_files,
+            resolution: cached.resolution,
             changed_files,
         }
     }
 
+    /// The cached graph for `entry` WITHOUT verifying that its files are
+    /// unchanged. Used to compare a fresh build against the last run.
+    pub fn take_graph(&mut self, entry: &Path) -> Option<ModuleGraph> {
+        match self.cached_graph.take() {
+            Some(c) if c.entry == entry => Some(c.graph),
+            _ => None,
+        }
+    }
+
     /// Get the cached parse result for a file WITHOUT verifying its mtime.
     /// Used by incremental update to compare old imports against new parse results.
     pub fn lookup_unchecked(&mut self, path: &Path) -> Option<&ParseResult> {
@@ -317,10 +701,23 @@ impl ParseCache {
         self.entries.get(path).map(|e| &e.result)
     }
 
+    /// Like [`lookup_unchecked`](Self::lookup_unchecked), with what each
+    /// import resolved to.
+    pub fn lookup_resolved_unchecked(
+        &mut self,
+        path: &Path,
+    ) -> Option<(&ParseResult, &[Option<PathBuf>])> {
+        self.ensure_entries();
+        self.entries
+            .get(path)
+            .map(|e| (&e.result, e.resolved_paths.as_slice()))
+    }
+
     /// Save after incremental update. Uses the preserved `file_mtimes` from the
     /// Stale result, updating only the changed files' mtimes instead of
     /// re-statting every file. Serialization and disk write happen on a
     /// background thread.
+    #[allow(clippy::too_many_arguments)]
     pub fn save_incremental(
         &mut self,
         root: &Path,
@@ -329,11 +726,13 @@ impl ParseCache {
         changed_files: &[PathBuf],
         unresolvable_dynamic: usize,
         unresolvable_dynamic_files: Vec<(PathBuf, usize)>,
+        resolution: ResolutionStats,
     ) -> CacheWriteHandle {
         let Some(mut file_mtimes) = self.stale_file_mtimes.take() else {
             return CacheWriteHandle::none();
         };
         let unresolved_specifiers = self.stale_unresolved.take().unwrap_or_default();
+        self.ensure_entries();
 
         // Update only changed files' mtimes (cheap, typically 1-2 files)
         for path in changed_files {
@@ -343,18 +742,31 @@ impl ParseCache {
             {
                 saved.mtime_nanos = mtime;
                 saved.size = meta.len();
+                saved.content_hash = match self.mode {
+                    CacheMode::Mtime => None,
+                    CacheMode::Hash => hash_file(path),
+                };
+                saved.glob_dirs = self
+                    .entries
+                    .get(path)
+                    .map_or_else(Vec::new, |e| glob_stamps(&e.result.glob_dirs, self.mode));
             }
         }
 
-        self.ensure_entries();
         let entries = std::mem::take(&mut self.entries);
         let root = root.to_path_buf();
         let entry = entry.to_path_buf();
         let graph = graph.clone();
-        let dep_sentinels = find_dep_sentinels(&root);
+        let dep_sentinels = find_dep_sentinels(&root, self.mode);
+        let path = self.file_path(&root);
+        let defines = self.defines.clone();
+        let prefer_published_entry = self.prefer_published_entry;
+        let no_default_aliases = self.no_default_aliases;
+        let no_gitignore = self.no_gitignore;
 
         CacheWriteHandle(Some(thread::spawn(move || {
             write_cache_to_disk(
+                path,
                 root,
                 entry,
                 graph,
@@ -363,13 +775,19 @@ impl ParseCache {
                 unresolved_specifiers,
                 unresolvable_dynamic,
                 unresolvable_dynamic_files,
+                resolution,
                 dep_sentinels,
+                defines,
+                prefer_published_entry,
+                no_default_aliases,
+                no_gitignore,
             );
         })))
     }
 
     /// Save the full graph + parse cache to disk. File mtime collection,
     /// serialization, and disk write all happen on a background thread.
+    #[allow(clippy::too_many_arguments)]
     pub fn save(
         &mut self,
         root: &Path,
@@ -378,6 +796,7 @@ impl ParseCache {
         unresolved_specifiers: Vec<String>,
         unresolvable_dynamic: usize,
         unresolvable_dynamic_files: Vec<(PathBuf, usize)>,
+        resolution: ResolutionStats,
     ) -> CacheWriteHandle {
         self.ensure_entries();
         let entries = std::mem::take(&mut self.entries);
@@ -385,7 +804,13 @@ impl ParseCache {
         let entry = entry.to_path_buf();
         let graph = graph.clone();
 
-        let dep_sentinels = find_dep_sentinels(&root);
+        let dep_sentinels = find_dep_sentinels(&root, self.mode);
+        let path = self.file_path(&root);
+        let defines = self.defines.clone();
+        let prefer_published_entry = self.prefer_published_entry;
+        let no_default_aliases = self.no_default_aliases;
+        let no_gitignore = self.no_gitignore;
+        let mode = self.mode;
 
         CacheWriteHandle(Some(thread::spawn(move || {
             let file_mtimes: HashMap<PathBuf, CachedMtime> = graph
@@ -399,12 +824,20 @@ impl ParseCache {
                         CachedMtime {
                             mtime_nanos: mtime,
                             size: meta.len(),
+                            content_hash: match mode {
+                                CacheMode::Mtime => None,
+                                CacheMode::Hash => hash_file(&m.path),
+                            },
+                            glob_dirs: entries
+                                .get(&m.path)
+                                .map_or_else(Vec::new, |e| glob_stamps(&e.result.glob_dirs, mode)),
                         },
                     ))
                 })
                 .collect();
 
             write_cache_to_disk(
+                path,
                 root,
                 entry,
                 graph,
@@ -413,11 +846,20 @@ impl ParseCache {
                 unresolved_specifiers,
                 unresolvable_dynamic,
                 unresolvable_dynamic_files,
+                resolution,
                 dep_sentinels,
+                defines,
+                prefer_published_entry,
+                no_default_aliases,
+                no_gitignore,
             );
         })))
     }
 
+    fn file_path(&self, root: &Path) -> PathBuf {
+        self.path.clone().unwrap_or_else(|| cache_path(root))
+    }
+
     pub fn lookup(&mut self, path: &Path) -> Option<(ParseResult, Vec<Option<PathBuf>>)> {
         self.ensure_entries();
         let entry = self.entries.get(path)?;
@@ -437,6 +879,7 @@ impl ParseCache {
         mtime_nanos: u128,
         result: ParseResult,
         resolved_paths: Vec<Option<PathBuf>>,
+        content_hash: Option<u64>,
     ) {
         self.ensure_entries();
         self.entries.insert(
@@ -446,6 +889,7 @@ impl ParseCache {
                 size,
                 result,
                 resolved_paths,
+                content_hash,
             },
         );
     }
@@ -454,6 +898,7 @@ impl ParseCache {
 /// Serialize and write the cache to disk. Runs on a background thread.
 #[allow(clippy::too_many_arguments, clippy::needless_pass_by_value)]
 fn write_cache_to_disk(
+    path: PathBuf,
     root: PathBuf,
     entry: PathBuf,
     graph: ModuleGraph,
@@ -462,16 +907,27 @@ fn write_cache_to_disk(
     unresolved_specifiers: Vec<String>,
     unresolvable_dynamic: usize,
     unresolvable_dynamic_files: Vec<(PathBuf, usize)>,
+    resolution: ResolutionStats,
     dep_sentinels: Vec<(PathBuf, u128)>,
+    defines: Vec<(String, String)>,
+    prefer_published_entry: bool,
+    no_default_aliases: bool,
+    no_gitignore: bool,
 ) {
     let graph_cache = CachedGraph {
+        root: root.clone(),
         entry,
         graph,
         file_mtimes,
         unresolved_specifiers,
         unresolvable_dynamic,
         unresolvable_dynamic_files,
+        resolution,
         dep_sentinels,
+        defines,
+        prefer_published_entry,
+        no_default_aliases,
+        no_gitignore,
     };
 
     let graph_data = match bitcode::serialize(&graph_cache) {
@@ -481,7 +937,7 @@ fn write_cache_to_disk(
             return;
         }
     };
-    let parse_data = match bitcode::serialize(&entries) {
+    let parse_data = match bitcode::serialize(&CachedParses { root, entries }) {
         Ok(d) => d,
         Err(e) => {
             eprintln!("warning: failed to serialize parse cache: {e}");
@@ -489,15 +945,222 @@ fn write_cache_to_disk(
         }
     };
 
+    if let Err(e) = write_cache_file(&path, &graph_data, &parse_data) {
+        eprintln!("warning: failed to write cache: {e}");
+    }
+}
+
+/// Write the header and the serialized sections. An empty `graph_data`
+/// leaves the cache without a graph.
+fn write_cache_file(path: &Path, graph_data: &[u8], parse_data: &[u8]) -> std::io::Result<()> {
     let mut out = Vec::with_capacity(HEADER_SIZE + graph_data.len() + parse_data.len());
     out.extend_from_slice(&CACHE_MAGIC.to_le_bytes());
     out.extend_from_slice(&CACHE_VERSION.to_le_bytes());
     out.extend_from_slice(&(graph_data.len() as u64).to_le_bytes());
-    out.extend_from_slice(&graph_data);
-    out.extend_from_slice(&parse_data);
+    out.extend_from_slice(graph_data);
+    out.extend_from_slice(parse_data);
+    write_atomically(path, &out)
+}
 
-    if let Err(e) = fs::write(cache_path(&root), &out) {
-        eprintln!("warning: failed to write cache: {e}");
+/// Write to a sibling temp file and rename it over `path`, so a reader (or
+/// a CI cache upload) never sees a half-written file. A missing parent
+/// directory (a fresh `--cache-dir`) is created first.
+fn write_atomically(path: &Path, data: &[u8]) -> std::io::Result<()> {
+    #[allow(clippy::or_fun_call)]
+    let dir = path
+        .parent()
+        .filter(|d| !d.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    fs::create_dir_all(dir)?;
+    let mut tmp = tempfile::NamedTempFile::new_in(dir)?;
+    tmp.write_all(data)?;
+    tmp.persist(path).map_err(|e| e.error)?;
+    Ok(())
+}
+
+/// What [`prune_cache`] removed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct PruneStats {
+    /// Parse entries dropped because their file is gone or has changed.
+    pub entries_removed: usize,
+    pub entries_kept: usize,
+    /// The cached graph was dropped because its entry file is gone.
+    pub graph_discarded: bool,
+    pub bytes_before: u64,
+    pub bytes_after: u64,
+}
+
+/// Drop parse entries for files that were deleted or changed since they
+/// were cached, and the cached graph if its entry file is gone, then
+/// rewrite the cache file compactly. A cache written by another chainsaw
+/// version holds nothing usable and is rewritten empty.
+pub fn prune_cache(path: &Path, root: &Path) -> std::io::Result<PruneStats> {
+    let bytes_before = fs::metadata(path)?.len();
+    let mut cache = ParseCache::load_from(path, root);
+    cache.ensure_entries();
+
+    let before = cache.entries.len();
+    cache.entries.retain(|file, entry| {
+        let Ok(meta) = fs::metadata(file) else {
+            return false;
+        };
+        meta.len() == entry.size
+            && (mtime_of(&meta) == Some(entry.mtime_nanos)
+                || (entry.content_hash.is_some() && hash_file(file) == entry.content_hash))
+    });
+    let entries_kept = cache.entries.len();
+
+    let graph_discarded = cache.cached_graph.take_if(|g| !g.entry.exists()).is_some();
+
+    let graph_data = match &cache.cached_graph {
+        Some(graph) => bitcode::serialize(graph).map_err(std::io::Error::other)?,
+        None => Vec::new(),
+    };
+    let parses = CachedParses {
+        root: root.to_path_buf(),
+        entries: std::mem::take(&mut cache.entries),
+    };
+    let parse_data = bitcode::serialize(&parses).map_err(std::io::Error::other)?;
+    write_cache_file(path, &graph_data, &parse_data)?;
+
+    Ok(PruneStats {
+        entries_removed: before - entries_kept,
+        entries_kept,
+        graph_discarded,
+        bytes_before,
+        bytes_after: fs::metadata(path)?.len(),
+    })
+}
+
+// --- Compressed size cache (--weight-source gzip) ---
+
+/// Gzip size of `bytes` at the default compression level (6), header and
+/// trailer included, as a web server would send it.
+pub fn gzip_size(bytes: &[u8]) -> u64 {
+    let config = zlib_rs::DeflateConfig {
+        // 15-bit window plus 16 selects the gzip wrapper
+        window_bits: 31,
+        ..zlib_rs::DeflateConfig::default()
+    };
+    let mut buf = vec![0u8; zlib_rs::compress_bound(bytes.len()) + 32];
+    let (compressed, rc) = zlib_rs::compress_slice(&mut buf, bytes, config);
+    if rc == zlib_rs::ReturnCode::Ok {
+        compressed.len() as u64
+    } else {
+        bytes.len() as u64
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedCompressed {
+    mtime_nanos: u128,
+    size: u64,
+    gzip_size: u64,
+}
+
+/// Gzip sizes keyed by file path, valid while the file's mtime and size are
+/// unchanged. Stored next to the graph cache with a `.gzip` extension.
+#[derive(Debug)]
+pub struct CompressedSizeCache {
+    path: PathBuf,
+    entries: HashMap<PathBuf, CachedCompressed>,
+    dirty: bool,
+}
+
+impl CompressedSizeCache {
+    /// Load the sidecar for the graph cache at `cache_file`. A missing or
+    /// unreadable sidecar starts empty.
+    pub fn load(cache_file: &Path) -> Self {
+        let path = cache_file.with_extension("gzip");
+        let entries = fs::read(&path)
+            .ok()
+            .and_then(|data| bitcode::deserialize(&data).ok())
+            .unwrap_or_default();
+        Self {
+            path,
+            entries,
+            dirty: false,
+        }
+    }
+
+    /// An empty cache that still saves to the sidecar of `cache_file`.
+    pub fn new_at(cache_file: &Path) -> Self {
+        Self {
+            path: cache_file.with_extension("gzip"),
+            entries: HashMap::new(),
+            dirty: false,
+        }
+    }
+
+    /// Replace every module's size with its gzip size, compressing only
+    /// files that are new or changed since the last run. Files the VFS
+    /// cannot stat with an mtime (git trees) are compressed but not cached.
+    /// Files that cannot be read weigh 0, so the totals never mix in raw
+    /// sizes; the returned warnings name them.
+    #[must_use = "unreadable files are only reported through the warnings"]
+    pub fn apply(&mut self, graph: &mut ModuleGraph, vfs: &dyn Vfs) -> Vec<String> {
+        // Per module: gzip size and the entry to store if newly computed,
+        // or why the file could not be read
+        type Computed = Result<(u64, Option<CachedCompressed>), String>;
+        let computed: Vec<Computed> = graph
+            .modules
+            .par_iter()
+            .map(|m| {
+                let unreadable = |e: std::io::Error| format!("{}: {e}", m.path.display());
+                let meta = vfs.metadata(&m.path).map_err(unreadable)?;
+                let stamp = meta.mtime_nanos.map(|t| (t, meta.len));
+                if let (Some((mtime, size)), Some(c)) = (stamp, self.entries.get(&m.path))
+                    && c.mtime_nanos == mtime
+                    && c.size == size
+                {
+                    return Ok((c.gzip_size, None));
+                }
+                let bytes = vfs.read(&m.path).map_err(unreadable)?;
+                let gzip = gzip_size(&bytes);
+                let fresh = stamp.map(|(mtime_nanos, size)| CachedCompressed {
+                    mtime_nanos,
+                    size,
+                    gzip_size: gzip,
+                });
+                Ok((gzip, fresh))
+            })
+            .collect();
+
+        let mut warnings = Vec::new();
+        for (i, result) in computed.into_iter().enumerate() {
+            let (gzip, fresh) = match result {
+                Ok(computed) => computed,
+                Err(warning) => {
+                    graph.modules[i].size_bytes = 0;
+                    warnings.push(format!("gzip size unavailable, counted as 0: {warning}"));
+                    continue;
+                }
+            };
+            let module = &mut graph.modules[i];
+            module.size_bytes = gzip;
+            if let Some(entry) = fresh {
+                self.entries.insert(module.path.clone(), entry);
+                self.dirty = true;
+            }
+        }
+        graph.package_map.clear();
+        graph.compute_package_info();
+        warnings
+    }
+
+    pub fn save(&self) {
+        if !self.dirty {
+            return;
+        }
+        match bitcode::serialize(&self.entries) {
+            Ok(data) => {
+                if let Err(e) = write_atomically(&self.path, &data) {
+                    eprintln!("warning: failed to write compressed size cache: {e}");
+                }
+            }
+            Err(e) => eprintln!("warning: failed to serialize compressed size cache: {e}"),
+        }
     }
 }
 
@@ -516,7 +1179,7 @@ mod tests {
     ) {
         let meta = fs::metadata(&path).unwrap();
         let mtime = mtime_of(&meta).unwrap();
-        cache.insert(path, meta.len(), mtime, result, resolved);
+        cache.insert(path, meta.len(), mtime, result, resolved, None);
     }
 
     #[test]
@@ -531,8 +1194,10 @@ mod tests {
             imports: vec![RawImport {
                 specifier: "os".into(),
                 kind: EdgeKind::Static,
+                require: false,
             }],
             unresolvable_dynamic: 0,
+            glob_dirs: Vec::new(),
         };
         let resolved = vec![None];
         insert_with_stat(&mut cache, file.clone(), result, resolved);
@@ -556,6 +1221,7 @@ mod tests {
         let result = ParseResult {
             imports: vec![],
             unresolvable_dynamic: 0,
+            glob_dirs: Vec::new(),
         };
         insert_with_stat(&mut cache, file.clone(), result, vec![]);
 
@@ -564,6 +1230,50 @@ mod tests {
         assert!(cache.lookup(&file).is_none());
     }
 
+    #[test]
+    fn prune_drops_entries_for_deleted_files() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        let kept = root.join("kept.py");
+        let deleted = root.join("deleted.py");
+        fs::write(&kept, "import os").unwrap();
+        fs::write(&deleted, "import sys").unwrap();
+
+        let mut cache = ParseCache::new();
+        for file in [&kept, &deleted] {
+            let result = ParseResult {
+                imports: vec![],
+                unresolvable_dynamic: 0,
+                glob_dirs: Vec::new(),
+            };
+            insert_with_stat(&mut cache, file.clone(), result, vec![]);
+        }
+        let mut graph = ModuleGraph::new();
+        graph.add_module(deleted.clone(), 10, None);
+        drop(cache.save(
+            &root,
+            &deleted,
+            &graph,
+            vec![],
+            0,
+            vec![],
+            ResolutionStats::default(),
+        ));
+        fs::remove_file(&deleted).unwrap();
+
+        let stats = prune_cache(&cache_path(&root), &root).unwrap();
+        assert_eq!(stats.entries_removed, 1);
+        assert_eq!(stats.entries_kept, 1);
+        assert!(stats.graph_discarded);
+        assert!(stats.bytes_after < stats.bytes_before);
+
+        let mut loaded = ParseCache::load(&root);
+        assert!(loaded.cached_graph.is_none());
+        assert!(loaded.lookup(&kept).is_some());
+        loaded.ensure_entries();
+        assert!(!loaded.entries.contains_key(&deleted));
+    }
+
     #[test]
     fn parse_cache_save_and_load_roundtrip() {
         let tmp = tempfile::tempdir().unwrap();
@@ -578,14 +1288,24 @@ mod tests {
             imports: vec![RawImport {
                 specifier: "os".into(),
                 kind: EdgeKind::Static,
+                require: false,
             }],
             unresolvable_dynamic: 1,
+            glob_dirs: Vec::new(),
         };
         let resolved = vec![Some(target.clone())];
         insert_with_stat(&mut cache, file.clone(), result, resolved);
 
         let graph = ModuleGraph::new();
-        drop(cache.save(&root, &file, &graph, vec![], 0, vec![]));
+        drop(cache.save(
+            &root,
+            &file,
+            &graph,
+            vec![],
+            0,
+            vec![],
+            ResolutionStats::default(),
+        ));
 
         let mut loaded = ParseCache::load(&root);
         let cached = loaded.lookup(&file);
@@ -609,8 +1329,14 @@ mod tests {
         let size = fs::metadata(&file).unwrap().len();
         graph.add_module(file.clone(), size, None);
 
+        let stats = ResolutionStats {
+            imports: 1,
+            unresolved: 1,
+            unresolvable_dynamic: 2,
+            ..ResolutionStats::default()
+        };
         let mut cache = ParseCache::new();
-        drop(cache.save(&root, &file, &graph, vec!["os".into()], 2, vec![]));
+        drop(cache.save(&root, &file, &graph, vec!["os".into()], 2, vec![], stats));
 
         let mut loaded = ParseCache::load(&root);
         let resolve_fn = |_: &str| false;
@@ -619,11 +1345,13 @@ mod tests {
         if let GraphCacheResult::Hit {
             graph: g,
             unresolvable_dynamic: unresolvable,
+            resolution,
             ..
         } = result
         {
             assert_eq!(g.module_count(), 1);
             assert_eq!(unresolvable, 2);
+            assert_eq!(resolution, stats);
         }
     }
 
@@ -644,7 +1372,15 @@ mod tests {
 
         let dynamic_files = vec![(file_a.clone(), 3), (file_b.clone(), 2)];
         let mut cache = ParseCache::new();
-        drop(cache.save(&root, &file_a, &graph, vec![], 5, dynamic_files));
+        drop(cache.save(
+            &root,
+            &file_a,
+            &graph,
+            vec![],
+            5,
+            dynamic_files,
+            ResolutionStats::default(),
+        ));
 
         let mut loaded = ParseCache::load(&root);
         let resolve_fn = |_: &str| false;
@@ -676,7 +1412,15 @@ mod tests {
         graph.add_module(file.clone(), size, None);
 
         let mut cache = ParseCache::new();
-        drop(cache.save(&root, &file, &graph, vec![], 0, vec![]));
+        drop(cache.save(
+            &root,
+            &file,
+            &graph,
+            vec![],
+            0,
+            vec![],
+            ResolutionStats::default(),
+        ));
 
         fs::write(&file, "x = 2; y = 3").unwrap();
 
@@ -690,6 +1434,135 @@ mod tests {
         }
     }
 
+    #[test]
+    fn hash_mode_hits_when_file_touched_but_unchanged() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        let file = root.join("entry.py");
+        fs::write(&file, "x = 1").unwrap();
+
+        let mut graph = ModuleGraph::new();
+        let size = fs::metadata(&file).unwrap().len();
+        graph.add_module(file.clone(), size, None);
+
+        let mut cache = ParseCache::new();
+        cache.set_mode(CacheMode::Hash);
+        drop(cache.save(
+            &root,
+            &file,
+            &graph,
+            vec![],
+            0,
+            vec![],
+            ResolutionStats::default(),
+        ));
+
+        let touched = SystemTime::now() + std::time::Duration::from_secs(60);
+        fs::File::options()
+            .write(true)
+            .open(&file)
+            .unwrap()
+            .set_modified(touched)
+            .unwrap();
+
+        let resolve_fn = |_: &str| false;
+        let mut loaded = ParseCache::load(&root);
+        let result = loaded.try_load_graph(&file, &resolve_fn);
+        assert!(matches!(result, GraphCacheResult::Stale { .. }));
+
+        let mut loaded = ParseCache::load(&root);
+        loaded.set_mode(CacheMode::Hash);
+        let result = loaded.try_load_graph(&file, &resolve_fn);
+        assert!(
+            matches!(
+                result,
+                GraphCacheResult::Hit {
+                    needs_resave: true,
+                    ..
+                }
+            ),
+            "expected Hit, got {result:?}"
+        );
+    }
+
+    #[test]
+    fn hash_mode_validates_cache_restored_into_another_checkout() {
+        let tmp = tempfile::tempdir().unwrap();
+        let base = tmp.path().canonicalize().unwrap();
+        let root = base.join("one");
+        fs::create_dir(&root).unwrap();
+        let file = root.join("entry.py");
+        fs::write(&file, "import foo").unwrap();
+        fs::write(root.join("uv.lock"), "version = 1").unwrap();
+
+        let mut graph = ModuleGraph::new();
+        let size = fs::metadata(&file).unwrap().len();
+        graph.add_module(file.clone(), size, None);
+
+        let mut cache = ParseCache::new();
+        cache.set_mode(CacheMode::Hash);
+        let result = ParseResult {
+            imports: vec![],
+            unresolvable_dynamic: 0,
+            glob_dirs: Vec::new(),
+        };
+        insert_with_stat(
+            &mut cache,
+            file.clone(),
+            result,
+            vec![Some(root.join("lib.py"))],
+        );
+        drop(cache.save(
+            &root,
+            &file,
+            &graph,
+            vec!["foo".into()],
+            0,
+            vec![],
+            ResolutionStats::default(),
+        ));
+
+        // Another checkout at another path: same contents, every mtime different
+        let moved = base.join("two");
+        fs::rename(&root, &moved).unwrap();
+        let file = moved.join("entry.py");
+        let restored = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_000_000);
+        for path in [&file, &moved.join("uv.lock")] {
+            fs::File::options()
+                .write(true)
+                .open(path)
+                .unwrap()
+                .set_modified(restored)
+                .unwrap();
+        }
+
+        // An unchanged lockfile means "foo" is not re-resolved
+        let resolve_fn = |_: &str| -> bool { panic!("sentinels should match by content") };
+        let mut loaded = ParseCache::load(&moved);
+        loaded.set_mode(CacheMode::Hash);
+        let (_, resolved) = loaded.lookup_resolved_unchecked(&file).unwrap();
+        assert_eq!(resolved, [Some(moved.join("lib.py"))]);
+        let result = loaded.try_load_graph(&file, &resolve_fn);
+        let GraphCacheResult::Hit { graph, .. } = result else {
+            panic!("expected Hit, got {result:?}");
+        };
+        assert!(graph.module_id(&file).is_some());
+    }
+
+    #[test]
+    fn rebase_keeps_paths_relative_to_the_root() {
+        let (from, to) = (Path::new("/a/ws/pkg"), Path::new("/b/checkout/pkg"));
+        assert_eq!(
+            rebase(Path::new("/a/ws/pkg/src/x.ts"), from, to),
+            Path::new("/b/checkout/pkg/src/x.ts")
+        );
+        // Hoisted workspace packages sit above the root
+        assert_eq!(
+            rebase(Path::new("/a/ws/node_modules/zod/index.js"), from, to),
+            Path::new("/b/checkout/node_modules/zod/index.js")
+        );
+    }
+
     #[test]
     fn graph_cache_invalidates_when_unresolved_import_resolves() {
         let tmp = tempfile::tempdir().unwrap();
@@ -702,7 +1575,15 @@ mod tests {
         graph.add_module(file.clone(), size, None);
 
         let mut cache = ParseCache::new();
-        drop(cache.save(&root, &file, &graph, vec!["foo".into()], 0, vec![]));
+        drop(cache.save(
+            &root,
+            &file,
+            &graph,
+            vec!["foo".into()],
+            0,
+            vec![],
+            ResolutionStats::default(),
+        ));
 
         let mut loaded = ParseCache::load(&root);
         let resolve_fn = |spec: &str| spec == "foo";
@@ -726,7 +1607,15 @@ mod tests {
         graph.add_module(file_a.clone(), size, None);
 
         let mut cache = ParseCache::new();
-        drop(cache.save(&root, &file_a, &graph, vec![], 0, vec![]));
+        drop(cache.save(
+            &root,
+            &file_a,
+            &graph,
+            vec![],
+            0,
+            vec![],
+            ResolutionStats::default(),
+        ));
 
         let mut loaded = ParseCache::load(&root);
         let resolve_fn = |_: &str| false;
@@ -748,7 +1637,15 @@ mod tests {
         graph.add_module(file.clone(), size, None);
 
         let mut cache = ParseCache::new();
-        drop(cache.save(&root, &file, &graph, vec![], 0, vec![]));
+        drop(cache.save(
+            &root,
+            &file,
+            &graph,
+            vec![],
+            0,
+            vec![],
+            ResolutionStats::default(),
+        ));
 
         // Modify the file — bump mtime by 2s to guarantee a different
         // timestamp on filesystems with coarse granularity (e.g. ext4 on CI).
@@ -767,7 +1664,15 @@ mod tests {
         } = result
         {
             // Incremental save with updated mtimes
-            drop(loaded.save_incremental(&root, &file, &graph, &changed_files, 0, vec![]));
+            drop(loaded.save_incremental(
+                &root,
+                &file,
+                &graph,
+                &changed_files,
+                0,
+                vec![],
+                ResolutionStats::default(),
+            ));
 
             // Reload — should now be a Hit
             let mut reloaded = ParseCache::load(&root);
@@ -796,7 +1701,15 @@ mod tests {
 
         let dynamic_files = vec![(file_a.clone(), 3), (file_b.clone(), 2)];
         let mut cache = ParseCache::new();
-        drop(cache.save(&root, &file_a, &graph, vec![], 5, dynamic_files));
+        drop(cache.save(
+            &root,
+            &file_a,
+            &graph,
+            vec![],
+            5,
+            dynamic_files,
+            ResolutionStats::default(),
+        ));
 
         // Modify one file to trigger Stale
         std::thread::sleep(std::time::Duration::from_millis(50));
@@ -824,6 +1737,7 @@ mod tests {
                 &changed_files,
                 5,
                 unresolvable_dynamic_files,
+                ResolutionStats::default(),
             ));
 
             // Reload — Hit should have per-file data intact
@@ -865,7 +1779,15 @@ mod tests {
 
         // Save with package root — should find pnpm-lock.yaml via walk-up
         let mut cache = ParseCache::new();
-        drop(cache.save(&pkg, &file, &graph, vec![], 0, vec![]));
+        drop(cache.save(
+            &pkg,
+            &file,
+            &graph,
+            vec![],
+            0,
+            vec![],
+            ResolutionStats::default(),
+        ));
 
         // First load: sentinels should be present → no resave needed
         let mut loaded = ParseCache::load(&pkg);
@@ -878,4 +1800,169 @@ mod tests {
             other => panic!("expected Hit, got {other:?}"),
         }
     }
+
+    #[test]
+    fn custom_cache_path_round_trip() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap().join("project");
+        fs::create_dir_all(&root).unwrap();
+        let file = root.join("entry.py");
+        fs::write(&file, "x = 1").unwrap();
+        let cache_file = tmp.path().join("elsewhere").join("graph.cache");
+        fs::create_dir_all(cache_file.parent().unwrap()).unwrap();
+
+        let mut graph = ModuleGraph::new();
+        let size = fs::metadata(&file).unwrap().len();
+        graph.add_module(file.clone(), size, None);
+
+        let mut cache = ParseCache::new_at(&cache_file);
+        drop(cache.save(
+            &root,
+            &file,
+            &graph,
+            vec![],
+            0,
+            vec![],
+            ResolutionStats::default(),
+        ));
+
+        assert!(cache_file.exists());
+        assert!(
+            !cache_path(&root).exists(),
+            "default location left untouched"
+        );
+
+        let mut loaded = ParseCache::load_from(&cache_file, &root);
+        let resolve_fn = |_: &str| false;
+        let result = loaded.try_load_graph(&file, &resolve_fn);
+        assert!(matches!(result, GraphCacheResult::Hit { .. }));
+    }
+
+    #[test]
+    fn save_creates_missing_cache_dir() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        let file = root.join("entry.py");
+        fs::write(&file, "x = 1").unwrap();
+        let location = CacheLocation::Dir(root.join("ci-cache/chainsaw"));
+        let cache_file = location.path_for(&root);
+        assert!(!cache_file.parent().unwrap().exists());
+
+        let mut graph = ModuleGraph::new();
+        let size = fs::metadata(&file).unwrap().len();
+        graph.add_module(file.clone(), size, None);
+        let mut cache = ParseCache::new_at(&cache_file);
+        drop(cache.save(
+            &root,
+            &file,
+            &graph,
+            vec![],
+            0,
+            vec![],
+            ResolutionStats::default(),
+        ));
+
+        assert!(cache_file.exists());
+    }
+
+    #[test]
+    fn cache_dir_location_is_per_root() {
+        let dir = PathBuf::from("/tmp/caches");
+        let loc = CacheLocation::Dir(dir.clone());
+        let a = loc.path_for(Path::new("/work/a"));
+        let b = loc.path_for(Path::new("/work/b"));
+        assert!(a.starts_with(&dir));
+        assert_ne!(a, b);
+        assert_eq!(a, loc.path_for(Path::new("/work/a")));
+    }
+
+    #[test]
+    fn changed_defines_invalidate_cache() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        let file = root.join("entry.js");
+        fs::write(&file, "x = 1").unwrap();
+
+        let mut graph = ModuleGraph::new();
+        let size = fs::metadata(&file).unwrap().len();
+        graph.add_module(file.clone(), size, None);
+
+        let prod = vec![("NODE_ENV".to_string(), "production".to_string())];
+        let mut cache = ParseCache::new();
+        cache.set_defines(&prod);
+        drop(cache.save(
+            &root,
+            &file,
+            &graph,
+            vec![],
+            0,
+            vec![],
+            ResolutionStats::default(),
+        ));
+
+        let resolve_fn = |_: &str| false;
+        let mut same = ParseCache::load(&root);
+        same.set_defines(&prod);
+        assert!(matches!(
+            same.try_load_graph(&file, &resolve_fn),
+            GraphCacheResult::Hit { .. }
+        ));
+
+        let mut other = ParseCache::load(&root);
+        other.set_defines(&[]);
+        assert!(matches!(
+            other.try_load_graph(&file, &resolve_fn),
+            GraphCacheResult::Miss
+        ));
+    }
+
+    #[test]
+    fn gzip_size_is_smaller_for_compressible_file() {
+        let source = "export const value = 'chainsaw';\n".repeat(200);
+        let gzip = gzip_size(source.as_bytes());
+        assert!(gzip > 0);
+        assert!(
+            gzip < source.len() as u64 / 10,
+            "{gzip} vs {}",
+            source.len()
+        );
+    }
+
+    #[test]
+    fn compressed_sizes_replace_module_sizes_and_are_cached() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        let file = root.join("big.js");
+        let source = "module.exports = 'chainsaw';\n".repeat(500);
+        fs::write(&file, &source).unwrap();
+        let cache_file = root.join(".chainsaw.cache");
+
+        let mut graph = ModuleGraph::new();
+        graph.add_module(file.clone(), source.len() as u64, None);
+        let mut sizes = CompressedSizeCache::load(&cache_file);
+        let warnings = sizes.apply(&mut graph, &crate::vfs::OsVfs);
+        assert!(warnings.is_empty(), "{warnings:?}");
+        sizes.save();
+        let gzip = graph.modules[0].size_bytes;
+        assert!(gzip < source.len() as u64);
+        assert!(cache_file.with_extension("gzip").exists());
+
+        // A second run reuses the stored size without recompressing
+        let reloaded = CompressedSizeCache::load(&cache_file);
+        assert_eq!(reloaded.entries[&file].gzip_size, gzip);
+        assert!(!reloaded.dirty);
+    }
+
+    #[test]
+    fn unreadable_file_weighs_zero_with_a_warning() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        let mut graph = ModuleGraph::new();
+        graph.add_module(root.join("gone.js"), 4096, None);
+        let mut sizes = CompressedSizeCache::new_at(&root.join(".chainsaw.cache"));
+        let warnings = sizes.apply(&mut graph, &crate::vfs::OsVfs);
+        assert_eq!(graph.modules[0].size_bytes, 0);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("gone.js"), "{}", warnings[0]);
+    }
 }
@@ -8,7 +8,7 @@ use std::path::PathBuf;
 pub enum Error {
     /// Entry point file not found on disk.
     EntryNotFound(PathBuf, std::io::Error),
-    /// Entry point path refers to a directory, not a file.
+    /// Entry point is a directory with no index file or package.json `main`.
     EntryIsDirectory(PathBuf),
     /// File has an unsupported or missing extension.
     UnsupportedFileType(Option<String>),
@@ -18,11 +18,13 @@ pub enum Error {
     SnapshotRead(PathBuf, std::io::Error),
     /// Snapshot file contains invalid JSON.
     SnapshotParse(PathBuf, serde_json::Error),
+    /// Snapshot predates module lists, so it cannot be diffed module by module.
+    SnapshotWithoutModules(PathBuf),
     /// Cannot write a snapshot file to disk.
     SnapshotWrite(PathBuf, std::io::Error),
     /// Mutually exclusive CLI flags were used together.
     MutuallyExclusiveFlags(String),
-    /// --chain/--cut target is the entry point itself.
+    /// --chain/--cut/--without target is the entry point itself.
     TargetIsEntryPoint(String),
     /// --entry is required when comparing git refs.
     EntryRequired,
@@ -38,6 +40,20 @@ pub enum Error {
     InvalidTopValue(&'static str, i32),
     /// Readline/REPL initialization failed.
     Readline(String),
+    /// --resolver-script does not point at a file.
+    ResolverScriptNotFound(PathBuf),
+    /// Cannot read the --cycle-allowlist file.
+    CycleAllowlistRead(PathBuf, std::io::Error),
+    /// --aggregate-monorepo found no workspaces config above the path.
+    NoWorkspaces(PathBuf),
+    /// --diff-cache found no cached graph for the entry.
+    NoCachedGraph(PathBuf),
+    /// An --exclude pattern is not a valid glob.
+    InvalidExclude(globset::Error),
+    /// `prune-cache` found no cache file, or could not rewrite it.
+    CacheIo(PathBuf, std::io::Error),
+    /// `--watch` could not start the file watcher.
+    Watch(notify::Error),
 }
 
 impl Error {
@@ -48,15 +64,22 @@ impl Error {
                 "chainsaw supports TypeScript/JavaScript (.ts, .tsx, .js, .jsx, .mjs, .cjs) and Python (.py) files",
             ),
             Self::EntryNotInGraph(_) => Some("is it reachable from the project root?"),
-            Self::TargetIsEntryPoint(flag) => Some(if flag == "--chain" {
-                "--chain finds import chains from the entry to a dependency"
-            } else {
-                "--cut finds where to sever import chains to a dependency"
+            Self::TargetIsEntryPoint(flag) => Some(match flag.as_str() {
+                "--chain" => "--chain finds import chains from the entry to a dependency",
+                "--without" => "--without detaches a module imported by the entry",
+                _ => "--cut finds where to sever import chains to a dependency",
             }),
             Self::EntryRequired => Some("use --entry to specify the entry point to trace"),
             Self::EntryIsDirectory(_) => {
                 Some("provide a source file (e.g. src/index.ts or main.py)")
             }
+            Self::NoWorkspaces(_) => {
+                Some("add a `workspaces` field to package.json or a pnpm-workspace.yaml")
+            }
+            Self::NoCachedGraph(_) => Some("run `chainsaw trace` once to create the cache"),
+            Self::CacheIo(_, e) if e.kind() == std::io::ErrorKind::NotFound => {
+                Some("run `chainsaw trace` once to create the cache")
+            }
             _ => None,
         }
     }
@@ -71,7 +94,11 @@ impl std::fmt::Display for Error {
                 write!(f, "cannot find entry file '{}': {source}", path.display())
             }
             Self::EntryIsDirectory(path) => {
-                write!(f, "'{}' is a directory, not a source file", path.display())
+                write!(
+                    f,
+                    "'{}' is a directory with no index file or package.json main",
+                    path.display()
+                )
             }
             Self::UnsupportedFileType(Some(ext)) => {
                 write!(f, "unsupported file type '.{ext}'")
@@ -88,6 +115,13 @@ impl std::fmt::Display for Error {
             Self::SnapshotParse(path, source) => {
                 write!(f, "invalid snapshot '{}': {source}", path.display())
             }
+            Self::SnapshotWithoutModules(path) => {
+                write!(
+                    f,
+                    "snapshot '{}' has no module list; re-save it with `chainsaw trace --save`",
+                    path.display()
+                )
+            }
             Self::SnapshotWrite(path, source) => {
                 write!(f, "cannot write snapshot '{}': {source}", path.display())
             }
@@ -113,6 +147,31 @@ impl std::fmt::Display for Error {
                 write!(f, "invalid value {n} for {flag}: must be -1 (all) or 0+")
             }
             Self::Readline(msg) => write!(f, "readline: {msg}"),
+            Self::ResolverScriptNotFound(path) => {
+                write!(f, "resolver script '{}' not found", path.display())
+            }
+            Self::CycleAllowlistRead(path, source) => {
+                write!(
+                    f,
+                    "cannot read cycle allowlist '{}': {source}",
+                    path.display()
+                )
+            }
+            Self::NoWorkspaces(path) => {
+                write!(
+                    f,
+                    "no workspaces config found at or above '{}'",
+                    path.display()
+                )
+            }
+            Self::NoCachedGraph(entry) => {
+                write!(f, "no cached graph for '{}'", entry.display())
+            }
+            Self::InvalidExclude(source) => write!(f, "invalid --exclude pattern: {source}"),
+            Self::CacheIo(path, source) => {
+                write!(f, "cannot prune cache '{}': {source}", path.display())
+            }
+            Self::Watch(source) => write!(f, "cannot watch for changes: {source}"),
         }
     }
 }
@@ -121,10 +180,14 @@ impl std::fmt::Display for Error {
 impl std::error::Error for Error {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
-            Self::EntryNotFound(_, e) | Self::SnapshotRead(_, e) | Self::SnapshotWrite(_, e) => {
-                Some(e)
-            }
+            Self::EntryNotFound(_, e)
+            | Self::SnapshotRead(_, e)
+            | Self::SnapshotWrite(_, e)
+            | Self::CycleAllowlistRead(_, e)
+            | Self::CacheIo(_, e) => Some(e),
             Self::SnapshotParse(_, e) => Some(e),
+            Self::InvalidExclude(e) => Some(e),
+            Self::Watch(e) => Some(e),
             _ => None,
         }
     }
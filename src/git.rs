@@ -320,18 +320,22 @@ mod tests {
             include_dynamic: false,
             top_n: 0,
             ignore: vec![],
+            dynamic_depth: None,
+            exclude: None,
+            count_type_only: false,
+            max_package_depth: None,
         };
 
         let vfs1 = Arc::new(crate::vfs::GitTreeVfs::new(dir, &sha1, dir).unwrap());
         let (loaded1, _cw1) =
             crate::loader::load_graph_with_vfs(&dir.join(entry), true, vfs1).unwrap();
-        let eid1 = *loaded1.graph.path_to_id.get(&loaded1.entry).unwrap();
+        let eid1 = loaded1.graph.module_id(&loaded1.entry).unwrap();
         let snap1 = crate::query::trace(&loaded1.graph, eid1, &opts).to_snapshot("v1");
 
         let vfs2 = Arc::new(crate::vfs::GitTreeVfs::new(dir, &sha2, dir).unwrap());
         let (loaded2, _cw2) =
             crate::loader::load_graph_with_vfs(&dir.join(entry), true, vfs2).unwrap();
-        let eid2 = *loaded2.graph.path_to_id.get(&loaded2.entry).unwrap();
+        let eid2 = loaded2.graph.module_id(&loaded2.entry).unwrap();
         let snap2 = crate::query::trace(&loaded2.graph, eid2, &opts).to_snapshot("v2");
 
         // Diff: v2 should be heavier than v1 (added b.ts)
@@ -2,11 +2,16 @@
 //!
 //! A [`ModuleGraph`] is a directed graph of source files (modules) connected by
 //! import edges. Nodes are dense `u32`-indexed [`ModuleId`]s, edges carry an
-//! [`EdgeKind`] distinguishing static, dynamic, and type-only imports.
+//! [`EdgeKind`] distinguishing static, dynamic, type-only, and asset imports.
 
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, VecDeque};
-use std::path::PathBuf;
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+
+/// Out-degree past which [`ModuleGraph::add_edge`] dedups through a hash
+/// index instead of scanning the module's outgoing edges.
+const EDGE_SCAN_LIMIT: usize = 32;
 
 /// Dense index into [`ModuleGraph::modules`].
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -28,6 +33,9 @@ pub enum EdgeKind {
     Dynamic,
     /// TypeScript `import type` or Python import inside `if TYPE_CHECKING`.
     TypeOnly,
+    /// Static import of a non-code asset (stylesheet, JSON, WASM, image).
+    /// Loads bytes but no code, so it is not counted toward code weight.
+    Asset,
 }
 
 /// A single source file in the dependency graph.
@@ -39,6 +47,9 @@ pub struct Module {
     pub size_bytes: u64,
     /// None for source files, Some("package-name") for `node_modules`
     pub package: Option<String>,
+    /// Number of lines in the file. 0 for files that are not parsed
+    /// (assets, JSON). Misleading for minified `node_modules` code.
+    pub line_count: u32,
 }
 
 /// A directed import edge between two modules.
@@ -70,8 +81,21 @@ pub struct ModuleGraph {
     pub edges: Vec<Edge>,
     /// Outgoing edges per module (indexed by `ModuleId`)
     pub forward_adj: Vec<Vec<EdgeId>>,
+    /// Keyed by [`path_key`](Self::path_key); look up with
+    /// [`module_id`](Self::module_id).
     pub path_to_id: HashMap<PathBuf, ModuleId>,
     pub package_map: HashMap<String, PackageInfo>,
+    /// Modules the graph was built from, in the order they were marked.
+    pub entries: Vec<ModuleId>,
+    /// Match paths case-insensitively, so `./Foo` and `./foo` on a
+    /// case-insensitive filesystem are one module. Modules keep the casing
+    /// they were first added with.
+    pub fold_case: bool,
+    /// `(to, kind)` -> edge for modules with more than `EDGE_SCAN_LIMIT`
+    /// outgoing edges, built on demand so a generated index re-exporting
+    /// thousands of files dedups in O(1) per edge.
+    #[serde(skip)]
+    wide_edges: HashMap<ModuleId, HashMap<(ModuleId, EdgeKind), EdgeId>>,
 }
 
 impl Default for ModuleGraph {
@@ -81,16 +105,40 @@ impl Default for ModuleGraph {
 }
 
 impl ModuleGraph {
+    /// An empty graph that matches paths exactly.
     pub fn new() -> Self {
+        Self::with_case_folding(false)
+    }
+
+    /// An empty graph that matches paths case-insensitively when
+    /// `fold_case` is set (`--case-insensitive-paths`).
+    pub fn with_case_folding(fold_case: bool) -> Self {
         Self {
             modules: Vec::new(),
             edges: Vec::new(),
             forward_adj: Vec::new(),
             path_to_id: HashMap::new(),
             package_map: HashMap::new(),
+            entries: Vec::new(),
+            fold_case,
+            wide_edges: HashMap::new(),
         }
     }
 
+    /// The `path_to_id` key for `path`: with ASCII letters lowercased when
+    /// folding case. Non-UTF-8 paths are folded as they are, not mangled.
+    pub fn path_key<'a>(&self, path: &'a Path) -> Cow<'a, Path> {
+        if self.fold_case {
+            Cow::Owned(PathBuf::from(path.as_os_str().to_ascii_lowercase()))
+        } else {
+            Cow::Borrowed(path)
+        }
+    }
+
+    pub fn module_id(&self, path: &Path) -> Option<ModuleId> {
+        self.path_to_id.get(self.path_key(path).as_ref()).copied()
+    }
+
     #[allow(clippy::cast_possible_truncation)]
     pub fn add_module(
         &mut self,
@@ -98,18 +146,20 @@ impl ModuleGraph {
         size_bytes: u64,
         package: Option<String>,
     ) -> ModuleId {
-        if let Some(&id) = self.path_to_id.get(&path) {
+        if let Some(id) = self.module_id(&path) {
             return id;
         }
         let id = ModuleId(self.modules.len() as u32);
+        let key = self.path_key(&path).into_owned();
         self.modules.push(Module {
             id,
-            path: path.clone(),
+            path,
             size_bytes,
             package,
+            line_count: 0,
         });
         self.forward_adj.push(Vec::new());
-        self.path_to_id.insert(path, id);
+        self.path_to_id.insert(key, id);
         id
     }
 
@@ -121,14 +171,33 @@ impl ModuleGraph {
         kind: EdgeKind,
         specifier: &str,
     ) -> EdgeId {
-        // Deduplicate by (from, to, kind) — scan outgoing edges (typically <30)
-        if let Some(&existing) = self.forward_adj[from.0 as usize].iter().find(|&&eid| {
-            let e = &self.edges[eid.0 as usize];
-            e.to == to && e.kind == kind
-        }) {
-            return existing;
-        }
+        // Deduplicate by (from, to, kind) — scan outgoing edges (typically <30),
+        // or look up the hash index once the module fans out wider than that
+        let outgoing = &self.forward_adj[from.0 as usize];
         let id = EdgeId(self.edges.len() as u32);
+        if outgoing.len() < EDGE_SCAN_LIMIT {
+            if let Some(&existing) = outgoing.iter().find(|&&eid| {
+                let e = &self.edges[eid.0 as usize];
+                e.to == to && e.kind == kind
+            }) {
+                return existing;
+            }
+        } else {
+            let edges = &self.edges;
+            let index = self.wide_edges.entry(from).or_insert_with(|| {
+                outgoing
+                    .iter()
+                    .map(|&eid| {
+                        let e = &edges[eid.0 as usize];
+                        ((e.to, e.kind), eid)
+                    })
+                    .collect()
+            });
+            if let Some(&existing) = index.get(&(to, kind)) {
+                return existing;
+            }
+            index.insert((to, kind), id);
+        }
         self.edges.push(Edge {
             id,
             from,
@@ -140,6 +209,61 @@ impl ModuleGraph {
         id
     }
 
+    /// Replace the outgoing edges of `from` with `imports`, deduplicated like
+    /// [`add_edge`](Self::add_edge), keeping `reverse_adj` (incoming edges
+    /// per module) in step. The old edges are swap-removed, so the last
+    /// edges may move to the ids they free.
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn replace_edges(
+        &mut self,
+        from: ModuleId,
+        imports: &[(ModuleId, EdgeKind, &str)],
+        reverse_adj: &mut [Vec<EdgeId>],
+    ) {
+        let mut old = std::mem::take(&mut self.forward_adj[from.0 as usize]);
+        self.wide_edges.remove(&from);
+        // Highest first, so an edge moved into a freed slot is never one
+        // still to be removed
+        old.sort_unstable_by(|a, b| b.0.cmp(&a.0));
+        for freed in old {
+            let removed = self.edges.swap_remove(freed.0 as usize);
+            reverse_adj[removed.to.0 as usize].retain(|&eid| eid != freed);
+            let Some(moved) = self.edges.get_mut(freed.0 as usize) else {
+                continue;
+            };
+            let last = std::mem::replace(&mut moved.id, freed);
+            let (moved_from, moved_to, moved_kind) = (moved.from, moved.to, moved.kind);
+            for eid in self.forward_adj[moved_from.0 as usize]
+                .iter_mut()
+                .chain(&mut reverse_adj[moved_to.0 as usize])
+                .filter(|eid| **eid == last)
+            {
+                *eid = freed;
+            }
+            if let Some(index) = self.wide_edges.get_mut(&moved_from) {
+                index.insert((moved_to, moved_kind), freed);
+            }
+        }
+        for &(to, kind, specifier) in imports {
+            let count = self.edges.len();
+            let id = self.add_edge(from, to, kind, specifier);
+            if self.edges.len() > count {
+                reverse_adj[to.0 as usize].push(id);
+            }
+        }
+    }
+
+    /// Record `id` as an entry point. Marking the same module twice is a no-op.
+    pub fn mark_entry(&mut self, id: ModuleId) {
+        if !self.entries.contains(&id) {
+            self.entries.push(id);
+        }
+    }
+
+    pub fn is_entry(&self, id: ModuleId) -> bool {
+        self.entries.contains(&id)
+    }
+
     pub fn module(&self, id: ModuleId) -> &Module {
         &self.modules[id.0 as usize]
     }
@@ -156,12 +280,165 @@ impl ModuleGraph {
         self.modules.len()
     }
 
+    /// Union `other` into this graph. Modules are matched by path, so files
+    /// shared between the two graphs are not duplicated; edges are remapped
+    /// and deduplicated by `(from, to, kind)` like [`add_edge`](Self::add_edge).
+    ///
+    /// Returns the mapping from `other`'s module IDs to IDs in `self`.
+    /// `other`'s entries become entries of `self`. Package info is
+    /// recomputed if either graph had it.
+    pub fn merge(&mut self, other: &ModuleGraph) -> Vec<ModuleId> {
+        let remap: Vec<ModuleId> = other
+            .modules
+            .iter()
+            .map(|m| {
+                let is_new = self.module_id(&m.path).is_none();
+                let id = self.add_module(m.path.clone(), m.size_bytes, m.package.clone());
+                if is_new {
+                    self.modules[id.0 as usize].line_count = m.line_count;
+                }
+                id
+            })
+            .collect();
+        for edge in &other.edges {
+            self.add_edge(
+                remap[edge.from.0 as usize],
+                remap[edge.to.0 as usize],
+                edge.kind,
+                &edge.specifier,
+            );
+        }
+        for &id in &other.entries {
+            self.mark_entry(remap[id.0 as usize]);
+        }
+        if !self.package_map.is_empty() || !other.package_map.is_empty() {
+            self.package_map.clear();
+            self.compute_package_info();
+        }
+        remap
+    }
+
+    /// Modules reachable from `roots` (inclusive) through edges accepted by
+    /// `follow`, as a mask indexed by `ModuleId`. Each module is visited
+    /// once, so import cycles such as barrels re-exporting each other are
+    /// neither followed forever nor counted twice.
+    pub fn reachable_set(&self, roots: &[ModuleId], follow: impl Fn(&Edge) -> bool) -> Vec<bool> {
+        let mut visited = vec![false; self.modules.len()];
+        let mut queue = VecDeque::new();
+        for &id in roots {
+            if !visited[id.0 as usize] {
+                visited[id.0 as usize] = true;
+                queue.push_back(id);
+            }
+        }
+        while let Some(mid) = queue.pop_front() {
+            for &edge_id in &self.forward_adj[mid.0 as usize] {
+                let edge = &self.edges[edge_id.0 as usize];
+                if !visited[edge.to.0 as usize] && follow(edge) {
+                    visited[edge.to.0 as usize] = true;
+                    queue.push_back(edge.to);
+                }
+            }
+        }
+        visited
+    }
+
     /// Compute aggregated package info (total reachable size + file count).
     /// For each package, BFS from its entry module following only edges within the same package.
+    /// When the graph has [`entries`](Self::entries), only packages reachable
+    /// from one of them are included.
     pub fn compute_package_info(&mut self) {
+        self.compute_package_info_for(None, None);
+    }
+
+    /// Recompute package info after the sizes of `changed` modules changed
+    /// (edges unchanged). A package's info covers only its own files, so
+    /// only the packages owning a changed module are recomputed.
+    pub fn update_package_info(&mut self, changed: &[ModuleId]) {
+        let affected: HashSet<String> = changed
+            .iter()
+            .filter_map(|mid| self.modules[mid.0 as usize].package.clone())
+            .collect();
+        if !affected.is_empty() {
+            self.compute_package_info_for(Some(&affected), None);
+        }
+    }
+
+    /// Recompute the info of `packages` after their modules were reached
+    /// or left behind, or their edges changed. `reached` marks the modules
+    /// reachable from the [`entries`](Self::entries), as the caller tracks
+    /// it. Packages no longer reached are dropped.
+    pub fn recompute_packages(&mut self, packages: &HashSet<String>, reached: &[bool]) {
+        for name in packages {
+            self.package_map.remove(name);
+        }
+        self.compute_package_info_for(Some(packages), Some(reached));
+    }
+
+    /// The other packages `package` statically pulls in, with the weight
+    /// reached in each, heaviest first. Costs a walk of the package's
+    /// closure, so it is computed on demand (`packages --with-deps`)
+    /// rather than kept in [`PackageInfo`].
+    pub fn transitive_dependencies(&self, package: &str) -> Vec<(String, u64)> {
+        let from_entries =
+            (!self.entries.is_empty()).then(|| self.reachable_set(&self.entries, |_| true));
+        let roots: Vec<ModuleId> = self
+            .modules
+            .iter()
+            .filter(|m| {
+                m.package.as_deref() == Some(package)
+                    && from_entries
+                        .as_ref()
+                        .is_none_or(|reached| reached[m.id.0 as usize])
+            })
+            .map(|m| m.id)
+            .collect();
+        let closure = self.reachable_set(&roots, |edge| edge.kind == EdgeKind::Static);
+        let mut dep_sizes: HashMap<&str, u64> = HashMap::new();
+        for module in &self.modules {
+            if let Some(dep) = module.package.as_deref()
+                && dep != package
+                && closure[module.id.0 as usize]
+            {
+                *dep_sizes.entry(dep).or_default() += module.size_bytes;
+            }
+        }
+        let mut deps: Vec<(String, u64)> = dep_sizes
+            .into_iter()
+            .map(|(name, size)| (name.to_string(), size))
+            .collect();
+        deps.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        deps
+    }
+
+    /// [`compute_package_info`](Self::compute_package_info), limited to the
+    /// packages in `only` when given. `reached` saves the walk from the
+    /// entries when the caller already has it.
+    fn compute_package_info_for(
+        &mut self,
+        only: Option<&HashSet<String>>,
+        reached: Option<&[bool]>,
+    ) {
+        let walked;
+        let from_entries = match reached {
+            Some(reached) => Some(reached),
+            None if self.entries.is_empty() => None,
+            None => {
+                walked = self.reachable_set(&self.entries, |_| true);
+                Some(walked.as_slice())
+            }
+        };
         let mut package_entries: HashMap<String, Vec<ModuleId>> = HashMap::new();
         for module in &self.modules {
-            if let Some(ref pkg) = module.package {
+            if from_entries
+                .as_ref()
+                .is_some_and(|reached| !reached[module.id.0 as usize])
+            {
+                continue;
+            }
+            if let Some(ref pkg) = module.package
+                && only.is_none_or(|only| only.contains(pkg))
+            {
                 package_entries
                     .entry(pkg.clone())
                     .or_default()
@@ -169,36 +446,19 @@ impl ModuleGraph {
             }
         }
 
-        let num_modules = self.modules.len();
         for (pkg_name, module_ids) in package_entries {
+            let reachable = self.reachable_set(&module_ids, |edge| {
+                edge.kind == EdgeKind::Static
+                    && self.modules[edge.to.0 as usize].package.as_deref()
+                        == Some(pkg_name.as_str())
+            });
             let mut total_size: u64 = 0;
             let mut total_files: u32 = 0;
-            let mut visited = vec![false; num_modules];
-
-            let mut queue: VecDeque<ModuleId> = module_ids.iter().copied().collect();
-            for &id in &module_ids {
-                visited[id.0 as usize] = true;
-            }
-
-            while let Some(mid) = queue.pop_front() {
-                let module = &self.modules[mid.0 as usize];
-                if module.package.as_deref() == Some(pkg_name.as_str()) {
+            for module in &self.modules {
+                if reachable[module.id.0 as usize] {
                     total_size += module.size_bytes;
                     total_files += 1;
                 }
-
-                for &edge_id in &self.forward_adj[mid.0 as usize] {
-                    let edge = &self.edges[edge_id.0 as usize];
-                    if edge.kind == EdgeKind::Static {
-                        let target = &self.modules[edge.to.0 as usize];
-                        if target.package.as_deref() == Some(pkg_name.as_str())
-                            && !visited[edge.to.0 as usize]
-                        {
-                            visited[edge.to.0 as usize] = true;
-                            queue.push_back(edge.to);
-                        }
-                    }
-                }
             }
 
             let entry_module = module_ids[0];
@@ -211,6 +471,30 @@ impl ModuleGraph {
             self.package_map.insert(pkg_name, info);
         }
     }
+
+    /// The type-dependency graph: the same modules, keeping only
+    /// [`EdgeKind::TypeOnly`] edges. They are recast as `Static` so every
+    /// query follows them as if types were loaded at runtime.
+    #[must_use]
+    pub fn type_graph(&self) -> ModuleGraph {
+        let mut graph = ModuleGraph {
+            modules: self.modules.clone(),
+            edges: Vec::new(),
+            forward_adj: vec![Vec::new(); self.modules.len()],
+            path_to_id: self.path_to_id.clone(),
+            package_map: HashMap::new(),
+            entries: self.entries.clone(),
+            fold_case: self.fold_case,
+            wide_edges: HashMap::new(),
+        };
+        for edge in self.edges.iter().filter(|e| e.kind == EdgeKind::TypeOnly) {
+            graph.add_edge(edge.from, edge.to, EdgeKind::Static, &edge.specifier);
+        }
+        if !self.package_map.is_empty() {
+            graph.compute_package_info();
+        }
+        graph
+    }
 }
 
 #[cfg(test)]
@@ -233,6 +517,33 @@ mod tests {
         assert_eq!(g.forward_adj[a.0 as usize].len(), 1);
     }
 
+    #[test]
+    fn add_edge_deduplicates_wide_fan_out() {
+        let mut g = ModuleGraph::new();
+        let index = g.add_module("index.ts".into(), 100, None);
+        let targets: Vec<ModuleId> = (0..5000)
+            .map(|i| g.add_module(format!("m{i}.ts").into(), 10, None))
+            .collect();
+        let first: Vec<EdgeId> = targets
+            .iter()
+            .map(|&t| g.add_edge(index, t, EdgeKind::Static, "./m"))
+            .collect();
+        for (&t, &id) in targets.iter().zip(&first) {
+            assert_eq!(g.add_edge(index, t, EdgeKind::Static, "./again"), id);
+        }
+        assert_eq!(g.outgoing_edges(index).len(), 5000);
+
+        // The index is not serialized; a reloaded graph rebuilds it
+        let mut reloaded: ModuleGraph =
+            serde_json::from_str(&serde_json::to_string(&g).unwrap()).unwrap();
+        assert_eq!(
+            reloaded.add_edge(index, targets[4999], EdgeKind::Static, "./m"),
+            first[4999]
+        );
+        reloaded.add_edge(index, targets[0], EdgeKind::Dynamic, "./m");
+        assert_eq!(reloaded.outgoing_edges(index).len(), 5001);
+    }
+
     #[test]
     fn add_edge_allows_different_kinds() {
         let mut g = ModuleGraph::new();
@@ -249,4 +560,248 @@ mod tests {
             "different edge kinds should not be deduped"
         );
     }
+
+    #[test]
+    fn add_edge_keeps_asset_separate_from_static() {
+        let mut g = ModuleGraph::new();
+        let a = g.add_module("a.ts".into(), 100, None);
+        let css = g.add_module("a.css".into(), 50, None);
+
+        g.add_edge(a, css, EdgeKind::Asset, "./a.css");
+        g.add_edge(a, css, EdgeKind::Asset, "./a.css");
+        g.add_edge(a, css, EdgeKind::Static, "./a.css");
+
+        assert_eq!(g.edges.len(), 2, "asset edges dedupe among themselves only");
+        assert_eq!(g.edges[0].kind, EdgeKind::Asset);
+    }
+
+    #[test]
+    fn replace_edges_keeps_ids_and_reverse_adj_in_step() {
+        let mut g = ModuleGraph::new();
+        let a = g.add_module("a.ts".into(), 1, None);
+        let b = g.add_module("b.ts".into(), 1, None);
+        let c = g.add_module("c.ts".into(), 1, None);
+        g.add_edge(a, b, EdgeKind::Static, "./b");
+        g.add_edge(a, c, EdgeKind::Dynamic, "./c");
+        g.add_edge(b, c, EdgeKind::Static, "./c");
+        let mut rev = vec![Vec::new(); g.module_count()];
+        for edge in &g.edges {
+            rev[edge.to.0 as usize].push(edge.id);
+        }
+
+        g.replace_edges(a, &[(c, EdgeKind::Static, "./c")], &mut rev);
+
+        assert_eq!(g.edges.len(), 2);
+        for (i, edge) in g.edges.iter().enumerate() {
+            assert_eq!(edge.id, EdgeId(u32::try_from(i).unwrap()));
+            assert!(g.outgoing_edges(edge.from).contains(&edge.id));
+            assert!(rev[edge.to.0 as usize].contains(&edge.id));
+        }
+        let targets: Vec<ModuleId> = g.outgoing_edges(a).iter().map(|&e| g.edge(e).to).collect();
+        assert_eq!(targets, vec![c]);
+        assert!(rev[b.0 as usize].is_empty());
+        assert_eq!(rev[c.0 as usize].len(), 2);
+    }
+
+    #[test]
+    fn package_info_only_covers_packages_reachable_from_entries() {
+        let mut g = ModuleGraph::new();
+        let entry = g.add_module("index.ts".into(), 10, None);
+        let used = g.add_module(
+            "node_modules/used/index.js".into(),
+            100,
+            Some("used".into()),
+        );
+        g.add_module(
+            "node_modules/stray/index.js".into(),
+            200,
+            Some("stray".into()),
+        );
+        g.add_edge(entry, used, EdgeKind::Dynamic, "used");
+        g.mark_entry(entry);
+        g.mark_entry(entry);
+        g.compute_package_info();
+
+        assert_eq!(g.entries, vec![entry]);
+        assert!(g.package_map.contains_key("used"));
+        assert!(!g.package_map.contains_key("stray"));
+    }
+
+    #[test]
+    fn update_package_info_recomputes_only_affected_packages() {
+        let mut g = ModuleGraph::new();
+        let entry = g.add_module("index.ts".into(), 10, None);
+        let util = g.add_module("util.ts".into(), 10, None);
+        let a = g.add_module("node_modules/a/index.js".into(), 100, Some("a".into()));
+        let a_lib = g.add_module("node_modules/a/lib.js".into(), 50, Some("a".into()));
+        let b = g.add_module("node_modules/b/index.js".into(), 200, Some("b".into()));
+        let c = g.add_module("node_modules/c/index.js".into(), 300, Some("c".into()));
+        g.add_edge(entry, util, EdgeKind::Static, "./util");
+        g.add_edge(entry, a, EdgeKind::Static, "a");
+        g.add_edge(a, a_lib, EdgeKind::Static, "./lib");
+        g.add_edge(entry, b, EdgeKind::Static, "b");
+        g.add_edge(b, a, EdgeKind::Static, "a");
+        g.add_edge(entry, c, EdgeKind::Static, "c");
+        g.compute_package_info();
+        assert_eq!(g.package_map["a"].total_reachable_size, 150);
+
+        // Sentinel: a package that is recomputed loses it
+        g.package_map.get_mut("b").unwrap().total_reachable_files = 99;
+        g.package_map.get_mut("c").unwrap().total_reachable_files = 99;
+
+        // Editing app code touches no package
+        g.modules[util.0 as usize].size_bytes = 20;
+        g.update_package_info(&[util]);
+        assert_eq!(g.package_map["c"].total_reachable_files, 99);
+
+        // a/lib.js grows: only a changes; b pulls a in, but its own files
+        // are untouched and its dependencies are computed on demand
+        g.modules[a_lib.0 as usize].size_bytes = 80;
+        g.update_package_info(&[a_lib]);
+        assert_eq!(g.package_map["a"].total_reachable_size, 180);
+        assert_eq!(g.package_map["b"].total_reachable_files, 99);
+        assert_eq!(g.package_map["c"].total_reachable_files, 99);
+        assert_eq!(g.transitive_dependencies("b"), vec![("a".to_string(), 180)]);
+    }
+
+    #[test]
+    fn merge_unions_modules_by_path() {
+        let mut g1 = ModuleGraph::new();
+        let a = g1.add_module("a.ts".into(), 100, None);
+        let shared = g1.add_module("shared.ts".into(), 50, None);
+        g1.add_edge(a, shared, EdgeKind::Static, "./shared");
+
+        let mut g2 = ModuleGraph::new();
+        let b = g2.add_module("b.ts".into(), 200, None);
+        let shared2 = g2.add_module("shared.ts".into(), 50, None);
+        let zod = g2.add_module("node_modules/zod/index.js".into(), 300, Some("zod".into()));
+        g2.add_edge(b, shared2, EdgeKind::Static, "./shared");
+        g2.add_edge(shared2, zod, EdgeKind::Dynamic, "zod");
+        g2.compute_package_info();
+
+        let remap = g1.merge(&g2);
+
+        assert_eq!(g1.module_count(), 4, "shared.ts must not be duplicated");
+        assert_eq!(remap[shared2.0 as usize], shared);
+        let b = remap[b.0 as usize];
+        let zod = remap[zod.0 as usize];
+        assert_eq!(g1.module(b).path, PathBuf::from("b.ts"));
+
+        assert_eq!(g1.edges.len(), 3);
+        let targets = |from: ModuleId| -> Vec<(ModuleId, EdgeKind)> {
+            g1.outgoing_edges(from)
+                .iter()
+                .map(|&e| (g1.edge(e).to, g1.edge(e).kind))
+                .collect()
+        };
+        assert_eq!(targets(a), vec![(shared, EdgeKind::Static)]);
+        assert_eq!(targets(b), vec![(shared, EdgeKind::Static)]);
+        assert_eq!(targets(shared), vec![(zod, EdgeKind::Dynamic)]);
+        assert_eq!(g1.package_map["zod"].entry_module, zod);
+    }
+
+    #[test]
+    fn merge_does_not_duplicate_existing_edges() {
+        let mut g1 = ModuleGraph::new();
+        let a = g1.add_module("a.ts".into(), 100, None);
+        let b = g1.add_module("b.ts".into(), 100, None);
+        g1.add_edge(a, b, EdgeKind::Static, "./b");
+        let copy = g1.clone();
+
+        g1.merge(&copy);
+
+        assert_eq!(g1.module_count(), 2);
+        assert_eq!(g1.edges.len(), 1);
+    }
+
+    #[test]
+    fn package_info_counts_cyclic_barrels_once() {
+        let mut g = ModuleGraph::new();
+        let entry = g.add_module("index.ts".into(), 10, None);
+        let pkg = Some("ui".to_string());
+        let a = g.add_module("node_modules/ui/a/index.ts".into(), 100, pkg.clone());
+        let b = g.add_module("node_modules/ui/b/index.ts".into(), 200, pkg.clone());
+        let leaf = g.add_module("node_modules/ui/b/button.ts".into(), 40, pkg);
+        g.add_edge(entry, a, EdgeKind::Static, "ui");
+        g.add_edge(a, b, EdgeKind::Static, "../b");
+        g.add_edge(b, a, EdgeKind::Static, "../a");
+        g.add_edge(b, leaf, EdgeKind::Static, "./button");
+        g.compute_package_info();
+
+        let info = &g.package_map["ui"];
+        assert_eq!(info.total_reachable_files, 3);
+        assert_eq!(info.total_reachable_size, 340);
+
+        let reachable = g.reachable_set(&[a], |e| e.kind == EdgeKind::Static);
+        assert!(!reachable[entry.0 as usize]);
+        assert_eq!(reachable.iter().filter(|&&r| r).count(), 3);
+    }
+
+    #[test]
+    fn case_folding_collapses_case_differing_paths() {
+        let mut g = ModuleGraph::with_case_folding(true);
+        let entry = g.add_module("src/index.ts".into(), 10, None);
+        let foo = g.add_module("src/Foo.ts".into(), 100, None);
+        let again = g.add_module("src/foo.ts".into(), 100, None);
+        g.add_edge(entry, foo, EdgeKind::Static, "./Foo");
+        g.add_edge(entry, again, EdgeKind::Static, "./foo");
+
+        assert_eq!(foo, again);
+        assert_eq!(g.module_count(), 2);
+        assert_eq!(g.edges.len(), 1);
+        assert_eq!(
+            g.module(foo).path,
+            PathBuf::from("src/Foo.ts"),
+            "display keeps first casing"
+        );
+        assert_eq!(g.module_id(Path::new("SRC/FOO.TS")), Some(foo));
+
+        let mut g = ModuleGraph::with_case_folding(false);
+        let foo = g.add_module("src/Foo.ts".into(), 100, None);
+        let other = g.add_module("src/foo.ts".into(), 100, None);
+        assert_ne!(foo, other);
+        assert_eq!(g.module_id(Path::new("src/FOO.ts")), None);
+        assert!(!ModuleGraph::new().fold_case, "folding is opt-in");
+    }
+
+    #[test]
+    fn package_info_aggregates_transitive_dependencies() {
+        let mut g = ModuleGraph::new();
+        let entry = g.add_module("index.ts".into(), 10, None);
+        let tiny = g.add_module("node_modules/tiny/index.js".into(), 50, Some("tiny".into()));
+        let huge = g.add_module(
+            "node_modules/huge/index.js".into(),
+            9_000,
+            Some("huge".into()),
+        );
+        let huge2 = g.add_module(
+            "node_modules/huge/lib.js".into(),
+            1_000,
+            Some("huge".into()),
+        );
+        let leaf = g.add_module(
+            "node_modules/leaf/index.js".into(),
+            200,
+            Some("leaf".into()),
+        );
+        g.add_edge(entry, tiny, EdgeKind::Static, "tiny");
+        g.add_edge(tiny, huge, EdgeKind::Static, "huge");
+        g.add_edge(huge, huge2, EdgeKind::Static, "./lib");
+        g.add_edge(huge2, leaf, EdgeKind::Static, "leaf");
+        // Lazily loaded deps are not part of the package's load cost
+        let lazy = g.add_module(
+            "node_modules/lazy/index.js".into(),
+            5_000,
+            Some("lazy".into()),
+        );
+        g.add_edge(tiny, lazy, EdgeKind::Dynamic, "lazy");
+        g.compute_package_info();
+
+        assert_eq!(g.package_map["tiny"].total_reachable_size, 50);
+        assert_eq!(
+            g.transitive_dependencies("tiny"),
+            vec![("huge".to_string(), 10_000), ("leaf".to_string(), 200)]
+        );
+        assert!(g.transitive_dependencies("leaf").is_empty());
+    }
 }
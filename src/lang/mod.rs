@@ -4,6 +4,7 @@
 //! statements and resolve specifiers to filesystem paths.
 
 pub mod python;
+pub mod script;
 pub mod typescript;
 
 use std::path::{Path, PathBuf};
@@ -41,6 +42,9 @@ impl std::error::Error for ParseError {}
 pub struct RawImport {
     pub specifier: String,
     pub kind: EdgeKind,
+    /// Written as a `require()` call, which loads the target with the
+    /// `require` conditions of its package's `exports`.
+    pub require: bool,
 }
 
 /// All imports extracted from a single source file.
@@ -49,6 +53,93 @@ pub struct RawImport {
 pub struct ParseResult {
     pub imports: Vec<RawImport>,
     pub unresolvable_dynamic: usize,
+    /// Directories an `import.meta.glob()` listed. Adding or removing a file
+    /// in one can change the imports without touching the source.
+    pub glob_dirs: Vec<PathBuf>,
+}
+
+/// Which resolution strategy produced (or failed to produce) a path.
+/// Reported by `trace --resolve-report`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+#[non_exhaustive]
+pub enum ResolveStrategy {
+    /// `./` or `../` path relative to the importing file.
+    Relative,
+    /// Entry picked from a package.json `exports` map.
+    ExportsField,
+    /// Third-party package (`node_modules`, site-packages).
+    NodeModules,
+    /// Bare specifier that resolved to a first-party file (Python source
+    /// roots, workspace packages).
+    FirstParty,
+    /// Runtime built-in such as `fs` or `node:path`; never part of the graph.
+    Builtin,
+    /// Resolved by the `--resolver-script` hook.
+    Script,
+    Unresolved,
+}
+
+impl std::fmt::Display for ResolveStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Relative => "relative",
+            Self::ExportsField => "exports-field",
+            Self::NodeModules => "node-modules",
+            Self::FirstParty => "first-party",
+            Self::Builtin => "builtin",
+            Self::Script => "script",
+            Self::Unresolved => "unresolved",
+        })
+    }
+}
+
+/// What became of one candidate path tried during resolution. Reported by
+/// `trace --explain-unresolved`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+#[non_exhaustive]
+pub enum CandidateOutcome {
+    /// The specifier is not a runtime built-in.
+    NotABuiltin,
+    NotAFile,
+    NotADirectory,
+    /// A directory with no `index.*` file or package.json entry.
+    NoIndex,
+    /// No `node_modules/<package>` in this ancestor directory.
+    NotInNodeModules,
+    /// The package has an `exports` map with no entry for this subpath, so
+    /// the file is off limits even if it exists.
+    NotExported,
+    Found,
+}
+
+impl std::fmt::Display for CandidateOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::NotABuiltin => "not a builtin",
+            Self::NotAFile => "not a file",
+            Self::NotADirectory => "not a directory",
+            Self::NoIndex => "no index",
+            Self::NotInNodeModules => "not in node_modules",
+            Self::NotExported => "not exported",
+            Self::Found => "found",
+        })
+    }
+}
+
+/// One candidate path tried while resolving a specifier.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ResolveAttempt {
+    pub candidate: PathBuf,
+    pub outcome: CandidateOutcome,
+}
+
+impl ResolveAttempt {
+    pub fn new(candidate: PathBuf, outcome: CandidateOutcome) -> Self {
+        Self { candidate, outcome }
+    }
 }
 
 /// Language-specific import parsing and specifier resolution.
@@ -58,6 +149,56 @@ pub trait LanguageSupport: Send + Sync {
     fn resolve(&self, from_dir: &Path, specifier: &str) -> Option<PathBuf>;
     fn package_name(&self, resolved_path: &Path) -> Option<String>;
     fn workspace_package_name(&self, file_path: &Path, project_root: &Path) -> Option<String>;
+
+    /// Whether `specifier` names a module built into the runtime (`fs`,
+    /// `node:path`), which resolves to no file by design.
+    fn is_builtin(&self, _specifier: &str) -> bool {
+        false
+    }
+
+    /// Directory names never holding project source (installed packages,
+    /// build caches), skipped when walking the project for files.
+    fn skip_dirs(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Resolve `import` as written in the file `from`.
+    ///
+    /// The default resolves its specifier from `from`'s directory. Languages
+    /// where the importing file or the kind of import changes the result
+    /// (package `exports` conditions) override this.
+    fn resolve_import(&self, from: &Path, import: &RawImport) -> Option<PathBuf> {
+        #[allow(clippy::or_fun_call)]
+        let dir = from.parent().unwrap_or(Path::new("."));
+        self.resolve(dir, &import.specifier)
+    }
+
+    /// Like [`resolve`](Self::resolve), also reporting which strategy matched.
+    /// Diagnostic only (`--resolve-report`), so it may do extra work.
+    ///
+    /// The default classifies the result of `resolve` by its shape.
+    fn resolve_with_strategy(
+        &self,
+        from_dir: &Path,
+        specifier: &str,
+    ) -> (Option<PathBuf>, ResolveStrategy) {
+        let resolved = self.resolve(from_dir, specifier);
+        let strategy = match &resolved {
+            None => ResolveStrategy::Unresolved,
+            Some(_) if specifier.starts_with('.') => ResolveStrategy::Relative,
+            Some(p) if self.package_name(p).is_some() => ResolveStrategy::NodeModules,
+            Some(_) => ResolveStrategy::FirstParty,
+        };
+        (resolved, strategy)
+    }
+
+    /// Every candidate path a lookup of `specifier` tries, in order, with
+    /// why each was rejected. Diagnostic only (`--explain-unresolved`).
+    ///
+    /// The default has no candidates to report.
+    fn explain_resolve(&self, _from_dir: &Path, _specifier: &str) -> Vec<ResolveAttempt> {
+        Vec::new()
+    }
 }
 
 /// Which language ecosystem a project belongs to.
@@ -76,7 +217,7 @@ pub fn detect_project(entry: &Path, vfs: &dyn Vfs) -> Option<(PathBuf, ProjectKi
         Some("ts" | "tsx" | "js" | "jsx" | "mjs" | "cjs" | "mts" | "cts") => {
             ProjectKind::TypeScript
         }
-        Some("py") => ProjectKind::Python,
+        Some("py" | "pyi") => ProjectKind::Python,
         _ => return None,
     };
 
@@ -138,6 +279,19 @@ mod tests {
         assert_eq!(detected_root, root);
     }
 
+    #[test]
+    fn detect_python_from_stub_entry() {
+        let tmp = tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        fs::write(root.join("pyproject.toml"), "").unwrap();
+        let entry = root.join("api.pyi");
+        fs::write(&entry, "").unwrap();
+
+        let (detected_root, kind) = detect_project(&entry, &OsVfs).unwrap();
+        assert_eq!(kind, ProjectKind::Python);
+        assert_eq!(detected_root, root);
+    }
+
     #[test]
     fn detect_python_setup_py_fallback() {
         let tmp = tempdir().unwrap();
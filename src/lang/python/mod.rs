@@ -42,7 +42,20 @@ impl PythonSupport {
 
 impl LanguageSupport for PythonSupport {
     fn extensions(&self) -> &'static [&'static str] {
-        &["py"]
+        &["py", "pyi"]
+    }
+
+    fn skip_dirs(&self) -> &'static [&'static str] {
+        &[
+            "__pycache__",
+            ".venv",
+            "venv",
+            "site-packages",
+            ".mypy_cache",
+            ".pytest_cache",
+            ".tox",
+            ".eggs",
+        ]
     }
 
     fn parse(&self, path: &Path, source: &str) -> Result<ParseResult, ParseError> {
@@ -21,9 +21,11 @@ fn parse_source(source: &str) -> Result<ParseResult, ParseError> {
 
     let mut imports = Vec::new();
     let mut unresolvable_dynamic = 0;
+    let import_module_names = import_module_aliases(tree.root_node(), source.as_bytes());
     collect_imports(
         tree.root_node(),
         source.as_bytes(),
+        &import_module_names,
         &mut imports,
         &mut unresolvable_dynamic,
         false,
@@ -32,15 +34,18 @@ fn parse_source(source: &str) -> Result<ParseResult, ParseError> {
     Ok(ParseResult {
         imports,
         unresolvable_dynamic,
+        glob_dirs: Vec::new(),
     })
 }
 
 /// Recursively walk the tree-sitter AST, collecting import statements.
 /// `in_type_checking` is true when we are inside an `if TYPE_CHECKING:` block.
 /// `in_function` is true when we are inside a function/method body.
+#[allow(clippy::too_many_lines)]
 fn collect_imports(
     node: tree_sitter::Node,
     source: &[u8],
+    import_module_names: &[String],
     imports: &mut Vec<RawImport>,
     unresolvable: &mut usize,
     in_type_checking: bool,
@@ -75,6 +80,7 @@ fn collect_imports(
                         imports.push(RawImport {
                             specifier,
                             kind: edge_kind,
+                            require: false,
                         });
                     }
                 }
@@ -103,6 +109,7 @@ fn collect_imports(
                     collect_imports(
                         child,
                         source,
+                        import_module_names,
                         imports,
                         unresolvable,
                         propagated,
@@ -116,18 +123,27 @@ fn collect_imports(
         "function_definition" => {
             for i in 0..node.named_child_count() {
                 if let Some(child) = node.named_child(i) {
-                    collect_imports(child, source, imports, unresolvable, in_type_checking, true);
+                    collect_imports(
+                        child,
+                        source,
+                        import_module_names,
+                        imports,
+                        unresolvable,
+                        in_type_checking,
+                        true,
+                    );
                 }
             }
             return;
         }
 
         "call" => {
-            match extract_dynamic_import(node, source) {
+            match extract_dynamic_import(node, source, import_module_names) {
                 Some(Some(specifier)) => {
                     imports.push(RawImport {
                         specifier,
                         kind: EdgeKind::Dynamic,
+                        require: false,
                     });
                 }
                 Some(None) => {
@@ -147,6 +163,7 @@ fn collect_imports(
             collect_imports(
                 child,
                 source,
+                import_module_names,
                 imports,
                 unresolvable,
                 in_type_checking,
@@ -194,6 +211,7 @@ fn collect_from_import(
                     imports.push(RawImport {
                         specifier: format!("{dot_prefix}{name}"),
                         kind: edge_kind,
+                        require: false,
                     });
                 }
             }
@@ -210,6 +228,7 @@ fn collect_from_import(
             imports.push(RawImport {
                 specifier,
                 kind: edge_kind,
+                require: false,
             });
         }
     }
@@ -272,15 +291,59 @@ fn is_type_checking_guard(node: tree_sitter::Node, source: &[u8]) -> bool {
     false
 }
 
+/// Local names bound to `importlib.import_module` by a module-level
+/// `from importlib import import_module [as name]`.
+fn import_module_aliases(root: tree_sitter::Node, source: &[u8]) -> Vec<String> {
+    let mut names = Vec::new();
+    for i in 0..root.named_child_count() {
+        let Some(stmt) = root.named_child(i) else {
+            continue;
+        };
+        if stmt.kind() != "import_from_statement"
+            || stmt
+                .child_by_field_name("module_name")
+                .is_none_or(|m| text_ref(m, source) != "importlib")
+        {
+            continue;
+        }
+        for j in 0..stmt.named_child_count() {
+            let Some(child) = stmt.named_child(j) else {
+                continue;
+            };
+            match child.kind() {
+                "dotted_name" if text_ref(child, source) == "import_module" => {
+                    names.push("import_module".to_string());
+                }
+                "aliased_import"
+                    if child
+                        .child_by_field_name("name")
+                        .is_some_and(|n| text_ref(n, source) == "import_module") =>
+                {
+                    if let Some(alias) = child.child_by_field_name("alias") {
+                        names.push(text(alias, source));
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    names
+}
+
 /// Extract the specifier from a dynamic import call:
-/// `importlib.import_module("x")` or `__import__("x")`.
+/// `importlib.import_module("x")`, `__import__("x")`, or a bare
+/// `import_module("x")` under one of `import_module_names`.
 ///
 /// Returns:
 /// - `None` — not a dynamic import call at all
 /// - `Some(None)` — IS a dynamic import but the specifier is not a string literal
 /// - `Some(Some(s))` — dynamic import with extractable string literal specifier
 #[allow(clippy::option_option)]
-fn extract_dynamic_import(node: tree_sitter::Node, source: &[u8]) -> Option<Option<String>> {
+fn extract_dynamic_import(
+    node: tree_sitter::Node,
+    source: &[u8],
+    import_module_names: &[String],
+) -> Option<Option<String>> {
     let function_node = node.child_by_field_name("function")?;
     let is_dynamic = match function_node.kind() {
         "attribute" => {
@@ -288,8 +351,9 @@ fn extract_dynamic_import(node: tree_sitter::Node, source: &[u8]) -> Option<Opti
             text_ref(function_node, source) == "importlib.import_module"
         }
         "identifier" => {
-            // __import__(...)
-            text_ref(function_node, source) == "__import__"
+            // __import__(...) or import_module(...) from importlib
+            let name = text_ref(function_node, source);
+            name == "__import__" || import_module_names.iter().any(|n| n == name)
         }
         _ => false,
     };
@@ -298,18 +362,19 @@ fn extract_dynamic_import(node: tree_sitter::Node, source: &[u8]) -> Option<Opti
         return None;
     }
 
-    // Extract the first string argument
+    // The module name is the first positional argument; a later string
+    // (`import_module(name, "pkg")`) is the anchor package, not the target
     let args_node = node.child_by_field_name("arguments")?;
-    for i in 0..args_node.named_child_count() {
-        if let Some(arg) = args_node.named_child(i)
-            && arg.kind() == "string"
-        {
-            let raw = text_ref(arg, source);
-            // Strip surrounding quotes (single, double, or triple)
-            let stripped = strip_string_quotes(raw);
-            if !stripped.is_empty() {
-                return Some(Some(stripped.to_string()));
-            }
+    let first = (0..args_node.named_child_count())
+        .filter_map(|i| args_node.named_child(i))
+        .find(|arg| arg.kind() != "comment");
+    if let Some(arg) = first
+        && arg.kind() == "string"
+    {
+        // Strip surrounding quotes (single, double, or triple)
+        let stripped = strip_string_quotes(text_ref(arg, source));
+        if !stripped.is_empty() {
+            return Some(Some(stripped.to_string()));
         }
     }
     Some(None)
@@ -467,6 +532,29 @@ mod tests {
         assert_eq!(imports[1].kind, EdgeKind::Dynamic);
     }
 
+    #[test]
+    fn import_module_imported_from_importlib() {
+        let result = parse_source(
+            "from importlib import import_module\nfrom importlib import import_module as load\n\
+             import_module(\"foo\")\nload(\"bar.baz\")\nimport_module(name, \"pkg\")\n",
+        )
+        .expect("parse failed");
+        let dynamic: Vec<&str> = result
+            .imports
+            .iter()
+            .filter(|i| i.kind == EdgeKind::Dynamic)
+            .map(|i| i.specifier.as_str())
+            .collect();
+        assert_eq!(dynamic, ["foo", "bar.baz"]);
+        assert_eq!(result.unresolvable_dynamic, 1);
+    }
+
+    #[test]
+    fn unrelated_import_module_function_is_not_dynamic() {
+        let imports = parse_py("def import_module(x): pass\nimport_module(\"foo\")");
+        assert!(imports.is_empty());
+    }
+
     #[test]
     fn dunder_import_dynamic() {
         let imports = parse_py("__import__(\"foo.bar\")");
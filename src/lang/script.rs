@@ -0,0 +1,186 @@
+//! External resolver hook (`--resolver-script`).
+//!
+//! Wraps a [`LanguageSupport`] and, for specifiers the built-in resolver
+//! cannot resolve, runs a user-provided program as
+//! `<script> <from_dir> <specifier>`. The first line of its stdout is the
+//! resolved path (relative paths are taken relative to `from_dir`). Empty
+//! output, a non-zero exit, or a path that is not a file means "unresolved".
+//!
+//! Results are memoized per `(from_dir, specifier)`, so the script runs at
+//! most once per distinct lookup.
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use dashmap::DashMap;
+
+use super::{LanguageSupport, ParseError, ParseResult, RawImport, ResolveAttempt, ResolveStrategy};
+
+pub struct ScriptResolver {
+    inner: Box<dyn LanguageSupport>,
+    script: PathBuf,
+    resolved: DashMap<(PathBuf, String), Option<PathBuf>>,
+}
+
+impl std::fmt::Debug for ScriptResolver {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ScriptResolver")
+            .field("script", &self.script)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ScriptResolver {
+    pub fn new(inner: Box<dyn LanguageSupport>, script: PathBuf) -> Self {
+        Self {
+            inner,
+            script,
+            resolved: DashMap::new(),
+        }
+    }
+
+    fn resolve_via_script(&self, from_dir: &Path, specifier: &str) -> Option<PathBuf> {
+        let key = (from_dir.to_path_buf(), specifier.to_string());
+        if let Some(cached) = self.resolved.get(&key).map(|e| e.value().clone()) {
+            return cached;
+        }
+        let result = self.run_script(from_dir, specifier);
+        self.resolved.insert(key, result.clone());
+        result
+    }
+
+    fn run_script(&self, from_dir: &Path, specifier: &str) -> Option<PathBuf> {
+        let output = Command::new(&self.script)
+            .arg(from_dir)
+            .arg(specifier)
+            .stdin(Stdio::null())
+            .stderr(Stdio::inherit())
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        let stdout = String::from_utf8(output.stdout).ok()?;
+        let line = stdout.lines().next()?.trim();
+        if line.is_empty() {
+            return None;
+        }
+        let path = from_dir.join(line);
+        let path = path.canonicalize().ok()?;
+        path.is_file().then_some(path)
+    }
+}
+
+impl LanguageSupport for ScriptResolver {
+    fn extensions(&self) -> &'static [&'static str] {
+        self.inner.extensions()
+    }
+
+    fn parse(&self, path: &Path, source: &str) -> Result<ParseResult, ParseError> {
+        self.inner.parse(path, source)
+    }
+
+    fn resolve(&self, from_dir: &Path, specifier: &str) -> Option<PathBuf> {
+        if let Some(path) = self.inner.resolve(from_dir, specifier) {
+            return Some(path);
+        }
+        self.resolve_via_script(from_dir, specifier)
+    }
+
+    fn resolve_import(&self, from: &Path, import: &RawImport) -> Option<PathBuf> {
+        if let Some(path) = self.inner.resolve_import(from, import) {
+            return Some(path);
+        }
+        #[allow(clippy::or_fun_call)]
+        let dir = from.parent().unwrap_or(Path::new("."));
+        self.resolve_via_script(dir, &import.specifier)
+    }
+
+    fn resolve_with_strategy(
+        &self,
+        from_dir: &Path,
+        specifier: &str,
+    ) -> (Option<PathBuf>, ResolveStrategy) {
+        let (resolved, strategy) = self.inner.resolve_with_strategy(from_dir, specifier);
+        if resolved.is_some() {
+            return (resolved, strategy);
+        }
+        match self.resolve_via_script(from_dir, specifier) {
+            Some(path) => (Some(path), ResolveStrategy::Script),
+            None => (None, strategy),
+        }
+    }
+
+    fn explain_resolve(&self, from_dir: &Path, specifier: &str) -> Vec<ResolveAttempt> {
+        self.inner.explain_resolve(from_dir, specifier)
+    }
+
+    fn package_name(&self, resolved_path: &Path) -> Option<String> {
+        self.inner.package_name(resolved_path)
+    }
+
+    fn workspace_package_name(&self, file_path: &Path, project_root: &Path) -> Option<String> {
+        self.inner.workspace_package_name(file_path, project_root)
+    }
+
+    fn skip_dirs(&self) -> &'static [&'static str] {
+        self.inner.skip_dirs()
+    }
+
+    fn is_builtin(&self, specifier: &str) -> bool {
+        self.inner.is_builtin(specifier)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lang::typescript::TypeScriptSupport;
+    use std::fs;
+
+    #[test]
+    #[cfg(unix)]
+    fn script_resolves_fake_specifier() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        fs::create_dir_all(root.join("generated")).unwrap();
+        fs::write(root.join("generated/api.ts"), "export {};").unwrap();
+        let log = root.join("calls.log");
+        let script = root.join("resolve.sh");
+        fs::write(
+            &script,
+            format!(
+                "#!/bin/sh\necho \"$2\" >> '{}'\n[ \"$2\" = \"@codegen/api\" ] && echo generated/api.ts\n",
+                log.display()
+            ),
+        )
+        .unwrap();
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let resolver = ScriptResolver::new(Box::new(TypeScriptSupport::new(&root)), script);
+        assert_eq!(
+            resolver.resolve(&root, "@codegen/api"),
+            Some(root.join("generated/api.ts"))
+        );
+        assert_eq!(resolver.resolve(&root, "@codegen/missing"), None);
+
+        // Memoized: a second lookup does not run the script again
+        resolver.resolve(&root, "@codegen/api");
+        let calls = fs::read_to_string(&log).unwrap();
+        assert_eq!(calls.lines().count(), 2, "{calls}");
+    }
+
+    #[test]
+    fn script_not_consulted_when_builtin_resolves() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        fs::write(root.join("a.ts"), "").unwrap();
+        let resolver = ScriptResolver::new(
+            Box::new(TypeScriptSupport::new(&root)),
+            root.join("does-not-exist.sh"),
+        );
+        assert_eq!(resolver.resolve(&root, "./a"), Some(root.join("a.ts")));
+    }
+}
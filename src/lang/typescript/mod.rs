@@ -3,14 +3,18 @@
 mod parser;
 mod resolver;
 
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 
 use dashmap::DashMap;
 
-use crate::lang::{LanguageSupport, ParseError, ParseResult};
+use crate::lang::{
+    LanguageSupport, ParseError, ParseResult, RawImport, ResolveAttempt, ResolveStrategy,
+};
 use crate::vfs::{OsVfs, Vfs};
 
+pub use self::resolver::ModuleSystem;
 use self::resolver::{ImportResolver, package_name_from_path};
 
 const EXTENSIONS: &[&str] = &["ts", "tsx", "js", "jsx", "mjs", "cjs", "mts", "cts"];
@@ -18,7 +22,12 @@ const EXTENSIONS: &[&str] = &["ts", "tsx", "js", "jsx", "mjs", "cjs", "mts", "ct
 pub struct TypeScriptSupport {
     resolver: ImportResolver,
     workspace_cache: DashMap<PathBuf, Option<String>>,
+    /// Directories the workspace config declares as packages, found on
+    /// first use.
+    workspace_dirs: OnceLock<HashSet<PathBuf>>,
     vfs: Arc<dyn Vfs>,
+    /// `process.env` values used to prune statically dead `require()` calls.
+    defines: Vec<(String, String)>,
 }
 
 impl std::fmt::Debug for TypeScriptSupport {
@@ -33,12 +42,58 @@ impl TypeScriptSupport {
     }
 
     pub fn with_vfs(root: &Path, vfs: Arc<dyn Vfs>) -> Self {
+        Self::with_defines(root, vfs, Vec::new())
+    }
+
+    /// Like [`with_vfs`](Self::with_vfs), treating code guarded by
+    /// `process.env.KEY` comparisons that are false under `defines` as dead.
+    pub fn with_defines(root: &Path, vfs: Arc<dyn Vfs>, defines: Vec<(String, String)>) -> Self {
         Self {
             resolver: ImportResolver::new(root, vfs.clone()),
             workspace_cache: DashMap::new(),
+            workspace_dirs: OnceLock::new(),
             vfs,
+            defines,
         }
     }
+
+    /// Resolve a package import that lands in the package's `src/` to the
+    /// built entry its `exports`/`main` publish, when it has one.
+    #[must_use]
+    pub fn prefer_published_entry(mut self, yes: bool) -> Self {
+        self.resolver.prefer_published_entry = yes;
+        self
+    }
+
+    /// Whether Node loads `path` as an ES module or as CJS, by extension and
+    /// the nearest package.json `type`. Package conditions are picked by
+    /// import syntax instead, so resolving imports never needs this.
+    pub fn module_system(&self, path: &Path) -> ModuleSystem {
+        self.resolver.module_system(path)
+    }
+
+    fn is_workspace_dir(&self, dir: &Path, project_root: &Path) -> bool {
+        self.workspace_dirs
+            .get_or_init(|| {
+                find_workspace_root(project_root, &*self.vfs)
+                    .map(|root| {
+                        workspace_packages(&root, &*self.vfs)
+                            .into_iter()
+                            .map(|pkg| pkg.dir)
+                            .collect()
+                    })
+                    .unwrap_or_default()
+            })
+            .contains(dir)
+    }
+
+    /// Resolve `@/` to `src/` and `~/` to the project root when no tsconfig
+    /// `paths` entry maps them. On by default.
+    #[must_use]
+    pub fn default_aliases(mut self, yes: bool) -> Self {
+        self.resolver.default_aliases = yes;
+        self
+    }
 }
 
 impl LanguageSupport for TypeScriptSupport {
@@ -46,14 +101,39 @@ impl LanguageSupport for TypeScriptSupport {
         EXTENSIONS
     }
 
+    fn skip_dirs(&self) -> &'static [&'static str] {
+        &["node_modules", "bower_components", "jspm_packages"]
+    }
+
+    fn is_builtin(&self, specifier: &str) -> bool {
+        resolver::is_node_builtin(specifier)
+    }
+
     fn parse(&self, path: &Path, source: &str) -> Result<ParseResult, ParseError> {
-        parser::parse_file(path, source)
+        parser::parse_file(path, source, &self.defines, &*self.vfs)
     }
 
     fn resolve(&self, from_dir: &Path, specifier: &str) -> Option<PathBuf> {
         self.resolver.resolve(from_dir, specifier)
     }
 
+    fn resolve_import(&self, from: &Path, import: &RawImport) -> Option<PathBuf> {
+        self.resolver
+            .resolve_import(from, &import.specifier, import.kind, import.require)
+    }
+
+    fn resolve_with_strategy(
+        &self,
+        from_dir: &Path,
+        specifier: &str,
+    ) -> (Option<PathBuf>, ResolveStrategy) {
+        self.resolver.resolve_with_strategy(from_dir, specifier)
+    }
+
+    fn explain_resolve(&self, from_dir: &Path, specifier: &str) -> Vec<ResolveAttempt> {
+        self.resolver.explain(from_dir, specifier)
+    }
+
     fn package_name(&self, resolved_path: &Path) -> Option<String> {
         package_name_from_path(resolved_path)
     }
@@ -79,10 +159,17 @@ impl LanguageSupport for TypeScriptSupport {
 
             let pkg_json = dir.join("package.json");
             if self.vfs.exists(&pkg_json) {
+                // A workspace package without a `name` is still a package;
+                // label it by its directory. Other unnamed package.json files
+                // (`{"type": "module"}` markers) don't make a package.
                 let result = if dir == project_root {
                     None
                 } else {
-                    resolver::read_package_name(&pkg_json, &*self.vfs)
+                    resolver::read_package_name(&pkg_json, &*self.vfs).or_else(|| {
+                        self.is_workspace_dir(dir, project_root)
+                            .then(|| dir.file_name().map(|n| n.to_string_lossy().into_owned()))
+                            .flatten()
+                    })
                 };
                 uncached.push(dir.to_path_buf());
                 break result;
@@ -104,9 +191,194 @@ impl LanguageSupport for TypeScriptSupport {
     }
 }
 
+/// A package declared by a monorepo's workspaces config.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkspacePackage {
+    /// The package.json `name`, or the directory name when it has none.
+    pub name: String,
+    pub dir: PathBuf,
+}
+
+/// The nearest directory at or above `start` that declares workspaces,
+/// either as a package.json `workspaces` field or a `pnpm-workspace.yaml`.
+pub fn find_workspace_root(start: &Path, vfs: &dyn Vfs) -> Option<PathBuf> {
+    let mut dir = if vfs.is_dir(start) {
+        start
+    } else {
+        start.parent()?
+    };
+    loop {
+        if workspace_patterns(dir, vfs).is_some() {
+            return Some(dir.to_path_buf());
+        }
+        dir = dir.parent()?;
+    }
+}
+
+/// The packages matched by `root`'s workspace patterns, sorted by directory.
+/// Patterns are matched one path segment at a time: `*` matches within a
+/// segment and `**` matches any number of directories. `!` patterns exclude.
+pub fn workspace_packages(root: &Path, vfs: &dyn Vfs) -> Vec<WorkspacePackage> {
+    let patterns = workspace_patterns(root, vfs).unwrap_or_default();
+    let (excludes, includes): (Vec<&String>, Vec<&String>) =
+        patterns.iter().partition(|p| p.starts_with('!'));
+
+    let mut dirs = Vec::new();
+    for pattern in includes {
+        let segments: Vec<&str> = pattern
+            .split('/')
+            .filter(|s| !s.is_empty() && *s != ".")
+            .collect();
+        expand_segments(root, &segments, vfs, &mut dirs);
+    }
+    dirs.retain(|dir| {
+        let rel = dir
+            .strip_prefix(root)
+            .unwrap_or(dir)
+            .to_string_lossy()
+            .replace('\\', "/");
+        !excludes.iter().any(|ex| {
+            let ex: Vec<&str> = ex[1..]
+                .split('/')
+                .filter(|s| !s.is_empty() && *s != ".")
+                .collect();
+            let rel: Vec<&str> = rel.split('/').collect();
+            path_matches(&ex, &rel)
+        })
+    });
+    dirs.sort();
+    dirs.dedup();
+
+    dirs.into_iter()
+        .map(|dir| {
+            let name = resolver::read_package_name(&dir.join("package.json"), vfs)
+                .or_else(|| dir.file_name().map(|n| n.to_string_lossy().into_owned()))
+                .unwrap_or_default();
+            WorkspacePackage { name, dir }
+        })
+        .collect()
+}
+
+/// Workspace globs from `dir`'s package.json (`workspaces` as an array or
+/// `{ "packages": [...] }`) or its `pnpm-workspace.yaml`. `None` when `dir`
+/// declares no workspaces.
+fn workspace_patterns(dir: &Path, vfs: &dyn Vfs) -> Option<Vec<String>> {
+    let from_package_json = vfs
+        .read_to_string(&dir.join("package.json"))
+        .ok()
+        .and_then(|c| serde_json::from_str::<serde_json::Value>(&c).ok())
+        .and_then(|v| {
+            let ws = v.get("workspaces")?;
+            let list = ws.get("packages").unwrap_or(ws).as_array()?;
+            Some(
+                list.iter()
+                    .filter_map(|p| p.as_str().map(str::to_string))
+                    .collect(),
+            )
+        });
+    from_package_json.or_else(|| {
+        let yaml = vfs.read_to_string(&dir.join("pnpm-workspace.yaml")).ok()?;
+        Some(pnpm_workspace_patterns(&yaml))
+    })
+}
+
+/// The `packages:` list of a `pnpm-workspace.yaml`. Only the block-sequence
+/// form pnpm documents is understood.
+fn pnpm_workspace_patterns(yaml: &str) -> Vec<String> {
+    let mut patterns = Vec::new();
+    let mut in_packages = false;
+    for line in yaml.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if !line.starts_with([' ', '\t', '-']) {
+            in_packages = trimmed.starts_with("packages:");
+            continue;
+        }
+        if in_packages && let Some(item) = trimmed.strip_prefix('-') {
+            let item = item.split(" #").next().unwrap_or_default().trim();
+            patterns.push(item.trim_matches(['\'', '"']).to_string());
+        }
+    }
+    patterns
+}
+
+/// Collect the directories under `dir` matching `segments` that hold a
+/// package.json. `node_modules` is never descended into.
+fn expand_segments(dir: &Path, segments: &[&str], vfs: &dyn Vfs, out: &mut Vec<PathBuf>) {
+    let Some((&first, rest)) = segments.split_first() else {
+        if vfs.is_file(&dir.join("package.json")) {
+            out.push(dir.to_path_buf());
+        }
+        return;
+    };
+    if first == "**" {
+        expand_segments(dir, rest, vfs, out);
+        for child in child_dirs(dir, vfs) {
+            expand_segments(&child, segments, vfs, out);
+        }
+    } else if first.contains('*') {
+        for child in child_dirs(dir, vfs) {
+            let name = child.file_name().map(|n| n.to_string_lossy().into_owned());
+            if name.is_some_and(|n| segment_matches(first, &n)) {
+                expand_segments(&child, rest, vfs, out);
+            }
+        }
+    } else {
+        expand_segments(&dir.join(first), rest, vfs, out);
+    }
+}
+
+fn child_dirs(dir: &Path, vfs: &dyn Vfs) -> Vec<PathBuf> {
+    let mut children: Vec<PathBuf> = vfs
+        .read_dir(dir)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|p| vfs.is_dir(p) && p.file_name().is_some_and(|n| n != "node_modules"))
+        .collect();
+    children.sort();
+    children
+}
+
+/// Whether the path `segments` matches the pattern segments `pattern`.
+fn path_matches(pattern: &[&str], segments: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => segments.is_empty(),
+        Some((&"**", rest)) => {
+            (0..=segments.len()).any(|skip| path_matches(rest, &segments[skip..]))
+        }
+        Some((first, rest)) => segments
+            .split_first()
+            .is_some_and(|(seg, tail)| segment_matches(first, seg) && path_matches(rest, tail)),
+    }
+}
+
+/// Match one path segment against a pattern where `*` stands for any run
+/// of characters.
+fn segment_matches(pattern: &str, name: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = name.strip_prefix(first) else {
+        return false;
+    };
+    let mut parts: Vec<&str> = parts.collect();
+    let Some(last) = parts.pop() else {
+        return rest.is_empty();
+    };
+    for part in parts {
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::graph::EdgeKind;
     use std::fs;
 
     fn setup_workspace(tmp: &Path) {
@@ -137,14 +409,63 @@ mod tests {
     }
 
     #[test]
-    fn read_package_name_missing_name_field() {
+    fn read_package_name_missing_name_field_falls_back_to_dir() {
         let tmp = tempfile::tempdir().unwrap();
-        let nested = tmp.path().join("packages/pkg");
+        let root = tmp.path().canonicalize().unwrap();
+        fs::write(
+            root.join("package.json"),
+            r#"{"workspaces": ["packages/*"]}"#,
+        )
+        .unwrap();
+        let nested = root.join("packages/pkg");
         fs::create_dir_all(&nested).unwrap();
         fs::write(nested.join("package.json"), r#"{"version": "1.0.0"}"#).unwrap();
-        let support = TypeScriptSupport::new(tmp.path());
+        let support = TypeScriptSupport::new(&root);
         assert_eq!(
-            support.workspace_package_name(&nested.join("index.ts"), tmp.path()),
+            support.workspace_package_name(&nested.join("index.ts"), &root),
+            Some("pkg".to_string())
+        );
+    }
+
+    #[test]
+    fn module_type_marker_is_not_a_package() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        fs::write(root.join("package.json"), r#"{"name": "app"}"#).unwrap();
+        let esm = root.join("src/esm");
+        fs::create_dir_all(&esm).unwrap();
+        fs::write(esm.join("package.json"), r#"{"type": "module"}"#).unwrap();
+        let support = TypeScriptSupport::new(&root);
+        assert_eq!(
+            support.workspace_package_name(&esm.join("index.js"), &root),
+            None
+        );
+    }
+
+    #[test]
+    fn workspace_scoped_and_unscoped_packages() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        fs::write(root.join("package.json"), r#"{"name": "monorepo"}"#).unwrap();
+        let foo = root.join("packages/foo");
+        let bar = root.join("packages/bar");
+        fs::create_dir_all(foo.join("src")).unwrap();
+        fs::create_dir_all(bar.join("lib")).unwrap();
+        fs::write(foo.join("package.json"), r#"{"name": "@org/foo"}"#).unwrap();
+        fs::write(bar.join("package.json"), r#"{"name": "bar"}"#).unwrap();
+
+        let support = TypeScriptSupport::new(&root);
+        assert_eq!(
+            support.workspace_package_name(&foo.join("src/index.ts"), &root),
+            Some("@org/foo".to_string())
+        );
+        assert_eq!(
+            support.workspace_package_name(&bar.join("lib/util.ts"), &root),
+            Some("bar".to_string())
+        );
+        // Files directly under the workspace root are first-party
+        assert_eq!(
+            support.workspace_package_name(&root.join("scripts/build.ts"), &root),
             None
         );
     }
@@ -269,4 +590,571 @@ mod tests {
             None
         );
     }
+
+    #[test]
+    fn resolve_directory_with_package_json_main() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        let lib = root.join("lib");
+        fs::create_dir_all(lib.join("dist")).unwrap();
+        fs::write(lib.join("package.json"), r#"{"main": "dist/entry.js"}"#).unwrap();
+        fs::write(lib.join("dist/entry.js"), "module.exports = 1;").unwrap();
+        fs::write(lib.join("index.js"), "module.exports = 2;").unwrap();
+
+        let support = TypeScriptSupport::new(&root);
+        assert_eq!(
+            support.resolve(&root, "./lib"),
+            Some(lib.join("dist/entry.js"))
+        );
+    }
+
+    #[test]
+    fn resolve_directory_with_package_json_exports() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        let lib = root.join("lib");
+        fs::create_dir_all(lib.join("src")).unwrap();
+        fs::write(
+            lib.join("package.json"),
+            r#"{"exports": {".": {"import": "./src/main.ts"}}}"#,
+        )
+        .unwrap();
+        fs::write(lib.join("src/main.ts"), "export const x = 1;").unwrap();
+        fs::write(lib.join("index.ts"), "export const y = 2;").unwrap();
+
+        let support = TypeScriptSupport::new(&root);
+        assert_eq!(
+            support.resolve(&root, "./lib"),
+            Some(lib.join("src/main.ts"))
+        );
+    }
+
+    #[test]
+    fn resolve_parent_directory_with_package_json_exports() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        let pkg = root.join("packages/pkg");
+        fs::create_dir_all(pkg.join("src")).unwrap();
+        fs::create_dir_all(root.join("packages/app")).unwrap();
+        fs::write(
+            pkg.join("package.json"),
+            r#"{"exports": {".": {"import": "./src/main.ts"}}}"#,
+        )
+        .unwrap();
+        fs::write(pkg.join("src/main.ts"), "export const x = 1;").unwrap();
+        fs::write(pkg.join("index.ts"), "export const y = 2;").unwrap();
+
+        let support = TypeScriptSupport::new(&root);
+        assert_eq!(
+            support.resolve(&root.join("packages/app"), "../pkg"),
+            Some(pkg.join("src/main.ts"))
+        );
+    }
+
+    #[test]
+    fn resolve_sibling_file_beats_directory_exports() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        let lib = root.join("lib");
+        fs::create_dir_all(&lib).unwrap();
+        fs::write(lib.join("package.json"), r#"{"exports": "./main.ts"}"#).unwrap();
+        fs::write(lib.join("main.ts"), "").unwrap();
+        fs::write(root.join("lib.ts"), "").unwrap();
+
+        let support = TypeScriptSupport::new(&root);
+        assert_eq!(support.resolve(&root, "./lib"), Some(root.join("lib.ts")));
+    }
+
+    /// A package whose `require` entry comes first and is the bigger build.
+    fn setup_dual_package(root: &Path, exports: &str) -> PathBuf {
+        let pkg = root.join("node_modules/dual");
+        fs::create_dir_all(pkg.join("dist")).unwrap();
+        fs::write(
+            pkg.join("package.json"),
+            format!(r#"{{"name": "dual", "exports": {exports}}}"#),
+        )
+        .unwrap();
+        fs::write(pkg.join("dist/index.cjs"), "x".repeat(4000)).unwrap();
+        fs::write(pkg.join("dist/index.mjs"), "x".repeat(100)).unwrap();
+        fs::write(pkg.join("dist/index.d.ts"), "").unwrap();
+        pkg
+    }
+
+    fn import(specifier: &str, kind: EdgeKind) -> RawImport {
+        RawImport {
+            specifier: specifier.into(),
+            kind,
+            require: false,
+        }
+    }
+
+    #[test]
+    fn exports_conditions_follow_importer_module_system() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        let pkg = setup_dual_package(
+            &root,
+            r#"{".": {"require": "./dist/index.cjs", "types": "./dist/index.d.ts", "import": "./dist/index.mjs"}}"#,
+        );
+
+        let support = TypeScriptSupport::new(&root);
+        let resolve =
+            |from: &str, kind| support.resolve_import(&root.join(from), &import("dual", kind));
+        let esm = Some(pkg.join("dist/index.mjs"));
+        let cjs = Some(pkg.join("dist/index.cjs"));
+        assert_eq!(resolve("app.ts", EdgeKind::Static), esm);
+        assert_eq!(resolve("app.mjs", EdgeKind::Dynamic), esm);
+        assert_eq!(resolve("app.cjs", EdgeKind::Static), cjs);
+        assert_eq!(resolve("app.cts", EdgeKind::Static), cjs);
+        assert_eq!(
+            resolve("app.ts", EdgeKind::TypeOnly),
+            Some(pkg.join("dist/index.d.ts"))
+        );
+        assert_eq!(support.resolve(&root, "dual"), esm);
+    }
+
+    #[test]
+    fn js_module_system_follows_nearest_package_type() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        let pkg = setup_dual_package(
+            &root,
+            r#"{".": {"require": "./dist/index.cjs", "import": "./dist/index.mjs"}}"#,
+        );
+        fs::write(root.join("package.json"), r#"{"name": "app"}"#).unwrap();
+        fs::create_dir_all(root.join("esm/lib")).unwrap();
+        fs::write(root.join("esm/package.json"), r#"{"type": "module"}"#).unwrap();
+
+        let support = TypeScriptSupport::new(&root);
+        assert_eq!(
+            support.module_system(&root.join("esm/lib/a.js")),
+            ModuleSystem::Esm
+        );
+        assert_eq!(
+            support.module_system(&root.join("src/a.js")),
+            ModuleSystem::CommonJs
+        );
+        // Explicit extensions and TypeScript sources ignore the package type
+        assert_eq!(
+            support.module_system(&root.join("esm/a.cjs")),
+            ModuleSystem::CommonJs
+        );
+        assert_eq!(
+            support.module_system(&root.join("src/a.mjs")),
+            ModuleSystem::Esm
+        );
+        assert_eq!(
+            support.module_system(&root.join("src/a.ts")),
+            ModuleSystem::Esm
+        );
+
+        let resolve = |from: &str, source: &str| {
+            let from = root.join(from);
+            let parsed = support.parse(&from, source).unwrap();
+            support.resolve_import(&from, &parsed.imports[0])
+        };
+        let esm = Some(pkg.join("dist/index.mjs"));
+        let cjs = Some(pkg.join("dist/index.cjs"));
+        assert_eq!(resolve("esm/lib/a.js", r#"import x from "dual";"#), esm);
+        assert_eq!(resolve("esm/lib/a.js", r#"require("dual");"#), cjs);
+        // A type-less `.js` file is CJS, but `import` syntax still loads
+        // the package's ES module build; only `require()` matches `require`
+        assert_eq!(resolve("src/a.js", r#"import x from "dual";"#), esm);
+        assert_eq!(resolve("src/a.js", r#"const x = require("dual");"#), cjs);
+        assert_eq!(resolve("src/a.cjs", r#"const x = require("dual");"#), cjs);
+    }
+
+    #[test]
+    fn exports_conditions_recurse_into_nested_objects() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        let pkg = setup_dual_package(
+            &root,
+            r#"{"node": {"require": "./dist/index.cjs", "default": "./dist/index.mjs"}}"#,
+        );
+
+        let support = TypeScriptSupport::new(&root);
+        let resolve = |from: &str| {
+            support.resolve_import(&root.join(from), &import("dual", EdgeKind::Static))
+        };
+        assert_eq!(resolve("app.ts"), Some(pkg.join("dist/index.mjs")));
+        assert_eq!(resolve("app.cjs"), Some(pkg.join("dist/index.cjs")));
+    }
+
+    #[test]
+    fn exports_subpath_patterns_and_unexported_subpaths() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        let pkg = setup_dual_package(
+            &root,
+            r#"{
+                ".": "./dist/index.mjs",
+                "./feature/*": {"import": "./dist/feature/*.mjs", "require": "./dist/feature/*.cjs"},
+                "./package.json": "./package.json"
+            }"#,
+        );
+        fs::create_dir_all(pkg.join("dist/feature")).unwrap();
+        fs::write(pkg.join("dist/feature/a.mjs"), "").unwrap();
+        fs::write(pkg.join("dist/feature/a.cjs"), "").unwrap();
+        // Present on disk and reachable via `main`, but not exported
+        fs::write(pkg.join("index.js"), "").unwrap();
+        fs::write(pkg.join("dist/internal.js"), "").unwrap();
+
+        let support = TypeScriptSupport::new(&root);
+        let resolve = |from: &str, specifier: &str| {
+            support.resolve_import(&root.join(from), &import(specifier, EdgeKind::Static))
+        };
+        assert_eq!(
+            resolve("app.ts", "dual/feature/a"),
+            Some(pkg.join("dist/feature/a.mjs"))
+        );
+        assert_eq!(
+            resolve("app.cjs", "dual/feature/a"),
+            Some(pkg.join("dist/feature/a.cjs"))
+        );
+        assert_eq!(
+            resolve("app.ts", "dual/package.json"),
+            Some(pkg.join("package.json"))
+        );
+        assert_eq!(resolve("app.ts", "dual/dist/internal.js"), None);
+        assert_eq!(resolve("app.ts", "dual/feature/missing"), None);
+        assert_eq!(
+            support.resolve_with_strategy(&root, "dual/dist/internal"),
+            (None, ResolveStrategy::Unresolved)
+        );
+        let last = support
+            .explain_resolve(&root, "dual/dist/internal.js")
+            .pop()
+            .unwrap();
+        assert_eq!(
+            (last.candidate, last.outcome),
+            (
+                pkg.join("dist/internal.js"),
+                crate::lang::CandidateOutcome::NotExported
+            )
+        );
+    }
+
+    #[test]
+    fn exports_without_importer_condition_fall_back() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        let pkg = setup_dual_package(&root, r#"{".": {"require": "./dist/index.cjs"}}"#);
+
+        let support = TypeScriptSupport::new(&root);
+        assert_eq!(
+            support.resolve_import(&root.join("app.ts"), &import("dual", EdgeKind::Static)),
+            Some(pkg.join("dist/index.cjs"))
+        );
+    }
+
+    #[test]
+    fn resolve_with_strategy_detects_exports_field() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        let pkg = root.join("node_modules/@scope/pkg");
+        fs::create_dir_all(pkg.join("dist")).unwrap();
+        fs::write(
+            pkg.join("package.json"),
+            r#"{"name": "@scope/pkg", "exports": {".": "./dist/index.js"}}"#,
+        )
+        .unwrap();
+        fs::write(pkg.join("dist/index.js"), "").unwrap();
+        let plain = root.join("node_modules/plain");
+        fs::create_dir_all(&plain).unwrap();
+        fs::write(plain.join("package.json"), r#"{"name": "plain"}"#).unwrap();
+        fs::write(plain.join("index.js"), "").unwrap();
+
+        let support = TypeScriptSupport::new(&root);
+        assert_eq!(
+            support.resolve_with_strategy(&root, "@scope/pkg"),
+            (
+                Some(pkg.join("dist/index.js")),
+                ResolveStrategy::ExportsField
+            )
+        );
+        assert_eq!(
+            support.resolve_with_strategy(&root, "plain").1,
+            ResolveStrategy::NodeModules
+        );
+        assert_eq!(
+            support.resolve_with_strategy(&root, "node:path"),
+            (None, ResolveStrategy::Builtin)
+        );
+    }
+
+    #[test]
+    fn resolve_walks_up_nested_node_modules() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        // `dep` is only installed inside `outer`'s own node_modules
+        let outer = root.join("node_modules/outer");
+        let deep = outer.join("lib/internal/util");
+        let nested_dep = outer.join("node_modules/dep");
+        fs::create_dir_all(&deep).unwrap();
+        fs::create_dir_all(&nested_dep).unwrap();
+        fs::write(outer.join("package.json"), r#"{"name": "outer"}"#).unwrap();
+        fs::write(deep.join("index.js"), "require('dep');").unwrap();
+        fs::write(
+            nested_dep.join("package.json"),
+            r#"{"name": "dep", "main": "main.js"}"#,
+        )
+        .unwrap();
+        fs::write(nested_dep.join("main.js"), "").unwrap();
+        // A different `dep` at the root must not shadow the nested one
+        let root_dep = root.join("node_modules/dep");
+        fs::create_dir_all(&root_dep).unwrap();
+        fs::write(
+            root_dep.join("package.json"),
+            r#"{"name": "dep", "main": "root.js"}"#,
+        )
+        .unwrap();
+        fs::write(root_dep.join("root.js"), "").unwrap();
+
+        let support = TypeScriptSupport::new(&root);
+        assert_eq!(
+            support.resolve(&deep, "dep"),
+            Some(nested_dep.join("main.js"))
+        );
+        assert_eq!(
+            support.resolve(&root, "dep"),
+            Some(root_dep.join("root.js"))
+        );
+    }
+
+    #[test]
+    fn tsconfig_paths_resolve_aliases_through_extends() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        fs::create_dir_all(root.join("src/app")).unwrap();
+        fs::create_dir_all(root.join("generated")).unwrap();
+        fs::write(root.join("src/app/foo.ts"), "").unwrap();
+        fs::write(root.join("generated/bar.ts"), "").unwrap();
+        fs::write(root.join("src/util.ts"), "").unwrap();
+        fs::write(
+            root.join("tsconfig.base.json"),
+            r#"{
+                // comments and trailing commas, as tsc allows
+                "compilerOptions": {
+                    "baseUrl": ".",
+                    "paths": {"@app/*": ["src/app/*", "generated/*"],},
+                },
+            }"#,
+        )
+        .unwrap();
+        fs::write(
+            root.join("tsconfig.json"),
+            r#"{"extends": "./tsconfig.base.json"}"#,
+        )
+        .unwrap();
+
+        let support = TypeScriptSupport::new(&root);
+        // First existing candidate wins
+        assert_eq!(
+            support.resolve(&root, "@app/foo"),
+            Some(root.join("src/app/foo.ts"))
+        );
+        assert_eq!(
+            support.resolve(&root, "@app/bar"),
+            Some(root.join("generated/bar.ts"))
+        );
+        // baseUrl makes non-relative specifiers resolve from the project root
+        assert_eq!(
+            support.resolve(&root.join("src/app"), "src/util"),
+            Some(root.join("src/util.ts"))
+        );
+        assert_eq!(support.resolve(&root, "@app/missing"), None);
+    }
+
+    #[test]
+    fn default_aliases_map_at_to_src_and_tilde_to_root() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        fs::create_dir_all(root.join("src/components")).unwrap();
+        fs::write(root.join("src/foo.ts"), "").unwrap();
+        fs::write(root.join("src/components/Button.tsx"), "").unwrap();
+        fs::write(root.join("config.ts"), "").unwrap();
+
+        let support = TypeScriptSupport::new(&root);
+        let nested = root.join("src/components");
+        assert_eq!(
+            support.resolve(&nested, "@/foo"),
+            Some(root.join("src/foo.ts"))
+        );
+        assert_eq!(
+            support.resolve(&root, "@/components/Button"),
+            Some(root.join("src/components/Button.tsx"))
+        );
+        assert_eq!(
+            support.resolve(&nested, "~/config"),
+            Some(root.join("config.ts"))
+        );
+        assert_eq!(
+            support.resolve_with_strategy(&root, "@/foo").1,
+            ResolveStrategy::FirstParty
+        );
+
+        let support = TypeScriptSupport::new(&root).default_aliases(false);
+        assert_eq!(support.resolve(&root, "@/foo"), None);
+    }
+
+    #[test]
+    fn tsconfig_paths_win_over_default_aliases() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::create_dir_all(root.join("app")).unwrap();
+        fs::write(root.join("src/foo.ts"), "").unwrap();
+        fs::write(root.join("app/foo.ts"), "").unwrap();
+        fs::write(
+            root.join("tsconfig.json"),
+            r#"{"compilerOptions": {"paths": {"@/*": ["./app/*"]}}}"#,
+        )
+        .unwrap();
+
+        let support = TypeScriptSupport::new(&root);
+        assert_eq!(
+            support.resolve(&root, "@/foo"),
+            Some(root.join("app/foo.ts"))
+        );
+    }
+
+    #[test]
+    fn tsconfig_paths_fall_through_to_packages() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        let ui = root.join("node_modules/real-ui");
+        let preact = root.join("node_modules/preact");
+        fs::create_dir_all(ui.join("dist")).unwrap();
+        fs::create_dir_all(preact.join("compat")).unwrap();
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(ui.join("package.json"), r#"{"name": "real-ui"}"#).unwrap();
+        fs::write(ui.join("dist/index.js"), "").unwrap();
+        fs::write(preact.join("package.json"), r#"{"name": "preact"}"#).unwrap();
+        fs::write(preact.join("compat/index.js"), "").unwrap();
+        fs::write(
+            root.join("tsconfig.json"),
+            r#"{"compilerOptions": {"baseUrl": "src", "paths": {
+                "@vendor/ui": ["node_modules/real-ui/dist/index.js"],
+                "react": ["preact/compat"]
+            }}}"#,
+        )
+        .unwrap();
+
+        let support = TypeScriptSupport::new(&root);
+        // src/node_modules/... does not exist, so the target is a package path
+        assert_eq!(
+            support.resolve(&root.join("src"), "@vendor/ui"),
+            Some(ui.join("dist/index.js"))
+        );
+        // A package stubbed by another one
+        assert_eq!(
+            support.resolve_with_strategy(&root.join("src"), "react"),
+            (
+                Some(preact.join("compat/index.js")),
+                ResolveStrategy::NodeModules
+            )
+        );
+    }
+
+    #[test]
+    fn resolves_declaration_file_target() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        fs::write(root.join("types.d.ts"), "export interface T {}").unwrap();
+        let support = TypeScriptSupport::new(&root);
+        assert_eq!(
+            support.resolve(&root, "./types"),
+            Some(root.join("types.d.ts"))
+        );
+    }
+
+    #[test]
+    fn explain_lists_attempted_extensions_for_missing_relative_import() {
+        use crate::lang::CandidateOutcome;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        let support = TypeScriptSupport::new(&root);
+        let attempts = support.explain_resolve(&root, "./foo");
+        let tried: Vec<_> = attempts
+            .iter()
+            .map(|a| {
+                (
+                    a.candidate.strip_prefix(&root).unwrap().to_path_buf(),
+                    a.outcome,
+                )
+            })
+            .collect();
+        for name in ["foo", "foo.ts", "foo.tsx", "foo.d.ts", "foo.js", "foo.json"] {
+            assert!(
+                tried.contains(&(PathBuf::from(name), CandidateOutcome::NotAFile)),
+                "{name} missing from {tried:?}"
+            );
+        }
+        assert_eq!(
+            tried.last(),
+            Some(&(PathBuf::from("foo"), CandidateOutcome::NotADirectory))
+        );
+        assert_eq!(support.resolve(&root, "./foo"), None);
+    }
+
+    #[test]
+    fn explain_walks_node_modules_and_stops_at_hit() {
+        use crate::lang::CandidateOutcome;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        let src = root.join("src");
+        fs::create_dir_all(&src).unwrap();
+        fs::create_dir_all(root.join("node_modules/zod")).unwrap();
+        fs::write(root.join("node_modules/zod/index.js"), "").unwrap();
+        let support = TypeScriptSupport::new(&root);
+        let attempts = support.explain_resolve(&src, "zod");
+        assert_eq!(attempts[0].outcome, CandidateOutcome::NotABuiltin);
+        assert_eq!(attempts[1].candidate, src.join("node_modules/zod"));
+        assert_eq!(attempts[1].outcome, CandidateOutcome::NotInNodeModules);
+        let last = attempts.last().unwrap();
+        assert_eq!(last.candidate, root.join("node_modules/zod/index.js"));
+        assert_eq!(last.outcome, CandidateOutcome::Found);
+    }
+
+    #[test]
+    fn workspace_packages_from_package_json_globs() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        fs::write(
+            root.join("package.json"),
+            r#"{"workspaces": {"packages": ["packages/*", "tools/cli", "!packages/skip-*"]}}"#,
+        )
+        .unwrap();
+        setup_workspace(&root);
+        fs::create_dir_all(root.join("packages/skip-me")).unwrap();
+        fs::write(root.join("packages/skip-me/package.json"), "{}").unwrap();
+        fs::create_dir_all(root.join("packages/not-a-package")).unwrap();
+        fs::create_dir_all(root.join("tools/cli")).unwrap();
+        fs::write(root.join("tools/cli/package.json"), "{}").unwrap();
+
+        let nested = root.join("packages/lib/src/index.ts");
+        assert_eq!(find_workspace_root(&nested, &OsVfs), Some(root.clone()));
+        let packages = workspace_packages(&root, &OsVfs);
+        let names: Vec<&str> = packages.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["my-app", "@my/lib", "cli"]);
+        assert_eq!(packages[1].dir, root.join("packages/lib"));
+    }
+
+    #[test]
+    fn pnpm_workspace_yaml_patterns() {
+        let yaml = "packages:\n  - 'packages/*'\n  - \"apps/**\" # comment\n  - '!**/test/**'\ncatalog:\n  - nope\n";
+        assert_eq!(
+            pnpm_workspace_patterns(yaml),
+            ["packages/*", "apps/**", "!**/test/**"]
+        );
+        assert!(segment_matches("skip-*", "skip-me"));
+        assert!(!segment_matches("*-ui", "core"));
+        assert!(path_matches(
+            &["**", "test", "**"],
+            &["apps", "test", "fixture"]
+        ));
+    }
 }
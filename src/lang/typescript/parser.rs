@@ -1,12 +1,39 @@
-use std::path::Path;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
 
+use globset::{GlobBuilder, GlobSetBuilder};
 use oxc_allocator::Allocator;
-use oxc_ast::ast::{Argument, ArrayExpressionElement, Expression, ObjectPropertyKind, Statement};
+use oxc_ast::ast::{
+    Argument, ArrayExpression, ArrayExpressionElement, CallExpression, Class, ClassElement,
+    Declaration, ExportDefaultDeclarationKind, Expression, FormalParameters, Function,
+    ObjectPropertyKind, Statement, TSImportType, TSModuleDeclaration, TSModuleDeclarationBody,
+    TSSignature, TSTupleElement, TSType, TSTypeAnnotation, TSTypeParameterDeclaration,
+    TSTypeParameterInstantiation, TSTypeQueryExprName,
+};
 use oxc_parser::Parser;
-use oxc_span::SourceType;
+use oxc_span::{GetSpan, SourceType, Span};
+use oxc_syntax::operator::{BinaryOperator, LogicalOperator, UnaryOperator};
 
 use crate::graph::EdgeKind;
 use crate::lang::{ParseError, ParseResult, RawImport};
+use crate::vfs::Vfs;
+
+/// File extensions of non-code assets. Imports of these produce
+/// [`EdgeKind::Asset`] edges instead of [`EdgeKind::Static`].
+const ASSET_EXTENSIONS: &[&str] = &[
+    "css", "scss", "sass", "less", "styl", "json", "json5", "jsonc", "toml", "yaml", "yml", "wasm",
+    "svg", "png", "jpg", "jpeg", "gif", "webp", "avif", "ico", "bmp", "woff", "woff2", "ttf",
+    "otf", "eot", "mp3", "mp4", "webm", "wav", "ogg", "txt", "md", "html",
+];
+
+/// Whether a specifier points at a non-code asset, judged by its extension.
+/// Bundler query/hash suffixes (`./icon.svg?url`) are ignored.
+fn is_asset_specifier(specifier: &str) -> bool {
+    let path = specifier.split(['?', '#']).next().unwrap_or(specifier);
+    let file = path.rsplit('/').next().unwrap_or(path);
+    file.rsplit_once('.')
+        .is_some_and(|(stem, ext)| !stem.is_empty() && ASSET_EXTENSIONS.contains(&ext))
+}
 
 /// A raw import tagged with its byte offset in the source for ordering.
 struct PositionedImport {
@@ -14,7 +41,17 @@ struct PositionedImport {
     import: RawImport,
 }
 
+/// Whether `path` is a TypeScript declaration file (`.d.ts`, `.d.mts`, `.d.cts`).
+fn is_declaration_file(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(|n| [".d.ts", ".d.mts", ".d.cts"].iter().any(|s| n.ends_with(s)))
+}
+
 fn source_type_for_path(path: &Path) -> SourceType {
+    if is_declaration_file(path) {
+        return SourceType::d_ts();
+    }
     match path.extension().and_then(|e| e.to_str()) {
         Some("ts") => SourceType::ts(),
         Some("tsx") => SourceType::tsx(),
@@ -24,13 +61,28 @@ fn source_type_for_path(path: &Path) -> SourceType {
     }
 }
 
+/// Parse a file's imports. `defines` maps `process.env` names to values;
+/// `require()`/`import()` calls in branches that are statically false under
+/// them are dropped. `import.meta.glob()` patterns are expanded against the
+/// files `vfs` lists next to `path`.
 #[allow(clippy::unnecessary_wraps)] // trait LanguageSupport::parse requires Result
-pub fn parse_file(path: &Path, source: &str) -> Result<ParseResult, ParseError> {
+pub fn parse_file(
+    path: &Path,
+    source: &str,
+    defines: &[(String, String)],
+    vfs: &dyn Vfs,
+) -> Result<ParseResult, ParseError> {
     let source_type = source_type_for_path(path);
-    Ok(extract_all(source, source_type))
+    let glob = GlobRoot { file: path, vfs };
+    Ok(extract_all(source, source_type, defines, Some(&glob)))
 }
 
-fn extract_all(source: &str, source_type: SourceType) -> ParseResult {
+fn extract_all(
+    source: &str,
+    source_type: SourceType,
+    defines: &[(String, String)],
+    glob: Option<&GlobRoot<'_>>,
+) -> ParseResult {
     let allocator = Allocator::default();
     let ret = Parser::new(&allocator, source, source_type).parse();
 
@@ -39,25 +91,50 @@ fn extract_all(source: &str, source_type: SourceType) -> ParseResult {
 
     // --- Static imports from ModuleRecord ---
     extract_import_entries(&ret.module_record.import_entries, &mut positioned);
+    extract_side_effect_imports(&ret.module_record, &mut positioned);
 
     // --- Re-exports from ModuleRecord ---
     extract_export_entries(&ret.module_record.star_export_entries, &mut positioned);
     extract_export_entries(&ret.module_record.indirect_export_entries, &mut positioned);
 
+    // --- require() calls from AST walking ---
+    let mut ctx = WalkContext {
+        defines,
+        dead: Vec::new(),
+        require_aliases: Vec::new(),
+        glob,
+        glob_dirs: Vec::new(),
+    };
+    for stmt in &ret.program.body {
+        walk_stmt(stmt, &mut positioned, &mut unresolvable_dynamic, &mut ctx);
+    }
+
+    // --- `import("mod").Type` in type annotations ---
+    for stmt in &ret.program.body {
+        walk_type_stmt(stmt, &mut positioned);
+    }
+
     // --- Dynamic imports from ModuleRecord ---
     for di in &ret.module_record.dynamic_imports {
+        if ctx.is_dead(di.span.start) {
+            continue;
+        }
         let start = di.module_request.start as usize;
         let end = di.module_request.end as usize;
         if start < end && end <= source.len() {
             let text = &source[start..end];
-            if text.starts_with('"') || text.starts_with('\'') {
-                // String literal — strip quotes
+            // String literal, or a template literal without `${}` — strip quotes
+            let is_literal = text.starts_with('"')
+                || text.starts_with('\'')
+                || (text.starts_with('`') && !text.contains("${"));
+            if is_literal {
                 let specifier = &text[1..text.len() - 1];
                 positioned.push(PositionedImport {
                     offset: di.span.start,
                     import: RawImport {
                         specifier: specifier.to_string(),
                         kind: EdgeKind::Dynamic,
+                        require: false,
                     },
                 });
             } else {
@@ -68,9 +145,18 @@ fn extract_all(source: &str, source_type: SourceType) -> ParseResult {
         }
     }
 
-    // --- require() calls from AST walking ---
-    for stmt in &ret.program.body {
-        walk_stmt(stmt, &mut positioned, &mut unresolvable_dynamic);
+    // Static imports of stylesheets, JSON, images, etc. load bytes but no code
+    for p in &mut positioned {
+        if p.import.kind == EdgeKind::Static && is_asset_specifier(&p.import.specifier) {
+            p.import.kind = EdgeKind::Asset;
+        }
+    }
+
+    // Declaration files never run, so nothing they import is loaded either
+    if source_type.is_typescript_definition() {
+        for p in &mut positioned {
+            p.import.kind = EdgeKind::TypeOnly;
+        }
     }
 
     // Sort all collected imports by source position
@@ -80,10 +166,14 @@ fn extract_all(source: &str, source_type: SourceType) -> ParseResult {
     // (shouldn't happen since ModuleRecord handles ESM and we only walk for require,
     // but sort is needed for interleaving)
     let imports = positioned.into_iter().map(|p| p.import).collect();
+    let mut glob_dirs = ctx.glob_dirs;
+    glob_dirs.sort();
+    glob_dirs.dedup();
 
     ParseResult {
         imports,
         unresolvable_dynamic,
+        glob_dirs,
     }
 }
 
@@ -126,13 +216,50 @@ fn extract_import_entries(
             import: RawImport {
                 specifier: specifier.to_string(),
                 kind,
+                require: false,
             },
         });
     }
 }
 
+/// Process bare `import "x"` statements. They bind nothing, so they have no
+/// `import_entries` and only show up in `requested_modules`.
+fn extract_side_effect_imports(
+    record: &oxc_syntax::module_record::ModuleRecord<'_>,
+    positioned: &mut Vec<PositionedImport>,
+) {
+    for (specifier, requests) in &record.requested_modules {
+        for request in requests {
+            let stmt_start = request.statement_span.start;
+            if !request.is_import
+                || record
+                    .import_entries
+                    .iter()
+                    .any(|e| e.statement_span.start == stmt_start)
+            {
+                continue;
+            }
+            let kind = if request.is_type {
+                EdgeKind::TypeOnly
+            } else {
+                EdgeKind::Static
+            };
+            positioned.push(PositionedImport {
+                offset: stmt_start,
+                import: RawImport {
+                    specifier: specifier.to_string(),
+                    kind,
+                    require: false,
+                },
+            });
+        }
+    }
+}
+
 /// Process `ModuleRecord` export entries (`star_export_entries` or `indirect_export_entries`),
 /// grouping by `module_request` to determine type-only status.
+/// Renamed re-exports like `export { x as default } from "y"` are indirect
+/// entries too; the `default` export name has no bearing on `is_type`.
 fn extract_export_entries(
     entries: &[oxc_syntax::module_record::ExportEntry<'_>],
     positioned: &mut Vec<PositionedImport>,
@@ -169,172 +296,300 @@ fn extract_export_entries(
             import: RawImport {
                 specifier: specifier.to_string(),
                 kind,
+                require: false,
             },
         });
     }
 }
 
+// --- State carried through the AST walk ---
+
+/// Per-file state for the `require()` walk: `--define` values and the
+/// statically dead branches found under them, local names bound to a
+/// `createRequire(...)` result, and where `import.meta.glob()` looks for
+/// files along with the directories it listed.
+struct WalkContext<'d> {
+    defines: &'d [(String, String)],
+    dead: Vec<Span>,
+    require_aliases: Vec<String>,
+    glob: Option<&'d GlobRoot<'d>>,
+    glob_dirs: Vec<PathBuf>,
+}
+
+impl WalkContext<'_> {
+    fn mark(&mut self, span: Span) {
+        self.dead.push(span);
+    }
+
+    fn is_dead(&self, offset: u32) -> bool {
+        self.dead
+            .iter()
+            .any(|s| s.start <= offset && offset < s.end)
+    }
+
+    /// Whether `callee` is a `require` function: the global one or a local
+    /// `createRequire(...)` result such as `const req = createRequire(...)`.
+    fn is_require(&self, callee: &Expression<'_>) -> bool {
+        match callee {
+            Expression::Identifier(id) => {
+                id.name == "require" || self.require_aliases.iter().any(|a| a == id.name.as_str())
+            }
+            _ => false,
+        }
+    }
+
+    /// Statically evaluate a trivial condition such as
+    /// `process.env.NODE_ENV === "production"`. Returns `None` when the
+    /// condition references anything other than defined env vars and
+    /// string literals.
+    fn eval(&self, expr: &Expression<'_>) -> Option<bool> {
+        if self.defines.is_empty() {
+            return None;
+        }
+        match expr {
+            Expression::ParenthesizedExpression(paren) => self.eval(&paren.expression),
+            Expression::UnaryExpression(unary) if unary.operator == UnaryOperator::LogicalNot => {
+                self.eval(&unary.argument).map(|b| !b)
+            }
+            Expression::LogicalExpression(logical) => {
+                let left = self.eval(&logical.left);
+                let right = self.eval(&logical.right);
+                match logical.operator {
+                    LogicalOperator::And => match (left, right) {
+                        (Some(false), _) | (_, Some(false)) => Some(false),
+                        (Some(true), Some(true)) => Some(true),
+                        _ => None,
+                    },
+                    LogicalOperator::Or => match (left, right) {
+                        (Some(true), _) | (_, Some(true)) => Some(true),
+                        (Some(false), Some(false)) => Some(false),
+                        _ => None,
+                    },
+                    LogicalOperator::Coalesce => None,
+                }
+            }
+            Expression::BinaryExpression(bin) => {
+                let equal = match bin.operator {
+                    BinaryOperator::StrictEquality | BinaryOperator::Equality => true,
+                    BinaryOperator::StrictInequality | BinaryOperator::Inequality => false,
+                    _ => return None,
+                };
+                let (value, literal) = match (&bin.left, &bin.right) {
+                    (env, Expression::StringLiteral(lit))
+                    | (Expression::StringLiteral(lit), env) => {
+                        (self.env_value(env)?, lit.value.as_str())
+                    }
+                    _ => return None,
+                };
+                Some((value == literal) == equal)
+            }
+            _ => None,
+        }
+    }
+
+    /// The defined value of `process.env.X`, if `expr` is such a read.
+    fn env_value(&self, expr: &Expression<'_>) -> Option<&str> {
+        let Expression::StaticMemberExpression(member) = expr else {
+            return None;
+        };
+        let Expression::StaticMemberExpression(env) = &member.object else {
+            return None;
+        };
+        if env.property.name != "env" || !env.object.is_specific_id("process") {
+            return None;
+        }
+        self.defines
+            .iter()
+            .find(|(k, _)| k == member.property.name.as_str())
+            .map(|(_, v)| v.as_str())
+    }
+}
+
 // --- AST walking for require() calls ---
 
-fn walk_stmt(stmt: &Statement<'_>, imports: &mut Vec<PositionedImport>, unresolvable: &mut usize) {
+#[allow(clippy::too_many_lines)]
+fn walk_stmt(
+    stmt: &Statement<'_>,
+    imports: &mut Vec<PositionedImport>,
+    unresolvable: &mut usize,
+    ctx: &mut WalkContext<'_>,
+) {
     match stmt {
         Statement::ExpressionStatement(expr_stmt) => {
-            walk_expr(&expr_stmt.expression, imports, unresolvable);
+            walk_expr(&expr_stmt.expression, imports, unresolvable, ctx);
         }
         Statement::VariableDeclaration(var_decl) => {
             for decl in &var_decl.declarations {
                 if let Some(init) = &decl.init {
-                    walk_expr(init, imports, unresolvable);
+                    if is_create_require(init)
+                        && let Some(name) = decl.id.get_identifier_name()
+                    {
+                        ctx.require_aliases.push(name.to_string());
+                    }
+                    walk_expr(init, imports, unresolvable, ctx);
                 }
             }
         }
         Statement::FunctionDeclaration(fn_decl) => {
             if let Some(body) = &fn_decl.body {
                 for s in &body.statements {
-                    walk_stmt(s, imports, unresolvable);
+                    walk_stmt(s, imports, unresolvable, ctx);
                 }
             }
         }
+        Statement::ClassDeclaration(class) => walk_class(class, imports, unresolvable, ctx),
+        Statement::ExportNamedDeclaration(export) => {
+            if let Some(Declaration::ClassDeclaration(class)) = &export.declaration {
+                walk_class(class, imports, unresolvable, ctx);
+            }
+        }
+        Statement::ExportDefaultDeclaration(export) => {
+            if let ExportDefaultDeclarationKind::ClassDeclaration(class) = &export.declaration {
+                walk_class(class, imports, unresolvable, ctx);
+            }
+        }
         Statement::BlockStatement(block) => {
             for s in &block.body {
-                walk_stmt(s, imports, unresolvable);
+                walk_stmt(s, imports, unresolvable, ctx);
             }
         }
         Statement::IfStatement(if_stmt) => {
-            walk_stmt(&if_stmt.consequent, imports, unresolvable);
+            let test = ctx.eval(&if_stmt.test);
+            if test == Some(false) {
+                ctx.mark(if_stmt.consequent.span());
+            } else {
+                walk_stmt(&if_stmt.consequent, imports, unresolvable, ctx);
+            }
             if let Some(alt) = &if_stmt.alternate {
-                walk_stmt(alt, imports, unresolvable);
+                if test == Some(true) {
+                    ctx.mark(alt.span());
+                } else {
+                    walk_stmt(alt, imports, unresolvable, ctx);
+                }
             }
         }
         Statement::SwitchStatement(switch) => {
-            walk_expr(&switch.discriminant, imports, unresolvable);
+            walk_expr(&switch.discriminant, imports, unresolvable, ctx);
             for case in &switch.cases {
                 for s in &case.consequent {
-                    walk_stmt(s, imports, unresolvable);
+                    walk_stmt(s, imports, unresolvable, ctx);
                 }
             }
         }
         Statement::TryStatement(try_stmt) => {
             for s in &try_stmt.block.body {
-                walk_stmt(s, imports, unresolvable);
+                walk_stmt(s, imports, unresolvable, ctx);
             }
             if let Some(catch) = &try_stmt.handler {
                 for s in &catch.body.body {
-                    walk_stmt(s, imports, unresolvable);
+                    walk_stmt(s, imports, unresolvable, ctx);
                 }
             }
             if let Some(finalizer) = &try_stmt.finalizer {
                 for s in &finalizer.body {
-                    walk_stmt(s, imports, unresolvable);
+                    walk_stmt(s, imports, unresolvable, ctx);
                 }
             }
         }
         Statement::WhileStatement(while_stmt) => {
-            walk_stmt(&while_stmt.body, imports, unresolvable);
+            walk_stmt(&while_stmt.body, imports, unresolvable, ctx);
         }
         Statement::DoWhileStatement(do_while) => {
-            walk_stmt(&do_while.body, imports, unresolvable);
+            walk_stmt(&do_while.body, imports, unresolvable, ctx);
         }
         Statement::ForStatement(for_stmt) => {
-            walk_stmt(&for_stmt.body, imports, unresolvable);
+            walk_stmt(&for_stmt.body, imports, unresolvable, ctx);
         }
         Statement::ForInStatement(for_in) => {
-            walk_stmt(&for_in.body, imports, unresolvable);
+            walk_stmt(&for_in.body, imports, unresolvable, ctx);
         }
         Statement::ForOfStatement(for_of) => {
-            walk_stmt(&for_of.body, imports, unresolvable);
+            walk_stmt(&for_of.body, imports, unresolvable, ctx);
         }
         Statement::ReturnStatement(ret) => {
             if let Some(arg) = &ret.argument {
-                walk_expr(arg, imports, unresolvable);
+                walk_expr(arg, imports, unresolvable, ctx);
             }
         }
         Statement::LabeledStatement(labeled) => {
-            walk_stmt(&labeled.body, imports, unresolvable);
+            walk_stmt(&labeled.body, imports, unresolvable, ctx);
         }
         _ => {}
     }
 }
 
-fn walk_expr(expr: &Expression<'_>, imports: &mut Vec<PositionedImport>, unresolvable: &mut usize) {
+fn walk_expr(
+    expr: &Expression<'_>,
+    imports: &mut Vec<PositionedImport>,
+    unresolvable: &mut usize,
+    ctx: &mut WalkContext<'_>,
+) {
     match expr {
-        Expression::CallExpression(call) => {
-            // require("...")
-            if let Some(str_lit) = call.common_js_require() {
-                imports.push(PositionedImport {
-                    offset: call.span.start,
-                    import: RawImport {
-                        specifier: str_lit.value.to_string(),
-                        kind: EdgeKind::Static,
-                    },
-                });
-                return;
-            }
-            // require(variable) — unresolvable
-            if call.callee.is_specific_id("require") && !call.arguments.is_empty() {
-                *unresolvable += 1;
-                return;
-            }
-            // Walk callee and arguments for nested require/import calls
-            walk_expr(&call.callee, imports, unresolvable);
-            for arg in &call.arguments {
-                walk_argument(arg, imports, unresolvable);
-            }
-        }
+        Expression::CallExpression(call) => walk_call(call, imports, unresolvable, ctx),
         Expression::ArrowFunctionExpression(arrow) => {
             for s in &arrow.body.statements {
-                walk_stmt(s, imports, unresolvable);
+                walk_stmt(s, imports, unresolvable, ctx);
             }
         }
         Expression::FunctionExpression(fn_expr) => {
             if let Some(body) = &fn_expr.body {
                 for s in &body.statements {
-                    walk_stmt(s, imports, unresolvable);
+                    walk_stmt(s, imports, unresolvable, ctx);
                 }
             }
         }
+        Expression::ClassExpression(class) => walk_class(class, imports, unresolvable, ctx),
         Expression::AssignmentExpression(assign) => {
-            walk_expr(&assign.right, imports, unresolvable);
+            walk_expr(&assign.right, imports, unresolvable, ctx);
         }
         Expression::SequenceExpression(seq) => {
             for e in &seq.expressions {
-                walk_expr(e, imports, unresolvable);
+                walk_expr(e, imports, unresolvable, ctx);
             }
         }
         Expression::ParenthesizedExpression(paren) => {
-            walk_expr(&paren.expression, imports, unresolvable);
+            walk_expr(&paren.expression, imports, unresolvable, ctx);
         }
         Expression::AwaitExpression(await_expr) => {
-            walk_expr(&await_expr.argument, imports, unresolvable);
+            walk_expr(&await_expr.argument, imports, unresolvable, ctx);
         }
         Expression::ConditionalExpression(cond) => {
-            walk_expr(&cond.test, imports, unresolvable);
-            walk_expr(&cond.consequent, imports, unresolvable);
-            walk_expr(&cond.alternate, imports, unresolvable);
+            walk_expr(&cond.test, imports, unresolvable, ctx);
+            let test = ctx.eval(&cond.test);
+            let live = test != Some(false);
+            walk_branch(live, &cond.consequent, imports, unresolvable, ctx);
+            let live = test != Some(true);
+            walk_branch(live, &cond.alternate, imports, unresolvable, ctx);
         }
         Expression::BinaryExpression(bin) => {
-            walk_expr(&bin.left, imports, unresolvable);
-            walk_expr(&bin.right, imports, unresolvable);
+            walk_expr(&bin.left, imports, unresolvable, ctx);
+            walk_expr(&bin.right, imports, unresolvable, ctx);
         }
         Expression::LogicalExpression(logical) => {
-            walk_expr(&logical.left, imports, unresolvable);
-            walk_expr(&logical.right, imports, unresolvable);
+            walk_expr(&logical.left, imports, unresolvable, ctx);
+            // `cond && require(..)` / `cond || require(..)` short-circuit
+            let live = match logical.operator {
+                LogicalOperator::And => ctx.eval(&logical.left) != Some(false),
+                LogicalOperator::Or => ctx.eval(&logical.left) != Some(true),
+                LogicalOperator::Coalesce => true,
+            };
+            walk_branch(live, &logical.right, imports, unresolvable, ctx);
         }
         Expression::UnaryExpression(unary) => {
-            walk_expr(&unary.argument, imports, unresolvable);
+            walk_expr(&unary.argument, imports, unresolvable, ctx);
         }
         Expression::StaticMemberExpression(member) => {
-            walk_expr(&member.object, imports, unresolvable);
+            walk_expr(&member.object, imports, unresolvable, ctx);
         }
         Expression::ComputedMemberExpression(member) => {
-            walk_expr(&member.object, imports, unresolvable);
+            walk_expr(&member.object, imports, unresolvable, ctx);
         }
         Expression::ArrayExpression(array) => {
             for elem in &array.elements {
                 if let Some(expr) = elem.as_expression() {
-                    walk_expr(expr, imports, unresolvable);
+                    walk_expr(expr, imports, unresolvable, ctx);
                 } else if let ArrayExpressionElement::SpreadElement(spread) = elem {
-                    walk_expr(&spread.argument, imports, unresolvable);
+                    walk_expr(&spread.argument, imports, unresolvable, ctx);
                 }
             }
         }
@@ -342,17 +597,17 @@ fn walk_expr(expr: &Expression<'_>, imports: &mut Vec<PositionedImport>, unresol
             for prop in &object.properties {
                 match prop {
                     ObjectPropertyKind::ObjectProperty(p) => {
-                        walk_expr(&p.value, imports, unresolvable);
+                        walk_expr(&p.value, imports, unresolvable, ctx);
                     }
                     ObjectPropertyKind::SpreadProperty(spread) => {
-                        walk_expr(&spread.argument, imports, unresolvable);
+                        walk_expr(&spread.argument, imports, unresolvable, ctx);
                     }
                 }
             }
         }
         Expression::TemplateLiteral(tpl) => {
             for expr in &tpl.expressions {
-                walk_expr(expr, imports, unresolvable);
+                walk_expr(expr, imports, unresolvable, ctx);
             }
         }
         // Dynamic import expressions are already handled by ModuleRecord,
@@ -361,26 +616,713 @@ fn walk_expr(expr: &Expression<'_>, imports: &mut Vec<PositionedImport>, unresol
     }
 }
 
+/// Walk a class: its decorators and those of its members and constructor
+/// parameters (where dependency-injection frameworks name their
+/// dependencies), the `extends` expression, initializers, and method bodies.
+fn walk_class(
+    class: &Class<'_>,
+    imports: &mut Vec<PositionedImport>,
+    unresolvable: &mut usize,
+    ctx: &mut WalkContext<'_>,
+) {
+    for decorator in &class.decorators {
+        walk_expr(&decorator.expression, imports, unresolvable, ctx);
+    }
+    if let Some(super_class) = &class.super_class {
+        walk_expr(super_class, imports, unresolvable, ctx);
+    }
+    for element in &class.body.body {
+        match element {
+            ClassElement::MethodDefinition(method) => {
+                for decorator in &method.decorators {
+                    walk_expr(&decorator.expression, imports, unresolvable, ctx);
+                }
+                for param in &method.value.params.items {
+                    for decorator in &param.decorators {
+                        walk_expr(&decorator.expression, imports, unresolvable, ctx);
+                    }
+                }
+                if let Some(body) = &method.value.body {
+                    for s in &body.statements {
+                        walk_stmt(s, imports, unresolvable, ctx);
+                    }
+                }
+            }
+            ClassElement::PropertyDefinition(prop) => {
+                for decorator in &prop.decorators {
+                    walk_expr(&decorator.expression, imports, unresolvable, ctx);
+                }
+                if let Some(value) = &prop.value {
+                    walk_expr(value, imports, unresolvable, ctx);
+                }
+            }
+            ClassElement::AccessorProperty(prop) => {
+                for decorator in &prop.decorators {
+                    walk_expr(&decorator.expression, imports, unresolvable, ctx);
+                }
+                if let Some(value) = &prop.value {
+                    walk_expr(value, imports, unresolvable, ctx);
+                }
+            }
+            ClassElement::StaticBlock(block) => {
+                for s in &block.body {
+                    walk_stmt(s, imports, unresolvable, ctx);
+                }
+            }
+            ClassElement::TSIndexSignature(_) => {}
+        }
+    }
+}
+
+fn walk_call(
+    call: &CallExpression<'_>,
+    imports: &mut Vec<PositionedImport>,
+    unresolvable: &mut usize,
+    ctx: &mut WalkContext<'_>,
+) {
+    // require("...")
+    if let Some(str_lit) = call.common_js_require() {
+        imports.push(PositionedImport {
+            offset: call.span.start,
+            import: RawImport {
+                specifier: str_lit.value.to_string(),
+                kind: EdgeKind::Static,
+                require: true,
+            },
+        });
+        return;
+    }
+    // req("...") where `const req = createRequire(import.meta.url)`,
+    // require(`./x`) with an expression-free template literal, and
+    // Electron's window.require("electron") / globalThis.require("fs")
+    if (ctx.is_require(&call.callee) || is_member_require(&call.callee))
+        && let [arg] = call.arguments.as_slice()
+        && let Some(specifier) = static_specifier(arg)
+    {
+        imports.push(PositionedImport {
+            offset: call.span.start,
+            import: RawImport {
+                specifier,
+                kind: EdgeKind::Static,
+                require: true,
+            },
+        });
+        return;
+    }
+    // Vite: import.meta.glob("./pages/*.ts") and import.meta.globEager(...)
+    if let Some(always_eager) = import_meta_glob(&call.callee) {
+        expand_import_meta_glob(call, always_eager, imports, unresolvable, ctx);
+        return;
+    }
+    // AMD: require(["a", "b"], cb) and define([name,] ["a"], factory)
+    if let Some(deps) = amd_dependencies(call, ctx) {
+        for elem in &deps.elements {
+            match elem {
+                ArrayExpressionElement::StringLiteral(lit) => {
+                    if !AMD_SPECIAL_DEPENDENCIES.contains(&lit.value.as_str()) {
+                        imports.push(PositionedImport {
+                            offset: lit.span.start,
+                            import: RawImport {
+                                specifier: lit.value.to_string(),
+                                kind: EdgeKind::Static,
+                                require: false,
+                            },
+                        });
+                    }
+                }
+                ArrayExpressionElement::Elision(_) => {}
+                _ => *unresolvable += 1,
+            }
+        }
+        for arg in &call.arguments {
+            if !matches!(arg, Argument::ArrayExpression(_)) {
+                walk_argument(arg, imports, unresolvable, ctx);
+            }
+        }
+        return;
+    }
+    // require(variable) — unresolvable
+    if ctx.is_require(&call.callee) && !call.arguments.is_empty() {
+        *unresolvable += 1;
+        return;
+    }
+    // Walk callee and arguments for nested require/import calls
+    walk_expr(&call.callee, imports, unresolvable, ctx);
+    for arg in &call.arguments {
+        walk_argument(arg, imports, unresolvable, ctx);
+    }
+}
+
+/// AMD dependency names that the loader supplies itself rather than loading.
+const AMD_SPECIAL_DEPENDENCIES: &[&str] = &["require", "exports", "module"];
+
+/// The dependency array of an AMD `require([deps], cb)` or
+/// `define([id,] [deps], factory)` call.
+fn amd_dependencies<'a>(
+    call: &'a CallExpression<'a>,
+    ctx: &WalkContext<'_>,
+) -> Option<&'a ArrayExpression<'a>> {
+    let args = call.arguments.as_slice();
+    let deps = if ctx.is_require(&call.callee) {
+        args.first()?
+    } else if matches!(&call.callee, Expression::Identifier(id) if id.name == "define") {
+        match args {
+            // The module id, when present, comes first
+            [Argument::StringLiteral(_), deps, ..] | [deps, ..] => deps,
+            [] => return None,
+        }
+    } else {
+        return None;
+    };
+    match deps {
+        Argument::ArrayExpression(array) => Some(array),
+        _ => None,
+    }
+}
+
+/// A `require()` argument that is a plain string: a string literal or a
+/// template literal with no `${}` expressions.
+fn static_specifier(arg: &Argument<'_>) -> Option<String> {
+    match arg {
+        Argument::StringLiteral(lit) => Some(lit.value.to_string()),
+        Argument::TemplateLiteral(tpl) if tpl.expressions.is_empty() => {
+            Some(tpl.quasis.first()?.value.cooked?.to_string())
+        }
+        _ => None,
+    }
+}
+
+/// A `require` reached through an object, as Electron renderers and
+/// bundler-hidden code do: `window.require`, `globalThis.require`.
+fn is_member_require(callee: &Expression<'_>) -> bool {
+    matches!(callee, Expression::StaticMemberExpression(member) if member.property.name == "require")
+}
+
+/// Whether `callee` is Vite's `import.meta.glob` (`Some(false)`) or the
+/// deprecated, always-eager `import.meta.globEager` (`Some(true)`).
+fn import_meta_glob(callee: &Expression<'_>) -> Option<bool> {
+    let Expression::StaticMemberExpression(member) = callee else {
+        return None;
+    };
+    let Expression::MetaProperty(meta) = &member.object else {
+        return None;
+    };
+    if meta.meta.name != "import" || meta.property.name != "meta" {
+        return None;
+    }
+    match member.property.name.as_str() {
+        "glob" => Some(false),
+        "globEager" => Some(true),
+        _ => None,
+    }
+}
+
+/// Emit one import per file an `import.meta.glob()` call matches, in path
+/// order. Eager globs load their modules up front; lazy ones (the default)
+/// produce `() => import(...)` thunks, so they become dynamic edges.
+fn expand_import_meta_glob(
+    call: &CallExpression<'_>,
+    always_eager: bool,
+    imports: &mut Vec<PositionedImport>,
+    unresolvable: &mut usize,
+    ctx: &mut WalkContext<'_>,
+) {
+    let matches = call
+        .arguments
+        .first()
+        .and_then(glob_patterns)
+        .zip(ctx.glob)
+        .and_then(|(patterns, root)| root.expand(&patterns, &mut ctx.glob_dirs));
+    let Some(matches) = matches else {
+        *unresolvable += 1;
+        return;
+    };
+    let eager = always_eager || call.arguments.get(1).is_some_and(is_eager_option);
+    let kind = if eager {
+        EdgeKind::Static
+    } else {
+        EdgeKind::Dynamic
+    };
+    for specifier in matches {
+        imports.push(PositionedImport {
+            offset: call.span.start,
+            import: RawImport {
+                specifier,
+                kind,
+                require: false,
+            },
+        });
+    }
+}
+
+/// The patterns of an `import.meta.glob()` call: one string or an array of
+/// strings. `None` when any of them is not a literal.
+fn glob_patterns(arg: &Argument<'_>) -> Option<Vec<String>> {
+    match arg {
+        Argument::ArrayExpression(array) => array
+            .elements
+            .iter()
+            .map(|elem| match elem {
+                ArrayExpressionElement::StringLiteral(lit) => Some(lit.value.to_string()),
+                _ => None,
+            })
+            .collect(),
+        _ => static_specifier(arg).map(|p| vec![p]),
+    }
+}
+
+/// `{ eager: true }` as the options argument of `import.meta.glob()`.
+fn is_eager_option(arg: &Argument<'_>) -> bool {
+    let Argument::ObjectExpression(obj) = arg else {
+        return false;
+    };
+    obj.properties.iter().any(|prop| match prop {
+        ObjectPropertyKind::ObjectProperty(prop) => {
+            prop.key.is_specific_static_name("eager")
+                && matches!(&prop.value, Expression::BooleanLiteral(b) if b.value)
+        }
+        ObjectPropertyKind::SpreadProperty(_) => false,
+    })
+}
+
+/// The file being parsed, for expanding `import.meta.glob()` patterns
+/// relative to its directory.
+struct GlobRoot<'a> {
+    file: &'a Path,
+    vfs: &'a dyn Vfs,
+}
+
+impl GlobRoot<'_> {
+    /// The relative specifiers (`./pages/a.ts`) matching `patterns`, sorted.
+    /// Patterns starting with `!` exclude matches. `None` when a pattern is
+    /// not relative to the file (`/src/*.ts`, aliases) or is not a valid glob.
+    /// Every directory looked in is added to `dirs`, so the cache can tell
+    /// when a match is added or removed.
+    fn expand(&self, patterns: &[String], dirs: &mut Vec<PathBuf>) -> Option<Vec<String>> {
+        let dir = self.file.parent()?;
+        let mut include = GlobSetBuilder::new();
+        let mut exclude = GlobSetBuilder::new();
+        let mut walks = Vec::new();
+        for pattern in patterns {
+            let (negated, pattern) = match pattern.strip_prefix('!') {
+                Some(rest) => (true, rest),
+                None => (false, pattern.as_str()),
+            };
+            if !pattern.starts_with("./") && !pattern.starts_with("../") {
+                return None;
+            }
+            let glob = GlobBuilder::new(pattern)
+                .literal_separator(true)
+                .build()
+                .ok()?;
+            if negated {
+                exclude.add(glob);
+            } else {
+                include.add(glob);
+                walks.push(glob_walk_start(pattern));
+            }
+        }
+        if walks.is_empty() {
+            return None;
+        }
+        let include = include.build().ok()?;
+        let exclude = exclude.build().ok()?;
+
+        let mut found = Vec::new();
+        for (prefix, depth) in walks {
+            let mut base = dir.to_path_buf();
+            for part in prefix.split('/') {
+                match part {
+                    "." => {}
+                    ".." => {
+                        base.pop();
+                    }
+                    name => base.push(name),
+                }
+            }
+            collect_glob_files(self.vfs, &base, prefix, depth, &mut found, dirs);
+        }
+        let matched: BTreeSet<String> = found
+            .into_iter()
+            .filter(|(spec, path)| {
+                include.is_match(spec) && !exclude.is_match(spec) && path != self.file
+            })
+            .map(|(spec, _)| spec)
+            .collect();
+        Some(matched.into_iter().collect())
+    }
+}
+
+/// The literal directory a glob pattern starts in (`./pages` for
+/// `./pages/**/*.ts`) and how many levels below it can match: `None` when
+/// the pattern contains `**`.
+fn glob_walk_start(pattern: &str) -> (&str, Option<usize>) {
+    let parts: Vec<&str> = pattern.split('/').collect();
+    let literal = parts
+        .iter()
+        .take_while(|p| !p.contains(['*', '?', '[', '{']))
+        .count()
+        .min(parts.len() - 1);
+    let prefix_len = parts[..literal].iter().map(|p| p.len() + 1).sum::<usize>() - 1;
+    let depth = (!pattern.contains("**")).then_some(parts.len() - literal);
+    (&pattern[..prefix_len], depth)
+}
+
+/// Collect `(specifier, path)` for every file under `dir` down to `depth`
+/// levels, skipping dot entries and `node_modules` as Vite does. Each
+/// directory visited, even a missing one, is added to `dirs`.
+fn collect_glob_files(
+    vfs: &dyn Vfs,
+    dir: &Path,
+    specifier: &str,
+    depth: Option<usize>,
+    found: &mut Vec<(String, PathBuf)>,
+    dirs: &mut Vec<PathBuf>,
+) {
+    dirs.push(dir.to_path_buf());
+    let Ok(entries) = vfs.read_dir(dir) else {
+        return;
+    };
+    for entry in entries {
+        let Some(name) = entry.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        if name.starts_with('.') || name == "node_modules" {
+            continue;
+        }
+        let child = format!("{specifier}/{name}");
+        if vfs.is_dir(&entry) {
+            if depth != Some(1) {
+                collect_glob_files(vfs, &entry, &child, depth.map(|d| d - 1), found, dirs);
+            }
+        } else {
+            found.push((child, entry));
+        }
+    }
+}
+
+/// `createRequire(...)` or `module.createRequire(...)` from `node:module`.
+fn is_create_require(expr: &Expression<'_>) -> bool {
+    let Expression::CallExpression(call) = expr.without_parentheses() else {
+        return false;
+    };
+    match &call.callee {
+        Expression::Identifier(id) => id.name == "createRequire",
+        Expression::StaticMemberExpression(member) => member.property.name == "createRequire",
+        _ => false,
+    }
+}
+
+/// Walk `expr` if its branch can run, otherwise record it as dead.
+fn walk_branch(
+    live: bool,
+    expr: &Expression<'_>,
+    imports: &mut Vec<PositionedImport>,
+    unresolvable: &mut usize,
+    ctx: &mut WalkContext<'_>,
+) {
+    if live {
+        walk_expr(expr, imports, unresolvable, ctx);
+    } else {
+        ctx.mark(expr.span());
+    }
+}
+
 fn walk_argument(
     arg: &Argument<'_>,
     imports: &mut Vec<PositionedImport>,
     unresolvable: &mut usize,
+    ctx: &mut WalkContext<'_>,
 ) {
     if let Some(expr) = arg.as_expression() {
-        walk_expr(expr, imports, unresolvable);
+        walk_expr(expr, imports, unresolvable, ctx);
     } else if let Argument::SpreadElement(spread) = arg {
-        walk_expr(&spread.argument, imports, unresolvable);
+        walk_expr(&spread.argument, imports, unresolvable, ctx);
+    }
+}
+
+// --- AST walking for `import("mod").Type` type queries ---
+//
+// Import types live in type annotations, which the statement walker above
+// never looks at. This covers declarations (aliases, interfaces, variables,
+// functions, classes, namespaces) and the function bodies inside them, but
+// not types nested in expressions such as `x as import("a").T`.
+
+fn walk_type_stmt(stmt: &Statement<'_>, imports: &mut Vec<PositionedImport>) {
+    if let Some(decl) = stmt.as_declaration() {
+        walk_type_decl(decl, imports);
+        return;
+    }
+    match stmt {
+        Statement::ExportNamedDeclaration(export) => {
+            if let Some(decl) = &export.declaration {
+                walk_type_decl(decl, imports);
+            }
+        }
+        Statement::ExportDefaultDeclaration(export) => match &export.declaration {
+            ExportDefaultDeclarationKind::FunctionDeclaration(func) => {
+                walk_type_function(func, imports);
+            }
+            ExportDefaultDeclarationKind::ClassDeclaration(class) => {
+                walk_type_class(class, imports);
+            }
+            ExportDefaultDeclarationKind::TSInterfaceDeclaration(iface) => {
+                walk_type_params(iface.type_parameters.as_deref(), imports);
+                for heritage in &iface.extends {
+                    walk_type_args(heritage.type_arguments.as_deref(), imports);
+                }
+                walk_signatures(&iface.body.body, imports);
+            }
+            _ => {}
+        },
+        _ => {}
+    }
+}
+
+fn walk_type_decl(decl: &Declaration<'_>, imports: &mut Vec<PositionedImport>) {
+    match decl {
+        Declaration::TSTypeAliasDeclaration(alias) => {
+            walk_type_params(alias.type_parameters.as_deref(), imports);
+            walk_type(&alias.type_annotation, imports);
+        }
+        Declaration::TSInterfaceDeclaration(iface) => {
+            walk_type_params(iface.type_parameters.as_deref(), imports);
+            for heritage in &iface.extends {
+                walk_type_args(heritage.type_arguments.as_deref(), imports);
+            }
+            walk_signatures(&iface.body.body, imports);
+        }
+        Declaration::VariableDeclaration(var_decl) => {
+            for d in &var_decl.declarations {
+                walk_annotation(d.type_annotation.as_deref(), imports);
+            }
+        }
+        Declaration::FunctionDeclaration(func) => walk_type_function(func, imports),
+        Declaration::ClassDeclaration(class) => walk_type_class(class, imports),
+        Declaration::TSModuleDeclaration(module) => walk_type_module(module, imports),
+        Declaration::TSGlobalDeclaration(global) => {
+            for s in &global.body.body {
+                walk_type_stmt(s, imports);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn walk_type_module(module: &TSModuleDeclaration<'_>, imports: &mut Vec<PositionedImport>) {
+    match &module.body {
+        Some(TSModuleDeclarationBody::TSModuleBlock(block)) => {
+            for s in &block.body {
+                walk_type_stmt(s, imports);
+            }
+        }
+        Some(TSModuleDeclarationBody::TSModuleDeclaration(nested)) => {
+            walk_type_module(nested, imports);
+        }
+        None => {}
+    }
+}
+
+fn walk_type_function(func: &Function<'_>, imports: &mut Vec<PositionedImport>) {
+    walk_type_params(func.type_parameters.as_deref(), imports);
+    walk_type_formals(&func.params, imports);
+    walk_annotation(func.return_type.as_deref(), imports);
+    if let Some(body) = &func.body {
+        for s in &body.statements {
+            walk_type_stmt(s, imports);
+        }
+    }
+}
+
+fn walk_type_class(class: &Class<'_>, imports: &mut Vec<PositionedImport>) {
+    walk_type_params(class.type_parameters.as_deref(), imports);
+    walk_type_args(class.super_type_arguments.as_deref(), imports);
+    for implements in &class.implements {
+        walk_type_args(implements.type_arguments.as_deref(), imports);
+    }
+    for element in &class.body.body {
+        match element {
+            ClassElement::MethodDefinition(method) => walk_type_function(&method.value, imports),
+            ClassElement::PropertyDefinition(prop) => {
+                walk_annotation(prop.type_annotation.as_deref(), imports);
+            }
+            ClassElement::AccessorProperty(prop) => {
+                walk_annotation(prop.type_annotation.as_deref(), imports);
+            }
+            ClassElement::StaticBlock(_) | ClassElement::TSIndexSignature(_) => {}
+        }
+    }
+}
+
+fn walk_type_formals(params: &FormalParameters<'_>, imports: &mut Vec<PositionedImport>) {
+    for param in &params.items {
+        walk_annotation(param.type_annotation.as_deref(), imports);
+    }
+    if let Some(rest) = &params.rest {
+        walk_annotation(rest.type_annotation.as_deref(), imports);
+    }
+}
+
+fn walk_signatures(signatures: &[TSSignature<'_>], imports: &mut Vec<PositionedImport>) {
+    for sig in signatures {
+        match sig {
+            TSSignature::TSIndexSignature(index) => {
+                walk_annotation(Some(&index.type_annotation), imports);
+            }
+            TSSignature::TSPropertySignature(prop) => {
+                walk_annotation(prop.type_annotation.as_deref(), imports);
+            }
+            TSSignature::TSCallSignatureDeclaration(call) => {
+                walk_type_params(call.type_parameters.as_deref(), imports);
+                walk_type_formals(&call.params, imports);
+                walk_annotation(call.return_type.as_deref(), imports);
+            }
+            TSSignature::TSConstructSignatureDeclaration(ctor) => {
+                walk_type_params(ctor.type_parameters.as_deref(), imports);
+                walk_type_formals(&ctor.params, imports);
+                walk_annotation(ctor.return_type.as_deref(), imports);
+            }
+            TSSignature::TSMethodSignature(method) => {
+                walk_type_params(method.type_parameters.as_deref(), imports);
+                walk_type_formals(&method.params, imports);
+                walk_annotation(method.return_type.as_deref(), imports);
+            }
+        }
+    }
+}
+
+fn walk_annotation(annotation: Option<&TSTypeAnnotation<'_>>, imports: &mut Vec<PositionedImport>) {
+    if let Some(annotation) = annotation {
+        walk_type(&annotation.type_annotation, imports);
+    }
+}
+
+fn walk_type_params(
+    params: Option<&TSTypeParameterDeclaration<'_>>,
+    imports: &mut Vec<PositionedImport>,
+) {
+    for param in params.iter().flat_map(|p| &p.params) {
+        if let Some(constraint) = &param.constraint {
+            walk_type(constraint, imports);
+        }
+        if let Some(default) = &param.default {
+            walk_type(default, imports);
+        }
     }
 }
 
+fn walk_type_args(
+    args: Option<&TSTypeParameterInstantiation<'_>>,
+    imports: &mut Vec<PositionedImport>,
+) {
+    for ty in args.iter().flat_map(|a| &a.params) {
+        walk_type(ty, imports);
+    }
+}
+
+fn walk_type(ty: &TSType<'_>, imports: &mut Vec<PositionedImport>) {
+    match ty {
+        TSType::TSImportType(import) => push_import_type(import, imports),
+        TSType::TSTypeQuery(query) => {
+            if let TSTypeQueryExprName::TSImportType(import) = &query.expr_name {
+                push_import_type(import, imports);
+            }
+            walk_type_args(query.type_arguments.as_deref(), imports);
+        }
+        TSType::TSTypeReference(reference) => {
+            walk_type_args(reference.type_arguments.as_deref(), imports);
+        }
+        TSType::TSArrayType(array) => walk_type(&array.element_type, imports),
+        TSType::TSConditionalType(cond) => {
+            walk_type(&cond.check_type, imports);
+            walk_type(&cond.extends_type, imports);
+            walk_type(&cond.true_type, imports);
+            walk_type(&cond.false_type, imports);
+        }
+        TSType::TSFunctionType(func) => {
+            walk_type_params(func.type_parameters.as_deref(), imports);
+            walk_type_formals(&func.params, imports);
+            walk_annotation(Some(&func.return_type), imports);
+        }
+        TSType::TSConstructorType(ctor) => {
+            walk_type_params(ctor.type_parameters.as_deref(), imports);
+            walk_type_formals(&ctor.params, imports);
+            walk_annotation(Some(&ctor.return_type), imports);
+        }
+        TSType::TSIndexedAccessType(access) => {
+            walk_type(&access.object_type, imports);
+            walk_type(&access.index_type, imports);
+        }
+        TSType::TSUnionType(union) => {
+            for t in &union.types {
+                walk_type(t, imports);
+            }
+        }
+        TSType::TSIntersectionType(intersection) => {
+            for t in &intersection.types {
+                walk_type(t, imports);
+            }
+        }
+        TSType::TSMappedType(mapped) => {
+            walk_type(&mapped.constraint, imports);
+            for t in mapped.name_type.iter().chain(&mapped.type_annotation) {
+                walk_type(t, imports);
+            }
+        }
+        TSType::TSTupleType(tuple) => {
+            for element in &tuple.element_types {
+                walk_tuple_element(element, imports);
+            }
+        }
+        TSType::TSNamedTupleMember(member) => walk_tuple_element(&member.element_type, imports),
+        TSType::TSTypeLiteral(literal) => walk_signatures(&literal.members, imports),
+        TSType::TSTypeOperatorType(op) => walk_type(&op.type_annotation, imports),
+        TSType::TSParenthesizedType(paren) => walk_type(&paren.type_annotation, imports),
+        TSType::TSTypePredicate(predicate) => {
+            walk_annotation(predicate.type_annotation.as_deref(), imports);
+        }
+        TSType::TSInferType(infer) => {
+            if let Some(constraint) = &infer.type_parameter.constraint {
+                walk_type(constraint, imports);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn walk_tuple_element(element: &TSTupleElement<'_>, imports: &mut Vec<PositionedImport>) {
+    match element {
+        TSTupleElement::TSOptionalType(opt) => walk_type(&opt.type_annotation, imports),
+        TSTupleElement::TSRestType(rest) => walk_type(&rest.type_annotation, imports),
+        _ => {
+            if let Some(ty) = element.as_ts_type() {
+                walk_type(ty, imports);
+            }
+        }
+    }
+}
+
+fn push_import_type(import: &TSImportType<'_>, imports: &mut Vec<PositionedImport>) {
+    imports.push(PositionedImport {
+        offset: import.span.start,
+        import: RawImport {
+            specifier: import.source.value.to_string(),
+            kind: EdgeKind::TypeOnly,
+            require: false,
+        },
+    });
+    walk_type_args(import.type_arguments.as_deref(), imports);
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::vfs::OsVfs;
 
     /// Parse TypeScript source and extract imports without touching the filesystem.
     fn parse_ts(source: &str) -> Vec<RawImport> {
         let source_type = SourceType::ts();
-        extract_all(source, source_type).imports
+        extract_all(source, source_type, &[], None).imports
     }
 
     // --- Static imports ---
@@ -425,6 +1367,73 @@ mod tests {
         assert_eq!(imports[0].kind, EdgeKind::Static);
     }
 
+    #[test]
+    fn side_effect_import() {
+        let imports = parse_ts(r#"import "./polyfills"; import { a } from "./a";"#);
+        assert_eq!(imports.len(), 2);
+        assert_eq!(imports[0].specifier, "./polyfills");
+        assert_eq!(imports[0].kind, EdgeKind::Static);
+        assert_eq!(imports[1].specifier, "./a");
+    }
+
+    // --- Shebang ---
+
+    #[test]
+    fn shebang_entry_keeps_imports() {
+        let source = "#!/usr/bin/env node\nimport { run } from \"./cli\";\nrequire(\"chalk\");\n";
+        for path in ["bin/cli.js", "bin/cli.ts", "bin/cli.mjs"] {
+            let result = parse_file(Path::new(path), source, &[], &OsVfs).unwrap();
+            let specifiers: Vec<&str> = result
+                .imports
+                .iter()
+                .map(|i| i.specifier.as_str())
+                .collect();
+            assert_eq!(specifiers, ["./cli", "chalk"], "{path}");
+        }
+    }
+
+    // --- Asset imports ---
+
+    #[test]
+    fn asset_side_effect_import() {
+        let imports = parse_ts(r#"import "./styles.css";"#);
+        assert_eq!(imports.len(), 1);
+        assert_eq!(imports[0].specifier, "./styles.css");
+        assert_eq!(imports[0].kind, EdgeKind::Asset);
+    }
+
+    #[test]
+    fn asset_default_import_and_require() {
+        let imports =
+            parse_ts(r#"import logo from "./logo.svg?url"; const data = require("./data.json");"#);
+        assert_eq!(imports.len(), 2);
+        assert_eq!(imports[0].kind, EdgeKind::Asset);
+        assert_eq!(imports[1].specifier, "./data.json");
+        assert_eq!(imports[1].kind, EdgeKind::Asset);
+    }
+
+    #[test]
+    fn config_file_imports_are_assets() {
+        let imports = parse_ts(
+            r#"import a from "./a.json5"; import b from "./b.jsonc"; import c from "./c.toml"; import d from "./d.yaml";"#,
+        );
+        assert_eq!(imports.len(), 4);
+        assert!(imports.iter().all(|i| i.kind == EdgeKind::Asset));
+    }
+
+    #[test]
+    fn asset_dynamic_import_stays_dynamic() {
+        let imports = parse_ts(r#"const m = import("./big.json");"#);
+        assert_eq!(imports.len(), 1);
+        assert_eq!(imports[0].kind, EdgeKind::Dynamic);
+    }
+
+    #[test]
+    fn package_named_like_extension_is_not_asset() {
+        let imports = parse_ts(r#"import "highlight.js"; import x from "chart.css-parser";"#);
+        assert!(imports.iter().all(|i| i.kind == EdgeKind::Static));
+    }
+
     // --- Type-only imports ---
 
     #[test]
@@ -451,6 +1460,61 @@ mod tests {
         assert_eq!(imports[0].kind, EdgeKind::Static);
     }
 
+    #[test]
+    fn import_type_query_is_type_only() {
+        let imports = parse_ts(r#"type X = import("lib").Foo;"#);
+        assert_eq!(imports.len(), 1);
+        assert_eq!(imports[0].specifier, "lib");
+        assert_eq!(imports[0].kind, EdgeKind::TypeOnly);
+    }
+
+    #[test]
+    fn import_types_in_nested_annotations() {
+        let source = r#"
+            export function f(a: Array<import("./a").A>): typeof import("./b") {
+                return null!;
+            }
+            export class C {
+                field: { x: import("./c").C } | null = null;
+            }
+            const y: Promise<void> = import("./d");
+        "#;
+        let imports: Vec<_> = parse_ts(source)
+            .into_iter()
+            .map(|i| (i.specifier, i.kind))
+            .collect();
+        assert_eq!(
+            imports,
+            vec![
+                ("./a".to_string(), EdgeKind::TypeOnly),
+                ("./b".to_string(), EdgeKind::TypeOnly),
+                ("./c".to_string(), EdgeKind::TypeOnly),
+                ("./d".to_string(), EdgeKind::Dynamic),
+            ]
+        );
+    }
+
+    #[test]
+    fn declaration_file_imports_are_type_only() {
+        let source = r#"
+            import { Foo } from "./foo";
+            import "./side-effect";
+            export * from "./bar";
+            export { Baz as default } from "baz";
+            import styles from "./theme.css";
+        "#;
+        let result = parse_file(Path::new("types/index.d.ts"), source, &[], &OsVfs).unwrap();
+        assert_eq!(result.imports.len(), 5);
+        assert!(
+            result.imports.iter().all(|i| i.kind == EdgeKind::TypeOnly),
+            "{:?}",
+            result.imports
+        );
+        // Same source in a regular module keeps its runtime kinds
+        let result = parse_file(Path::new("types/index.ts"), source, &[], &OsVfs).unwrap();
+        assert_eq!(result.imports[0].kind, EdgeKind::Static);
+    }
+
     #[test]
     fn type_only_reexport() {
         let imports = parse_ts(r#"export type { Foo } from "bar";"#);
@@ -459,6 +1523,35 @@ mod tests {
         assert_eq!(imports[0].kind, EdgeKind::TypeOnly);
     }
 
+    #[test]
+    fn reexport_renamed_to_default() {
+        let imports = parse_ts(r#"export { foo as default } from "y";"#);
+        assert_eq!(imports.len(), 1);
+        assert_eq!(imports[0].specifier, "y");
+        assert_eq!(imports[0].kind, EdgeKind::Static);
+    }
+
+    #[test]
+    fn reexport_default_barrel() {
+        let imports = parse_ts(
+            r#"export { default } from "./button";
+export { default as Icon, type IconProps } from "./icon";
+export { type Theme as default } from "./theme";"#,
+        );
+        let kinds: Vec<(&str, EdgeKind)> = imports
+            .iter()
+            .map(|i| (i.specifier.as_str(), i.kind))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ("./button", EdgeKind::Static),
+                ("./icon", EdgeKind::Static),
+                ("./theme", EdgeKind::TypeOnly),
+            ]
+        );
+    }
+
     // --- Dynamic imports ---
 
     #[test]
@@ -486,6 +1579,25 @@ mod tests {
         assert_eq!(imports[0].kind, EdgeKind::Dynamic);
     }
 
+    #[test]
+    fn dynamic_import_in_react_lazy() {
+        let imports = parse_ts(r#"const X = React.lazy(() => import("./X"));"#);
+        assert_eq!(imports.len(), 1);
+        assert_eq!(imports[0].specifier, "./X");
+        assert_eq!(imports[0].kind, EdgeKind::Dynamic);
+    }
+
+    #[test]
+    fn dynamic_import_in_loadable() {
+        let imports = parse_ts(
+            r#"import loadable from "@loadable/component";
+const Y = loadable(() => import("./Y"), { fallback: null });"#,
+        );
+        assert_eq!(imports.len(), 2);
+        assert_eq!(imports[1].specifier, "./Y");
+        assert_eq!(imports[1].kind, EdgeKind::Dynamic);
+    }
+
     #[test]
     fn require_in_if_block() {
         let imports = parse_ts(r#"if (cond) { const x = require("bar"); }"#);
@@ -531,6 +1643,87 @@ mod tests {
 
     // --- Negative cases (should NOT extract) ---
 
+    #[test]
+    fn member_require_is_static() {
+        let imports = parse_ts(
+            r#"const { ipcRenderer } = window.require("electron");
+            const fs = globalThis.require("fs");
+            const p = window.requireModule("not-a-require");
+            const q = window.require.resolve("not-a-require-either");"#,
+        );
+        let specs: Vec<_> = imports
+            .iter()
+            .map(|i| (i.specifier.as_str(), i.kind))
+            .collect();
+        assert_eq!(
+            specs,
+            [("electron", EdgeKind::Static), ("fs", EdgeKind::Static)]
+        );
+    }
+
+    #[test]
+    fn import_meta_glob_expands_to_matched_files() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        std::fs::create_dir_all(root.join("pages/admin")).unwrap();
+        for file in [
+            "pages/a.ts",
+            "pages/b.ts",
+            "pages/skip.ts",
+            "pages/admin/c.ts",
+        ] {
+            std::fs::write(root.join(file), "").unwrap();
+        }
+        let source = r#"const lazy = import.meta.glob("./pages/*.ts");
+const eager = import.meta.glob(["./pages/**/*.ts", "!./pages/skip.ts"], { eager: true });
+const legacy = import.meta.globEager("./pages/admin/*.ts");
+const unknown = import.meta.glob(pattern);"#;
+        let result = parse_file(&root.join("main.ts"), source, &[], &OsVfs).unwrap();
+        let imports: Vec<(&str, EdgeKind)> = result
+            .imports
+            .iter()
+            .map(|i| (i.specifier.as_str(), i.kind))
+            .collect();
+        assert_eq!(
+            imports,
+            [
+                ("./pages/a.ts", EdgeKind::Dynamic),
+                ("./pages/b.ts", EdgeKind::Dynamic),
+                ("./pages/skip.ts", EdgeKind::Dynamic),
+                ("./pages/a.ts", EdgeKind::Static),
+                ("./pages/admin/c.ts", EdgeKind::Static),
+                ("./pages/b.ts", EdgeKind::Static),
+                ("./pages/admin/c.ts", EdgeKind::Static),
+            ]
+        );
+        assert_eq!(result.unresolvable_dynamic, 1);
+    }
+
+    #[test]
+    fn imports_inside_decorators_followed() {
+        let imports = parse_ts(
+            r#"@Module({ imports: [lazy(() => import("./feature.module"))] })
+export class AppModule {
+    constructor(@Inject(require("./tokens").CONFIG) config: Config) {}
+
+    @UseGuards(require("./auth.guard"))
+    handle() {}
+}"#,
+        );
+        let specs: Vec<(&str, EdgeKind)> = imports
+            .iter()
+            .map(|i| (i.specifier.as_str(), i.kind))
+            .collect();
+        assert_eq!(
+            specs,
+            [
+                ("./feature.module", EdgeKind::Dynamic),
+                ("./tokens", EdgeKind::Static),
+                ("./auth.guard", EdgeKind::Static),
+            ]
+        );
+    }
+
     #[test]
     fn require_resolve_not_extracted() {
         let imports = parse_ts(r#"const p = require.resolve("pkg");"#);
@@ -608,7 +1801,7 @@ mod tests {
     #[test]
     fn dynamic_import_variable_unresolvable() {
         let source_type = SourceType::ts();
-        let result = extract_all("const m = import(someVar);", source_type);
+        let result = extract_all("const m = import(someVar);", source_type, &[], None);
         assert_eq!(result.imports.len(), 0);
         assert_eq!(result.unresolvable_dynamic, 1);
     }
@@ -616,17 +1809,180 @@ mod tests {
     #[test]
     fn require_variable_unresolvable() {
         let source_type = SourceType::mjs();
-        let result = extract_all("const m = require(moduleName);", source_type);
+        let result = extract_all("const m = require(moduleName);", source_type, &[], None);
         assert_eq!(result.imports.len(), 0);
         assert_eq!(result.unresolvable_dynamic, 1);
     }
 
+    #[test]
+    fn amd_require_array_emits_each_dependency() {
+        let source_type = SourceType::mjs();
+        let result = extract_all(
+            r#"require(["./a", "b", dep], function (a, b) { require("./c"); });"#,
+            source_type,
+            &[],
+            None,
+        );
+        let imports: Vec<(&str, EdgeKind)> = result
+            .imports
+            .iter()
+            .map(|i| (i.specifier.as_str(), i.kind))
+            .collect();
+        assert_eq!(
+            imports,
+            [
+                ("./a", EdgeKind::Static),
+                ("b", EdgeKind::Static),
+                ("./c", EdgeKind::Static)
+            ]
+        );
+        assert_eq!(result.unresolvable_dynamic, 1);
+    }
+
+    #[test]
+    fn amd_define_skips_loader_supplied_dependencies() {
+        let source_type = SourceType::mjs();
+        let result = extract_all(
+            r#"define("mod", ["require", "exports", "./dep"], function (require, exports, dep) {});"#,
+            source_type,
+            &[],
+            None,
+        );
+        assert_eq!(result.imports.len(), 1);
+        assert_eq!(result.imports[0].specifier, "./dep");
+        assert_eq!(result.unresolvable_dynamic, 0);
+    }
+
     #[test]
     fn dynamic_import_literal_still_works_ts() {
         let source_type = SourceType::ts();
-        let result = extract_all(r#"const m = import("./foo");"#, source_type);
+        let result = extract_all(r#"const m = import("./foo");"#, source_type, &[], None);
         assert_eq!(result.imports.len(), 1);
         assert_eq!(result.imports[0].specifier, "./foo");
         assert_eq!(result.unresolvable_dynamic, 0);
     }
+
+    // --- --define dead-branch pruning ---
+
+    fn parse_with_defines(source: &str, defines: &[(&str, &str)]) -> ParseResult {
+        let defines: Vec<(String, String)> = defines
+            .iter()
+            .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+            .collect();
+        extract_all(source, SourceType::mjs(), &defines, None)
+    }
+
+    #[test]
+    fn define_drops_require_in_false_branch() {
+        let source = r#"
+if (process.env.NODE_ENV === "development") {
+  require("./devtools");
+} else {
+  require("./prod");
+}
+"#;
+        let result = parse_with_defines(source, &[("NODE_ENV", "production")]);
+        let specs: Vec<&str> = result
+            .imports
+            .iter()
+            .map(|i| i.specifier.as_str())
+            .collect();
+        assert_eq!(specs, vec!["./prod"]);
+
+        let result = parse_with_defines(source, &[]);
+        assert_eq!(result.imports.len(), 2);
+    }
+
+    #[test]
+    fn define_handles_negation_ternary_and_logical() {
+        let source = r#"
+if (process.env.NODE_ENV !== "production") require("./a");
+const b = "production" == process.env.NODE_ENV ? require("./b") : require("./c");
+process.env.NODE_ENV === "test" && require("./d");
+const e = import("./e");
+if (!(process.env.NODE_ENV === "production")) { import("./f"); }
+"#;
+        let result = parse_with_defines(source, &[("NODE_ENV", "production")]);
+        let specs: Vec<&str> = result
+            .imports
+            .iter()
+            .map(|i| i.specifier.as_str())
+            .collect();
+        assert_eq!(specs, vec!["./b", "./e"]);
+    }
+
+    #[test]
+    fn define_ignores_undefined_vars() {
+        let source = r#"if (process.env.DEBUG === "1") { require("./debug"); }"#;
+        let result = parse_with_defines(source, &[("NODE_ENV", "production")]);
+        assert_eq!(result.imports.len(), 1);
+    }
+
+    // --- createRequire ---
+
+    #[test]
+    fn create_require_named_require() {
+        let imports = parse_ts(
+            r#"import { createRequire } from "node:module";
+const require = createRequire(import.meta.url);
+require("dep");"#,
+        );
+        let specs: Vec<&str> = imports.iter().map(|i| i.specifier.as_str()).collect();
+        assert_eq!(specs, vec!["node:module", "dep"]);
+        assert_eq!(imports[1].kind, EdgeKind::Static);
+    }
+
+    #[test]
+    fn create_require_aliased() {
+        let source = r#"import module from "node:module";
+const req = module.createRequire(import.meta.url);
+const a = req("./a");
+const b = req(name);"#;
+        let result = extract_all(source, SourceType::ts(), &[], None);
+        let specs: Vec<&str> = result
+            .imports
+            .iter()
+            .map(|i| i.specifier.as_str())
+            .collect();
+        assert_eq!(specs, vec!["node:module", "./a"]);
+        assert_eq!(result.unresolvable_dynamic, 1);
+    }
+
+    #[test]
+    fn template_literal_without_expressions_is_static_specifier() {
+        let result = extract_all(
+            "const a = import(`./foo`);\nconst b = require(`./bar`);",
+            SourceType::ts(),
+            &[],
+            None,
+        );
+        let imports: Vec<(&str, EdgeKind)> = result
+            .imports
+            .iter()
+            .map(|i| (i.specifier.as_str(), i.kind))
+            .collect();
+        assert_eq!(
+            imports,
+            vec![("./foo", EdgeKind::Dynamic), ("./bar", EdgeKind::Static)]
+        );
+        assert_eq!(result.unresolvable_dynamic, 0);
+    }
+
+    #[test]
+    fn template_literal_with_expressions_is_unresolvable() {
+        let result = extract_all(
+            "const a = import(`./locales/${lang}`);\nconst b = require(`./${name}`);",
+            SourceType::ts(),
+            &[],
+            None,
+        );
+        assert!(result.imports.is_empty());
+        assert_eq!(result.unresolvable_dynamic, 2);
+    }
+
+    #[test]
+    fn unrelated_call_not_treated_as_require() {
+        let imports = parse_ts(r#"const req = makeThing(); req("./a");"#);
+        assert!(imports.is_empty());
+    }
 }
@@ -1,8 +1,14 @@
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
 use std::sync::Arc;
 
-use oxc_resolver::{ResolveOptions, ResolverGeneric};
+use dashmap::DashMap;
+use oxc_resolver::{
+    ExtendsField, ResolveOptions, ResolverGeneric, TsConfig, TsconfigDiscovery, TsconfigOptions,
+    TsconfigReferences,
+};
 
+use crate::graph::EdgeKind;
+use crate::lang::{CandidateOutcome, ResolveAttempt, ResolveStrategy};
 use crate::vfs::{OxcVfsAdapter, Vfs};
 
 const NODE_BUILTINS: &[&str] = &[
@@ -51,6 +57,11 @@ const NODE_BUILTINS: &[&str] = &[
     "zlib",
 ];
 
+/// Extensions tried, in order, for an extensionless specifier.
+const RESOLVE_EXTENSIONS: &[&str] = &[
+    ".ts", ".tsx", ".d.ts", ".js", ".jsx", ".mjs", ".cjs", ".json", ".node",
+];
+
 pub fn is_node_builtin(specifier: &str) -> bool {
     if let Some(stripped) = specifier.strip_prefix("node:") {
         return NODE_BUILTINS.contains(&stripped) || stripped.starts_with("internal/");
@@ -58,8 +69,78 @@ pub fn is_node_builtin(specifier: &str) -> bool {
     NODE_BUILTINS.contains(&specifier)
 }
 
+/// The module system Node loads a file as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModuleSystem {
+    /// An ES module: `.mjs`/`.mts`, TypeScript sources, and `.js` under a
+    /// package.json with `"type": "module"`.
+    Esm,
+    /// CJS: `.cjs`/`.cts`, and `.js` under a package.json whose `type`
+    /// is `commonjs` or missing.
+    CommonJs,
+}
+
+/// Which conditions of a package's `exports` an import matches, decided by
+/// the importing file's module system and how the import is written.
+///
+/// Within one condition object the first key in the set wins, as in Node,
+/// and nested condition objects are matched the same way. Conditions from
+/// the other module system never match, so key order can't pick `require`
+/// for an ESM importer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum ImportConditions {
+    /// `node`, `import`, `default`, for ES module importers.
+    Import,
+    /// `node`, `require`, `default`, for CJS importers.
+    Require,
+    /// `types`, `node`, `import`, `default`, for `import type`.
+    Types,
+}
+
+impl ImportConditions {
+    pub(super) fn of(system: ModuleSystem, kind: EdgeKind, require: bool) -> Self {
+        match (kind, system) {
+            (EdgeKind::TypeOnly, _) => Self::Types,
+            _ if require => Self::Require,
+            (_, ModuleSystem::CommonJs) => Self::Require,
+            (_, ModuleSystem::Esm) => Self::Import,
+        }
+    }
+
+    /// Condition names in priority order.
+    const fn names(self) -> &'static [&'static str] {
+        match self {
+            Self::Import => &["node", "import", "default"],
+            Self::Require => &["node", "require", "default"],
+            Self::Types => &["types", "node", "import", "default"],
+        }
+    }
+}
+
+/// Conditions tried when an import matches none of its own, e.g. a `.ts`
+/// file `require()`-ing a package that only exports `require`.
+const FALLBACK_CONDITIONS: &[&str] = &["node", "import", "require", "default"];
+
 pub struct ImportResolver {
-    resolver: ResolverGeneric<OxcVfsAdapter>,
+    /// One resolver per [`ImportConditions`], sharing a cache.
+    import: ResolverGeneric<OxcVfsAdapter>,
+    require: ResolverGeneric<OxcVfsAdapter>,
+    types: ResolverGeneric<OxcVfsAdapter>,
+    fallback: ResolverGeneric<OxcVfsAdapter>,
+    vfs: Arc<dyn Vfs>,
+    root: PathBuf,
+    /// Module system of `.js` files per directory, from the nearest
+    /// package.json `type`.
+    package_types: DashMap<PathBuf, ModuleSystem>,
+    /// Resolve a package import that lands in the package's `src/` to its
+    /// published `exports`/`main` entry instead.
+    pub(super) prefer_published_entry: bool,
+    /// Fall back to `@/` -> `src/` and `~/` -> project root for specifiers
+    /// nothing else resolves.
+    pub(super) default_aliases: bool,
+    /// The tsconfig `paths` patterns with their targets as written, for
+    /// retrying a target that is not a local file as a package.
+    tsconfig_paths: Vec<(String, Vec<String>)>,
 }
 
 impl std::fmt::Debug for ImportResolver {
@@ -69,22 +150,32 @@ impl std::fmt::Debug for ImportResolver {
 }
 
 impl ImportResolver {
-    pub fn new(_root: &Path, vfs: Arc<dyn Vfs>) -> Self {
-        let resolver = ResolverGeneric::new_with_file_system(
-            OxcVfsAdapter(vfs),
+    pub fn new(root: &Path, vfs: Arc<dyn Vfs>) -> Self {
+        // `compilerOptions.paths` and `baseUrl` from the nearest tsconfig.json
+        // (following `extends`) are tried before `node_modules`. The resolver
+        // parses it once and shares it with the per-condition clones.
+        let tsconfig_file = root
+            .ancestors()
+            .map(|dir| dir.join("tsconfig.json"))
+            .find(|path| vfs.is_file(path));
+        let tsconfig_paths = tsconfig_file
+            .as_deref()
+            .map(|file| read_tsconfig_paths(&*vfs, file, 0))
+            .unwrap_or_default();
+        let tsconfig = tsconfig_file.map(|config_file| {
+            TsconfigDiscovery::Manual(TsconfigOptions {
+                config_file,
+                references: TsconfigReferences::Disabled,
+            })
+        });
+        let fallback = ResolverGeneric::new_with_file_system(
+            OxcVfsAdapter(vfs.clone()),
             ResolveOptions {
+                // Searched in every ancestor of the importing file, like
+                // Node: a dependency's own nested `node_modules` wins over
+                // the project root's.
                 modules: vec!["node_modules".into()],
-                extensions: vec![
-                    ".ts".into(),
-                    ".tsx".into(),
-                    ".d.ts".into(),
-                    ".js".into(),
-                    ".jsx".into(),
-                    ".mjs".into(),
-                    ".cjs".into(),
-                    ".json".into(),
-                    ".node".into(),
-                ],
+                extensions: RESOLVE_EXTENSIONS.iter().map(|&e| e.into()).collect(),
                 extension_alias: vec![
                     (
                         ".js".into(),
@@ -93,12 +184,7 @@ impl ImportResolver {
                     (".mjs".into(), vec![".mts".into(), ".mjs".into()]),
                     (".cjs".into(), vec![".cts".into(), ".cjs".into()]),
                 ],
-                condition_names: vec![
-                    "node".into(),
-                    "import".into(),
-                    "require".into(),
-                    "default".into(),
-                ],
+                condition_names: FALLBACK_CONDITIONS.iter().map(|&c| c.into()).collect(),
                 main_fields: vec!["module".into(), "main".into()],
                 // Disable symlink resolution: our VFS follows symlinks via
                 // stat (not lstat), so symlink_metadata never reports
@@ -108,23 +194,507 @@ impl ImportResolver {
                 // entirely, saving thousands of unnecessary stat calls per
                 // build in pnpm/yarn projects.
                 symlinks: false,
+                tsconfig,
                 ..ResolveOptions::default()
             },
         );
 
-        Self { resolver }
+        let with_conditions = |conditions: ImportConditions| {
+            fallback.clone_with_options(ResolveOptions {
+                condition_names: conditions.names().iter().map(|&c| c.into()).collect(),
+                ..fallback.options().clone()
+            })
+        };
+        Self {
+            import: with_conditions(ImportConditions::Import),
+            require: with_conditions(ImportConditions::Require),
+            types: with_conditions(ImportConditions::Types),
+            fallback,
+            vfs,
+            root: root.to_path_buf(),
+            package_types: DashMap::new(),
+            prefer_published_entry: false,
+            default_aliases: true,
+            tsconfig_paths,
+        }
     }
 
+    /// Resolve as an ES module import.
     pub fn resolve(&self, source_dir: &Path, specifier: &str) -> Option<PathBuf> {
+        self.resolve_tagged(source_dir, specifier, ImportConditions::Import)
+            .0
+    }
+
+    /// Resolve an import of the given kind made by the file `from`;
+    /// `require` marks a `require()` call.
+    ///
+    /// Conditions follow how the import is written, not the package type:
+    /// a `.js` file that is CJS by package type but written with `import`
+    /// statements is loaded as an ES module (Node's syntax detection, and
+    /// how bundlers treat it), so only `require()` calls match the
+    /// `require` conditions. The exception is `.cjs`/`.cts`, whose imports
+    /// compile to `require()`.
+    pub fn resolve_import(
+        &self,
+        from: &Path,
+        specifier: &str,
+        kind: EdgeKind,
+        require: bool,
+    ) -> Option<PathBuf> {
+        #[allow(clippy::or_fun_call)]
+        let dir = from.parent().unwrap_or(Path::new("."));
+        let system = match from.extension().and_then(|e| e.to_str()) {
+            Some("cjs" | "cts") => ModuleSystem::CommonJs,
+            _ => ModuleSystem::Esm,
+        };
+        let conditions = ImportConditions::of(system, kind, require);
+        self.resolve_tagged(dir, specifier, conditions).0
+    }
+
+    /// The module system Node loads `file` as. A `.js`/`.jsx` file follows
+    /// the `type` of the nearest package.json; TypeScript sources count as
+    /// ES modules, the way they are written and bundled. Resolution does not
+    /// consult it (see [`resolve_import`](Self::resolve_import)), so the
+    /// package.json files are read only when asked.
+    pub fn module_system(&self, file: &Path) -> ModuleSystem {
+        match file.extension().and_then(|e| e.to_str()) {
+            Some("cjs" | "cts") => ModuleSystem::CommonJs,
+            Some("js" | "jsx") => file
+                .parent()
+                .map_or(ModuleSystem::CommonJs, |dir| self.package_type(dir)),
+            _ => ModuleSystem::Esm,
+        }
+    }
+
+    /// The `type` of the package.json nearest to `dir`, cached for every
+    /// directory on the way up.
+    fn package_type(&self, dir: &Path) -> ModuleSystem {
+        let mut uncached = Vec::new();
+        let mut current = Some(dir);
+        let system = loop {
+            let Some(dir) = current else {
+                break ModuleSystem::CommonJs;
+            };
+            if let Some(system) = self.package_types.get(dir).map(|e| *e.value()) {
+                break system;
+            }
+            uncached.push(dir.to_path_buf());
+            let pkg_json = dir.join("package.json");
+            if let Ok(content) = self.vfs.read_to_string(&pkg_json) {
+                let is_module = serde_json::from_str::<serde_json::Value>(&content)
+                    .ok()
+                    .is_some_and(|v| v.get("type").and_then(|t| t.as_str()) == Some("module"));
+                break if is_module {
+                    ModuleSystem::Esm
+                } else {
+                    ModuleSystem::CommonJs
+                };
+            }
+            current = dir.parent();
+        };
+        for dir in uncached {
+            self.package_types.insert(dir, system);
+        }
+        system
+    }
+
+    /// Resolve and classify. Unlike [`resolve`](Self::resolve), this reads the
+    /// package.json of `node_modules` hits to tell `exports` entries apart.
+    pub fn resolve_with_strategy(
+        &self,
+        source_dir: &Path,
+        specifier: &str,
+    ) -> (Option<PathBuf>, ResolveStrategy) {
+        let (resolved, strategy) =
+            self.resolve_tagged(source_dir, specifier, ImportConditions::Import);
+        if strategy == ResolveStrategy::NodeModules
+            && let Some(pkg_dir) = resolved.as_deref().and_then(package_dir_from_path)
+            && self.has_exports(&pkg_dir.join("package.json"))
+        {
+            return (resolved, ResolveStrategy::ExportsField);
+        }
+        (resolved, strategy)
+    }
+
+    /// Shared by `resolve` and `resolve_with_strategy`; classification here
+    /// must stay cheap (no extra I/O) since it runs on every import.
+    fn resolve_tagged(
+        &self,
+        source_dir: &Path,
+        specifier: &str,
+        conditions: ImportConditions,
+    ) -> (Option<PathBuf>, ResolveStrategy) {
         if is_node_builtin(specifier) {
+            return (None, ResolveStrategy::Builtin);
+        }
+
+        let by_conditions = match conditions {
+            ImportConditions::Import => &self.import,
+            ImportConditions::Require => &self.require,
+            ImportConditions::Types => &self.types,
+        };
+        let resolved = by_conditions
+            .resolve(source_dir, specifier)
+            .ok()
+            .or_else(|| {
+                // Only package `exports` depend on conditions
+                if specifier.starts_with('.') {
+                    return None;
+                }
+                self.fallback.resolve(source_dir, specifier).ok()
+            })
+            .map(oxc_resolver::Resolution::into_path_buf)
+            .or_else(|| self.resolve_paths_as_package(source_dir, specifier, by_conditions))
+            .or_else(|| self.resolve_default_alias(specifier, by_conditions));
+
+        if specifier.starts_with('.') {
+            // Folded so `../lib` compares against the resolved (clean) path
+            let dir = lexical_join(source_dir, specifier);
+            // The resolver already honors `main`/`module` for directory
+            // targets, but ignores `exports` on relative imports. Prefer a
+            // local `exports` entry whenever the import resolved through the
+            // directory (rather than a sibling file like `./lib.ts`).
+            let via_dir = resolved.as_ref().is_none_or(|p| p.starts_with(&dir));
+            if via_dir && let Some(entry) = self.resolve_dir_exports(&dir, conditions) {
+                return (Some(entry), ResolveStrategy::ExportsField);
+            }
+        }
+
+        let strategy = match &resolved {
+            None => ResolveStrategy::Unresolved,
+            Some(_) if specifier.starts_with('.') => ResolveStrategy::Relative,
+            Some(p) if p.components().any(|c| c.as_os_str() == "node_modules") => {
+                ResolveStrategy::NodeModules
+            }
+            Some(_) => ResolveStrategy::FirstParty,
+        };
+        if self.prefer_published_entry
+            && strategy == ResolveStrategy::NodeModules
+            && let Some(entry) = resolved
+                .as_deref()
+                .and_then(|p| self.published_entry(p, specifier, conditions))
+        {
+            return (Some(entry), strategy);
+        }
+        (resolved, strategy)
+    }
+
+    /// A tsconfig `paths` target that is no local file, resolved as a
+    /// package instead: `"lodash": ["node_modules/lodash/index.js"]` under a
+    /// `baseUrl` elsewhere, or `"react": ["preact/compat"]` to stub one
+    /// package with another. Targets are tried in order.
+    fn resolve_paths_as_package(
+        &self,
+        source_dir: &Path,
+        specifier: &str,
+        resolver: &ResolverGeneric<OxcVfsAdapter>,
+    ) -> Option<PathBuf> {
+        if specifier.starts_with('.') {
             return None;
         }
+        let (targets, wildcard) = match_tsconfig_paths(&self.tsconfig_paths, specifier)?;
+        targets.iter().find_map(|target| {
+            let target = target.replacen('*', wildcard, 1);
+            let target = target.trim_start_matches("./");
+            // A path into node_modules names the package after it
+            let package = target
+                .rfind("node_modules/")
+                .map_or(target, |i| &target[i + "node_modules/".len()..]);
+            if package.is_empty() || package.starts_with(['.', '/']) || package == specifier {
+                return None;
+            }
+            resolver
+                .resolve(source_dir, package)
+                .ok()
+                .map(oxc_resolver::Resolution::into_path_buf)
+        })
+    }
 
-        if let Ok(resolution) = self.resolver.resolve(source_dir, specifier) {
-            return Some(resolution.into_path_buf());
+    /// `@/x` as `<root>/src/x` and `~/x` as `<root>/x`, the aliases Vue,
+    /// Nuxt and Vite templates set up, for projects whose tsconfig does not
+    /// map them (an explicit `paths` entry has already been tried).
+    fn resolve_default_alias(
+        &self,
+        specifier: &str,
+        resolver: &ResolverGeneric<OxcVfsAdapter>,
+    ) -> Option<PathBuf> {
+        if !self.default_aliases {
+            return None;
         }
+        let (dir, rest) = if let Some(rest) = specifier.strip_prefix("@/") {
+            (self.root.join("src"), rest)
+        } else if let Some(rest) = specifier.strip_prefix("~/") {
+            (self.root.clone(), rest)
+        } else {
+            return None;
+        };
+        resolver
+            .resolve(&dir, &format!("./{rest}"))
+            .ok()
+            .map(oxc_resolver::Resolution::into_path_buf)
+    }
 
-        None
+    /// When a bare package import resolved into the package's `src/` (say
+    /// through a `module` field pointing at sources), the built file its
+    /// `exports` or `main` publishes instead, if that lives outside `src/`.
+    fn published_entry(
+        &self,
+        resolved: &Path,
+        specifier: &str,
+        conditions: ImportConditions,
+    ) -> Option<PathBuf> {
+        let pkg_dir = package_dir_from_path(resolved)?;
+        let src = pkg_dir.join("src");
+        if package_name_from_path(resolved)? != specifier || !resolved.starts_with(&src) {
+            return None;
+        }
+        let content = self
+            .vfs
+            .read_to_string(&pkg_dir.join("package.json"))
+            .ok()?;
+        let parsed: serde_json::Value = serde_json::from_str(&content).ok()?;
+        let target = parsed
+            .get("exports")
+            .and_then(|e| exports_root_entry(e, conditions.names()))
+            .or_else(|| parsed.get("main")?.as_str())?;
+        self.resolve_in_dir(&pkg_dir, target)
+            .filter(|entry| !entry.starts_with(&src))
+    }
+
+    /// Replay the lookup `resolve` performs, recording each candidate path
+    /// and why it was rejected. Mirrors the resolver's search order (file,
+    /// file plus each extension, directory entry, then `node_modules` in
+    /// every ancestor) rather than instrumenting it. A package with an
+    /// `exports` map is looked up through the map alone, like `resolve`.
+    pub fn explain(&self, source_dir: &Path, specifier: &str) -> Vec<ResolveAttempt> {
+        let mut attempts = Vec::new();
+        if specifier.starts_with('.') || specifier.starts_with('/') {
+            self.explain_path(&lexical_join(source_dir, specifier), &mut attempts);
+            return attempts;
+        }
+        let as_path = PathBuf::from(specifier);
+        if is_node_builtin(specifier) {
+            attempts.push(ResolveAttempt::new(as_path, CandidateOutcome::Found));
+            return attempts;
+        }
+        attempts.push(ResolveAttempt::new(as_path, CandidateOutcome::NotABuiltin));
+        let mut segments = specifier.split('/');
+        let package = match (segments.next(), segments.next()) {
+            (Some(scope), Some(name)) if scope.starts_with('@') => format!("{scope}/{name}"),
+            (Some(name), _) => name.to_string(),
+            (None, _) => return attempts,
+        };
+        for dir in source_dir.ancestors() {
+            let node_modules = dir.join("node_modules");
+            let pkg_dir = node_modules.join(&package);
+            if !self.vfs.is_dir(&pkg_dir) {
+                attempts.push(ResolveAttempt::new(
+                    pkg_dir,
+                    CandidateOutcome::NotInNodeModules,
+                ));
+                continue;
+            }
+            if self.has_exports(&pkg_dir.join("package.json")) {
+                match self.import.resolve(source_dir, specifier) {
+                    Ok(resolved) => attempts.push(ResolveAttempt::new(
+                        resolved.into_path_buf(),
+                        CandidateOutcome::Found,
+                    )),
+                    Err(_) => attempts.push(ResolveAttempt::new(
+                        node_modules.join(specifier),
+                        CandidateOutcome::NotExported,
+                    )),
+                }
+                break;
+            }
+            if self.explain_path(&node_modules.join(specifier), &mut attempts) {
+                break;
+            }
+        }
+        attempts
+    }
+
+    /// Candidates for a path-like target: as a file, with each extension,
+    /// then as a directory. Returns whether one of them exists.
+    fn explain_path(&self, base: &Path, attempts: &mut Vec<ResolveAttempt>) -> bool {
+        let files =
+            std::iter::once(base.to_path_buf()).chain(RESOLVE_EXTENSIONS.iter().map(|ext| {
+                let mut name = base.as_os_str().to_owned();
+                name.push(ext);
+                PathBuf::from(name)
+            }));
+        for candidate in files {
+            if self.vfs.is_file(&candidate) {
+                attempts.push(ResolveAttempt::new(candidate, CandidateOutcome::Found));
+                return true;
+            }
+            attempts.push(ResolveAttempt::new(candidate, CandidateOutcome::NotAFile));
+        }
+        if !self.vfs.is_dir(base) {
+            attempts.push(ResolveAttempt::new(
+                base.to_path_buf(),
+                CandidateOutcome::NotADirectory,
+            ));
+            return false;
+        }
+        if let Ok(entry) = self.import.resolve(base, "./") {
+            attempts.push(ResolveAttempt::new(
+                entry.into_path_buf(),
+                CandidateOutcome::Found,
+            ));
+            return true;
+        }
+        attempts.push(ResolveAttempt::new(
+            base.to_path_buf(),
+            CandidateOutcome::NoIndex,
+        ));
+        false
+    }
+
+    fn has_exports(&self, pkg_json: &Path) -> bool {
+        self.vfs
+            .read_to_string(pkg_json)
+            .ok()
+            .and_then(|c| serde_json::from_str::<serde_json::Value>(&c).ok())
+            .is_some_and(|v| v.get("exports").is_some())
+    }
+
+    /// Resolve the `exports` entry of a local (non-`node_modules`) package
+    /// directory, e.g. an internal package imported as `./lib`.
+    fn resolve_dir_exports(&self, dir: &Path, conditions: ImportConditions) -> Option<PathBuf> {
+        let pkg_json = dir.join("package.json");
+        if !self.vfs.is_file(&pkg_json) {
+            return None;
+        }
+        let content = self.vfs.read_to_string(&pkg_json).ok()?;
+        let parsed: serde_json::Value = serde_json::from_str(&content).ok()?;
+        let exports = parsed.get("exports")?;
+        let target = exports_root_entry(exports, conditions.names())
+            .or_else(|| exports_root_entry(exports, FALLBACK_CONDITIONS))?;
+        self.resolve_in_dir(dir, target)
+    }
+
+    /// Resolve a package.json path value (`dist/index.js`, `./lib`)
+    /// relative to the package directory.
+    fn resolve_in_dir(&self, dir: &Path, target: &str) -> Option<PathBuf> {
+        let relative = if target.starts_with('.') {
+            target.to_string()
+        } else {
+            format!("./{target}")
+        };
+        self.import
+            .resolve(dir, &relative)
+            .ok()
+            .map(oxc_resolver::Resolution::into_path_buf)
+    }
+}
+
+/// `compilerOptions.paths` of the tsconfig at `file`, with targets as
+/// written. A config without `paths` inherits them from the last config it
+/// `extends` by relative path that has some; package configs are not read.
+fn read_tsconfig_paths(vfs: &dyn Vfs, file: &Path, depth: usize) -> Vec<(String, Vec<String>)> {
+    // Deep enough for real chains, and stops a config extending itself
+    const MAX_EXTENDS: usize = 8;
+    let Some(config) = vfs
+        .read_to_string(file)
+        .ok()
+        .and_then(|json| TsConfig::parse(false, file, json).ok())
+    else {
+        return Vec::new();
+    };
+    if let Some(paths) = config.compiler_options.paths {
+        return paths
+            .into_iter()
+            .map(|(pattern, targets)| {
+                let targets = targets
+                    .iter()
+                    .map(|t| t.to_string_lossy().into_owned())
+                    .collect();
+                (pattern, targets)
+            })
+            .collect();
+    }
+    if depth >= MAX_EXTENDS {
+        return Vec::new();
+    }
+    let extends = match config.extends {
+        Some(ExtendsField::Single(one)) => vec![one],
+        Some(ExtendsField::Multiple(many)) => many,
+        None => Vec::new(),
+    };
+    let dir = file.parent().unwrap_or(Path::new(""));
+    extends
+        .iter()
+        .rev()
+        .filter(|spec| spec.starts_with('.'))
+        .map(|spec| {
+            let base = lexical_join(dir, spec);
+            if base.extension().is_some_and(|e| e == "json") {
+                base
+            } else {
+                base.with_extension("json")
+            }
+        })
+        .map(|base| read_tsconfig_paths(vfs, &base, depth + 1))
+        .find(|paths| !paths.is_empty())
+        .unwrap_or_default()
+}
+
+/// The targets of the `paths` pattern `specifier` matches, with the text
+/// its `*` stands for. An exact pattern wins over wildcards, and among
+/// wildcards the longest prefix, as in TypeScript.
+fn match_tsconfig_paths<'a>(
+    paths: &'a [(String, Vec<String>)],
+    specifier: &'a str,
+) -> Option<(&'a [String], &'a str)> {
+    if let Some((_, targets)) = paths.iter().find(|(pattern, _)| pattern == specifier) {
+        return Some((targets, ""));
+    }
+    paths
+        .iter()
+        .filter_map(|(pattern, targets)| {
+            let (prefix, suffix) = pattern.split_once('*')?;
+            let middle = specifier.strip_prefix(prefix)?.strip_suffix(suffix)?;
+            Some((prefix.len(), targets.as_slice(), middle))
+        })
+        .max_by_key(|&(prefix_len, ..)| prefix_len)
+        .map(|(_, targets, middle)| (targets, middle))
+}
+
+/// `dir.join(relative)` with `.` and `..` folded away, without touching
+/// the filesystem, so `./a/../b` reads as the path the resolver looks at.
+fn lexical_join(dir: &Path, relative: &str) -> PathBuf {
+    let mut out = dir.to_path_buf();
+    for component in Path::new(relative).components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                out.pop();
+            }
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+/// Pick the `"."` entry from a package.json `exports` value. Handles the
+/// string shorthand, subpath maps, and (nested) condition maps, checking
+/// `conditions` in order.
+fn exports_root_entry<'a>(exports: &'a serde_json::Value, conditions: &[&str]) -> Option<&'a str> {
+    match exports {
+        serde_json::Value::String(s) => Some(s),
+        serde_json::Value::Object(map) => {
+            if let Some(root) = map.get(".") {
+                return exports_root_entry(root, conditions);
+            }
+            conditions
+                .iter()
+                .find_map(|c| map.get(*c).and_then(|v| exports_root_entry(v, conditions)))
+        }
+        serde_json::Value::Array(items) => {
+            items.iter().find_map(|v| exports_root_entry(v, conditions))
+        }
+        _ => None,
     }
 }
 
@@ -165,6 +735,21 @@ pub fn package_name_from_path(path: &Path) -> Option<String> {
     }
 }
 
+/// The package directory containing a `node_modules` file, e.g.
+/// `/p/node_modules/@scope/pkg/dist/index.js` -> `/p/node_modules/@scope/pkg`.
+fn package_dir_from_path(path: &Path) -> Option<PathBuf> {
+    let name = package_name_from_path(path)?;
+    path.ancestors()
+        .find(|dir| {
+            dir.ends_with(&name)
+                && dir
+                    .ancestors()
+                    .nth(Path::new(&name).components().count())
+                    .is_some_and(|nm| nm.ends_with("node_modules"))
+        })
+        .map(Path::to_path_buf)
+}
+
 pub(super) fn read_package_name(pkg_json: &Path, vfs: &dyn Vfs) -> Option<String> {
     let content = vfs.read_to_string(pkg_json).ok()?;
     let parsed: serde_json::Value = serde_json::from_str(&content).ok()?;
@@ -45,24 +45,66 @@ mod auto_trait_tests {
         is_normal::<crate::graph::Edge>();
         is_normal::<crate::graph::ModuleGraph>();
         is_normal::<crate::query::TraceResult>();
+        is_normal::<crate::query::TraceCounts>();
+        is_normal::<crate::query::BarrelPenalty>();
+        is_normal::<crate::query::DependentCount>();
+        is_normal::<crate::query::ImportCycle>();
         is_normal::<crate::query::TraceSnapshot>();
         is_normal::<crate::query::DiffResult>();
         is_normal::<crate::walker::BuildResult>();
         is_normal::<crate::error::Error>();
         is_normal::<crate::loader::LoadedGraph>();
+        is_normal::<crate::loader::LoadOptions>();
+        is_normal::<crate::loader::WorkspaceGraph>();
+        is_normal::<crate::loader::WeightSource>();
+        is_normal::<crate::cache::CacheLocation>();
+        is_normal::<crate::cache::CacheMode>();
+        is_normal::<crate::cache::CompressedSizeCache>();
         is_normal::<crate::session::Session>();
         is_normal::<crate::session::ResolvedTarget>();
         is_normal::<crate::lang::ParseError>();
         is_normal::<crate::lang::RawImport>();
         is_normal::<crate::lang::ParseResult>();
         is_normal::<crate::lang::ProjectKind>();
+        is_normal::<crate::lang::script::ScriptResolver>();
+        is_normal::<crate::lang::typescript::WorkspacePackage>();
         is_normal::<crate::git::DiffArg>();
         is_normal::<crate::repl::Command>();
         is_normal::<crate::report::TraceReport>();
+        is_normal::<crate::report::WeightEstimate>();
         is_normal::<crate::report::ChainReport>();
+        is_normal::<crate::report::ChainHop>();
         is_normal::<crate::report::CutReport>();
         is_normal::<crate::report::DiffReport>();
+        is_normal::<crate::report::ModuleChangeset>();
         is_normal::<crate::report::PackagesReport>();
+        is_normal::<crate::report::DependencyEntry>();
+        is_normal::<crate::report::CountReport>();
+        is_normal::<crate::report::BarrelReport>();
+        is_normal::<crate::report::DependedOnReport>();
+        is_normal::<crate::report::CyclesReport>();
+        is_normal::<crate::report::DependentsReport>();
+        is_normal::<crate::report::SharedModulesReport>();
+        is_normal::<crate::report::DuplicatesReport>();
+        is_normal::<crate::report::SavingsReport>();
+        is_normal::<crate::report::DynamicOnlyReport>();
+        is_normal::<crate::report::DeadcodeReport>();
+        is_normal::<crate::report::PruneReport>();
+        is_normal::<crate::report::ResolveReport>();
+        is_normal::<crate::report::ExplainReport>();
+        is_normal::<crate::report::ExplainAttempt>();
+        is_normal::<crate::report::JsonMeta>();
+        is_normal::<crate::report::ModuleRow>();
+        is_normal::<crate::report::SbomReport>();
+        is_normal::<crate::report::WithoutReport>();
+        is_normal::<crate::report::MonorepoReport>();
+        is_normal::<crate::report::WorkspaceCost>();
+        is_normal::<crate::report::WorkspaceEdge>();
+        is_normal::<crate::query::WithoutResult>();
+        is_normal::<crate::query::PackageReach>();
+        is_normal::<crate::lang::ResolveStrategy>();
+        is_normal::<crate::lang::CandidateOutcome>();
+        is_normal::<crate::lang::ResolveAttempt>();
         is_normal::<crate::vfs::VfsMetadata>();
         is_normal::<crate::vfs::OsVfs>();
         is_normal::<crate::vfs::GitTreeVfs>();
@@ -2,13 +2,17 @@
 
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use crate::cache::{self, CacheWriteHandle, ParseCache};
+use crate::cache::{
+    self, CacheLocation, CacheMode, CacheWriteHandle, CompressedSizeCache, ParseCache,
+};
 use crate::error::Error;
-use crate::graph::ModuleGraph;
+use crate::graph::{ModuleGraph, ModuleId};
+use crate::lang::typescript::WorkspacePackage;
 use crate::lang::{self, LanguageSupport};
 use crate::vfs::{OsVfs, Vfs};
-use crate::walker;
+use crate::walker::{self, ResolutionStats};
 
 /// Result of loading or building a dependency graph.
 #[derive(Debug)]
@@ -26,8 +30,63 @@ pub struct LoadedGraph {
     pub unresolvable_dynamic_count: usize,
     /// Files containing unresolvable dynamic imports, with per-file counts.
     pub unresolvable_dynamic_files: Vec<(PathBuf, usize)>,
+    /// How imports resolved when the graph was built.
+    pub resolution: ResolutionStats,
     /// Warnings from files that could not be opened, read, or parsed.
     pub file_warnings: Vec<String>,
+    /// The build ran past [`LoadOptions::timeout`]; the graph is partial and
+    /// was not cached.
+    pub timed_out: bool,
+    /// Files left unparsed by [`LoadOptions::sample`].
+    pub sampled_out: usize,
+}
+
+/// Options controlling how a graph is loaded.
+#[derive(Debug, Clone, Default)]
+#[allow(clippy::struct_excessive_bools)] // independent CLI toggles
+pub struct LoadOptions {
+    /// Bypass reading the on-disk cache (it is still rewritten).
+    pub no_cache: bool,
+    /// Where the cache file lives.
+    pub cache_location: CacheLocation,
+    /// `process.env` values (`--define NODE_ENV=production`); `require()`
+    /// calls in branches that are statically false under them are not followed.
+    pub defines: Vec<(String, String)>,
+    /// External program consulted for specifiers the built-in resolver
+    /// cannot resolve (see [`lang::script`]).
+    pub resolver_script: Option<PathBuf>,
+    /// What each module's `size_bytes` measures.
+    pub weight_source: WeightSource,
+    /// Trace a package's published `exports`/`main` entry rather than its
+    /// `src/` when both exist (`--include-node-modules-source-only`).
+    pub prefer_published_entry: bool,
+    /// Don't resolve `@/` to `src/` and `~/` to the project root when no
+    /// tsconfig `paths` entry maps them (`--no-default-aliases`).
+    pub no_default_aliases: bool,
+    /// How cached files are checked for changes.
+    pub cache_mode: CacheMode,
+    /// Wall-clock budget for building the graph. When it runs out, file
+    /// discovery stops and the partial graph is returned uncached.
+    pub timeout: Option<Duration>,
+    /// Follow imports into gitignored project files too (`--no-gitignore`).
+    /// By default they are left out of the graph.
+    pub no_gitignore: bool,
+    /// Parse only a random share of the files (`trace --sample`). The
+    /// cached graph is neither read nor written.
+    pub sample: Option<walker::Sample>,
+    /// Treat paths differing only in case as one module
+    /// (`--case-insensitive-paths`), as on macOS and Windows filesystems.
+    pub case_insensitive_paths: bool,
+}
+
+/// What a module's weight measures.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WeightSource {
+    /// Bytes on disk, i.e. the install footprint.
+    #[default]
+    Install,
+    /// Gzip-compressed bytes, closer to what a server actually sends.
+    Gzip,
 }
 
 /// Load a dependency graph using the real filesystem.
@@ -46,37 +105,53 @@ pub fn load_graph(entry: &Path, no_cache: bool) -> Result<(LoadedGraph, CacheWri
 /// The returned [`CacheWriteHandle`] must be kept alive until you are done
 /// with the graph — it joins a background cache-write thread on drop.
 #[must_use = "the CacheWriteHandle joins a background thread on drop"]
-#[allow(clippy::needless_pass_by_value)] // Arc is cloned into lang support implementations
 pub fn load_graph_with_vfs(
     entry: &Path,
     no_cache: bool,
     vfs: Arc<dyn Vfs>,
 ) -> Result<(LoadedGraph, CacheWriteHandle), Error> {
-    let entry = vfs
-        .canonicalize(entry)
-        .map_err(|e| Error::EntryNotFound(entry.to_path_buf(), e))?;
-
-    if vfs.is_dir(&entry) {
-        return Err(Error::EntryIsDirectory(entry));
-    }
-
-    let (root, kind) = lang::detect_project(&entry, &*vfs).ok_or_else(|| {
-        let ext = entry.extension().and_then(|e| e.to_str()).map(String::from);
-        Error::UnsupportedFileType(ext)
-    })?;
-
-    let lang_support: Box<dyn LanguageSupport> = match kind {
-        lang::ProjectKind::TypeScript => Box::new(lang::typescript::TypeScriptSupport::with_vfs(
-            &root,
-            vfs.clone(),
-        )),
-        lang::ProjectKind::Python => {
-            Box::new(lang::python::PythonSupport::with_vfs(&root, vfs.clone()))
-        }
+    let opts = LoadOptions {
+        no_cache,
+        ..LoadOptions::default()
     };
+    load_graph_with_options(entry, &opts, vfs)
+}
+
+/// Like [`load_graph_with_vfs`], with full control over [`LoadOptions`].
+#[must_use = "the CacheWriteHandle joins a background thread on drop"]
+#[allow(clippy::needless_pass_by_value)] // Arc is cloned into lang support implementations
+pub fn load_graph_with_options(
+    entry: &Path,
+    opts: &LoadOptions,
+    vfs: Arc<dyn Vfs>,
+) -> Result<(LoadedGraph, CacheWriteHandle), Error> {
+    let deadline = opts.timeout.map(|t| Instant::now() + t);
+    let (entry, root, kind) = locate_entry(entry, &*vfs)?;
+    let lang_support = language_support(&root, kind, opts, &vfs)?;
 
     let valid_extensions = lang_support.extensions();
-    let (result, handle) = build_or_load(&entry, &root, no_cache, lang_support.as_ref(), &*vfs);
+    let cache_file = opts.cache_location.path_for(&root);
+    let (mut result, handle) = build_or_load(
+        &entry,
+        &root,
+        &cache_file,
+        opts,
+        deadline,
+        lang_support.as_ref(),
+        &*vfs,
+    );
+    // The graph cache always holds on-disk sizes; compressed sizes are
+    // swapped in afterwards so switching --weight-source never invalidates it.
+    if opts.weight_source == WeightSource::Gzip {
+        let mut sizes = if opts.no_cache {
+            CompressedSizeCache::new_at(&cache_file)
+        } else {
+            CompressedSizeCache::load(&cache_file)
+        };
+        let warnings = sizes.apply(&mut result.graph, &*vfs);
+        result.file_warnings.extend(warnings);
+        sizes.save();
+    }
 
     Ok((
         LoadedGraph {
@@ -87,39 +162,260 @@ pub fn load_graph_with_vfs(
             from_cache: result.from_cache,
             unresolvable_dynamic_count: result.unresolvable_dynamic_count,
             unresolvable_dynamic_files: result.unresolvable_dynamic_files,
+            resolution: result.resolution,
             file_warnings: result.file_warnings,
+            timed_out: result.timed_out,
+            sampled_out: result.sampled_out,
         },
         handle,
     ))
 }
 
+/// The graph the last run cached for `entry`, as it was then: nothing is
+/// checked against the files on disk (`trace --diff-cache`). `None` when
+/// the cache holds no graph for this entry.
+pub fn load_cached_graph(entry: &Path, opts: &LoadOptions) -> Result<Option<ModuleGraph>, Error> {
+    let (entry, root, _) = locate_entry(entry, &OsVfs)?;
+    let mut cache = ParseCache::load_from(&opts.cache_location.path_for(&root), &root);
+    Ok(cache.take_graph(&entry))
+}
+
+/// Canonicalize `entry` (a directory means its index file) and detect the
+/// project it belongs to. Returns the entry file, project root, and kind.
+fn locate_entry(
+    entry: &Path,
+    vfs: &dyn Vfs,
+) -> Result<(PathBuf, PathBuf, lang::ProjectKind), Error> {
+    let original = entry;
+    let entry = vfs
+        .canonicalize(entry)
+        .map_err(|e| Error::EntryNotFound(entry.to_path_buf(), e))?;
+
+    let (entry, logical) = if vfs.is_dir(&entry) {
+        let file = directory_entry(&entry, vfs).ok_or(Error::EntryIsDirectory(entry))?;
+        (file, None)
+    } else {
+        let logical = logical_path(original, vfs);
+        (entry, logical)
+    };
+
+    let (mut root, kind) = lang::detect_project(&entry, vfs).ok_or_else(|| {
+        let ext = entry.extension().and_then(|e| e.to_str()).map(String::from);
+        Error::UnsupportedFileType(ext)
+    })?;
+    // A symlinked entry (e.g. a linked bin) can point out of the project it
+    // was invoked from. Detect the root from where the link lives then;
+    // the real path is still what gets parsed.
+    if let Some(logical) = logical.filter(|l| *l != entry)
+        && let Some((logical_root, _)) = lang::detect_project(&logical, vfs)
+        && !entry.starts_with(&logical_root)
+    {
+        root = logical_root;
+    }
+    Ok((entry, root, kind))
+}
+
+/// Every package of a monorepo traced in one graph (`--aggregate-monorepo`).
+#[derive(Debug)]
+pub struct WorkspaceGraph {
+    /// One graph built from all package entries; each is a graph entry.
+    pub graph: ModuleGraph,
+    /// Directory holding the workspaces config.
+    pub root: PathBuf,
+    /// Each workspace package with its entry file, `None` when the package
+    /// directory has no usable `main` or index file.
+    pub packages: Vec<(WorkspacePackage, Option<PathBuf>)>,
+    /// Warnings from files that could not be opened, read, or parsed.
+    pub file_warnings: Vec<String>,
+}
+
+/// Find the monorepo around `path`, resolve each workspace package's entry
+/// the way a directory entry is resolved, and build a single graph from
+/// all of them. The result is not cached.
+#[allow(clippy::needless_pass_by_value)] // Arc is cloned into lang support implementations
+pub fn load_workspace_graph(
+    path: &Path,
+    opts: &LoadOptions,
+    vfs: Arc<dyn Vfs>,
+) -> Result<WorkspaceGraph, Error> {
+    let start = vfs
+        .canonicalize(path)
+        .map_err(|e| Error::EntryNotFound(path.to_path_buf(), e))?;
+    let root =
+        lang::typescript::find_workspace_root(&start, &*vfs).ok_or(Error::NoWorkspaces(start))?;
+    let packages: Vec<(WorkspacePackage, Option<PathBuf>)> =
+        lang::typescript::workspace_packages(&root, &*vfs)
+            .into_iter()
+            .map(|pkg| {
+                let entry = directory_entry(&pkg.dir, &*vfs);
+                (pkg, entry)
+            })
+            .collect();
+    let entries: Vec<PathBuf> = packages.iter().filter_map(|(_, e)| e.clone()).collect();
+
+    let lang_support = language_support(&root, lang::ProjectKind::TypeScript, opts, &vfs)?;
+    let mut cache = ParseCache::new();
+    let mut result = walker::build_graph_multi(
+        &entries,
+        &root,
+        lang_support.as_ref(),
+        &mut cache,
+        &*vfs,
+        !opts.no_gitignore,
+        opts.case_insensitive_paths,
+    );
+    if opts.weight_source == WeightSource::Gzip {
+        let mut sizes = CompressedSizeCache::load(&opts.cache_location.path_for(&root));
+        let warnings = sizes.apply(&mut result.graph, &*vfs);
+        result.file_warnings.extend(warnings);
+        sizes.save();
+    }
+
+    Ok(WorkspaceGraph {
+        graph: result.graph,
+        root,
+        packages,
+        file_warnings: result.file_warnings,
+    })
+}
+
+/// `path` with its directories canonicalized but its final component kept,
+/// so a symlinked file stays at the link's location.
+fn logical_path(path: &Path, vfs: &dyn Vfs) -> Option<PathBuf> {
+    let name = path.file_name()?;
+    let parent = match path.parent() {
+        Some(p) if !p.as_os_str().is_empty() => p,
+        _ => Path::new("."),
+    };
+    Some(vfs.canonicalize(parent).ok()?.join(name))
+}
+
+/// Index files tried, in order, when the entry is a directory and its
+/// package.json has no usable `main`.
+const DIRECTORY_INDEX_FILES: &[&str] = &[
+    "index.ts",
+    "index.tsx",
+    "index.js",
+    "index.jsx",
+    "index.mjs",
+    "index.cjs",
+];
+
+/// Resolve a directory entry to a file the way bundlers do: the `main` of
+/// its package.json if that exists, otherwise the first index file.
+fn directory_entry(dir: &Path, vfs: &dyn Vfs) -> Option<PathBuf> {
+    let main = vfs
+        .read_to_string(&dir.join("package.json"))
+        .ok()
+        .and_then(|c| serde_json::from_str::<serde_json::Value>(&c).ok())
+        .and_then(|v| v.get("main")?.as_str().map(|m| dir.join(m)))
+        .filter(|p| vfs.is_file(p));
+    let file = main.or_else(|| {
+        DIRECTORY_INDEX_FILES
+            .iter()
+            .map(|name| dir.join(name))
+            .find(|p| vfs.is_file(p))
+    })?;
+    vfs.canonicalize(&file).ok()
+}
+
+/// Build the [`LanguageSupport`] for a project, honoring `defines` and
+/// `resolver_script` from `opts`.
+pub(crate) fn language_support(
+    root: &Path,
+    kind: lang::ProjectKind,
+    opts: &LoadOptions,
+    vfs: &Arc<dyn Vfs>,
+) -> Result<Box<dyn LanguageSupport>, Error> {
+    let lang_support: Box<dyn LanguageSupport> = match kind {
+        lang::ProjectKind::TypeScript => Box::new(
+            lang::typescript::TypeScriptSupport::with_defines(
+                root,
+                vfs.clone(),
+                opts.defines.clone(),
+            )
+            .prefer_published_entry(opts.prefer_published_entry)
+            .default_aliases(!opts.no_default_aliases),
+        ),
+        lang::ProjectKind::Python => {
+            Box::new(lang::python::PythonSupport::with_vfs(root, vfs.clone()))
+        }
+    };
+
+    Ok(match &opts.resolver_script {
+        Some(script) => {
+            // Absolute, so a bare `resolve.sh` isn't looked up on PATH
+            let script = script
+                .canonicalize()
+                .ok()
+                .filter(|p| p.is_file())
+                .ok_or_else(|| Error::ResolverScriptNotFound(script.clone()))?;
+            Box::new(lang::script::ScriptResolver::new(lang_support, script))
+        }
+        None => lang_support,
+    })
+}
+
 // ---------------------------------------------------------------------------
 // Internal helpers (moved from main.rs)
 // ---------------------------------------------------------------------------
 
+/// The parse cache at `cache_file` for the project at `root`, dropping
+/// whatever was cached under options that parse or resolve differently.
+fn open_parse_cache(cache_file: &Path, root: &Path, opts: &LoadOptions) -> ParseCache {
+    let mut cache = if opts.no_cache {
+        ParseCache::new_at(cache_file)
+    } else {
+        ParseCache::load_from(cache_file, root)
+    };
+    cache.set_defines(&opts.defines);
+    cache.set_prefer_published_entry(opts.prefer_published_entry);
+    cache.set_no_default_aliases(opts.no_default_aliases);
+    cache.set_no_gitignore(opts.no_gitignore);
+    cache.set_case_insensitive_paths(opts.case_insensitive_paths);
+    cache.set_mode(opts.cache_mode);
+    cache
+}
+
+/// The language support and parse cache a load of `entry` uses, for
+/// re-parsing single files of a graph that is already loaded.
+pub(crate) fn file_parser(
+    entry: &Path,
+    opts: &LoadOptions,
+    vfs: &Arc<dyn Vfs>,
+) -> Result<(Box<dyn LanguageSupport>, ParseCache), Error> {
+    let (_, root, kind) = locate_entry(entry, &**vfs)?;
+    let lang = language_support(&root, kind, opts, vfs)?;
+    let cache = open_parse_cache(&opts.cache_location.path_for(&root), &root, opts);
+    Ok((lang, cache))
+}
+
 struct BuildResult {
     graph: ModuleGraph,
     unresolvable_dynamic_count: usize,
     unresolvable_dynamic_files: Vec<(PathBuf, usize)>,
+    resolution: ResolutionStats,
     file_warnings: Vec<String>,
     from_cache: bool,
+    timed_out: bool,
+    sampled_out: usize,
 }
 
+#[allow(clippy::too_many_lines)]
 fn build_or_load(
     entry: &Path,
     root: &Path,
-    no_cache: bool,
+    cache_file: &Path,
+    opts: &LoadOptions,
+    deadline: Option<Instant>,
     lang: &dyn LanguageSupport,
     vfs: &dyn Vfs,
 ) -> (BuildResult, CacheWriteHandle) {
-    let mut cache = if no_cache {
-        ParseCache::new()
-    } else {
-        ParseCache::load(root)
-    };
+    let no_cache = opts.no_cache;
+    let mut cache = open_parse_cache(cache_file, root, opts);
 
     // Tier 1: try whole-graph cache
-    if !no_cache {
+    if !no_cache && opts.sample.is_none() {
         let resolve_fn = |spec: &str| lang.resolve(root, spec).is_some();
         match cache.try_load_graph(entry, &resolve_fn) {
             cache::GraphCacheResult::Hit {
@@ -127,6 +423,7 @@ fn build_or_load(
                 unresolvable_dynamic,
                 unresolvable_dynamic_files,
                 unresolved_specifiers,
+                resolution,
                 needs_resave,
             } => {
                 let handle = if needs_resave {
@@ -137,6 +434,7 @@ fn build_or_load(
                         unresolved_specifiers,
                         unresolvable_dynamic,
                         unresolvable_dynamic_files.clone(),
+                        resolution,
                     )
                 } else {
                     CacheWriteHandle::none()
@@ -146,8 +444,11 @@ fn build_or_load(
                         graph,
                         unresolvable_dynamic_count: unresolvable_dynamic,
                         unresolvable_dynamic_files,
+                        resolution,
                         file_warnings: Vec::new(),
                         from_cache: true,
+                        timed_out: false,
+                        sampled_out: 0,
                     },
                     handle,
                 );
@@ -156,6 +457,7 @@ fn build_or_load(
                 mut graph,
                 unresolvable_dynamic,
                 unresolvable_dynamic_files,
+                resolution,
                 changed_files,
             } => {
                 // Tier 1.5: incremental update — re-parse only changed files,
@@ -169,7 +471,12 @@ fn build_or_load(
                     lang,
                     vfs,
                 ) {
-                    graph.compute_package_info();
+                    graph.update_package_info(&result.changed_modules);
+                    // Imports are unchanged; only non-literal dynamic ones can differ
+                    let resolution = ResolutionStats {
+                        unresolvable_dynamic: result.unresolvable_dynamic,
+                        ..resolution
+                    };
                     let handle = cache.save_incremental(
                         root,
                         entry,
@@ -177,14 +484,18 @@ fn build_or_load(
                         &changed_files,
                         result.unresolvable_dynamic,
                         result.unresolvable_dynamic_files.clone(),
+                        resolution,
                     );
                     return (
                         BuildResult {
                             graph,
                             unresolvable_dynamic_count: result.unresolvable_dynamic,
                             unresolvable_dynamic_files: result.unresolvable_dynamic_files,
+                            resolution,
                             file_warnings: Vec::new(),
                             from_cache: true,
+                            timed_out: false,
+                            sampled_out: 0,
                         },
                         handle,
                     );
@@ -196,23 +507,42 @@ fn build_or_load(
     }
 
     // Tier 2: BFS walk with per-file parse cache
-    let result = walker::build_graph(entry, root, lang, &mut cache, vfs);
-    let unresolvable_count: usize = result.unresolvable_dynamic.iter().map(|(_, c)| c).sum();
-    let handle = cache.save(
-        root,
+    let result = walker::build_graph_until(
         entry,
-        &result.graph,
-        result.unresolved_specifiers,
-        unresolvable_count,
-        result.unresolvable_dynamic.clone(),
+        root,
+        lang,
+        &mut cache,
+        vfs,
+        deadline,
+        !opts.no_gitignore,
+        opts.sample,
+        opts.case_insensitive_paths,
     );
+    let unresolvable_count: usize = result.unresolvable_dynamic.iter().map(|(_, c)| c).sum();
+    // A partial graph would pass as complete on the next run
+    let handle = if result.timed_out || opts.sample.is_some() {
+        CacheWriteHandle::none()
+    } else {
+        cache.save(
+            root,
+            entry,
+            &result.graph,
+            result.unresolved_specifiers,
+            unresolvable_count,
+            result.unresolvable_dynamic.clone(),
+            result.resolution,
+        )
+    };
     (
         BuildResult {
             graph: result.graph,
             unresolvable_dynamic_count: unresolvable_count,
             unresolvable_dynamic_files: result.unresolvable_dynamic,
+            resolution: result.resolution,
             file_warnings: result.file_warnings,
             from_cache: false,
+            timed_out: result.timed_out,
+            sampled_out: result.sampled_out,
         },
         handle,
     )
@@ -221,6 +551,8 @@ fn build_or_load(
 struct IncrementalResult {
     unresolvable_dynamic: usize,
     unresolvable_dynamic_files: Vec<(PathBuf, usize)>,
+    /// Modules whose size was updated.
+    changed_modules: Vec<ModuleId>,
 }
 
 /// Try to incrementally update the cached graph when only a few files changed.
@@ -237,6 +569,7 @@ fn try_incremental_update(
     vfs: &dyn Vfs,
 ) -> Option<IncrementalResult> {
     let mut unresolvable_delta: isize = 0;
+    let mut changed_modules = Vec::with_capacity(changed_files.len());
 
     for path in changed_files {
         // Get old imports without mtime check
@@ -271,23 +604,32 @@ fn try_incremental_update(
             unresolvable_files.push((path.clone(), new_result.unresolvable_dynamic));
         }
 
-        // Update file size in graph
-        let mid = *graph.path_to_id.get(path)?;
+        // Update file size and line count in graph
+        let mid = graph.module_id(path)?;
         let new_size = source.len() as u64;
         graph.modules[mid.0 as usize].size_bytes = new_size;
+        graph.modules[mid.0 as usize].line_count = walker::count_lines(&source);
+        changed_modules.push(mid);
 
         // Update parse cache entry
-        #[allow(clippy::or_fun_call)]
-        let dir = path.parent().unwrap_or(Path::new("."));
         let resolved_paths: Vec<Option<PathBuf>> = new_result
             .imports
             .iter()
-            .map(|imp| lang.resolve(dir, &imp.specifier))
+            .map(|imp| lang.resolve_import(path, imp))
             .collect();
+        let content_hash =
+            (cache.mode() == CacheMode::Hash).then(|| cache::content_hash(path, source.as_bytes()));
         if let Ok(meta) = vfs.metadata(path)
             && let Some(mtime) = meta.mtime_nanos
         {
-            cache.insert(path.clone(), new_size, mtime, new_result, resolved_paths);
+            cache.insert(
+                path.clone(),
+                new_size,
+                mtime,
+                new_result,
+                resolved_paths,
+                content_hash,
+            );
         }
     }
 
@@ -300,5 +642,6 @@ fn try_incremental_update(
     Some(IncrementalResult {
         unresolvable_dynamic: new_total,
         unresolvable_dynamic_files: unresolvable_files,
+        changed_modules,
     })
 }
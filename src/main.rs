@@ -1,25 +1,32 @@
 // compact_str 0.8 (oxc_span) + 0.9 (oxc_resolver) — transitive, out of our control.
 #![allow(clippy::multiple_crate_versions)]
 
+use std::collections::{BTreeSet, HashSet};
 use std::io::IsTerminal;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::time::Instant;
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
 
-use clap::{Args, CommandFactory, Parser, Subcommand};
+use clap::{Args, CommandFactory, Parser, Subcommand, ValueEnum};
 use clap_complete::Shell;
 
 use chainsaw::{
+    cache::{self, CacheLocation},
     error::Error,
-    git, loader, query, repl, report,
+    git,
+    graph::ModuleGraph,
+    loader, query, repl, report,
     session::{self, Session},
-    vfs,
+    vfs, walker,
 };
 
 #[derive(Parser)]
 #[command(
     name = "chainsaw",
     version,
+    disable_version_flag = true,
+    arg_required_else_help = true,
     about = "TypeScript/JavaScript and Python dependency graph analyzer",
     after_help = "Repository: https://github.com/rocketman-code/chainsaw"
 )]
@@ -28,40 +35,161 @@ struct Cli {
     #[arg(long, global = true)]
     no_color: bool,
 
+    /// Read and write the cache at this file instead of <root>/.chainsaw.cache
+    /// [env: CHAINSAW_CACHE]
+    #[arg(long, global = true, value_name = "FILE", conflicts_with = "cache_dir")]
+    cache_file: Option<PathBuf>,
+
+    /// Keep caches in this directory, one file per project root
+    #[arg(long, global = true, value_name = "DIR")]
+    cache_dir: Option<PathBuf>,
+
+    /// Treat `process.env.KEY` as VALUE and skip requires in branches that are then dead
+    /// (e.g. NODE_ENV=production)
+    #[arg(long = "define", global = true, value_name = "KEY=VALUE", value_parser = parse_define)]
+    defines: Vec<(String, String)>,
+
+    /// Program run as `<script> <from_dir> <specifier>` for specifiers chainsaw cannot
+    /// resolve; prints the resolved path on stdout
+    #[arg(long, global = true, value_name = "PATH")]
+    resolver_script: Option<PathBuf>,
+
+    /// In packages that ship both src/ and a built entry, trace the published
+    /// exports/main entry instead of src/
+    #[arg(long, global = true)]
+    include_node_modules_source_only: bool,
+
+    /// Don't resolve `@/` to src/ and `~/` to the project root when tsconfig.json
+    /// has no `paths` entry for them
+    #[arg(long, global = true)]
+    no_default_aliases: bool,
+
+    /// Treat paths that differ only in case as the same module, as macOS and
+    /// Windows filesystems do (`./Foo` and `./foo`)
+    #[arg(long, global = true)]
+    case_insensitive_paths: bool,
+
+    /// What module weight measures: bytes on disk, or gzip-compressed bytes
+    #[arg(long, global = true, value_enum, default_value_t = WeightSource::Install)]
+    weight_source: WeightSource,
+
+    /// How cached files are checked for changes: mtime and size, or content hash
+    /// (touched-but-unchanged files still hit, identical files share a parse)
+    #[arg(long, global = true, value_enum, default_value_t = CacheMode::Mtime)]
+    cache_mode: CacheMode,
+
+    /// Embed this string as `label` in JSON output, to correlate results across runs
+    #[arg(long, global = true, value_name = "STRING")]
+    label: Option<String>,
+
+    /// Print version (with --json: version, commit, and build date as JSON)
+    #[arg(short = 'V', long)]
+    version: bool,
+
+    /// Print --version output as JSON
+    #[arg(long, requires = "version")]
+    json: bool,
+
     #[command(subcommand)]
-    command: Commands,
+    command: Option<Commands>,
 }
 
 #[derive(Subcommand)]
 enum Commands {
     /// Trace the transitive import weight from an entry point
-    Trace(TraceArgs),
+    Trace(Box<TraceArgs>),
 
     /// Compare dependency weight across snapshots or git refs
-    Diff {
-        /// Snapshot file or git ref ("before" / "baseline").
-        /// If only one arg, this is the baseline and the working tree is "after".
-        a: String,
+    Diff(DiffArgs),
 
-        /// Snapshot file or git ref ("after" / "current").
-        /// If omitted, the current working tree is the "after" side.
-        b: Option<String>,
+    /// List all third-party packages in the dependency graph
+    Packages(PackagesArgs),
 
-        /// Entry point to trace (required for git refs and working tree)
+    /// Print a JSON bill of materials of the third-party packages an entry point loads
+    Sbom {
+        /// Entry point file to trace from
+        entry: PathBuf,
+
+        /// Force full re-parse, ignoring cache
         #[arg(long)]
-        entry: Option<PathBuf>,
+        no_cache: bool,
+    },
 
-        /// Max packages to show in diff output (-1 for all)
-        #[arg(long, default_value_t = report::DEFAULT_TOP, allow_hyphen_values = true)]
-        limit: i32,
+    /// Report circular imports reachable from an entry point, type-only ones flagged
+    Cycles {
+        /// Entry point file to trace from
+        entry: PathBuf,
 
-        /// Suppress informational output (timing, warnings)
-        #[arg(long, short)]
-        quiet: bool,
+        /// Output machine-readable JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Force full re-parse, ignoring cache
+        #[arg(long)]
+        no_cache: bool,
     },
 
-    /// List all third-party packages in the dependency graph
-    Packages(PackagesArgs),
+    /// List project files an entry point never imports, grouped by directory
+    Deadcode {
+        /// Entry point file to trace from
+        entry: PathBuf,
+
+        /// Leave out files matching GLOB, relative to the project root
+        /// ('scripts/**'). Repeatable
+        #[arg(long, value_name = "GLOB")]
+        exclude: Vec<String>,
+
+        /// Also list unreachable test files (*.test.ts, *.spec.ts, test_*.py),
+        /// which are otherwise treated as entry points of their own
+        #[arg(long)]
+        include_tests: bool,
+
+        /// Also list unreachable declaration files (*.d.ts), which the
+        /// compiler loads without an import
+        #[arg(long)]
+        include_declarations: bool,
+
+        /// Output machine-readable JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Force full re-parse, ignoring cache
+        #[arg(long)]
+        no_cache: bool,
+    },
+
+    /// List the modules that import a file, to check it is safe to delete
+    Dependents {
+        /// File to look up importers of
+        file: PathBuf,
+
+        /// Entry point whose dependency graph is searched
+        #[arg(long)]
+        entry: PathBuf,
+
+        /// Also list indirect dependents: every module with an import path to FILE
+        #[arg(long)]
+        transitive: bool,
+
+        /// Output machine-readable JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Force full re-parse, ignoring cache
+        #[arg(long)]
+        no_cache: bool,
+    },
+
+    /// Drop cache entries for deleted or changed files and compact the cache
+    PruneCache {
+        /// Project root whose cache to prune
+        #[arg(default_value = ".")]
+        root: PathBuf,
+
+        /// Output machine-readable JSON
+        #[arg(long)]
+        json: bool,
+    },
 
     /// Interactive exploration mode
     Repl {
@@ -76,7 +204,7 @@ enum Commands {
     },
 }
 
-#[derive(Args)]
+#[derive(Args, Clone)]
 #[allow(clippy::struct_excessive_bools)] // CLI flags are inherently boolean
 struct TraceArgs {
     /// Entry point file to trace from
@@ -94,14 +222,55 @@ struct TraceArgs {
     #[arg(long)]
     diff_from: Option<PathBuf>,
 
+    /// Compare the current graph against the one cached by the last run
+    #[arg(long)]
+    diff_cache: bool,
+
     /// Also traverse dynamic imports
     #[arg(long)]
     include_dynamic: bool,
 
+    /// Report the type-dependency graph: follow only `import type` edges
+    #[arg(long)]
+    type_graph: bool,
+
+    /// Count modules reached only through `import type` in the static weight
+    /// and module count (by default they are reported separately)
+    #[arg(long, conflicts_with = "type_graph")]
+    count_type_only: bool,
+
+    /// Follow at most N levels of dynamic imports (static imports are unbounded)
+    #[arg(long, value_name = "N")]
+    dynamic_depth: Option<u32>,
+
+    /// Descend at most N package boundaries from the entry: 1 traces your
+    /// direct dependencies but not their dependencies
+    #[arg(long, value_name = "N")]
+    max_package_depth: Option<u32>,
+
     /// Show top N heaviest dependencies (0 to hide, -1 for all)
     #[arg(long, default_value_t = report::DEFAULT_TOP, allow_hyphen_values = true)]
     top: i32,
 
+    /// Add a line-count column to the modules table
+    #[arg(long)]
+    show_lines: bool,
+
+    /// Show only third-party packages: all of them, with versions and import chains,
+    /// and no source-file rows
+    #[arg(long)]
+    only_packages: bool,
+
+    /// Replace the module table with reachable modules rolled up by package:
+    /// each package's total size and largest module (first-party code under "(local)")
+    #[arg(long)]
+    group_by_package: bool,
+
+    /// Trace every workspace package of the monorepo around ENTRY in one
+    /// graph: per-package weight plus which packages import which
+    #[arg(long)]
+    aggregate_monorepo: bool,
+
     /// Show top N modules by exclusive weight — bytes not reachable through any other path (0 to hide, -1 for all)
     #[arg(long, default_value_t = report::DEFAULT_TOP_MODULES, allow_hyphen_values = true)]
     top_modules: i32,
@@ -110,22 +279,81 @@ struct TraceArgs {
     #[arg(long)]
     chain: Option<String>,
 
+    /// With --chain, only show chains of at least N hops (hides direct imports at 2)
+    #[arg(long, value_name = "N", requires = "chain")]
+    min_hops: Option<u32>,
+
+    /// With --chain, label each hop with the import specifier written in source
+    #[arg(long, requires = "chain")]
+    show_specifiers: bool,
+
     /// Show where to cut to sever all import chains to a package or file
     #[arg(long)]
     cut: Option<String>,
 
+    /// Show the static weight saved by detaching a file (e.g. lazy-loading it)
+    #[arg(long, value_name = "PATH")]
+    without: Option<PathBuf>,
+
     /// Output machine-readable JSON
     #[arg(long)]
     json: bool,
 
+    /// Print the module table (path, package, size, exclusive size, depth) as CSV or TSV,
+    /// folded stacks, the CI gate results as JUnit XML, or the import graph as DOT
+    /// or a Mermaid diagram.
+    /// `table` keeps the normal trace but draws its package and module sections as
+    /// bordered tables
+    #[arg(long, value_enum, conflicts_with = "json")]
+    format: Option<TableFormat>,
+
+    /// With --format dot, box each package's modules (and the project's own
+    /// files, by top-level directory) in a labeled cluster
+    #[arg(long, requires = "format")]
+    cluster_by_package: bool,
+
+    /// With --format dot or mermaid, draw each package reached more than N imports
+    /// from the entry as one node with its dependencies' combined size
+    #[arg(long, value_name = "N", requires = "format")]
+    collapse_depth: Option<u32>,
+
+    /// With --format table, draw borders with ASCII `+`, `-` and `|` instead of
+    /// box-drawing characters
+    #[arg(long, requires = "format")]
+    ascii: bool,
+
+    /// Field separator for --format (default: ',' for csv, tab for tsv)
+    #[arg(long, value_name = "CHAR", requires = "format")]
+    delimiter: Option<char>,
+
     /// Force full re-parse, ignoring cache
     #[arg(long)]
     no_cache: bool,
 
+    /// Keep running and re-trace whenever a project file in the graph, or a
+    /// new file next to one, changes. Only changed files are re-parsed
+    #[arg(long, conflicts_with_all = ["max_weight", "max_modules", "save", "diff_cache"])]
+    watch: bool,
+
+    /// Also follow imports into project files matched by a .gitignore
+    /// (build output, vendored copies); by default they are left out
+    #[arg(long)]
+    no_gitignore: bool,
+
     /// Exclude packages from the heavy dependencies list
     #[arg(long, num_args = 1..)]
     ignore: Vec<String>,
 
+    /// Drop modules matching GLOB from the weight, module table, and packages:
+    /// a package name ('@aws-sdk/*') or a path relative to the project root
+    /// ('node_modules/**'). Repeatable. Their own imports are still counted
+    #[arg(long, value_name = "GLOB")]
+    exclude: Vec<String>,
+
+    /// With --exclude, also drop everything reachable only through excluded modules
+    #[arg(long, requires = "exclude")]
+    exclude_subtree: bool,
+
     /// Suppress informational output (timing, warnings)
     #[arg(long, short)]
     quiet: bool,
@@ -134,9 +362,191 @@ struct TraceArgs {
     #[arg(long, default_value_t = report::DEFAULT_TOP, allow_hyphen_values = true)]
     limit: i32,
 
-    /// Exit with error if static weight exceeds this threshold (e.g. 5MB, 500KB)
+    /// Exit with code 3 if static weight exceeds this threshold (e.g. 5MB, 500KB)
     #[arg(long, value_parser = parse_size)]
     max_weight: Option<u64>,
+
+    /// Exit with code 3 if more than N modules are reachable
+    #[arg(long, value_name = "N")]
+    max_modules: Option<usize>,
+
+    /// Only report module count, weight, and package count (skips detailed sections)
+    #[arg(long)]
+    count_only: bool,
+
+    /// List every import with the resolution strategy that matched and the resolved path
+    #[arg(long)]
+    resolve_report: bool,
+
+    /// Show every candidate path tried for SPECIFIER and why each failed
+    #[arg(long, value_name = "SPECIFIER")]
+    explain_unresolved: Option<String>,
+
+    /// Report imports of barrel files (index re-exporting many modules) and how much they amplify cost
+    #[arg(long)]
+    barrel_penalty: bool,
+
+    /// List the N modules with the most transitive dependents: the files whose
+    /// changes ripple widest (default 10; `--most-depended-on=-1` for all)
+    #[arg(long, value_name = "N", num_args = 0..=1, default_missing_value = "10")]
+    most_depended_on: Option<i32>,
+
+    /// List packages installed more than once (e.g. two versions), with each
+    /// copy's version, size, and an import chain to it
+    #[arg(long)]
+    duplicates: bool,
+
+    /// Report only what dynamic imports load: the modules outside the static
+    /// startup set, their weight, and each lazy-load boundary's chunk
+    #[arg(long, conflicts_with = "include_dynamic")]
+    only_dynamic: bool,
+
+    /// Rank packages by the weight removing them would save: their own files
+    /// plus everything only they import (limited by --top)
+    #[arg(long)]
+    savings: bool,
+
+    /// List modules reached both from PACKAGE and from the entry's own code
+    /// without going through a package: unexpected coupling with a dependency
+    #[arg(long, value_name = "PACKAGE")]
+    shared_with: Option<String>,
+
+    /// Exit with error if any dynamic import has a non-literal argument
+    #[arg(long, conflicts_with = "ignore_unresolvable_dynamic")]
+    fail_on_unresolvable_dynamic: bool,
+
+    /// Don't warn about dynamic imports with non-literal arguments
+    #[arg(long)]
+    ignore_unresolvable_dynamic: bool,
+
+    /// Exit with error if the reachable graph has an import cycle (static or dynamic edges)
+    #[arg(long, group = "cycle_gate")]
+    fail_on_cycle: bool,
+
+    /// Like --fail-on-cycle, but ignore cycles that need a dynamic import to close
+    #[arg(long, group = "cycle_gate")]
+    fail_on_static_cycle: bool,
+
+    /// File of accepted cycles, one per line as its member paths separated by spaces
+    #[arg(long, value_name = "FILE", requires = "cycle_gate")]
+    cycle_allowlist: Option<PathBuf>,
+
+    /// Snapshot saved with --save to compare against: reports dynamic
+    /// imports added or removed since it was taken
+    #[arg(long, value_name = "FILE")]
+    compare_baseline: Option<PathBuf>,
+
+    /// Exit with error if a dynamic import is not in the --compare-baseline snapshot
+    #[arg(long, requires = "compare_baseline")]
+    fail_on_new_dynamic_import: bool,
+
+    /// Stop building the graph after this many seconds and report the
+    /// partial result (exit code 124; nothing is cached)
+    #[arg(long, value_name = "SECS")]
+    timeout: Option<u64>,
+
+    /// Parse only this share of the reachable files (0 < FRACTION <= 1) and
+    /// extrapolate the static weight, for a quick estimate on huge graphs.
+    /// Files are picked by path and --seed, so runs are reproducible;
+    /// nothing is cached
+    #[arg(
+        long,
+        value_name = "FRACTION",
+        value_parser = parse_fraction,
+        conflicts_with_all = ["save", "max_weight", "max_modules", "diff_cache", "watch"]
+    )]
+    sample: Option<f64>,
+
+    /// Seed choosing which files --sample parses
+    #[arg(long, requires = "sample", default_value_t = 0)]
+    seed: u64,
+}
+
+/// What a module's weight measures (`--weight-source`).
+#[derive(Clone, Copy, ValueEnum)]
+enum WeightSource {
+    /// File size on disk (install footprint)
+    Install,
+    /// Gzip-compressed size (level 6), an estimate of transfer size
+    Gzip,
+}
+
+impl From<WeightSource> for loader::WeightSource {
+    fn from(source: WeightSource) -> Self {
+        match source {
+            WeightSource::Install => Self::Install,
+            WeightSource::Gzip => Self::Gzip,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum CacheMode {
+    /// Compare modification time and size
+    Mtime,
+    /// Compare a hash of the contents when the modification time differs
+    Hash,
+}
+
+impl From<CacheMode> for cache::CacheMode {
+    fn from(mode: CacheMode) -> Self {
+        match mode {
+            CacheMode::Mtime => Self::Mtime,
+            CacheMode::Hash => Self::Hash,
+        }
+    }
+}
+
+/// Delimited-text flavor for `trace --format`.
+#[derive(Clone, Copy, ValueEnum)]
+enum TableFormat {
+    /// Comma-separated, RFC 4180 quoting
+    Csv,
+    /// Tab-separated, fields written raw
+    Tsv,
+    /// Folded stacks for flamegraph.pl / inferno, one per module
+    Flamegraph,
+    /// JUnit XML with one test case per CI gate (--max-weight, --fail-on-cycle,
+    /// --fail-on-static-cycle, --fail-on-unresolvable-dynamic)
+    Junit,
+    /// Graphviz DOT of the reachable import graph
+    Dot,
+    /// Mermaid `graph LR` block of the entry, its direct imports and the
+    /// heaviest packages, for Markdown docs
+    Mermaid,
+    /// The normal trace, with aligned, bordered package and module tables
+    Table,
+}
+
+#[derive(Args)]
+struct DiffArgs {
+    /// Snapshot file or git ref ("before" / "baseline").
+    /// If only one arg, this is the baseline and the working tree is "after".
+    a: String,
+
+    /// Snapshot file or git ref ("after" / "current").
+    /// If omitted, the current working tree is the "after" side.
+    b: Option<String>,
+
+    /// Entry point to trace (required for git refs and working tree)
+    #[arg(long)]
+    entry: Option<PathBuf>,
+
+    /// Max packages to show in diff output (-1 for all)
+    #[arg(long, default_value_t = report::DEFAULT_TOP, allow_hyphen_values = true)]
+    limit: i32,
+
+    /// Output machine-readable JSON
+    #[arg(long)]
+    json: bool,
+
+    /// Also list modules added and removed, with a one-line summary for PR comments
+    #[arg(long)]
+    module_level: bool,
+
+    /// Suppress informational output (timing, warnings)
+    #[arg(long, short)]
+    quiet: bool,
 }
 
 #[derive(Args)]
@@ -156,11 +566,40 @@ struct PackagesArgs {
     #[arg(long, default_value_t = report::DEFAULT_TOP, allow_hyphen_values = true)]
     top: i32,
 
+    /// List the third-party packages each package pulls in, with their weight
+    #[arg(long)]
+    with_deps: bool,
+
     /// Suppress informational output (timing, warnings)
     #[arg(long, short)]
     quiet: bool,
 }
 
+fn parse_define(s: &str) -> Result<(String, String), String> {
+    let (key, value) = s.split_once('=').ok_or_else(|| {
+        format!("invalid define: {s}\nhint: use KEY=VALUE, e.g. NODE_ENV=production")
+    })?;
+    let key = key
+        .trim()
+        .strip_prefix("process.env.")
+        .unwrap_or(key.trim());
+    if key.is_empty() {
+        return Err(format!(
+            "invalid define: {s}\nhint: use KEY=VALUE, e.g. NODE_ENV=production"
+        ));
+    }
+    Ok((key.to_string(), value.to_string()))
+}
+
+fn parse_fraction(s: &str) -> Result<f64, String> {
+    match s.trim().parse::<f64>() {
+        Ok(f) if f > 0.0 && f <= 1.0 => Ok(f),
+        _ => Err(format!(
+            "invalid fraction: {s}\nhint: use a number above 0 and at most 1, e.g. 0.1"
+        )),
+    }
+}
+
 #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
 fn parse_size(s: &str) -> Result<u64, String> {
     let s = s.trim();
@@ -204,10 +643,38 @@ fn main() {
         .ok(); // ignore if already initialized (e.g. in tests)
 
     let cli = Cli::parse();
+    if cli.version {
+        if cli.json {
+            println!("{}", version_json());
+        } else {
+            println!("chainsaw {}", long_version());
+        }
+        return;
+    }
+    let Some(command) = cli.command else {
+        Cli::command().print_help().ok();
+        std::process::exit(2);
+    };
     let no_color = cli.no_color;
     let sc = report::StderrColor::new(no_color);
+    let load_opts = loader::LoadOptions {
+        no_cache: false,
+        cache_location: cache_location(cli.cache_file, cli.cache_dir),
+        defines: cli.defines,
+        resolver_script: cli.resolver_script,
+        weight_source: cli.weight_source.into(),
+        prefer_published_entry: cli.include_node_modules_source_only,
+        no_default_aliases: cli.no_default_aliases,
+        cache_mode: cli.cache_mode.into(),
+        timeout: None,
+        no_gitignore: false,
+        sample: None,
+        case_insensitive_paths: cli.case_insensitive_paths,
+    };
 
-    if let Err(e) = run(cli.command, no_color, sc) {
+    let meta = report::JsonMeta::now(cli.label);
+
+    if let Err(e) = run(command, no_color, &load_opts, &meta, sc) {
         eprintln!("{} {e}", sc.error("error:"));
         if let Some(hint) = e.hint() {
             eprintln!("hint: {hint}");
@@ -216,22 +683,155 @@ fn main() {
     }
 }
 
-fn run(command: Commands, no_color: bool, sc: report::StderrColor) -> Result<(), Error> {
-    let color = resolve_color(no_color);
-    match command {
-        Commands::Trace(args) => run_trace(args, color, sc),
+/// Pick the cache location: `--cache-file`, then `--cache-dir`, then the
+/// `CHAINSAW_CACHE` environment variable (a cache file path).
+fn cache_location(cache_file: Option<PathBuf>, cache_dir: Option<PathBuf>) -> CacheLocation {
+    if let Some(file) = cache_file {
+        return CacheLocation::File(file);
+    }
+    if let Some(dir) = cache_dir {
+        return CacheLocation::Dir(dir);
+    }
+    match std::env::var_os("CHAINSAW_CACHE") {
+        Some(file) if !file.is_empty() => CacheLocation::File(PathBuf::from(file)),
+        _ => CacheLocation::ProjectRoot,
+    }
+}
 
-        Commands::Diff {
-            a,
-            b,
-            entry,
-            limit,
-            quiet,
-        } => run_diff(a, b, entry, limit, quiet, color, sc),
+/// Version plus build metadata, e.g. `0.3.0 (1a2b3c4d5e6f 2026-01-31)`.
+fn long_version() -> String {
+    format!(
+        "{} ({} {})",
+        env!("CARGO_PKG_VERSION"),
+        env!("CHAINSAW_GIT_COMMIT"),
+        env!("CHAINSAW_BUILD_DATE"),
+    )
+}
 
-        Commands::Packages(ref args) => run_packages(args, color, sc),
+/// Stable machine-readable version info for wrappers doing feature detection.
+fn version_json() -> String {
+    let info = serde_json::json!({
+        "name": "chainsaw",
+        "version": env!("CARGO_PKG_VERSION"),
+        "commit": env!("CHAINSAW_GIT_COMMIT"),
+        "build_date": env!("CHAINSAW_BUILD_DATE"),
+    });
+    serde_json::to_string_pretty(&info).unwrap()
+}
 
-        Commands::Repl { ref entry } => repl::run(entry, no_color, sc),
+fn run(
+    command: Commands,
+    no_color: bool,
+    load_opts: &loader::LoadOptions,
+    meta: &report::JsonMeta,
+    sc: report::StderrColor,
+) -> Result<(), Error> {
+    let color = resolve_color(no_color);
+    match command {
+        Commands::Trace(args) => run_trace(*args, load_opts, meta, color, sc),
+
+        Commands::Diff(args) => run_diff(args, load_opts, meta, color, sc),
+
+        Commands::Packages(ref args) => run_packages(args, load_opts, meta, color, sc),
+
+        Commands::Sbom {
+            ref entry,
+            no_cache,
+        } => {
+            let load_opts = loader::LoadOptions {
+                no_cache,
+                ..load_opts.clone()
+            };
+            let session = Session::open_with(entry, &load_opts)?;
+            println!("{}", meta.wrap(&session.sbom_report()));
+            Ok(())
+        }
+        Commands::Cycles {
+            ref entry,
+            json,
+            no_cache,
+        } => {
+            let load_opts = loader::LoadOptions {
+                no_cache,
+                ..load_opts.clone()
+            };
+            let session = Session::open_with(entry, &load_opts)?;
+            let report = session.cycles_report();
+            if json {
+                println!("{}", meta.wrap(&report));
+            } else {
+                print!("{}", report.to_terminal(color));
+            }
+            Ok(())
+        }
+        Commands::Deadcode {
+            ref entry,
+            ref exclude,
+            include_tests,
+            include_declarations,
+            json,
+            no_cache,
+        } => {
+            let load_opts = loader::LoadOptions {
+                no_cache,
+                ..load_opts.clone()
+            };
+            let session = Session::open_with(entry, &load_opts)?;
+            let exclude = (!exclude.is_empty())
+                .then(|| query::Exclude::new(exclude, session.root(), false))
+                .transpose()?;
+            let report =
+                session.deadcode_report(exclude.as_ref(), include_tests, include_declarations)?;
+            if json {
+                println!("{}", meta.wrap(&report));
+            } else {
+                print!("{}", report.to_terminal(color));
+            }
+            Ok(())
+        }
+        Commands::Dependents {
+            ref file,
+            ref entry,
+            transitive,
+            json,
+            no_cache,
+        } => {
+            let load_opts = loader::LoadOptions {
+                no_cache,
+                ..load_opts.clone()
+            };
+            let session = Session::open_with(entry, &load_opts)?;
+            let report = session.dependents_report(file, transitive)?;
+            if json {
+                println!("{}", meta.wrap(&report));
+            } else {
+                print!("{}", report.to_terminal(color));
+            }
+            Ok(())
+        }
+        Commands::PruneCache { ref root, json } => {
+            let root = root
+                .canonicalize()
+                .map_err(|e| Error::CacheIo(root.clone(), e))?;
+            let path = load_opts.cache_location.path_for(&root);
+            let stats =
+                cache::prune_cache(&path, &root).map_err(|e| Error::CacheIo(path.clone(), e))?;
+            let report = report::PruneReport {
+                cache_file: report::relative_path(&path, &root),
+                entries_removed: stats.entries_removed,
+                entries_kept: stats.entries_kept,
+                graph_discarded: stats.graph_discarded,
+                bytes_before: stats.bytes_before,
+                bytes_after: stats.bytes_after,
+            };
+            if json {
+                println!("{}", meta.wrap(&report));
+            } else {
+                print!("{}", report.to_terminal(color));
+            }
+            Ok(())
+        }
+        Commands::Repl { ref entry } => repl::run(entry, load_opts, no_color, sc),
 
         Commands::Completions { shell } => {
             clap_complete::generate(
@@ -249,7 +849,13 @@ fn run(command: Commands, no_color: bool, sc: report::StderrColor) -> Result<(),
 // trace subcommand
 // ---------------------------------------------------------------------------
 
-fn run_trace(args: TraceArgs, color: bool, sc: report::StderrColor) -> Result<(), Error> {
+fn run_trace(
+    args: TraceArgs,
+    load_opts: &loader::LoadOptions,
+    meta: &report::JsonMeta,
+    color: bool,
+    sc: report::StderrColor,
+) -> Result<(), Error> {
     let start = Instant::now();
 
     // Validate mutually exclusive flags before loading graph
@@ -258,6 +864,25 @@ fn run_trace(args: TraceArgs, color: bool, sc: report::StderrColor) -> Result<()
         args.cut.as_ref().map(|_| "--cut"),
         args.diff.as_ref().map(|_| "--diff"),
         args.diff_from.as_ref().map(|_| "--diff-from"),
+        args.diff_cache.then_some("--diff-cache"),
+        args.count_only.then_some("--count-only"),
+        args.barrel_penalty.then_some("--barrel-penalty"),
+        args.most_depended_on.map(|_| "--most-depended-on"),
+        args.shared_with.as_ref().map(|_| "--shared-with"),
+        args.duplicates.then_some("--duplicates"),
+        args.savings.then_some("--savings"),
+        args.only_dynamic.then_some("--only-dynamic"),
+        args.resolve_report.then_some("--resolve-report"),
+        args.explain_unresolved
+            .as_ref()
+            .map(|_| "--explain-unresolved"),
+        args.format
+            .filter(|f| !matches!(f, TableFormat::Table))
+            .map(|_| "--format"),
+        args.only_packages.then_some("--only-packages"),
+        args.group_by_package.then_some("--group-by-package"),
+        args.aggregate_monorepo.then_some("--aggregate-monorepo"),
+        args.without.as_ref().map(|_| "--without"),
     ]
     .into_iter()
     .flatten()
@@ -265,33 +890,497 @@ fn run_trace(args: TraceArgs, color: bool, sc: report::StderrColor) -> Result<()
     if query_flags.len() > 1 {
         return Err(Error::MutuallyExclusiveFlags(query_flags.join(" and ")));
     }
+    if let Some(
+        format @ (TableFormat::Flamegraph
+        | TableFormat::Junit
+        | TableFormat::Dot
+        | TableFormat::Mermaid
+        | TableFormat::Table),
+    ) = args.format
+        && args.delimiter.is_some()
+    {
+        let name = format.to_possible_value().unwrap();
+        return Err(Error::MutuallyExclusiveFlags(format!(
+            "--format {} and --delimiter",
+            name.get_name()
+        )));
+    }
+    if args.cluster_by_package
+        && let Some(format) = args.format
+        && !matches!(format, TableFormat::Dot)
+    {
+        let name = format.to_possible_value().unwrap();
+        return Err(Error::MutuallyExclusiveFlags(format!(
+            "--cluster-by-package and --format {}",
+            name.get_name()
+        )));
+    }
+    if args.collapse_depth.is_some()
+        && let Some(format) = args.format
+        && !matches!(format, TableFormat::Dot | TableFormat::Mermaid)
+    {
+        let name = format.to_possible_value().unwrap();
+        return Err(Error::MutuallyExclusiveFlags(format!(
+            "--collapse-depth and --format {}",
+            name.get_name()
+        )));
+    }
+    if args.ascii
+        && let Some(format) = args.format
+        && !matches!(format, TableFormat::Table)
+    {
+        let name = format.to_possible_value().unwrap();
+        return Err(Error::MutuallyExclusiveFlags(format!(
+            "--ascii and --format {}",
+            name.get_name()
+        )));
+    }
+    if args.count_only && args.save.is_some() {
+        return Err(Error::MutuallyExclusiveFlags(
+            "--count-only and --save".into(),
+        ));
+    }
+
+    if args.top < -1 {
+        return Err(Error::InvalidTopValue("--top", args.top));
+    }
+    if args.top_modules < -1 {
+        return Err(Error::InvalidTopValue("--top-modules", args.top_modules));
+    }
+    if args.limit < -1 {
+        return Err(Error::InvalidTopValue("--limit", args.limit));
+    }
+    if let Some(n) = args.most_depended_on
+        && n < -1
+    {
+        return Err(Error::InvalidTopValue("--most-depended-on", n));
+    }
+
+    let load_opts = loader::LoadOptions {
+        no_cache: args.no_cache,
+        timeout: args.timeout.map(Duration::from_secs),
+        no_gitignore: args.no_gitignore,
+        sample: args.sample.map(|fraction| walker::Sample {
+            fraction,
+            seed: args.seed,
+        }),
+        ..load_opts.clone()
+    };
+    if args.aggregate_monorepo && args.type_graph {
+        return Err(Error::MutuallyExclusiveFlags(
+            "--aggregate-monorepo and --type-graph".into(),
+        ));
+    }
+    if args.aggregate_monorepo {
+        return run_aggregate_monorepo(&args, &load_opts, meta, color, start, sc);
+    }
+    // Read before opening the session, which rewrites the cache
+    let cached_graph = if args.diff_cache {
+        let graph = loader::load_cached_graph(&args.entry, &load_opts)?;
+        Some(graph.ok_or_else(|| Error::NoCachedGraph(args.entry.clone()))?)
+    } else {
+        None
+    };
+    let mut session = Session::open_with(&args.entry, &load_opts)?;
+    if args.type_graph {
+        session.restrict_to_type_graph();
+    }
+    if session.timed_out() {
+        eprintln!(
+            "{} build timed out after {}s; results are partial (not cached)",
+            sc.warning("warning:"),
+            args.timeout.unwrap_or_default(),
+        );
+    }
+    let dynamic_policy = UnresolvableDynamic::from_flags(
+        args.fail_on_unresolvable_dynamic,
+        args.ignore_unresolvable_dynamic,
+    );
+    if !args.quiet {
+        print_session_status(&session, start, dynamic_policy, sc);
+    }
+    // --format junit reports the gates as test cases instead of exiting here
+    let junit = matches!(args.format, Some(TableFormat::Junit));
+    if !junit && let Some(msg) = dynamic_policy.failure(session.unresolvable_dynamic_count()) {
+        eprintln!("{} {msg}", sc.error("error:"));
+        std::process::exit(1);
+    }
+    if !junit && (args.fail_on_cycle || args.fail_on_static_cycle) {
+        check_cycles(
+            &session,
+            args.fail_on_cycle,
+            args.cycle_allowlist.as_deref(),
+            sc,
+        )?;
+    }
+    if !junit && let Some(ref baseline) = args.compare_baseline {
+        check_dynamic_imports(&session, baseline, args.fail_on_new_dynamic_import, sc)?;
+    }
+
+    if args.watch {
+        return watch_trace(session, &args, &load_opts, meta, color, start, sc);
+    }
+    let timed_out = session.timed_out();
+    let code = trace_output(
+        &session,
+        args,
+        cached_graph.as_ref(),
+        &load_opts,
+        meta,
+        color,
+        start,
+        sc,
+    )?;
+    if code != 0 {
+        std::process::exit(code);
+    }
+    if timed_out {
+        std::process::exit(EXIT_TIMED_OUT);
+    }
+    Ok(())
+}
+
+/// Quiet period after a change before re-tracing, so an editor's burst of
+/// writes (or a formatter run on save) triggers one trace, not several.
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// `trace --watch`: print the trace, then clear the terminal and print it
+/// again after every change. [`Session::refresh`] goes through the cache, so
+/// only changed files are re-parsed, and a rebuild picks up files the entry
+/// newly imports.
+fn watch_trace(
+    mut session: Session,
+    args: &TraceArgs,
+    load_opts: &loader::LoadOptions,
+    meta: &report::JsonMeta,
+    color: bool,
+    mut start: Instant,
+    sc: report::StderrColor,
+) -> Result<(), Error> {
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx).map_err(Error::Watch)?;
+    let mut watched = HashSet::new();
+    let dynamic_policy = UnresolvableDynamic::from_flags(
+        args.fail_on_unresolvable_dynamic,
+        args.ignore_unresolvable_dynamic,
+    );
+    let mut first = true;
+    loop {
+        if !first {
+            print!("\x1b[2J\x1b[H");
+            // Clear before the load status goes to stderr
+            let _ = std::io::Write::flush(&mut std::io::stdout());
+            if !args.quiet {
+                print_session_status(&session, start, dynamic_policy, sc);
+            }
+        }
+        first = false;
+        let result = trace_output(
+            &session,
+            args.clone(),
+            None,
+            load_opts,
+            meta,
+            color,
+            start,
+            sc,
+        );
+        // A failed check is printed with the output; keep watching for a fix
+        if let Err(e) = result {
+            eprintln!("{} {e}", sc.error("error:"));
+        }
+        watch_graph_dirs(&mut watcher, &session, &mut watched)?;
+        eprintln!(
+            "{} {} for changes (Ctrl-C to stop)",
+            sc.status("Watching"),
+            session.entry_label()
+        );
+        let Some(changed) = wait_for_change(&rx, &session) else {
+            return Ok(());
+        };
+        start = Instant::now();
+        // Edits to known files are patched in; anything else reloads. The
+        // type graph has its edges recast, so it always reloads.
+        if !args.type_graph && session.update_files(&changed).is_some() {
+            continue;
+        }
+        if let Err(e) = session.refresh() {
+            eprintln!("{} refresh failed: {e}", sc.warning("warning:"));
+        } else if args.type_graph {
+            session.restrict_to_type_graph();
+        }
+    }
+}
+
+/// Watch the project root and every directory holding a first-party module,
+/// so both edits and files created next to existing modules are seen.
+/// Installed packages are left alone; reinstalling changes the root's
+/// package.json or lockfile instead.
+fn watch_graph_dirs(
+    watcher: &mut impl notify::Watcher,
+    session: &Session,
+    watched: &mut HashSet<PathBuf>,
+) -> Result<(), Error> {
+    let dirs = std::iter::once(session.root()).chain(
+        session
+            .graph()
+            .modules
+            .iter()
+            .filter(|m| m.package.is_none())
+            .filter_map(|m| m.path.parent()),
+    );
+    for dir in dirs {
+        if !watched.contains(dir) {
+            watcher
+                .watch(dir, notify::RecursiveMode::NonRecursive)
+                .map_err(Error::Watch)?;
+            watched.insert(dir.to_path_buf());
+        }
+    }
+    Ok(())
+}
+
+/// Block until a change that can affect the graph, then wait out
+/// [`WATCH_DEBOUNCE`]. Returns the files changed meanwhile, or `None` if
+/// the watcher shut down.
+fn wait_for_change(
+    rx: &mpsc::Receiver<notify::Result<notify::Event>>,
+    session: &Session,
+) -> Option<Vec<PathBuf>> {
+    let mut changed: Vec<PathBuf> = Vec::new();
+    let record = |event: notify::Event, changed: &mut Vec<PathBuf>| {
+        use notify::EventKind;
+        if !matches!(
+            event.kind,
+            EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+        ) {
+            return;
+        }
+        for path in event.paths {
+            // Source files and manifests, not the cache chainsaw writes itself
+            let affects_graph = session.graph().module_id(&path).is_some()
+                || path
+                    .extension()
+                    .and_then(|e| e.to_str())
+                    .is_some_and(|ext| session.valid_extensions().contains(&ext))
+                || path
+                    .file_name()
+                    .is_some_and(|n| n == "package.json" || n == "tsconfig.json");
+            if affects_graph && !changed.contains(&path) {
+                changed.push(path);
+            }
+        }
+    };
+    while changed.is_empty() {
+        match rx.recv() {
+            Ok(Ok(event)) => record(event, &mut changed),
+            Ok(Err(_)) => {}
+            Err(_) => return None,
+        }
+    }
+    while let Ok(event) = rx.recv_timeout(WATCH_DEBOUNCE) {
+        if let Ok(event) = event {
+            record(event, &mut changed);
+        }
+    }
+    Some(changed)
+}
+
+/// `trace --aggregate-monorepo`: one uncached build from every workspace
+/// package's entry.
+fn run_aggregate_monorepo(
+    args: &TraceArgs,
+    load_opts: &loader::LoadOptions,
+    meta: &report::JsonMeta,
+    color: bool,
+    start: Instant,
+    sc: report::StderrColor,
+) -> Result<(), Error> {
+    let ws = loader::load_workspace_graph(&args.entry, load_opts, Arc::new(vfs::OsVfs))?;
+    if !args.quiet {
+        report::print_load_status(
+            false,
+            ws.graph.module_count(),
+            start.elapsed().as_secs_f64() * 1000.0,
+            &ws.file_warnings,
+            0,
+            &[],
+            &ws.root,
+            sc,
+        );
+    }
+    let opts = query::TraceOptions {
+        include_dynamic: args.include_dynamic,
+        dynamic_depth: args.dynamic_depth,
+        ..Default::default()
+    };
+    let report = session::monorepo_report(&ws, &opts);
+    if args.json {
+        println!("{}", meta.wrap(&report));
+    } else {
+        print!("{}", report.to_terminal(color));
+    }
+    Ok(())
+}
+
+/// Exit code for a `trace --timeout` that cut the build short, matching
+/// coreutils `timeout`.
+const EXIT_TIMED_OUT: i32 = 124;
+
+/// Everything `trace` prints once the graph is loaded and the gates passed.
+/// Returns the exit code: non-zero when the mode's own check failed (no
+/// chain found, a failing JUnit gate, a budget exceeded), which the caller
+/// exits with or, under `--watch`, reports and keeps going.
+#[allow(clippy::too_many_arguments)] // private dispatch shared by run_trace and watch_trace
+fn trace_output(
+    session: &Session,
+    args: TraceArgs,
+    cached_graph: Option<&ModuleGraph>,
+    load_opts: &loader::LoadOptions,
+    meta: &report::JsonMeta,
+    color: bool,
+    start: Instant,
+    sc: report::StderrColor,
+) -> Result<i32, Error> {
+    let opts = query::TraceOptions {
+        include_dynamic: args.include_dynamic,
+        top_n: args.top,
+        ignore: args.ignore.clone(),
+        dynamic_depth: args.dynamic_depth,
+        exclude: (!args.exclude.is_empty())
+            .then(|| query::Exclude::new(&args.exclude, session.root(), args.exclude_subtree))
+            .transpose()?,
+        count_type_only: args.count_type_only,
+        max_package_depth: args.max_package_depth,
+    };
+
+    if args.resolve_report {
+        let report = session.resolve_report()?;
+        if args.json {
+            println!("{}", meta.wrap(&report));
+        } else {
+            print!("{}", report.to_terminal(color));
+        }
+        return Ok(0);
+    }
 
-    if args.top < -1 {
-        return Err(Error::InvalidTopValue("--top", args.top));
-    }
-    if args.top_modules < -1 {
-        return Err(Error::InvalidTopValue("--top-modules", args.top_modules));
+    if let Some(ref specifier) = args.explain_unresolved {
+        let report = session.explain_unresolved(specifier)?;
+        if args.json {
+            println!("{}", meta.wrap(&report));
+        } else {
+            print!("{}", report.to_terminal(color));
+        }
+        return Ok(0);
     }
-    if args.limit < -1 {
-        return Err(Error::InvalidTopValue("--limit", args.limit));
+
+    if let Some(ref path) = args.without {
+        let report = session.without_report(path)?;
+        if args.json {
+            println!("{}", meta.wrap(&report));
+        } else {
+            print!("{}", report.to_terminal(color));
+        }
+        return Ok(0);
     }
 
-    let session = Session::open(&args.entry, args.no_cache)?;
-    if !args.quiet {
-        print_session_status(&session, start, sc);
+    // --count-only: headline totals only, skipping the full trace
+    if args.count_only {
+        let mut report = session.count_report(&opts);
+        let failures = budget_failures(
+            &args,
+            report.static_weight_bytes,
+            report.static_module_count,
+        );
+        report.budget_exceeded = has_budget(&args).then_some(!failures.is_empty());
+        if args.json {
+            println!("{}", meta.wrap(&report));
+        } else {
+            print!("{}", report.to_terminal(color));
+        }
+        return Ok(check_budget(&failures, sc));
     }
 
-    let opts = query::TraceOptions {
-        include_dynamic: args.include_dynamic,
-        top_n: args.top,
-        ignore: args.ignore,
-    };
     let result = session.trace(&opts);
     let entry_rel = session.entry_label();
 
     // Save snapshot if requested (works with any mode)
     if let Some(ref save_path) = args.save {
-        save_snapshot(save_path, &result, &entry_rel, args.quiet, sc)?;
+        let snapshot = result.to_snapshot(&entry_rel).with_modules(
+            session.graph(),
+            session.entry_id(),
+            session.root(),
+        );
+        save_snapshot(save_path, &snapshot, args.quiet, sc)?;
+    }
+
+    // --most-depended-on
+    if let Some(top) = args.most_depended_on {
+        let report = session.depended_on_report(args.include_dynamic, top);
+        if args.json {
+            println!("{}", meta.wrap(&report));
+        } else {
+            print!("{}", report.to_terminal(color));
+        }
+        return Ok(0);
+    }
+
+    // --duplicates
+    if args.duplicates {
+        let report = session.duplicates_report(args.include_dynamic);
+        if args.json {
+            println!("{}", meta.wrap(&report));
+        } else {
+            print!("{}", report.to_terminal(color));
+        }
+        return Ok(0);
+    }
+
+    // --only-dynamic
+    if args.only_dynamic {
+        let report = session.dynamic_only_report(&opts, args.top_modules);
+        if args.json {
+            println!("{}", meta.wrap(&report));
+        } else {
+            print!("{}", report.to_terminal(color));
+        }
+        return Ok(0);
+    }
+
+    // --savings
+    if args.savings {
+        let report = session.savings_report(&opts);
+        if args.json {
+            println!("{}", meta.wrap(&report));
+        } else {
+            print!("{}", report.to_terminal(color));
+        }
+        return Ok(0);
+    }
+
+    // --shared-with
+    if let Some(ref package) = args.shared_with {
+        let report = session.shared_modules_report(package, args.include_dynamic);
+        if args.json {
+            println!("{}", meta.wrap(&report));
+        } else {
+            print!("{}", report.to_terminal(color));
+        }
+        if !report.package_found {
+            return Ok(1);
+        }
+        return Ok(0);
+    }
+
+    // --barrel-penalty
+    if args.barrel_penalty {
+        let report = session.barrel_report(args.top);
+        if args.json {
+            println!("{}", meta.wrap(&report));
+        } else {
+            print!("{}", report.to_terminal(color));
+        }
+        return Ok(0);
     }
 
     // --chain
@@ -300,16 +1389,18 @@ fn run_trace(args: TraceArgs, color: bool, sc: report::StderrColor) -> Result<()
         if resolved.target == query::ChainTarget::Module(session.entry_id()) {
             return Err(Error::TargetIsEntryPoint("--chain".into()));
         }
-        let report = session.chain_report(chain_arg, args.include_dynamic);
+        let mut report =
+            session.chain_report(chain_arg, args.include_dynamic, args.min_hops.unwrap_or(0));
+        report.show_specifiers = args.show_specifiers;
         if args.json {
-            println!("{}", report.to_json());
+            println!("{}", meta.wrap(&report));
         } else {
             print!("{}", report.to_terminal(color));
         }
         if report.chains.is_empty() {
-            std::process::exit(1);
+            return Ok(1);
         }
-        return Ok(());
+        return Ok(0);
     }
 
     // --cut
@@ -320,14 +1411,25 @@ fn run_trace(args: TraceArgs, color: bool, sc: report::StderrColor) -> Result<()
         }
         let report = session.cut_report(cut_arg, args.top, args.include_dynamic);
         if args.json {
-            println!("{}", report.to_json());
+            println!("{}", meta.wrap(&report));
         } else {
             print!("{}", report.to_terminal(color));
         }
         if report.chain_count == 0 {
-            std::process::exit(1);
+            return Ok(1);
         }
-        return Ok(());
+        return Ok(0);
+    }
+
+    // --diff-cache
+    if let Some(cached) = cached_graph {
+        let report = session.cache_diff_report(cached, &opts, args.limit)?;
+        if args.json {
+            println!("{}", meta.wrap(&report));
+        } else {
+            print!("{}", report.to_terminal(color));
+        }
+        return Ok(0);
     }
 
     // --diff-from
@@ -336,51 +1438,87 @@ fn run_trace(args: TraceArgs, color: bool, sc: report::StderrColor) -> Result<()
         let diff = query::diff_snapshots(&saved, &result.to_snapshot(&entry_rel));
         let report = report::DiffReport::from_diff(&diff, &saved.entry, &entry_rel, args.limit);
         print!("{}", report.to_terminal(color));
-        return Ok(());
+        return Ok(0);
     }
 
     // --diff
     if let Some(ref diff_path) = args.diff {
-        return handle_trace_diff(
-            &session,
-            diff_path,
-            &result,
-            &entry_rel,
-            &opts,
-            args.no_cache,
-            args.limit,
-            color,
+        handle_trace_diff(
+            session, diff_path, &result, &entry_rel, &opts, load_opts, args.limit, color, sc,
+        )?;
+        return Ok(0);
+    }
+
+    if let Some(format) = args.format.filter(|f| !matches!(f, TableFormat::Table)) {
+        let out = match format {
+            TableFormat::Csv | TableFormat::Tsv => {
+                let (default_delimiter, quote) = if matches!(format, TableFormat::Csv) {
+                    (',', true)
+                } else {
+                    ('\t', false)
+                };
+                report::print_trace_delimited(
+                    &session.module_rows(&opts),
+                    args.delimiter.unwrap_or(default_delimiter),
+                    quote,
+                )
+            }
+            TableFormat::Flamegraph => report::print_folded_stacks(&session.folded_stacks(&opts)),
+            TableFormat::Dot => report::print_dot(
+                &session.dot_graph(&opts, args.collapse_depth),
+                args.cluster_by_package,
+            ),
+            TableFormat::Mermaid => {
+                report::print_mermaid(&session.mermaid_graph(&opts, args.collapse_depth))
+            }
+            TableFormat::Junit => {
+                let gates = ci_gates(session, &args, &result)?;
+                print!("{}", report::print_junit(&entry_rel, &gates));
+                if gates.iter().any(|g| g.failure.is_some()) {
+                    return Ok(1);
+                }
+                return Ok(0);
+            }
+            TableFormat::Table => unreachable!("rendered by the normal trace output"),
+        };
+        print!("{out}");
+        return Ok(check_budget(
+            &budget_failures(&args, result.static_weight, result.static_module_count),
             sc,
-        );
+        ));
     }
 
     // Normal trace output
-    let report = session.trace_report(&opts, args.top_modules);
+    let mut report = if args.only_packages {
+        let opts = query::TraceOptions { top_n: -1, ..opts };
+        session.packages_only_report(&opts)
+    } else if args.group_by_package {
+        session.package_cost_report(&opts, args.top_modules)
+    } else {
+        session.trace_report(&opts, args.top_modules)
+    };
+    report.show_lines = args.show_lines;
+    if matches!(args.format, Some(TableFormat::Table)) {
+        report.table = Some(if args.ascii {
+            report::TableBorders::Ascii
+        } else {
+            report::TableBorders::Unicode
+        });
+    }
+    let failures = budget_failures(
+        &args,
+        report.static_weight_bytes,
+        report.static_module_count,
+    );
+    report.budget_exceeded = has_budget(&args).then_some(!failures.is_empty());
     if args.json {
-        println!("{}", report.to_json());
+        println!("{}", meta.wrap(&report));
     } else {
         print!("{}", report.to_terminal(color));
     }
-
-    if let Some(threshold) = args.max_weight.filter(|&t| report.static_weight_bytes > t) {
-        let kind = if args.include_dynamic {
-            "total"
-        } else {
-            "static"
-        };
-        eprintln!(
-            "{} {kind} transitive weight {} ({} module{}) exceeds --max-weight threshold {}",
-            sc.error("error:"),
-            report::format_size(report.static_weight_bytes),
-            report.static_module_count,
-            if report.static_module_count == 1 {
-                ""
-            } else {
-                "s"
-            },
-            report::format_size(threshold),
-        );
-        std::process::exit(1);
+    let code = check_budget(&failures, sc);
+    if code != 0 {
+        return Ok(code);
     }
 
     if !args.quiet {
@@ -391,9 +1529,274 @@ fn run_trace(args: TraceArgs, color: bool, sc: report::StderrColor) -> Result<()
         );
     }
 
+    Ok(0)
+}
+
+/// Exit code for a `--max-weight` / `--max-modules` violation, distinct from
+/// errors (1) and command-line usage errors (2).
+const EXIT_BUDGET_EXCEEDED: i32 = 3;
+
+fn has_budget(args: &TraceArgs) -> bool {
+    args.max_weight.is_some() || args.max_modules.is_some()
+}
+
+/// Every `--max-weight` / `--max-modules` threshold the trace exceeds.
+fn budget_failures(args: &TraceArgs, weight: u64, module_count: usize) -> Vec<String> {
+    let mut failures = Vec::new();
+    if let Some(threshold) = args.max_weight {
+        failures.extend(max_weight_failure(
+            threshold,
+            weight,
+            module_count,
+            args.include_dynamic,
+        ));
+    }
+    if let Some(limit) = args.max_modules {
+        failures.extend(max_modules_failure(limit, module_count));
+    }
+    failures
+}
+
+/// Print each budget violation; the exit code is [`EXIT_BUDGET_EXCEEDED`]
+/// if there are any, else 0.
+fn check_budget(failures: &[String], sc: report::StderrColor) -> i32 {
+    if failures.is_empty() {
+        return 0;
+    }
+    for msg in failures {
+        eprintln!("{} {msg}", sc.error("error:"));
+    }
+    EXIT_BUDGET_EXCEEDED
+}
+
+/// The `--max-weight` violation, if `weight` exceeds `threshold`.
+fn max_weight_failure(
+    threshold: u64,
+    weight: u64,
+    module_count: usize,
+    include_dynamic: bool,
+) -> Option<String> {
+    (weight > threshold).then(|| {
+        let kind = if include_dynamic { "total" } else { "static" };
+        format!(
+            "{kind} transitive weight {} ({} module{}) exceeds --max-weight threshold {} by {}",
+            report::format_size(weight),
+            module_count,
+            if module_count == 1 { "" } else { "s" },
+            report::format_size(threshold),
+            report::format_size(weight - threshold),
+        )
+    })
+}
+
+/// The `--max-modules` violation, if more than `limit` modules are reachable.
+fn max_modules_failure(limit: usize, module_count: usize) -> Option<String> {
+    (module_count > limit).then(|| {
+        format!(
+            "{module_count} reachable modules exceed --max-modules threshold {limit} by {}",
+            module_count - limit
+        )
+    })
+}
+
+/// `--format junit`: evaluate every gate the flags enable, without exiting.
+fn ci_gates(
+    session: &Session,
+    args: &TraceArgs,
+    result: &query::TraceResult,
+) -> Result<Vec<report::GateResult>, Error> {
+    let mut gates = Vec::new();
+    if let Some(threshold) = args.max_weight {
+        gates.push(report::GateResult {
+            name: "max-weight".into(),
+            failure: max_weight_failure(
+                threshold,
+                result.static_weight,
+                result.static_module_count,
+                args.include_dynamic,
+            ),
+        });
+    }
+    if let Some(limit) = args.max_modules {
+        gates.push(report::GateResult {
+            name: "max-modules".into(),
+            failure: max_modules_failure(limit, result.static_module_count),
+        });
+    }
+    if args.fail_on_cycle || args.fail_on_static_cycle {
+        let cycles =
+            find_unexpected_cycles(session, args.fail_on_cycle, args.cycle_allowlist.as_deref())?;
+        let failure = (!cycles.is_empty()).then(|| {
+            let mut msg = format!(
+                "{} import cycle{} found",
+                cycles.len(),
+                if cycles.len() == 1 { "" } else { "s" }
+            );
+            for cycle in &cycles {
+                msg.push_str("\n  ");
+                msg.push_str(&cycle.join(" -> "));
+            }
+            msg
+        });
+        let name = if args.fail_on_cycle {
+            "cycles"
+        } else {
+            "static-cycles"
+        };
+        gates.push(report::GateResult {
+            name: name.into(),
+            failure,
+        });
+    }
+    if args.fail_on_unresolvable_dynamic {
+        gates.push(report::GateResult {
+            name: "unresolvable-dynamic".into(),
+            failure: UnresolvableDynamic::Fail.failure(session.unresolvable_dynamic_count()),
+        });
+    }
+    if args.fail_on_new_dynamic_import
+        && let Some(ref baseline) = args.compare_baseline
+    {
+        let diff = baseline_dynamic_imports(session, baseline)?;
+        gates.push(report::GateResult {
+            name: "new-dynamic-imports".into(),
+            failure: new_dynamic_import_failure(&diff),
+        });
+    }
+    Ok(gates)
+}
+
+/// `--compare-baseline`: print dynamic imports added or removed since the
+/// baseline snapshot. With `--fail-on-new-dynamic-import`, any addition
+/// exits non-zero.
+fn check_dynamic_imports(
+    session: &Session,
+    baseline: &Path,
+    fail_on_new: bool,
+    sc: report::StderrColor,
+) -> Result<(), Error> {
+    let diff = baseline_dynamic_imports(session, baseline)?;
+    if !diff.removed.is_empty() {
+        eprintln!(
+            "{} {} dynamic import{} removed since baseline (dropped or now static)",
+            sc.warning("warning:"),
+            diff.removed.len(),
+            if diff.removed.len() == 1 { "" } else { "s" }
+        );
+        for import in &diff.removed {
+            eprintln!("  - {} -> {}", import.from, import.to);
+        }
+    }
+    let Some(msg) = new_dynamic_import_failure(&diff) else {
+        return Ok(());
+    };
+    if fail_on_new {
+        eprintln!("{} {msg}", sc.error("error:"));
+        std::process::exit(1);
+    }
+    eprintln!("{} {msg}", sc.warning("warning:"));
     Ok(())
 }
 
+/// Diff the reachable dynamic imports against a `--save` snapshot.
+fn baseline_dynamic_imports(
+    session: &Session,
+    baseline: &Path,
+) -> Result<query::DynamicImportDiff, Error> {
+    let saved = load_snapshot(baseline)?;
+    // Snapshots from before module lists were recorded carry no imports
+    // either, which would make every dynamic import look new
+    if saved.modules.is_empty() {
+        return Err(Error::SnapshotWithoutModules(baseline.to_path_buf()));
+    }
+    let current = query::dynamic_imports(session.graph(), session.entry_id(), session.root());
+    Ok(query::diff_dynamic_imports(
+        &saved.dynamic_imports,
+        &current,
+    ))
+}
+
+/// The `--fail-on-new-dynamic-import` violation, if any import was added.
+fn new_dynamic_import_failure(diff: &query::DynamicImportDiff) -> Option<String> {
+    (!diff.added.is_empty()).then(|| {
+        let mut msg = format!(
+            "{} new dynamic import{} not in baseline",
+            diff.added.len(),
+            if diff.added.len() == 1 { "" } else { "s" }
+        );
+        for import in &diff.added {
+            msg.push_str(&format!("\n  + {} -> {}", import.from, import.to));
+        }
+        msg
+    })
+}
+
+/// `--fail-on-cycle` / `--fail-on-static-cycle`: print every import cycle not
+/// in the allowlist and exit non-zero if there are any.
+fn check_cycles(
+    session: &Session,
+    include_dynamic: bool,
+    allowlist: Option<&Path>,
+    sc: report::StderrColor,
+) -> Result<(), Error> {
+    let cycles = find_unexpected_cycles(session, include_dynamic, allowlist)?;
+    if cycles.is_empty() {
+        return Ok(());
+    }
+    eprintln!(
+        "{} {} import cycle{} found",
+        sc.error("error:"),
+        cycles.len(),
+        if cycles.len() == 1 { "" } else { "s" }
+    );
+    for cycle in &cycles {
+        eprintln!("  {}", cycle.join(" -> "));
+    }
+    std::process::exit(1);
+}
+
+/// Import cycles reachable from the entry that the allowlist does not accept.
+fn find_unexpected_cycles(
+    session: &Session,
+    include_dynamic: bool,
+    allowlist: Option<&Path>,
+) -> Result<Vec<Vec<String>>, Error> {
+    let allowed = match allowlist {
+        Some(path) => parse_cycle_allowlist(
+            &std::fs::read_to_string(path)
+                .map_err(|e| Error::CycleAllowlistRead(path.to_path_buf(), e))?,
+        ),
+        None => Vec::new(),
+    };
+    Ok(unexpected_cycles(
+        session.import_cycles(include_dynamic),
+        &allowed,
+    ))
+}
+
+/// One accepted cycle per line, as whitespace-separated member paths
+/// (order does not matter). Blank lines and `#` comments are skipped.
+fn parse_cycle_allowlist(content: &str) -> Vec<BTreeSet<String>> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .map(|l| l.split_whitespace().map(String::from).collect())
+        .collect()
+}
+
+fn unexpected_cycles(cycles: Vec<Vec<String>>, allowed: &[BTreeSet<String>]) -> Vec<Vec<String>> {
+    cycles
+        .into_iter()
+        .filter(|cycle| {
+            let members: BTreeSet<&str> = cycle.iter().map(String::as_str).collect();
+            !allowed
+                .iter()
+                .any(|a| a.iter().map(String::as_str).eq(members.iter().copied()))
+        })
+        .collect()
+}
+
 /// Handle `trace --diff <file>` by comparing two entry points.
 #[allow(clippy::too_many_arguments)] // private dispatch, called from one site
 fn handle_trace_diff(
@@ -402,7 +1805,7 @@ fn handle_trace_diff(
     result: &query::TraceResult,
     entry_rel: &str,
     opts: &query::TraceOptions,
-    no_cache: bool,
+    load_opts: &loader::LoadOptions,
     limit: i32,
     color: bool,
     sc: report::StderrColor,
@@ -417,13 +1820,13 @@ fn handle_trace_diff(
         );
     }
 
-    let diff_snapshot = if let Some(&diff_id) = session.graph().path_to_id.get(&diff_entry) {
+    let diff_snapshot = if let Some(diff_id) = session.graph().module_id(&diff_entry) {
         // Same graph — trace directly
         let diff_rel = session.entry_label_for(&diff_entry);
         query::trace(session.graph(), diff_id, opts).to_snapshot(&diff_rel)
     } else {
         // Different project — open a separate session
-        let diff_session = Session::open(&diff_entry, no_cache)?;
+        let diff_session = Session::open_with(&diff_entry, load_opts)?;
         diff_session
             .trace(opts)
             .to_snapshot(&diff_session.entry_label())
@@ -436,14 +1839,61 @@ fn handle_trace_diff(
     Ok(())
 }
 
-fn print_session_status(session: &Session, start: Instant, sc: report::StderrColor) {
+/// How `trace` treats dynamic imports whose argument is not a string literal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum UnresolvableDynamic {
+    /// Warn on stderr, exit normally (default).
+    Warn,
+    /// Say nothing.
+    Ignore,
+    /// Warn, then exit non-zero.
+    Fail,
+}
+
+impl UnresolvableDynamic {
+    fn from_flags(fail: bool, ignore: bool) -> Self {
+        if fail {
+            Self::Fail
+        } else if ignore {
+            Self::Ignore
+        } else {
+            Self::Warn
+        }
+    }
+
+    /// The error to exit with, if `count` unresolvable imports violate the policy.
+    fn failure(self, count: usize) -> Option<String> {
+        (self == Self::Fail && count > 0).then(|| {
+            format!(
+                "{count} dynamic import{} with non-literal argument{} (--fail-on-unresolvable-dynamic)",
+                if count == 1 { "" } else { "s" },
+                if count == 1 { "" } else { "s" },
+            )
+        })
+    }
+}
+
+fn print_session_status(
+    session: &Session,
+    start: Instant,
+    dynamic_policy: UnresolvableDynamic,
+    sc: report::StderrColor,
+) {
+    let (dynamic_count, dynamic_files) = if dynamic_policy == UnresolvableDynamic::Ignore {
+        (0, &[][..])
+    } else {
+        (
+            session.unresolvable_dynamic_count(),
+            session.unresolvable_dynamic_files(),
+        )
+    };
     report::print_load_status(
         session.from_cache(),
         session.graph().module_count(),
         start.elapsed().as_secs_f64() * 1000.0,
         session.file_warnings(),
-        session.unresolvable_dynamic_count(),
-        session.unresolvable_dynamic_files(),
+        dynamic_count,
+        dynamic_files,
         session.root(),
         sc,
     );
@@ -453,19 +1903,29 @@ fn print_session_status(session: &Session, start: Instant, sc: report::StderrCol
 // packages subcommand
 // ---------------------------------------------------------------------------
 
-fn run_packages(args: &PackagesArgs, color: bool, sc: report::StderrColor) -> Result<(), Error> {
+fn run_packages(
+    args: &PackagesArgs,
+    load_opts: &loader::LoadOptions,
+    meta: &report::JsonMeta,
+    color: bool,
+    sc: report::StderrColor,
+) -> Result<(), Error> {
     if args.top < -1 {
         return Err(Error::InvalidTopValue("--top", args.top));
     }
     let start = Instant::now();
-    let session = Session::open(&args.entry, args.no_cache)?;
+    let load_opts = loader::LoadOptions {
+        no_cache: args.no_cache,
+        ..load_opts.clone()
+    };
+    let session = Session::open_with(&args.entry, &load_opts)?;
     if !args.quiet {
-        print_session_status(&session, start, sc);
+        print_session_status(&session, start, UnresolvableDynamic::Warn, sc);
     }
 
-    let report = session.packages_report(args.top);
+    let report = session.packages_report(args.top, args.with_deps);
     if args.json {
-        println!("{}", report.to_json());
+        println!("{}", meta.wrap(&report));
     } else {
         print!("{}", report.to_terminal(color));
     }
@@ -478,13 +1938,11 @@ fn run_packages(args: &PackagesArgs, color: bool, sc: report::StderrColor) -> Re
 
 fn save_snapshot(
     path: &Path,
-    result: &query::TraceResult,
-    entry_rel: &str,
+    snapshot: &query::TraceSnapshot,
     quiet: bool,
     sc: report::StderrColor,
 ) -> Result<(), Error> {
-    let snapshot = result.to_snapshot(entry_rel);
-    let data = serde_json::to_string_pretty(&snapshot).unwrap();
+    let data = serde_json::to_string_pretty(snapshot).unwrap();
     std::fs::write(path, &data).map_err(|e| Error::SnapshotWrite(path.to_path_buf(), e))?;
     if !quiet {
         eprintln!("{} to {}", sc.status("Snapshot saved"), path.display());
@@ -515,19 +1973,19 @@ fn print_build_status(loaded: &loader::LoadedGraph, start: Instant, sc: report::
     );
 }
 
-#[allow(clippy::too_many_arguments)]
 fn run_diff(
-    a: String,
-    b: Option<String>,
-    entry: Option<PathBuf>,
-    limit: i32,
-    quiet: bool,
+    args: DiffArgs,
+    load_opts: &loader::LoadOptions,
+    meta: &report::JsonMeta,
     color: bool,
     sc: report::StderrColor,
 ) -> Result<(), Error> {
-    if limit < -1 {
-        return Err(Error::InvalidTopValue("--limit", limit));
+    if args.limit < -1 {
+        return Err(Error::InvalidTopValue("--limit", args.limit));
     }
+    let DiffArgs {
+        a, b, entry, quiet, ..
+    } = &args;
     let start = Instant::now();
 
     // Determine repo root (needed for git ref detection and in-process tree reading).
@@ -539,8 +1997,11 @@ fn run_diff(
 
     // Classify arguments.
     let root = repo_root.as_deref().unwrap_or(&cwd);
-    let arg_a = git::classify_diff_arg(&a, root)?;
-    let arg_b = b.map(|s| git::classify_diff_arg(&s, root)).transpose()?;
+    let arg_a = git::classify_diff_arg(a, root)?;
+    let arg_b = b
+        .as_deref()
+        .map(|s| git::classify_diff_arg(s, root))
+        .transpose()?;
 
     let has_ref =
         matches!(arg_a, git::DiffArg::GitRef(_)) || matches!(&arg_b, Some(git::DiffArg::GitRef(_)));
@@ -552,42 +2013,65 @@ fn run_diff(
     }
 
     // Build snapshots from each side.
-    let (snap_a, label_a) = build_diff_side(&arg_a, entry.as_deref(), root, quiet, sc)?;
+    let (snap_a, label_a) = build_diff_side(&arg_a, entry.as_deref(), root, load_opts, *quiet, sc)?;
     let (snap_b, label_b) = match arg_b {
-        Some(ref arg) => build_diff_side(arg, entry.as_deref(), root, quiet, sc)?,
+        Some(ref arg) => build_diff_side(arg, entry.as_deref(), root, load_opts, *quiet, sc)?,
         None => {
             // One arg: the arg is "before" (baseline), working tree is "after" (current).
             // Matches `git diff <ref>` semantics.
             let entry_path = entry.as_ref().ok_or(Error::EntryRequired)?;
-            let wt_snap = build_snapshot_from_working_tree(entry_path, quiet, sc)?;
+            let wt_snap = build_snapshot_from_working_tree(entry_path, load_opts, *quiet, sc)?;
             let wt_label = wt_snap.entry.clone();
             return finish_diff(
-                &snap_a, &label_a, &wt_snap, &wt_label, limit, color, start, quiet, sc,
+                (&snap_a, &label_a),
+                (&wt_snap, &wt_label),
+                &args,
+                meta,
+                color,
+                start,
+                sc,
             );
         }
     };
 
     finish_diff(
-        &snap_a, &label_a, &snap_b, &label_b, limit, color, start, quiet, sc,
+        (&snap_a, &label_a),
+        (&snap_b, &label_b),
+        &args,
+        meta,
+        color,
+        start,
+        sc,
     )
 }
 
 #[allow(clippy::too_many_arguments)] // private dispatch, called from one site
 fn finish_diff(
-    snap_a: &query::TraceSnapshot,
-    label_a: &str,
-    snap_b: &query::TraceSnapshot,
-    label_b: &str,
-    limit: i32,
+    (snap_a, label_a): (&query::TraceSnapshot, &str),
+    (snap_b, label_b): (&query::TraceSnapshot, &str),
+    args: &DiffArgs,
+    meta: &report::JsonMeta,
     color: bool,
     start: Instant,
-    quiet: bool,
     sc: report::StderrColor,
 ) -> Result<(), Error> {
     let diff_output = query::diff_snapshots(snap_a, snap_b);
-    let report = report::DiffReport::from_diff(&diff_output, label_a, label_b, limit);
-    print!("{}", report.to_terminal(color));
-    if !quiet {
+    let mut report = report::DiffReport::from_diff(&diff_output, label_a, label_b, args.limit);
+    if args.module_level {
+        if !args.quiet && (snap_a.modules.is_empty() || snap_b.modules.is_empty()) {
+            eprintln!(
+                "{} snapshot has no module list (saved by an older chainsaw); skipping module-level changes",
+                sc.warning("warning:")
+            );
+        }
+        report.module_changes = Some(report::ModuleChangeset::from_diff(&diff_output));
+    }
+    if args.json {
+        println!("{}", meta.wrap(&report));
+    } else {
+        print!("{}", report.to_terminal(color));
+    }
+    if !args.quiet {
         eprintln!(
             "\n{} in {:.1}ms",
             sc.status("Compared"),
@@ -602,6 +2086,7 @@ fn build_diff_side(
     arg: &git::DiffArg,
     entry: Option<&Path>,
     repo_root: &Path,
+    load_opts: &loader::LoadOptions,
     quiet: bool,
     sc: report::StderrColor,
 ) -> Result<(query::TraceSnapshot, String), Error> {
@@ -613,7 +2098,7 @@ fn build_diff_side(
         }
         git::DiffArg::GitRef(git_ref) => {
             let entry = entry.ok_or(Error::EntryRequired)?;
-            let snap = build_snapshot_from_ref(repo_root, git_ref, entry, quiet, sc)?;
+            let snap = build_snapshot_from_ref(repo_root, git_ref, entry, load_opts, quiet, sc)?;
             let label = snap.entry.clone();
             Ok((snap, label))
         }
@@ -625,6 +2110,7 @@ fn build_snapshot_from_ref(
     repo_root: &Path,
     git_ref: &str,
     entry: &Path,
+    load_opts: &loader::LoadOptions,
     quiet: bool,
     sc: report::StderrColor,
 ) -> Result<query::TraceSnapshot, Error> {
@@ -634,7 +2120,18 @@ fn build_snapshot_from_ref(
             .map_err(|e| Error::GitError(e.to_string()))?,
     );
     let entry_in_vfs = repo_root.join(entry);
-    let (loaded, _cache_write) = loader::load_graph_with_vfs(&entry_in_vfs, true, git_vfs)?; // no-cache
+    let load_opts = loader::LoadOptions {
+        no_cache: true,
+        defines: load_opts.defines.clone(),
+        weight_source: load_opts.weight_source,
+        prefer_published_entry: load_opts.prefer_published_entry,
+        no_default_aliases: load_opts.no_default_aliases,
+        no_gitignore: load_opts.no_gitignore,
+        case_insensitive_paths: load_opts.case_insensitive_paths,
+        ..loader::LoadOptions::default()
+    };
+    let (loaded, _cache_write) =
+        loader::load_graph_with_options(&entry_in_vfs, &load_opts, git_vfs)?;
     if !quiet {
         eprintln!(
             "{} {} at {} ({} modules) in {:.1}ms",
@@ -645,47 +2142,176 @@ fn build_snapshot_from_ref(
             start.elapsed().as_secs_f64() * 1000.0,
         );
     }
-    let Some(&entry_id) = loaded.graph.path_to_id.get(&loaded.entry) else {
+    let Some(entry_id) = loaded.graph.module_id(&loaded.entry) else {
         return Err(Error::EntryNotInGraph(loaded.entry));
     };
     let opts = query::TraceOptions {
         include_dynamic: false,
         top_n: 0,
         ignore: vec![],
+        dynamic_depth: None,
+        exclude: None,
+        count_type_only: false,
+        max_package_depth: None,
     };
     let result = query::trace(&loaded.graph, entry_id, &opts);
     let label = format!("{} ({})", entry.display(), git_ref);
-    Ok(result.to_snapshot(&label))
+    Ok(result
+        .to_snapshot(&label)
+        .with_modules(&loaded.graph, entry_id, &loaded.root))
 }
 
 /// Build a snapshot from the current working tree.
 fn build_snapshot_from_working_tree(
     entry: &Path,
+    load_opts: &loader::LoadOptions,
     quiet: bool,
     sc: report::StderrColor,
 ) -> Result<query::TraceSnapshot, Error> {
     let start = Instant::now();
-    let (loaded, _cache_write) = loader::load_graph(entry, false)?;
+    let (loaded, _cache_write) =
+        loader::load_graph_with_options(entry, load_opts, Arc::new(vfs::OsVfs))?;
     if !quiet {
         print_build_status(&loaded, start, sc);
     }
-    let Some(&entry_id) = loaded.graph.path_to_id.get(&loaded.entry) else {
+    let Some(entry_id) = loaded.graph.module_id(&loaded.entry) else {
         return Err(Error::EntryNotInGraph(loaded.entry));
     };
     let opts = query::TraceOptions {
         include_dynamic: false,
         top_n: 0,
         ignore: vec![],
+        dynamic_depth: None,
+        exclude: None,
+        count_type_only: false,
+        max_package_depth: None,
     };
     let result = query::trace(&loaded.graph, entry_id, &opts);
     let label = session::entry_label(&loaded.entry, &loaded.root);
-    Ok(result.to_snapshot(&label))
+    Ok(result
+        .to_snapshot(&label)
+        .with_modules(&loaded.graph, entry_id, &loaded.root))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn version_json_has_version_and_build_metadata() {
+        let json: serde_json::Value = serde_json::from_str(&version_json()).unwrap();
+        assert_eq!(json["version"], env!("CARGO_PKG_VERSION"));
+        assert!(json["commit"].is_string());
+        assert!(json["build_date"].is_string());
+    }
+
+    #[test]
+    fn version_json_flag_parses_without_subcommand() {
+        let cli = Cli::try_parse_from(["chainsaw", "--version", "--json"]).unwrap();
+        assert!(cli.version && cli.json);
+        assert!(cli.command.is_none());
+        assert!(Cli::try_parse_from(["chainsaw", "--json"]).is_err());
+    }
+
+    #[test]
+    fn budget_failures_report_overage() {
+        let argv = [
+            "chainsaw",
+            "trace",
+            "a.ts",
+            "--max-weight",
+            "500KB",
+            "--max-modules",
+            "10",
+        ];
+        let Some(Commands::Trace(args)) = Cli::try_parse_from(argv).unwrap().command else {
+            panic!("expected trace");
+        };
+        assert!(has_budget(&args));
+        assert!(budget_failures(&args, 500_000, 10).is_empty());
+
+        let failures = budget_failures(&args, 600_000, 12);
+        assert_eq!(failures.len(), 2, "{failures:?}");
+        assert!(failures[0].ends_with("by 100 KB"), "{}", failures[0]);
+        assert!(failures[1].ends_with("by 2"), "{}", failures[1]);
+        assert_ne!(EXIT_BUDGET_EXCEEDED, 1);
+    }
+
+    #[test]
+    fn trace_output_returns_failed_checks_as_exit_codes() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        let entry = root.join("index.ts");
+        std::fs::write(&entry, "import './a';").unwrap();
+        std::fs::write(root.join("a.ts"), "export {};").unwrap();
+        let session = Session::open(&entry, true).unwrap();
+        let run = |extra: &[&str]| {
+            let mut argv = vec!["chainsaw", "trace", "index.ts", "--quiet"];
+            argv.extend(extra);
+            let Some(Commands::Trace(args)) = Cli::try_parse_from(argv).unwrap().command else {
+                panic!("expected trace");
+            };
+            trace_output(
+                &session,
+                *args,
+                None,
+                &loader::LoadOptions::default(),
+                &report::JsonMeta::now(None),
+                false,
+                Instant::now(),
+                report::StderrColor::new(true),
+            )
+            .unwrap()
+        };
+        // Returned rather than exiting, so --watch survives them
+        assert_eq!(run(&["--chain", "zod"]), 1);
+        assert_eq!(run(&["--max-modules", "1"]), EXIT_BUDGET_EXCEEDED);
+        assert_eq!(run(&["--max-modules", "5"]), 0);
+    }
+
+    #[test]
+    fn unresolvable_dynamic_policy_from_flags() {
+        let parse = |extra: &[&str]| {
+            let mut argv = vec!["chainsaw", "trace", "a.ts"];
+            argv.extend_from_slice(extra);
+            let Some(Commands::Trace(args)) = Cli::try_parse_from(argv).unwrap().command else {
+                panic!("expected trace");
+            };
+            UnresolvableDynamic::from_flags(
+                args.fail_on_unresolvable_dynamic,
+                args.ignore_unresolvable_dynamic,
+            )
+        };
+        assert_eq!(parse(&[]), UnresolvableDynamic::Warn);
+        assert_eq!(
+            parse(&["--fail-on-unresolvable-dynamic"]),
+            UnresolvableDynamic::Fail
+        );
+        assert_eq!(
+            parse(&["--ignore-unresolvable-dynamic"]),
+            UnresolvableDynamic::Ignore
+        );
+        assert!(
+            Cli::try_parse_from([
+                "chainsaw",
+                "trace",
+                "a.ts",
+                "--fail-on-unresolvable-dynamic",
+                "--ignore-unresolvable-dynamic",
+            ])
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn unresolvable_dynamic_only_fails_in_fail_mode() {
+        assert_eq!(UnresolvableDynamic::Warn.failure(3), None);
+        assert_eq!(UnresolvableDynamic::Ignore.failure(3), None);
+        assert_eq!(UnresolvableDynamic::Fail.failure(0), None);
+        let msg = UnresolvableDynamic::Fail.failure(2).unwrap();
+        assert!(msg.starts_with("2 dynamic imports"), "{msg}");
+    }
+
     #[test]
     fn parse_size_units() {
         assert_eq!(parse_size("5MB").unwrap(), 5_000_000);
@@ -696,6 +2322,15 @@ mod tests {
         assert!(parse_size("abc").is_err());
     }
 
+    #[test]
+    fn parse_fraction_rejects_out_of_range() {
+        assert_eq!(parse_fraction("0.1").unwrap(), 0.1);
+        assert_eq!(parse_fraction("1").unwrap(), 1.0);
+        assert!(parse_fraction("0").is_err());
+        assert!(parse_fraction("1.5").is_err());
+        assert!(parse_fraction("NaN").is_err());
+    }
+
     #[test]
     fn parse_size_error_includes_hint() {
         let err = parse_size("abc").unwrap_err();
@@ -723,4 +2358,109 @@ mod tests {
             "expected flag name in error: {msg}"
         );
     }
+
+    #[test]
+    fn cycle_allowlist_matches_member_sets() {
+        let allowed = parse_cycle_allowlist("# accepted\nsrc/b.ts src/a.ts\n\n");
+        let cycles = vec![
+            vec!["src/a.ts".to_string(), "src/b.ts".to_string()],
+            vec!["src/c.ts".to_string(), "src/d.ts".to_string()],
+        ];
+        assert_eq!(
+            unexpected_cycles(cycles, &allowed),
+            vec![vec!["src/c.ts".to_string(), "src/d.ts".to_string()]]
+        );
+    }
+
+    #[test]
+    fn new_dynamic_import_fails_gate() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        let entry = root.join("index.ts");
+        std::fs::write(&entry, "import './a';\nimport('./lazy');").unwrap();
+        std::fs::write(root.join("a.ts"), "export {};").unwrap();
+        std::fs::write(root.join("lazy.ts"), "export {};").unwrap();
+        std::fs::write(root.join("chart.ts"), "export {};").unwrap();
+
+        let session = Session::open(&entry, true).unwrap();
+        let snapshot = session
+            .trace(&query::TraceOptions::default())
+            .to_snapshot("index.ts")
+            .with_modules(session.graph(), session.entry_id(), session.root());
+        let baseline = root.join("baseline.json");
+        std::fs::write(&baseline, serde_json::to_string(&snapshot).unwrap()).unwrap();
+        let diff = baseline_dynamic_imports(&session, &baseline).unwrap();
+        assert_eq!(new_dynamic_import_failure(&diff), None);
+
+        std::fs::write(
+            root.join("a.ts"),
+            "export const c = () => import('./chart');",
+        )
+        .unwrap();
+        let session = Session::open(&entry, true).unwrap();
+        let diff = baseline_dynamic_imports(&session, &baseline).unwrap();
+        let msg = new_dynamic_import_failure(&diff).unwrap();
+        assert!(msg.contains("a.ts -> chart.ts"), "{msg}");
+        assert!(diff.removed.is_empty());
+    }
+
+    #[test]
+    fn cycle_gate_flags_are_exclusive() {
+        assert!(
+            Cli::try_parse_from(["chainsaw", "trace", "a.ts", "--cycle-allowlist", "x"]).is_err()
+        );
+        assert!(
+            Cli::try_parse_from([
+                "chainsaw",
+                "trace",
+                "a.ts",
+                "--fail-on-cycle",
+                "--fail-on-static-cycle"
+            ])
+            .is_err()
+        );
+        assert!(
+            Cli::try_parse_from(["chainsaw", "trace", "a.ts", "--fail-on-static-cycle"]).is_ok()
+        );
+    }
+
+    #[test]
+    fn watch_ignores_changes_outside_the_graph() {
+        use notify::event::{CreateKind, ModifyKind};
+        use notify::{Event, EventKind};
+
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        let entry = root.join("index.ts");
+        std::fs::write(&entry, "import './a';").unwrap();
+        std::fs::write(root.join("a.ts"), "export {};").unwrap();
+        let session = Session::open(&entry, true).unwrap();
+
+        let (tx, rx) = mpsc::channel();
+        let modify =
+            |path: PathBuf| Ok(Event::new(EventKind::Modify(ModifyKind::Any)).add_path(path));
+        // Chainsaw's own cache writes and reads of files never wake the loop
+        tx.send(modify(root.join(".chainsaw.cache"))).unwrap();
+        tx.send(Ok(Event::new(EventKind::Access(
+            notify::event::AccessKind::Any,
+        ))
+        .add_path(root.join("a.ts"))))
+            .unwrap();
+        // A new source file next to the modules does
+        tx.send(Ok(
+            Event::new(EventKind::Create(CreateKind::File)).add_path(root.join("b.ts"))
+        ))
+        .unwrap();
+        tx.send(modify(root.join("a.ts"))).unwrap();
+        // The burst after the first change is drained by the debounce and
+        // recorded with it
+        assert_eq!(
+            wait_for_change(&rx, &session),
+            Some(vec![root.join("b.ts"), root.join("a.ts")])
+        );
+        assert!(rx.try_recv().is_err());
+
+        drop(tx);
+        assert_eq!(wait_for_change(&rx, &session), None);
+    }
 }
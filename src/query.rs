@@ -1,10 +1,13 @@
 //! Graph queries: trace weight, import chains, cut points, and diffs.
 
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
 
+use globset::{Glob, GlobSet, GlobSetBuilder};
 use serde::{Deserialize, Serialize};
 
-use crate::graph::{EdgeKind, ModuleGraph, ModuleId};
+use crate::error::Error;
+use crate::graph::{Edge, EdgeId, EdgeKind, Module, ModuleGraph, ModuleId};
 
 /// Results of tracing transitive import weight from an entry module.
 #[derive(Debug)]
@@ -18,14 +21,34 @@ pub struct TraceResult {
     pub dynamic_only_weight: u64,
     /// Number of modules reachable only via dynamic imports
     pub dynamic_only_module_count: usize,
+    /// Total file size reachable only through type-only imports (erased at
+    /// runtime, so not in `static_weight` unless `count_type_only`)
+    pub type_only_weight: u64,
+    /// Number of modules reachable only through type-only imports
+    pub type_only_module_count: usize,
     /// Heavy packages found via static imports, sorted by total reachable size descending
     pub heavy_packages: Vec<HeavyPackage>,
     /// All reachable modules with their exclusive weight, sorted descending
     pub modules_by_cost: Vec<ModuleCost>,
+    /// The modules counted in `static_weight`, in BFS order
+    pub reachable: Vec<ModuleId>,
     /// All statically reachable packages with their total size
     pub all_packages: HashMap<String, u64>,
     /// Packages reachable only via dynamic imports (not in static set)
     pub dynamic_packages: HashMap<String, u64>,
+    /// Total size of assets imported by reachable modules (not in `static_weight`)
+    pub asset_weight: u64,
+    /// Assets imported by reachable modules, sorted by size descending
+    pub assets: Vec<ModuleId>,
+    /// Dynamic edges from statically reachable modules to modules outside
+    /// the static set: where eager loading hands off to lazy loading
+    pub lazy_boundary_count: usize,
+    /// Total size of the modules loaded through those boundaries (their
+    /// static closure, minus anything already loaded eagerly)
+    pub lazy_boundary_weight: u64,
+    /// Packages past `max_package_depth` that traced modules import, which
+    /// were not expanded, sorted by name
+    pub unexpanded_packages: Vec<String>,
 }
 
 /// A third-party package with its reachable size and shortest import chain.
@@ -53,6 +76,18 @@ pub struct TraceOptions {
     pub include_dynamic: bool,
     pub top_n: i32,
     pub ignore: Vec<String>,
+    /// Max number of dynamic edges followed along any path (static edges are
+    /// unbounded). `None` follows dynamic imports to any depth.
+    pub dynamic_depth: Option<u32>,
+    /// Modules left out of the weight, module costs, and packages.
+    pub exclude: Option<Exclude>,
+    /// Count modules reached only through type-only imports as part of the
+    /// static weight (`--count-type-only`). Off by default: they are erased
+    /// at runtime.
+    pub count_type_only: bool,
+    /// Don't descend into packages more than N package boundaries from the
+    /// entry (`--max-package-depth`). `None` descends into every package.
+    pub max_package_depth: Option<u32>,
 }
 
 impl Default for TraceOptions {
@@ -61,10 +96,64 @@ impl Default for TraceOptions {
             include_dynamic: false,
             top_n: 10,
             ignore: Vec::new(),
+            dynamic_depth: None,
+            exclude: None,
+            count_type_only: false,
+            max_package_depth: None,
         }
     }
 }
 
+/// `--exclude` globs, matched against a module's package name
+/// (`@aws-sdk/*`) and its path relative to the project root
+/// (`node_modules/**`). The entry module is never excluded.
+#[derive(Debug, Clone)]
+pub struct Exclude {
+    globs: GlobSet,
+    root: PathBuf,
+    /// Also prune everything reached only through excluded modules
+    /// (`--exclude-subtree`). Otherwise excluded modules are still
+    /// traversed and only their own weight is dropped.
+    pub subtree: bool,
+}
+
+impl Exclude {
+    pub fn new(patterns: &[String], root: &Path, subtree: bool) -> Result<Self, Error> {
+        let mut builder = GlobSetBuilder::new();
+        for pattern in patterns {
+            builder.add(Glob::new(pattern).map_err(Error::InvalidExclude)?);
+        }
+        Ok(Self {
+            globs: builder.build().map_err(Error::InvalidExclude)?,
+            root: root.to_path_buf(),
+            subtree,
+        })
+    }
+
+    pub fn matches(&self, module: &Module) -> bool {
+        module
+            .package
+            .as_deref()
+            .is_some_and(|pkg| self.globs.is_match(pkg))
+            || self.matches_path(&module.path)
+    }
+
+    /// Whether a file matches by its path relative to the project root.
+    pub fn matches_path(&self, path: &Path) -> bool {
+        self.globs
+            .is_match(path.strip_prefix(&self.root).unwrap_or(path))
+    }
+
+    /// Excluded modules as a mask indexed by `ModuleId`.
+    fn mask(&self, graph: &ModuleGraph, entry: ModuleId) -> Vec<bool> {
+        graph
+            .modules
+            .iter()
+            .map(|m| m.id != entry && self.matches(m))
+            .collect()
+    }
+}
+
 /// Target for `--chain`/`--cut` queries: a package name or a specific module.
 #[derive(Debug, Clone, PartialEq)]
 #[non_exhaustive]
@@ -98,6 +187,7 @@ fn reverse_postorder_with_preds(
     graph: &ModuleGraph,
     entry: ModuleId,
     include_dynamic: bool,
+    allowed: Option<&[bool]>,
 ) -> (Vec<ModuleId>, Vec<Vec<u32>>) {
     let n = graph.modules.len();
     let mut visited = vec![false; n];
@@ -118,7 +208,9 @@ fn reverse_postorder_with_preds(
         stack.push((mid, true));
         for &edge_id in graph.outgoing_edges(mid) {
             let edge = graph.edge(edge_id);
-            if should_follow(edge.kind, include_dynamic) {
+            if should_follow(edge.kind, include_dynamic)
+                && allowed.is_none_or(|a| a[edge.to.0 as usize])
+            {
                 let to_idx = edge.to.0 as usize;
                 if visited[to_idx] {
                     // Back/cross edge to already-visited (reachable) node
@@ -154,18 +246,85 @@ fn intersect_idom(idom: &[u32], rpo_num: &[u32], mut a: u32, mut b: u32) -> u32
 /// Exclusive weight of module M = total size of all modules in M's dominator
 /// subtree (modules that become unreachable if M is removed from the graph).
 /// Uses the Cooper-Harvey-Kennedy iterative dominator algorithm: O(N).
-#[allow(clippy::cast_possible_truncation)]
+///
+/// When `allowed` is set, only modules marked `true` are traversed (used to
+/// keep the dominator tree consistent with a depth-limited reachable set).
 fn compute_exclusive_weights(
     graph: &ModuleGraph,
     entry: ModuleId,
     include_dynamic: bool,
+    allowed: Option<&[bool]>,
+) -> Vec<u64> {
+    exclusive_weights(graph, entry, include_dynamic, allowed, None)
+}
+
+/// [`compute_exclusive_weights`], counting modules marked in `dropped` as
+/// weightless: they are still walked, so they still dominate their imports.
+#[allow(clippy::cast_possible_truncation)]
+fn exclusive_weights(
+    graph: &ModuleGraph,
+    entry: ModuleId,
+    include_dynamic: bool,
+    allowed: Option<&[bool]>,
+    dropped: Option<&[bool]>,
 ) -> Vec<u64> {
     let n = graph.modules.len();
+    let (rpo, idom) = dominator_tree(graph, entry, include_dynamic, allowed);
+    if rpo.is_empty() {
+        return vec![0; n];
+    }
+    let entry_idx = entry.0;
+
+    // Step 5: Build dominator tree children
+    let mut children: Vec<Vec<u32>> = vec![Vec::new(); n];
+    for &mid in &rpo {
+        let idx = mid.0 as usize;
+        let dom = idom[idx];
+        if dom != u32::MAX && dom != idx as u32 {
+            children[dom as usize].push(idx as u32);
+        }
+    }
+
+    // Step 6: Post-order DFS of dominator tree to accumulate subtree sums
+    let mut weights = vec![0u64; n];
+    let mut stack: Vec<(u32, bool)> = vec![(entry_idx, false)];
+    while let Some((node, post_visit)) = stack.pop() {
+        if post_visit {
+            weights[node as usize] = if dropped.is_some_and(|d| d[node as usize]) {
+                0
+            } else {
+                graph.modules[node as usize].size_bytes
+            };
+            for &child in &children[node as usize] {
+                weights[node as usize] += weights[child as usize];
+            }
+            continue;
+        }
+        stack.push((node, true));
+        for &child in &children[node as usize] {
+            stack.push((child, false));
+        }
+    }
+
+    weights
+}
+
+/// Immediate dominator of every module reachable from `entry`, with the
+/// reverse postorder it was computed over. `idom[entry]` is the entry
+/// itself; unreachable modules are `u32::MAX`.
+#[allow(clippy::cast_possible_truncation)]
+fn dominator_tree(
+    graph: &ModuleGraph,
+    entry: ModuleId,
+    include_dynamic: bool,
+    allowed: Option<&[bool]>,
+) -> (Vec<ModuleId>, Vec<u32>) {
+    let n = graph.modules.len();
 
     // Step 1+3: DFS for reverse postorder and predecessor lists in one pass
-    let (rpo, preds) = reverse_postorder_with_preds(graph, entry, include_dynamic);
+    let (rpo, preds) = reverse_postorder_with_preds(graph, entry, include_dynamic, allowed);
     if rpo.is_empty() {
-        return vec![0; n];
+        return (rpo, vec![u32::MAX; n]);
     }
 
     // Step 2: RPO numbering (lower = earlier)
@@ -201,34 +360,44 @@ fn compute_exclusive_weights(
         }
     }
 
-    // Step 5: Build dominator tree children
-    let mut children: Vec<Vec<u32>> = vec![Vec::new(); n];
-    for &mid in &rpo {
-        let idx = mid.0 as usize;
-        let dom = idom[idx];
-        if dom != u32::MAX && dom != idx as u32 {
-            children[dom as usize].push(idx as u32);
-        }
-    }
+    (rpo, idom)
+}
 
-    // Step 6: Post-order DFS of dominator tree to accumulate subtree sums
-    let mut weights = vec![0u64; n];
-    let mut stack: Vec<(u32, bool)> = vec![(entry_idx, false)];
-    while let Some((node, post_visit)) = stack.pop() {
-        if post_visit {
-            weights[node as usize] = graph.modules[node as usize].size_bytes;
-            for &child in &children[node as usize] {
-                weights[node as usize] += weights[child as usize];
+/// Dominator-tree path from `entry` to every reachable module, in reverse
+/// postorder. Each module appears once, under the module that must load
+/// before it, which makes these paths flamegraph stacks whose widths add up
+/// to exclusive weight.
+#[must_use]
+pub fn dominator_paths(
+    graph: &ModuleGraph,
+    entry: ModuleId,
+    include_dynamic: bool,
+) -> Vec<Vec<ModuleId>> {
+    let (rpo, idom) = dominator_tree(graph, entry, include_dynamic, None);
+    rpo.iter()
+        .map(|&mid| {
+            let mut path = vec![mid];
+            let mut current = mid.0;
+            while current != entry.0 {
+                current = idom[current as usize];
+                path.push(ModuleId(current));
             }
-            continue;
-        }
-        stack.push((node, true));
-        for &child in &children[node as usize] {
-            stack.push((child, false));
-        }
-    }
+            path.reverse();
+            path
+        })
+        .collect()
+}
 
-    weights
+/// Immediate dominator of every module over static edges: the last module
+/// that every static import path from `entry` passes through. `None` for
+/// the entry itself and for modules not statically reachable.
+#[must_use]
+pub fn dominators(graph: &ModuleGraph, entry: ModuleId) -> Vec<Option<ModuleId>> {
+    let (_, idom) = dominator_tree(graph, entry, false, None);
+    idom.iter()
+        .enumerate()
+        .map(|(idx, &dom)| (dom != u32::MAX && idx != entry.0 as usize).then_some(ModuleId(dom)))
+        .collect()
 }
 
 struct BfsResult {
@@ -242,9 +411,18 @@ struct BfsResult {
 
 /// BFS from entry point, collecting all reachable modules.
 /// Also records parent pointers during the static phase for chain reconstruction.
-fn bfs_reachable(graph: &ModuleGraph, entry: ModuleId) -> BfsResult {
+///
+/// `dynamic_depth` bounds how many dynamic edges may be crossed on the way to
+/// a dynamic-only module; static edges never add depth. Modules marked in
+/// `skip` are never entered.
+fn bfs_reachable(
+    graph: &ModuleGraph,
+    entry: ModuleId,
+    dynamic_depth: Option<u32>,
+    skip: Option<&[bool]>,
+) -> BfsResult {
     let n = graph.modules.len();
-    let mut visited = vec![false; n];
+    let mut visited = skip.map_or_else(|| vec![false; n], <[bool]>::to_vec);
     let mut parent = vec![u32::MAX; n];
     let mut static_set: Vec<ModuleId> = Vec::new();
     let mut queue: VecDeque<ModuleId> = VecDeque::new();
@@ -267,40 +445,173 @@ fn bfs_reachable(graph: &ModuleGraph, entry: ModuleId) -> BfsResult {
         }
     }
 
-    // BFS following dynamic edges from all statically reachable modules
+    // 0-1 BFS over dynamic-only modules, seeded from all statically reachable
+    // modules: static edges keep the dynamic depth (push front), dynamic
+    // edges add one (push back), so each module gets its minimal depth.
+    let max_depth = dynamic_depth.unwrap_or(u32::MAX);
+    let mut depth = vec![u32::MAX; n];
     let mut dynamic_set: Vec<ModuleId> = Vec::new();
-    let mut dyn_queue: VecDeque<ModuleId> = VecDeque::new();
-
+    let mut dyn_queue: VecDeque<(ModuleId, u32)> = VecDeque::new();
     for &mid in &static_set {
+        depth[mid.0 as usize] = 0;
+        dyn_queue.push_back((mid, 0));
+    }
+
+    while let Some((mid, d)) = dyn_queue.pop_front() {
+        if d > depth[mid.0 as usize] {
+            continue; // stale entry, already reached at a smaller depth
+        }
+        if !visited[mid.0 as usize] {
+            visited[mid.0 as usize] = true;
+            dynamic_set.push(mid);
+        }
         for &edge_id in graph.outgoing_edges(mid) {
             let edge = graph.edge(edge_id);
+            let next = match edge.kind {
+                EdgeKind::Static => d,
+                EdgeKind::Dynamic if d < max_depth => d + 1,
+                _ => continue,
+            };
             let idx = edge.to.0 as usize;
-            if edge.kind == EdgeKind::Dynamic && !visited[idx] {
-                visited[idx] = true;
-                dynamic_set.push(edge.to);
-                dyn_queue.push_back(edge.to);
+            if next < depth[idx] && skip.is_none_or(|s| !s[idx]) {
+                depth[idx] = next;
+                if next == d {
+                    dyn_queue.push_front((edge.to, next));
+                } else {
+                    dyn_queue.push_back((edge.to, next));
+                }
             }
         }
     }
 
-    // Continue BFS from dynamic-only modules (they may have static imports of their own)
-    while let Some(mid) = dyn_queue.pop_front() {
+    BfsResult {
+        static_set,
+        dynamic_set,
+        static_parent: parent,
+    }
+}
+
+/// Modules reached only through type-only imports: the closure of the
+/// counted set (static, plus dynamic when `include_dynamic`) over static
+/// and type-only edges, minus every module already found at runtime.
+fn type_only_reachable(
+    graph: &ModuleGraph,
+    bfs: &BfsResult,
+    include_dynamic: bool,
+    skip: Option<&[bool]>,
+) -> Vec<ModuleId> {
+    let mut visited = skip.map_or_else(|| vec![false; graph.modules.len()], <[bool]>::to_vec);
+    for &mid in bfs.static_set.iter().chain(&bfs.dynamic_set) {
+        visited[mid.0 as usize] = true;
+    }
+    let mut queue: VecDeque<ModuleId> = bfs.static_set.iter().copied().collect();
+    if include_dynamic {
+        queue.extend(&bfs.dynamic_set);
+    }
+    let mut type_only = Vec::new();
+    while let Some(mid) = queue.pop_front() {
         for &edge_id in graph.outgoing_edges(mid) {
             let edge = graph.edge(edge_id);
             let idx = edge.to.0 as usize;
-            if (edge.kind == EdgeKind::Static || edge.kind == EdgeKind::Dynamic) && !visited[idx] {
+            if matches!(edge.kind, EdgeKind::Static | EdgeKind::TypeOnly) && !visited[idx] {
                 visited[idx] = true;
-                dynamic_set.push(edge.to);
-                dyn_queue.push_back(edge.to);
+                type_only.push(edge.to);
+                queue.push_back(edge.to);
             }
         }
     }
+    type_only
+}
 
-    BfsResult {
-        static_set,
-        dynamic_set,
-        static_parent: parent,
+/// Fewest import hops from `entry` to every module (`u32::MAX` when
+/// unreachable). Follows static edges, plus dynamic ones if `include_dynamic`.
+#[must_use]
+pub fn import_depths(graph: &ModuleGraph, entry: ModuleId, include_dynamic: bool) -> Vec<u32> {
+    let mut depth = vec![u32::MAX; graph.modules.len()];
+    let mut queue: VecDeque<ModuleId> = VecDeque::new();
+    depth[entry.0 as usize] = 0;
+    queue.push_back(entry);
+    while let Some(mid) = queue.pop_front() {
+        let d = depth[mid.0 as usize];
+        for &edge_id in graph.outgoing_edges(mid) {
+            let edge = graph.edge(edge_id);
+            let follow = edge.kind == EdgeKind::Static
+                || (include_dynamic && edge.kind == EdgeKind::Dynamic);
+            let idx = edge.to.0 as usize;
+            if follow && depth[idx] == u32::MAX {
+                depth[idx] = d + 1;
+                queue.push_back(edge.to);
+            }
+        }
+    }
+    depth
+}
+
+/// Totals extrapolated from a graph built with a
+/// [`Sample`](crate::walker::Sample).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SampleEstimate {
+    pub weight: f64,
+    pub module_count: f64,
+}
+
+/// Estimate the total size and count of `modules` had every file been
+/// parsed, when each file past the entry was parsed with probability
+/// `fraction`. A module is found when at least one of its importers was
+/// found and parsed, so its chance of being found is built up from every
+/// importer seen before it (nearer the entry), taken as independent; each
+/// module then stands for `1 / chance` modules (Horvitz-Thompson).
+///
+/// Importers left unparsed hide their edges, so a shared module's chance
+/// is underrated and the estimate leans high; a subtree cut off behind one
+/// unparsed file leaves no trace at all, which pulls it low. It is an
+/// extrapolation, not a bound, and can land well off the exact weight,
+/// most of all at small fractions.
+#[must_use]
+#[allow(clippy::cast_precision_loss)]
+pub fn estimate_from_sample(
+    graph: &ModuleGraph,
+    entry: ModuleId,
+    modules: &[ModuleId],
+    fraction: f64,
+) -> SampleEstimate {
+    let mut incoming: Vec<Vec<ModuleId>> = vec![Vec::new(); graph.modules.len()];
+    for edge in &graph.edges {
+        incoming[edge.to.0 as usize].push(edge.from);
+    }
+    // Chance each module was found, settled in BFS order from the entry
+    let mut found = vec![None::<f64>; graph.modules.len()];
+    found[entry.0 as usize] = Some(1.0);
+    let mut queue: VecDeque<ModuleId> = VecDeque::from([entry]);
+    while let Some(mid) = queue.pop_front() {
+        for &edge_id in graph.outgoing_edges(mid) {
+            let to = graph.edge(edge_id).to;
+            if found[to.0 as usize].is_some() {
+                continue;
+            }
+            // The entry is always parsed; every other importer with `fraction`
+            let missed: f64 = incoming[to.0 as usize]
+                .iter()
+                .filter_map(|&from| {
+                    let parsed = if from == entry { 1.0 } else { fraction };
+                    found[from.0 as usize].map(|p| 1.0 - p * parsed)
+                })
+                .product();
+            found[to.0 as usize] = Some(1.0 - missed);
+            queue.push_back(to);
+        }
     }
+
+    let mut estimate = SampleEstimate {
+        weight: 0.0,
+        module_count: 0.0,
+    };
+    for &mid in modules {
+        let p = found[mid.0 as usize].unwrap_or(1.0).max(f64::MIN_POSITIVE);
+        estimate.weight += graph.module(mid).size_bytes as f64 / p;
+        estimate.module_count += 1.0 / p;
+    }
+    estimate
 }
 
 /// Reconstruct the shortest chain from entry to target using pre-computed
@@ -321,11 +632,31 @@ fn reconstruct_chain(parent: &[u32], entry: ModuleId, target: ModuleId) -> Vec<M
 }
 
 #[must_use]
-#[allow(clippy::cast_sign_loss)]
+#[allow(clippy::cast_sign_loss, clippy::too_many_lines)]
 pub fn trace(graph: &ModuleGraph, entry: ModuleId, opts: &TraceOptions) -> TraceResult {
-    let bfs = bfs_reachable(graph, entry);
+    let (skip, dropped, too_deep) = exclude_masks(graph, entry, opts);
+    let bfs = bfs_reachable(graph, entry, opts.dynamic_depth, skip.as_deref());
+    let unexpanded_packages = too_deep
+        .map(|mask| unexpanded_packages(graph, &bfs, &mask))
+        .unwrap_or_default();
+    let (lazy_boundary_count, lazy_boundary_weight) = lazy_boundaries(graph, &bfs.static_set);
+    let mut type_only = type_only_reachable(graph, &bfs, opts.include_dynamic, skip.as_deref());
     let mut reachable = bfs.static_set;
-    let dynamic_only = bfs.dynamic_set;
+    let mut dynamic_only = bfs.dynamic_set;
+
+    // Excluded modules were traversed for what they import; the dominator
+    // walk still needs them, but nothing else counts them
+    let mut traversed = None;
+    if let Some(dropped) = dropped.as_deref() {
+        let mut all = reachable.clone();
+        if opts.include_dynamic {
+            all.extend_from_slice(&dynamic_only);
+        }
+        traversed = Some(all);
+        reachable.retain(|mid| !dropped[mid.0 as usize]);
+        dynamic_only.retain(|mid| !dropped[mid.0 as usize]);
+        type_only.retain(|mid| !dropped[mid.0 as usize]);
+    }
 
     // When --include-dynamic is set, fold dynamic modules into the reachable
     // set. There's nothing "only dynamic" when the user asked to include them.
@@ -349,6 +680,17 @@ pub fn trace(graph: &ModuleGraph, entry: ModuleId, opts: &TraceOptions) -> Trace
         (w, dynamic_only.len())
     };
 
+    let (type_only_weight, type_only_module_count) = if opts.count_type_only {
+        reachable.extend_from_slice(&type_only);
+        (0, 0)
+    } else {
+        let w: u64 = type_only
+            .iter()
+            .map(|&mid| graph.module(mid).size_bytes)
+            .sum();
+        (w, type_only.len())
+    };
+
     let static_weight: u64 = reachable
         .iter()
         .map(|&mid| graph.module(mid).size_bytes)
@@ -401,109 +743,670 @@ pub fn trace(graph: &ModuleGraph, entry: ModuleId, opts: &TraceOptions) -> Trace
         })
         .collect();
 
-    // Compute exclusive weight for all reachable modules via dominator tree
-    let exclusive = compute_exclusive_weights(graph, entry, opts.include_dynamic);
-
-    // Prefer first-party (no package) modules for the per-file breakdown.
-    // Fall back to all modules when no first-party modules exist (e.g. Python
-    // projects where every reachable module is a third-party package).
-    let mut modules_by_cost: Vec<ModuleCost> = reachable
-        .iter()
-        .filter(|&&mid| mid != entry && graph.module(mid).package.is_none())
-        .map(|&mid| ModuleCost {
-            module_id: mid,
-            exclusive_size: exclusive[mid.0 as usize],
-        })
-        .collect();
-    if modules_by_cost.is_empty() {
-        modules_by_cost = reachable
-            .iter()
-            .filter(|&&mid| mid != entry)
-            .map(|&mid| ModuleCost {
-                module_id: mid,
-                exclusive_size: exclusive[mid.0 as usize],
-            })
-            .collect();
-    }
+    let modules_by_cost = modules_by_cost(
+        graph,
+        entry,
+        &reachable,
+        traversed.as_deref(),
+        dropped.as_deref(),
+        opts,
+    );
 
-    modules_by_cost.sort_by(|a, b| b.exclusive_size.cmp(&a.exclusive_size));
+    let assets = collect_assets(graph, &reachable);
+    let asset_weight = assets.iter().map(|&mid| graph.module(mid).size_bytes).sum();
 
     TraceResult {
         static_weight,
         static_module_count: reachable.len(),
         dynamic_only_weight,
         dynamic_only_module_count,
+        type_only_weight,
+        type_only_module_count,
         heavy_packages,
         modules_by_cost,
         all_packages,
         dynamic_packages: dynamic_pkg_sizes,
+        asset_weight,
+        assets,
+        lazy_boundary_count,
+        lazy_boundary_weight,
+        reachable,
+        unexpanded_packages,
     }
 }
 
-/// Find ALL shortest chains from entry to a specific target (package or module).
-///
-/// Returns up to `max_chains` distinct shortest paths (all same hop count),
-/// deduplicated at the package-name level so chains that differ only by
-/// internal file paths are collapsed into one.
-#[must_use]
-pub fn find_all_chains(
+/// `opts.exclude` as masks indexed by `ModuleId`: modules the traversal must
+/// not enter (`--exclude-subtree`), or modules it enters but must not count.
+/// Modules past `opts.max_package_depth` are not entered either; they are
+/// also returned on their own, third.
+#[allow(clippy::type_complexity)]
+fn exclude_masks(
     graph: &ModuleGraph,
     entry: ModuleId,
-    target: &ChainTarget,
-    include_dynamic: bool,
-) -> Vec<Vec<ModuleId>> {
-    let raw = all_shortest_chains(graph, entry, target, 10, include_dynamic);
-    dedup_chains_by_package(graph, raw)
+    opts: &TraceOptions,
+) -> (Option<Vec<bool>>, Option<Vec<bool>>, Option<Vec<bool>>) {
+    let (skip, dropped) = match &opts.exclude {
+        None => (None, None),
+        Some(exclude) if exclude.subtree => (Some(exclude.mask(graph, entry)), None),
+        Some(exclude) => (None, Some(exclude.mask(graph, entry))),
+    };
+    let Some(max) = opts.max_package_depth else {
+        return (skip, dropped, None);
+    };
+    let too_deep: Vec<bool> = package_depths(graph, entry)
+        .into_iter()
+        .map(|d| d != u32::MAX && d > max)
+        .collect();
+    let skip = match skip {
+        Some(mut skip) => {
+            for (s, &deep) in skip.iter_mut().zip(&too_deep) {
+                *s |= deep;
+            }
+            skip
+        }
+        None => too_deep.clone(),
+    };
+    (Some(skip), dropped, Some(too_deep))
 }
 
-/// Deduplicate chains that look identical at the package-name level.
-/// Two chains that differ only by which internal file within a package
-/// they pass through will have the same package-level key and only the
-/// first is kept.
-fn dedup_chains_by_package(graph: &ModuleGraph, chains: Vec<Vec<ModuleId>>) -> Vec<Vec<ModuleId>> {
-    let mut seen: HashSet<Vec<String>> = HashSet::new();
-    let mut result = Vec::new();
+/// Fewest package boundaries crossed from `entry` to every module
+/// (`u32::MAX` when unreachable), over edges of any kind. An import into a
+/// module of a different package, first-party code counting as one, is a
+/// crossing; imports within a package are free.
+#[must_use]
+pub fn package_depths(graph: &ModuleGraph, entry: ModuleId) -> Vec<u32> {
+    let mut depth = vec![u32::MAX; graph.modules.len()];
+    let mut queue: VecDeque<(ModuleId, u32)> = VecDeque::new();
+    depth[entry.0 as usize] = 0;
+    queue.push_back((entry, 0));
+    // 0-1 BFS: same-package edges push front, crossings push back
+    while let Some((mid, d)) = queue.pop_front() {
+        if d > depth[mid.0 as usize] {
+            continue;
+        }
+        let package = &graph.module(mid).package;
+        for &edge_id in graph.outgoing_edges(mid) {
+            let to = graph.edge(edge_id).to;
+            let crosses = graph.module(to).package != *package;
+            let next = d + u32::from(crosses);
+            if next < depth[to.0 as usize] {
+                depth[to.0 as usize] = next;
+                if crosses {
+                    queue.push_back((to, next));
+                } else {
+                    queue.push_front((to, next));
+                }
+            }
+        }
+    }
+    depth
+}
 
-    for chain in chains {
-        let key: Vec<String> = chain
-            .iter()
-            .map(|&mid| {
-                let m = graph.module(mid);
-                m.package
-                    .clone()
-                    .unwrap_or_else(|| m.path.to_string_lossy().into_owned())
-            })
-            .collect();
+/// Packages of the `too_deep` modules that traced modules import directly.
+fn unexpanded_packages(graph: &ModuleGraph, bfs: &BfsResult, too_deep: &[bool]) -> Vec<String> {
+    let mut packages: Vec<String> = bfs
+        .static_set
+        .iter()
+        .chain(&bfs.dynamic_set)
+        .flat_map(|&mid| graph.outgoing_edges(mid))
+        .map(|&edge_id| graph.module(graph.edge(edge_id).to))
+        .filter(|m| too_deep[m.id.0 as usize])
+        .map(|m| {
+            m.package
+                .clone()
+                .unwrap_or_else(|| m.path.display().to_string())
+        })
+        .collect();
+    packages.sort_unstable();
+    packages.dedup();
+    packages
+}
 
-        if seen.insert(key) {
-            result.push(chain);
+/// Count static → dynamic handoffs out of `static_set` and the weight they
+/// gate. Each boundary's target is followed through static edges only;
+/// nested `import()`s inside a lazy chunk are boundaries of the chunk, not
+/// of the eagerly loaded app, and are not counted.
+fn lazy_boundaries(graph: &ModuleGraph, static_set: &[ModuleId]) -> (usize, u64) {
+    let mut is_static = vec![false; graph.modules.len()];
+    for &mid in static_set {
+        is_static[mid.0 as usize] = true;
+    }
+    let mut count = 0;
+    let mut targets = Vec::new();
+    for &mid in static_set {
+        for &edge_id in graph.outgoing_edges(mid) {
+            let edge = graph.edge(edge_id);
+            if edge.kind == EdgeKind::Dynamic && !is_static[edge.to.0 as usize] {
+                count += 1;
+                targets.push(edge.to);
+            }
         }
     }
-
-    result
+    let gated = graph.reachable_set(&targets, |e| e.kind == EdgeKind::Static);
+    let weight = graph
+        .modules
+        .iter()
+        .filter(|m| gated[m.id.0 as usize] && !is_static[m.id.0 as usize])
+        .map(|m| m.size_bytes)
+        .sum();
+    (count, weight)
+}
+
+/// The modules only dynamic imports load (`--only-dynamic`): the lazy
+/// payload left out of the static trace.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct DynamicOnly {
+    /// Largest first.
+    pub modules: Vec<ModuleId>,
+    pub weight: u64,
+    /// Heaviest first.
+    pub boundaries: Vec<LazyBoundary>,
+}
+
+/// One `import()` from eagerly loaded code into a lazy chunk.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct LazyBoundary {
+    pub importer: ModuleId,
+    pub target: ModuleId,
+    /// The chunk: the target and what it imports statically, minus what is
+    /// already loaded at startup. Chunks can overlap.
+    pub weight: u64,
+    pub module_count: usize,
+}
+
+/// Everything reachable from `entry` only through dynamic imports, with the
+/// boundaries it is loaded through. Honors `dynamic_depth` and `exclude`
+/// like [`trace`].
+#[must_use]
+pub fn dynamic_only(graph: &ModuleGraph, entry: ModuleId, opts: &TraceOptions) -> DynamicOnly {
+    let (skip, dropped, _) = exclude_masks(graph, entry, opts);
+    let bfs = bfs_reachable(graph, entry, opts.dynamic_depth, skip.as_deref());
+    let counted = |mid: ModuleId| dropped.as_ref().is_none_or(|d| !d[mid.0 as usize]);
+    let mut is_static = vec![false; graph.modules.len()];
+    for &mid in &bfs.static_set {
+        is_static[mid.0 as usize] = true;
+    }
+    let mut is_lazy = vec![false; graph.modules.len()];
+    for &mid in &bfs.dynamic_set {
+        is_lazy[mid.0 as usize] = true;
+    }
+
+    let mut modules: Vec<ModuleId> = bfs
+        .dynamic_set
+        .into_iter()
+        .filter(|&m| counted(m))
+        .collect();
+    modules.sort_by(|&a, &b| {
+        graph
+            .module(b)
+            .size_bytes
+            .cmp(&graph.module(a).size_bytes)
+            .then(a.0.cmp(&b.0))
+    });
+    let weight = modules.iter().map(|&m| graph.module(m).size_bytes).sum();
+
+    let mut boundaries = Vec::new();
+    for &importer in &bfs.static_set {
+        for &edge_id in graph.outgoing_edges(importer) {
+            let edge = graph.edge(edge_id);
+            if edge.kind != EdgeKind::Dynamic || !is_lazy[edge.to.0 as usize] {
+                continue;
+            }
+            let chunk = graph.reachable_set(&[edge.to], |e| {
+                e.kind == EdgeKind::Static && skip.as_ref().is_none_or(|s| !s[e.to.0 as usize])
+            });
+            let (weight, module_count) = graph
+                .modules
+                .iter()
+                .filter(|m| chunk[m.id.0 as usize] && !is_static[m.id.0 as usize] && counted(m.id))
+                .fold((0, 0), |(w, n), m| (w + m.size_bytes, n + 1));
+            boundaries.push(LazyBoundary {
+                importer,
+                target: edge.to,
+                weight,
+                module_count,
+            });
+        }
+    }
+    boundaries.sort_by(|a, b| {
+        b.weight
+            .cmp(&a.weight)
+            .then((a.importer.0, a.target.0).cmp(&(b.importer.0, b.target.0)))
+    });
+    boundaries.dedup_by_key(|b| (b.importer, b.target));
+
+    DynamicOnly {
+        modules,
+        weight,
+        boundaries,
+    }
+}
+
+/// Import cycles among modules reachable from `entry`: every strongly
+/// connected component with more than one module, plus modules importing
+/// themselves. Follows static edges, and dynamic ones if `include_dynamic`
+/// (type-only imports are erased at runtime and never form a cycle).
+///
+/// Each cycle lists its modules in discovery order; cycles come out in
+/// reverse topological order (Tarjan's algorithm, iterative).
+#[must_use]
+pub fn find_cycles(
+    graph: &ModuleGraph,
+    entry: ModuleId,
+    include_dynamic: bool,
+) -> Vec<Vec<ModuleId>> {
+    strongly_connected(graph, entry, |kind| should_follow(kind, include_dynamic))
+}
+
+/// An import cycle found by [`find_all_cycles`].
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct ImportCycle {
+    /// Members in discovery order, starting where the loop closes.
+    pub modules: Vec<ModuleId>,
+    /// Edges from a member back to the first one.
+    pub closing_edges: Vec<EdgeId>,
+    /// Every loop among the members needs an `import type` edge, so the
+    /// cycle is erased at runtime.
+    pub type_only: bool,
+}
+
+/// Every import cycle reachable from `entry`, following all edges but
+/// asset imports. Unlike [`find_cycles`], cycles closed only by type-only
+/// imports are included and flagged as such.
+#[must_use]
+pub fn find_all_cycles(graph: &ModuleGraph, entry: ModuleId) -> Vec<ImportCycle> {
+    let in_runtime_cycle: HashSet<ModuleId> = find_cycles(graph, entry, true)
+        .into_iter()
+        .flatten()
+        .collect();
+    strongly_connected(graph, entry, |kind| kind != EdgeKind::Asset)
+        .into_iter()
+        .map(|modules| {
+            let head = modules[0];
+            let closing_edges = modules
+                .iter()
+                .flat_map(|&m| graph.outgoing_edges(m))
+                .copied()
+                .filter(|&eid| {
+                    let edge = graph.edge(eid);
+                    edge.to == head && edge.kind != EdgeKind::Asset
+                })
+                .collect();
+            let type_only = !modules.iter().any(|m| in_runtime_cycle.contains(m));
+            ImportCycle {
+                modules,
+                closing_edges,
+                type_only,
+            }
+        })
+        .collect()
+}
+
+/// Tarjan's algorithm over the edges `follow` accepts, from `entry`.
+/// Returns the components with more than one module or a self-loop.
+#[allow(clippy::cast_possible_truncation)]
+fn strongly_connected(
+    graph: &ModuleGraph,
+    entry: ModuleId,
+    follow: impl Fn(EdgeKind) -> bool,
+) -> Vec<Vec<ModuleId>> {
+    let n = graph.modules.len();
+    let mut index = vec![u32::MAX; n];
+    let mut lowlink = vec![0u32; n];
+    let mut on_stack = vec![false; n];
+    let mut stack: Vec<ModuleId> = Vec::new();
+    let mut next_index = 0u32;
+    let mut cycles = Vec::new();
+
+    // (module, position in its outgoing edge list)
+    let mut work: Vec<(ModuleId, usize)> = vec![(entry, 0)];
+    index[entry.0 as usize] = 0;
+    lowlink[entry.0 as usize] = 0;
+    next_index += 1;
+    stack.push(entry);
+    on_stack[entry.0 as usize] = true;
+
+    while let Some(&mut (mid, ref mut pos)) = work.last_mut() {
+        let idx = mid.0 as usize;
+        let edges = graph.outgoing_edges(mid);
+        if let Some(&edge_id) = edges.get(*pos) {
+            *pos += 1;
+            let edge = graph.edge(edge_id);
+            if !follow(edge.kind) {
+                continue;
+            }
+            let to = edge.to.0 as usize;
+            if index[to] == u32::MAX {
+                index[to] = next_index;
+                lowlink[to] = next_index;
+                next_index += 1;
+                stack.push(edge.to);
+                on_stack[to] = true;
+                work.push((edge.to, 0));
+            } else if on_stack[to] {
+                lowlink[idx] = lowlink[idx].min(index[to]);
+            }
+            continue;
+        }
+
+        work.pop();
+        if let Some(&(parent, _)) = work.last() {
+            let p = parent.0 as usize;
+            lowlink[p] = lowlink[p].min(lowlink[idx]);
+        }
+        if lowlink[idx] == index[idx] {
+            let mut component = Vec::new();
+            loop {
+                let member = stack.pop().expect("component root is on the stack");
+                on_stack[member.0 as usize] = false;
+                component.push(member);
+                if member == mid {
+                    break;
+                }
+            }
+            let self_loop = || {
+                edges.iter().any(|&e| {
+                    let edge = graph.edge(e);
+                    edge.to == mid && follow(edge.kind)
+                })
+            };
+            if component.len() > 1 || self_loop() {
+                component.reverse();
+                cycles.push(component);
+            }
+        }
+    }
+    cycles
+}
+
+/// Static totals with one module detached. Produced by [`trace_without`].
+#[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
+pub struct WithoutResult {
+    pub static_weight: u64,
+    pub static_module_count: usize,
+}
+
+/// Static weight and module count as if `removed`, and everything reachable
+/// only through it, were detached from the graph (e.g. moved behind an
+/// `import()`). The drop in weight is `removed`'s exclusive weight.
+#[must_use]
+pub fn trace_without(graph: &ModuleGraph, entry: ModuleId, removed: ModuleId) -> WithoutResult {
+    if removed == entry {
+        return WithoutResult {
+            static_weight: 0,
+            static_module_count: 0,
+        };
+    }
+    let reachable =
+        graph.reachable_set(&[entry], |e| e.kind == EdgeKind::Static && e.to != removed);
+    let (static_weight, static_module_count) = graph
+        .modules
+        .iter()
+        .filter(|m| reachable[m.id.0 as usize])
+        .fold((0, 0), |(w, n), m| (w + m.size_bytes, n + 1));
+    WithoutResult {
+        static_weight,
+        static_module_count,
+    }
+}
+
+/// Exclusive weight of every reachable module except the entry, sorted
+/// descending.
+/// `traversed` is the reachable set before `--exclude` dropped the modules
+/// marked in `dropped`, when it did.
+fn modules_by_cost(
+    graph: &ModuleGraph,
+    entry: ModuleId,
+    reachable: &[ModuleId],
+    traversed: Option<&[ModuleId]>,
+    dropped: Option<&[bool]>,
+    opts: &TraceOptions,
+) -> Vec<ModuleCost> {
+    // Compute exclusive weight for all reachable modules via dominator tree.
+    // A depth limit can leave dynamic targets out of the reachable set, and
+    // --exclude-subtree and --max-package-depth prune modules from it, so restrict the dominator
+    // walk to it.
+    let restrict = (opts.include_dynamic && opts.dynamic_depth.is_some())
+        || opts.exclude.as_ref().is_some_and(|e| e.subtree)
+        || opts.max_package_depth.is_some();
+    let allowed = restrict.then(|| {
+        let mut mask = vec![false; graph.modules.len()];
+        for &mid in traversed.unwrap_or(reachable) {
+            mask[mid.0 as usize] = true;
+        }
+        mask
+    });
+    let exclusive = exclusive_weights(
+        graph,
+        entry,
+        opts.include_dynamic,
+        allowed.as_deref(),
+        dropped,
+    );
+
+    // Prefer first-party (no package) modules for the per-file breakdown.
+    // Fall back to all modules when no first-party modules exist (e.g. Python
+    // projects where every reachable module is a third-party package).
+    let mut modules_by_cost: Vec<ModuleCost> = reachable
+        .iter()
+        .filter(|&&mid| mid != entry && graph.module(mid).package.is_none())
+        .map(|&mid| ModuleCost {
+            module_id: mid,
+            exclusive_size: exclusive[mid.0 as usize],
+        })
+        .collect();
+    if modules_by_cost.is_empty() {
+        modules_by_cost = reachable
+            .iter()
+            .filter(|&&mid| mid != entry)
+            .map(|&mid| ModuleCost {
+                module_id: mid,
+                exclusive_size: exclusive[mid.0 as usize],
+            })
+            .collect();
+    }
+
+    modules_by_cost.sort_by(|a, b| b.exclusive_size.cmp(&a.exclusive_size));
+    modules_by_cost
+}
+
+/// Targets of asset edges leaving the reachable set, sorted by size
+/// descending. Assets already counted as code (reachable some other way)
+/// are skipped so no byte is counted twice.
+fn collect_assets(graph: &ModuleGraph, reachable: &[ModuleId]) -> Vec<ModuleId> {
+    let mut seen = vec![false; graph.modules.len()];
+    for &mid in reachable {
+        seen[mid.0 as usize] = true;
+    }
+    let mut assets = Vec::new();
+    for &mid in reachable {
+        for &edge_id in graph.outgoing_edges(mid) {
+            let edge = graph.edge(edge_id);
+            if edge.kind == EdgeKind::Asset && !seen[edge.to.0 as usize] {
+                seen[edge.to.0 as usize] = true;
+                assets.push(edge.to);
+            }
+        }
+    }
+    assets.sort_by(|a, b| {
+        graph
+            .module(*b)
+            .size_bytes
+            .cmp(&graph.module(*a).size_bytes)
+    });
+    assets
+}
+
+/// Headline totals of a trace, without per-package or per-module detail.
+#[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
+pub struct TraceCounts {
+    pub static_weight: u64,
+    pub static_module_count: usize,
+    pub dynamic_only_weight: u64,
+    pub dynamic_only_module_count: usize,
+    pub type_only_weight: u64,
+    pub type_only_module_count: usize,
+    /// Number of distinct third-party packages in the reachable set
+    pub package_count: usize,
+}
+
+/// Fast path of [`trace`] that stops after the reachability and weight sums.
+///
+/// Skips sorting, per-package aggregation, chain reconstruction, and the
+/// dominator tree, so it stays cheap on very large graphs.
+#[must_use]
+pub fn trace_counts(graph: &ModuleGraph, entry: ModuleId, opts: &TraceOptions) -> TraceCounts {
+    let include_dynamic = opts.include_dynamic;
+    let (skip, dropped, _) = exclude_masks(graph, entry, opts);
+    let mut bfs = bfs_reachable(graph, entry, opts.dynamic_depth, skip.as_deref());
+    let mut type_only = type_only_reachable(graph, &bfs, include_dynamic, skip.as_deref());
+    if let Some(dropped) = dropped {
+        bfs.static_set.retain(|mid| !dropped[mid.0 as usize]);
+        bfs.dynamic_set.retain(|mid| !dropped[mid.0 as usize]);
+        type_only.retain(|mid| !dropped[mid.0 as usize]);
+    }
+    let weight =
+        |set: &[ModuleId]| -> u64 { set.iter().map(|&mid| graph.module(mid).size_bytes).sum() };
+
+    let mut static_weight = weight(&bfs.static_set);
+    let mut static_module_count = bfs.static_set.len();
+    let (dynamic_only_weight, dynamic_only_module_count) = if include_dynamic {
+        static_weight += weight(&bfs.dynamic_set);
+        static_module_count += bfs.dynamic_set.len();
+        (0, 0)
+    } else {
+        (weight(&bfs.dynamic_set), bfs.dynamic_set.len())
+    };
+    let (type_only_weight, type_only_module_count) = if opts.count_type_only {
+        static_weight += weight(&type_only);
+        static_module_count += type_only.len();
+        (0, 0)
+    } else {
+        (weight(&type_only), type_only.len())
+    };
+
+    let dynamic_counted: &[ModuleId] = if include_dynamic {
+        &bfs.dynamic_set
+    } else {
+        &[]
+    };
+    let type_only_counted: &[ModuleId] = if opts.count_type_only {
+        &type_only
+    } else {
+        &[]
+    };
+    let packages: HashSet<&str> = bfs
+        .static_set
+        .iter()
+        .chain(dynamic_counted)
+        .chain(type_only_counted)
+        .filter_map(|&mid| graph.module(mid).package.as_deref())
+        .collect();
+
+    TraceCounts {
+        static_weight,
+        static_module_count,
+        dynamic_only_weight,
+        dynamic_only_module_count,
+        type_only_weight,
+        type_only_module_count,
+        package_count: packages.len(),
+    }
+}
+
+/// Find ALL shortest chains from entry to a specific target (package or module).
+///
+/// Returns up to `max_chains` distinct shortest paths (all same hop count),
+/// deduplicated at the package-name level so chains that differ only by
+/// internal file paths are collapsed into one.
+#[must_use]
+pub fn find_all_chains(
+    graph: &ModuleGraph,
+    entry: ModuleId,
+    target: &ChainTarget,
+    include_dynamic: bool,
+) -> Vec<Vec<ModuleId>> {
+    find_chains_min_hops(graph, entry, target, include_dynamic, 0)
+}
+
+/// Like [`find_all_chains`], but only chains of at least `min_hops` edges.
+///
+/// Returns the shortest such chains, so `min_hops = 2` skips a direct import
+/// and surfaces the indirect routes that also pull the target in.
+#[must_use]
+pub fn find_chains_min_hops(
+    graph: &ModuleGraph,
+    entry: ModuleId,
+    target: &ChainTarget,
+    include_dynamic: bool,
+    min_hops: u32,
+) -> Vec<Vec<ModuleId>> {
+    let raw = all_shortest_chains(graph, entry, target, 10, include_dynamic, min_hops);
+    dedup_chains_by_package(graph, raw)
+}
+
+/// Deduplicate chains that look identical at the package-name level.
+/// Two chains that differ only by which internal file within a package
+/// they pass through will have the same package-level key and only the
+/// first is kept.
+fn dedup_chains_by_package(graph: &ModuleGraph, chains: Vec<Vec<ModuleId>>) -> Vec<Vec<ModuleId>> {
+    let mut seen: HashSet<Vec<String>> = HashSet::new();
+    let mut result = Vec::new();
+
+    for chain in chains {
+        let key: Vec<String> = chain
+            .iter()
+            .map(|&mid| {
+                let m = graph.module(mid);
+                m.package
+                    .clone()
+                    .unwrap_or_else(|| m.path.to_string_lossy().into_owned())
+            })
+            .collect();
+
+        if seen.insert(key) {
+            result.push(chain);
+        }
+    }
+
+    result
 }
 
 /// BFS with multi-parent tracking to find all shortest paths to a target.
+///
+/// The search runs over `(module, hops)` states with `hops` capped at
+/// `min_hops`, so a module already reached in fewer hops can be reached
+/// again on a longer route. Targets only count once `min_hops` is reached.
+/// With `min_hops = 0` this is a plain BFS.
 fn all_shortest_chains(
     graph: &ModuleGraph,
     entry: ModuleId,
     target: &ChainTarget,
     max_chains: usize,
     include_dynamic: bool,
+    min_hops: u32,
 ) -> Vec<Vec<ModuleId>> {
-    let n = graph.modules.len();
+    let layers = min_hops as usize + 1;
+    let state = |mid: ModuleId, hops: u32| mid.0 as usize * layers + hops as usize;
+    let n = graph.modules.len() * layers;
     let mut parents: Vec<Vec<u32>> = vec![Vec::new(); n];
     let mut depth: Vec<u32> = vec![u32::MAX; n];
-    let mut queue: VecDeque<ModuleId> = VecDeque::new();
+    let mut queue: VecDeque<(ModuleId, u32)> = VecDeque::new();
 
-    depth[entry.0 as usize] = 0;
-    queue.push_back(entry);
+    depth[state(entry, 0)] = 0;
+    queue.push_back((entry, 0));
 
     let mut target_depth: Option<u32> = None;
-    let mut targets: Vec<ModuleId> = Vec::new();
+    let mut targets: Vec<usize> = Vec::new();
 
-    while let Some(mid) = queue.pop_front() {
-        let d = depth[mid.0 as usize];
+    while let Some((mid, hops)) = queue.pop_front() {
+        let s = state(mid, hops);
+        let d = depth[s];
 
         // If we've found targets and moved past their depth, stop
         if let Some(td) = target_depth
@@ -514,13 +1417,16 @@ fn all_shortest_chains(
 
         // Check if this module matches the target
         if target.matches(graph, mid) {
-            if target_depth.is_none() {
-                target_depth = Some(d);
+            if hops == min_hops {
+                if target_depth.is_none() {
+                    target_depth = Some(d);
+                }
+                targets.push(s);
             }
-            targets.push(mid);
             continue; // Don't expand past target package modules
         }
 
+        let next_hops = (hops + 1).min(min_hops);
         for &edge_id in graph.outgoing_edges(mid) {
             let edge = graph.edge(edge_id);
             match edge.kind {
@@ -530,17 +1436,18 @@ fn all_shortest_chains(
             }
 
             let next_depth = d + 1;
-            let idx = edge.to.0 as usize;
+            let idx = state(edge.to, next_hops);
+            #[allow(clippy::cast_possible_truncation)]
             match depth[idx] {
                 d if d == next_depth => {
                     // Same depth -- add as alternate parent
-                    parents[idx].push(mid.0);
+                    parents[idx].push(s as u32);
                 }
                 u32::MAX => {
                     // First visit
                     depth[idx] = next_depth;
-                    parents[idx].push(mid.0);
-                    queue.push_back(edge.to);
+                    parents[idx].push(s as u32);
+                    queue.push_back((edge.to, next_hops));
                 }
                 _ => {} // Already visited at shorter depth, skip
             }
@@ -551,27 +1458,39 @@ fn all_shortest_chains(
         return Vec::new();
     }
 
+    backtrack_chains(&parents, &targets, state(entry, 0), layers, max_chains)
+}
+
+/// Reconstruct entry-to-target paths from the BFS parent lists, mapping
+/// `(module, hops)` states back to modules.
+fn backtrack_chains(
+    parents: &[Vec<u32>],
+    targets: &[usize],
+    entry_state: usize,
+    layers: usize,
+    max_chains: usize,
+) -> Vec<Vec<ModuleId>> {
     // Backtrack from each target to reconstruct all paths
     let mut all_chains: Vec<Vec<ModuleId>> = Vec::new();
-    for &target_mid in &targets {
-        let mut partial_paths: Vec<Vec<ModuleId>> = vec![vec![target_mid]];
+    for &target_state in targets {
+        let mut partial_paths: Vec<Vec<usize>> = vec![vec![target_state]];
 
         loop {
-            let mut next_partial: Vec<Vec<ModuleId>> = Vec::new();
+            let mut next_partial: Vec<Vec<usize>> = Vec::new();
             let mut any_extended = false;
 
             for path in &partial_paths {
                 let &head = path.last().unwrap();
-                if head == entry {
+                if head == entry_state {
                     next_partial.push(path.clone());
                     continue;
                 }
-                let pars = &parents[head.0 as usize];
+                let pars = &parents[head];
                 if !pars.is_empty() {
                     any_extended = true;
                     for &p in pars {
                         let mut new_path = path.clone();
-                        new_path.push(ModuleId(p));
+                        new_path.push(p as usize);
                         next_partial.push(new_path);
                         if next_partial.len() > max_chains * 2 {
                             break; // Prevent combinatorial explosion
@@ -586,13 +1505,24 @@ fn all_shortest_chains(
             }
         }
 
-        for mut path in partial_paths {
-            path.reverse();
-            if path.first() == Some(&entry) {
-                all_chains.push(path);
-                if all_chains.len() >= max_chains {
-                    return all_chains;
-                }
+        for path in partial_paths {
+            if path.last() != Some(&entry_state) {
+                continue;
+            }
+            #[allow(clippy::cast_possible_truncation)]
+            let chain: Vec<ModuleId> = path
+                .iter()
+                .rev()
+                .map(|&s| ModuleId((s / layers) as u32))
+                .collect();
+            // Longer routes may loop back through a module; keep simple paths
+            let mut seen = HashSet::new();
+            if !chain.iter().all(|m| seen.insert(*m)) {
+                continue;
+            }
+            all_chains.push(chain);
+            if all_chains.len() >= max_chains {
+                return all_chains;
             }
         }
     }
@@ -628,7 +1558,7 @@ pub fn find_cut_modules(
         return Vec::new();
     }
 
-    let exclusive = compute_exclusive_weights(graph, entry, include_dynamic);
+    let exclusive = compute_exclusive_weights(graph, entry, include_dynamic, None);
 
     let total = chains.len();
     let mut frequency = vec![0usize; graph.modules.len()];
@@ -676,100 +1606,678 @@ pub fn find_cut_modules(
     cuts
 }
 
-/// Minimal snapshot of a trace result for before/after comparison.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-#[non_exhaustive]
-pub struct TraceSnapshot {
-    pub entry: String,
-    pub static_weight: u64,
-    pub packages: HashMap<String, u64>,
-    #[serde(default)]
-    pub dynamic_weight: u64,
-    #[serde(default)]
-    pub dynamic_packages: HashMap<String, u64>,
-}
+/// Minimum number of static imports for an `index`/`__init__` module to be
+/// treated as a barrel that re-exports its directory.
+const MIN_BARREL_FANOUT: usize = 3;
 
-impl TraceResult {
-    pub fn to_snapshot(&self, entry: &str) -> TraceSnapshot {
-        TraceSnapshot {
-            entry: entry.to_string(),
-            static_weight: self.static_weight,
-            packages: self.all_packages.clone(),
-            dynamic_weight: self.dynamic_only_weight,
-            dynamic_packages: self.dynamic_packages.clone(),
-        }
-    }
-}
+/// Amplification at or above which a barrel import is flagged.
+pub const BARREL_PENALTY_THRESHOLD: f64 = 3.0;
 
-/// A package that appears in only one side of a diff, with its size.
-#[derive(Debug)]
+/// How a third-party package is reached from the entry point.
+#[derive(Debug, Clone)]
 #[non_exhaustive]
-pub struct DiffPackage {
+pub struct PackageReach {
     pub name: String,
-    pub size: u64,
+    /// Strongest edge kind on any path to the package: `Static` if it loads
+    /// at startup, else `Dynamic`, else `TypeOnly`.
+    pub reach: EdgeKind,
+    /// Files of the package reachable under that kind
+    pub file_count: u32,
+    /// Total size of those files
+    pub total_size: u64,
 }
 
-/// Compute a diff between two trace snapshots.
-#[derive(Debug)]
-#[non_exhaustive]
-pub struct DiffResult {
-    pub entry_a_weight: u64,
-    pub entry_b_weight: u64,
-    pub weight_delta: i64,
-    pub dynamic_a_weight: u64,
-    pub dynamic_b_weight: u64,
-    pub dynamic_weight_delta: i64,
-    pub shared_count: usize,
-    pub only_in_a: Vec<DiffPackage>,
-    pub only_in_b: Vec<DiffPackage>,
-    pub dynamic_only_in_a: Vec<DiffPackage>,
-    pub dynamic_only_in_b: Vec<DiffPackage>,
+/// Every third-party package reachable from `entry`, classified by how it is
+/// reached and sorted by total size descending.
+#[must_use]
+pub fn package_reach(graph: &ModuleGraph, entry: ModuleId) -> Vec<PackageReach> {
+    let reach_set = |kinds: &[EdgeKind]| {
+        let mut seen = vec![false; graph.modules.len()];
+        let mut queue = VecDeque::from([entry]);
+        seen[entry.0 as usize] = true;
+        while let Some(mid) = queue.pop_front() {
+            for &eid in graph.outgoing_edges(mid) {
+                let edge = graph.edge(eid);
+                if kinds.contains(&edge.kind) && !seen[edge.to.0 as usize] {
+                    seen[edge.to.0 as usize] = true;
+                    queue.push_back(edge.to);
+                }
+            }
+        }
+        seen
+    };
+    let levels = [
+        (EdgeKind::Static, reach_set(&[EdgeKind::Static])),
+        (
+            EdgeKind::Dynamic,
+            reach_set(&[EdgeKind::Static, EdgeKind::Dynamic]),
+        ),
+        (
+            EdgeKind::TypeOnly,
+            reach_set(&[EdgeKind::Static, EdgeKind::Dynamic, EdgeKind::TypeOnly]),
+        ),
+    ];
+
+    let mut by_name: HashMap<&str, PackageReach> = HashMap::new();
+    for (kind, seen) in &levels {
+        for module in &graph.modules {
+            let Some(name) = module.package.as_deref() else {
+                continue;
+            };
+            if !seen[module.id.0 as usize] {
+                continue;
+            }
+            let entry = by_name.entry(name).or_insert_with(|| PackageReach {
+                name: name.to_string(),
+                reach: *kind,
+                file_count: 0,
+                total_size: 0,
+            });
+            // Only count files at the package's strongest reach level
+            if entry.reach == *kind {
+                entry.file_count += 1;
+                entry.total_size += module.size_bytes;
+            }
+        }
+    }
+
+    let mut packages: Vec<PackageReach> = by_name.into_values().collect();
+    packages.sort_by(|a, b| {
+        b.total_size
+            .cmp(&a.total_size)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    packages
 }
 
+/// What removing every import of a package would save.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct PackageSavings {
+    pub name: String,
+    /// Size of the package's own reachable files
+    pub total_size: u64,
+    /// Everything that becomes unreachable once nothing imports the
+    /// package: its own files plus whatever only it pulls in
+    pub dominated_size: u64,
+}
+
+/// Every reachable third-party package with the weight it dominates, sorted
+/// by that weight descending and truncated to `top_n`. Each package is
+/// collapsed into a single node before computing the dominator tree, so
+/// files shared with the rest of the app (or with another package) are not
+/// counted, and a package imported through several of its own files still
+/// owns what only it reaches.
 #[must_use]
-#[allow(clippy::cast_possible_wrap)]
-pub fn diff_snapshots(a: &TraceSnapshot, b: &TraceSnapshot) -> DiffResult {
-    let keys_a: HashSet<&str> = a.packages.keys().map(String::as_str).collect();
-    let keys_b: HashSet<&str> = b.packages.keys().map(String::as_str).collect();
+#[allow(clippy::cast_sign_loss)]
+pub fn package_savings(
+    graph: &ModuleGraph,
+    entry: ModuleId,
+    include_dynamic: bool,
+    top_n: i32,
+) -> Vec<PackageSavings> {
+    let follows =
+        |kind: EdgeKind| kind == EdgeKind::Static || (include_dynamic && kind == EdgeKind::Dynamic);
+    let reachable = graph.reachable_set(&[entry], |e| follows(e.kind));
+
+    // One node per first-party module and one per package
+    let mut contracted = ModuleGraph::new();
+    let mut node = vec![ModuleId(u32::MAX); graph.modules.len()];
+    let mut package_nodes: HashMap<&str, ModuleId> = HashMap::new();
+    for module in graph.modules.iter().filter(|m| reachable[m.id.0 as usize]) {
+        let id = match module.package.as_deref() {
+            Some(name) if module.id != entry => *package_nodes
+                .entry(name)
+                .or_insert_with(|| contracted.add_module(module.path.clone(), 0, None)),
+            _ => contracted.add_module(module.path.clone(), 0, None),
+        };
+        contracted.modules[id.0 as usize].size_bytes += module.size_bytes;
+        node[module.id.0 as usize] = id;
+    }
+    for edge in &graph.edges {
+        let (from, to) = (node[edge.from.0 as usize], node[edge.to.0 as usize]);
+        if follows(edge.kind)
+            && reachable[edge.from.0 as usize]
+            && reachable[edge.to.0 as usize]
+            && from != to
+        {
+            contracted.add_edge(from, to, EdgeKind::Static, "");
+        }
+    }
 
-    let mut only_in_a: Vec<DiffPackage> = keys_a
-        .difference(&keys_b)
-        .map(|&name| DiffPackage {
+    let dominated = compute_exclusive_weights(&contracted, node[entry.0 as usize], false, None);
+    let mut savings: Vec<PackageSavings> = package_nodes
+        .into_iter()
+        .map(|(name, id)| PackageSavings {
             name: name.to_string(),
-            size: a.packages[name],
+            total_size: contracted.module(id).size_bytes,
+            dominated_size: dominated[id.0 as usize],
         })
         .collect();
-    only_in_a.sort_by(|x, y| y.size.cmp(&x.size));
+    savings.sort_by(|a, b| {
+        b.dominated_size
+            .cmp(&a.dominated_size)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    if top_n >= 0 {
+        savings.truncate(top_n as usize);
+    }
+    savings
+}
 
-    let mut only_in_b: Vec<DiffPackage> = keys_b
-        .difference(&keys_a)
-        .map(|&name| DiffPackage {
-            name: name.to_string(),
-            size: b.packages[name],
+/// One installed copy of a package: the reachable files under a single
+/// `node_modules/<name>` directory.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct PackageCopy {
+    pub dir: PathBuf,
+    pub file_count: u32,
+    pub total_size: u64,
+    /// The copy's file nearest to the entry (first in BFS order).
+    pub first_module: ModuleId,
+}
+
+/// A package installed more than once, e.g. two versions pulled in by
+/// different dependents.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct DuplicatePackage {
+    pub name: String,
+    /// Largest copy first.
+    pub copies: Vec<PackageCopy>,
+}
+
+/// Every package reachable from `entry` (over static edges, plus dynamic
+/// with `include_dynamic`) whose files come from more than one
+/// `node_modules/<name>` directory. Sorted by combined size, largest first.
+#[must_use]
+pub fn duplicate_packages(
+    graph: &ModuleGraph,
+    entry: ModuleId,
+    include_dynamic: bool,
+) -> Vec<DuplicatePackage> {
+    let mut seen = vec![false; graph.modules.len()];
+    seen[entry.0 as usize] = true;
+    let mut order = vec![entry];
+    let mut i = 0;
+    while let Some(&mid) = order.get(i) {
+        i += 1;
+        for &eid in graph.outgoing_edges(mid) {
+            let edge = graph.edge(eid);
+            let follows = edge.kind == EdgeKind::Static
+                || (include_dynamic && edge.kind == EdgeKind::Dynamic);
+            if follows && !seen[edge.to.0 as usize] {
+                seen[edge.to.0 as usize] = true;
+                order.push(edge.to);
+            }
+        }
+    }
+
+    let mut by_name: HashMap<&str, Vec<PackageCopy>> = HashMap::new();
+    for mid in order {
+        let module = graph.module(mid);
+        let Some(name) = module.package.as_deref() else {
+            continue;
+        };
+        let Some(dir) = package_dir(&module.path, name) else {
+            continue;
+        };
+        let copies = by_name.entry(name).or_default();
+        if !copies.iter().any(|c| c.dir == dir) {
+            copies.push(PackageCopy {
+                dir: dir.to_path_buf(),
+                file_count: 0,
+                total_size: 0,
+                first_module: mid,
+            });
+        }
+        let copy = copies.iter_mut().find(|c| c.dir == dir).unwrap();
+        copy.file_count += 1;
+        copy.total_size += module.size_bytes;
+    }
+
+    let mut duplicates: Vec<DuplicatePackage> = by_name
+        .into_iter()
+        .filter(|(_, copies)| copies.len() > 1)
+        .map(|(name, mut copies)| {
+            copies.sort_by(|a, b| {
+                b.total_size
+                    .cmp(&a.total_size)
+                    .then_with(|| a.dir.cmp(&b.dir))
+            });
+            DuplicatePackage {
+                name: name.to_string(),
+                copies,
+            }
         })
         .collect();
-    only_in_b.sort_by(|x, y| y.size.cmp(&x.size));
+    let combined = |d: &DuplicatePackage| d.copies.iter().map(|c| c.total_size).sum::<u64>();
+    duplicates.sort_by(|a, b| {
+        combined(b)
+            .cmp(&combined(a))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    duplicates
+}
 
-    let dyn_keys_a: HashSet<&str> = a.dynamic_packages.keys().map(String::as_str).collect();
-    let dyn_keys_b: HashSet<&str> = b.dynamic_packages.keys().map(String::as_str).collect();
+/// The `node_modules/<name>` directory holding `path`, nearest first, so a
+/// copy nested inside another package's `node_modules` is its own copy.
+fn package_dir<'a>(path: &'a Path, name: &str) -> Option<&'a Path> {
+    let depth = Path::new(name).components().count();
+    path.ancestors().find(|dir| {
+        dir.ends_with(name)
+            && dir
+                .ancestors()
+                .nth(depth)
+                .and_then(Path::file_name)
+                .is_some_and(|n| n == "node_modules")
+    })
+}
 
-    let mut dynamic_only_in_a: Vec<DiffPackage> = dyn_keys_a
-        .difference(&dyn_keys_b)
-        .map(|&name| DiffPackage {
-            name: name.to_string(),
-            size: a.dynamic_packages[name],
+/// An import of a barrel module, with how much it amplifies cost.
+#[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
+pub struct BarrelPenalty {
+    pub importer: ModuleId,
+    pub barrel: ModuleId,
+    /// Total size statically reachable from the barrel (barrel included)
+    pub barrel_weight: u64,
+    /// Approximate cost of the one module the importer wanted: the largest
+    /// single module behind the barrel
+    pub single_weight: u64,
+    /// `barrel_weight / single_weight`
+    pub amplification: f64,
+}
+
+/// Whether `mid` looks like a barrel: an `index.*` or `__init__.py` that
+/// statically imports at least [`MIN_BARREL_FANOUT`] modules.
+fn is_barrel(graph: &ModuleGraph, mid: ModuleId) -> bool {
+    let is_index = graph
+        .module(mid)
+        .path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .is_some_and(|stem| stem == "index" || stem == "__init__");
+    is_index
+        && graph
+            .outgoing_edges(mid)
+            .iter()
+            .filter(|&&eid| graph.edge(eid).kind == EdgeKind::Static)
+            .count()
+            >= MIN_BARREL_FANOUT
+}
+
+/// Find static imports of barrel modules reachable from `entry` and measure
+/// how much each one amplifies cost: the whole barrel subtree is loaded even
+/// when only one symbol is wanted. Sorted by amplification descending.
+#[must_use]
+#[allow(clippy::cast_precision_loss, clippy::cast_sign_loss)]
+pub fn barrel_penalties(graph: &ModuleGraph, entry: ModuleId, top_n: i32) -> Vec<BarrelPenalty> {
+    let reachable = bfs_reachable(graph, entry, None, None).static_set;
+    let mut subtree_cache: HashMap<ModuleId, (u64, u64)> = HashMap::new();
+    let mut penalties = Vec::new();
+
+    for &importer in &reachable {
+        for &edge_id in graph.outgoing_edges(importer) {
+            let edge = graph.edge(edge_id);
+            if edge.kind != EdgeKind::Static || !is_barrel(graph, edge.to) {
+                continue;
+            }
+            let (barrel_weight, single_weight) = *subtree_cache
+                .entry(edge.to)
+                .or_insert_with(|| barrel_subtree(graph, edge.to));
+            if single_weight == 0 {
+                continue;
+            }
+            penalties.push(BarrelPenalty {
+                importer,
+                barrel: edge.to,
+                barrel_weight,
+                single_weight,
+                amplification: barrel_weight as f64 / single_weight as f64,
+            });
+        }
+    }
+
+    penalties.sort_by(|a, b| b.amplification.total_cmp(&a.amplification));
+    if top_n >= 0 {
+        penalties.truncate(top_n as usize);
+    }
+    penalties
+}
+
+/// Total static weight reachable from `barrel`, and the size of the largest
+/// single module in that subtree (excluding the barrel itself).
+fn barrel_subtree(graph: &ModuleGraph, barrel: ModuleId) -> (u64, u64) {
+    let reachable = bfs_reachable(graph, barrel, None, None).static_set;
+    let total = reachable
+        .iter()
+        .map(|&mid| graph.module(mid).size_bytes)
+        .sum();
+    let largest = reachable
+        .iter()
+        .filter(|&&mid| mid != barrel)
+        .map(|&mid| graph.module(mid).size_bytes)
+        .max()
+        .unwrap_or(0);
+    (total, largest)
+}
+
+/// A module with how many others would be affected by changing it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct DependentCount {
+    pub module: ModuleId,
+    /// Reachable modules that import this one directly or transitively.
+    pub dependents: usize,
+    /// Reachable modules that import this one directly.
+    pub direct_importers: usize,
+}
+
+/// Modules outside `package` that are reachable both from the package's
+/// modules and from the entry's own code without going through any
+/// package: the points where app code and a dependency are coupled. Only
+/// the package's modules loaded from `entry` count. `None` when the entry
+/// does not load the package. Sorted by size, largest first.
+#[must_use]
+pub fn shared_with_package(
+    graph: &ModuleGraph,
+    entry: ModuleId,
+    package: &str,
+    include_dynamic: bool,
+) -> Option<Vec<ModuleId>> {
+    let follows =
+        |e: &Edge| e.kind == EdgeKind::Static || (include_dynamic && e.kind == EdgeKind::Dynamic);
+    let reachable = graph.reachable_set(&[entry], follows);
+    let in_package = |m: &Module| m.package.as_deref() == Some(package);
+    let roots: Vec<ModuleId> = graph
+        .modules
+        .iter()
+        .filter(|m| reachable[m.id.0 as usize] && in_package(m))
+        .map(|m| m.id)
+        .collect();
+    if roots.is_empty() {
+        return None;
+    }
+    let from_package = graph.reachable_set(&roots, follows);
+    // Package modules are leaves here, so this is what local code reaches
+    let from_local = graph.reachable_set(&[entry], |e| {
+        follows(e) && graph.module(e.from).package.is_none()
+    });
+    let mut shared: Vec<ModuleId> = graph
+        .modules
+        .iter()
+        .filter(|m| {
+            let i = m.id.0 as usize;
+            from_package[i] && from_local[i] && !in_package(m) && m.id != entry
         })
+        .map(|m| m.id)
         .collect();
-    dynamic_only_in_a.sort_by(|x, y| y.size.cmp(&x.size));
+    shared.sort_by(|a, b| {
+        let (a, b) = (graph.module(*a), graph.module(*b));
+        b.size_bytes
+            .cmp(&a.size_bytes)
+            .then_with(|| a.path.cmp(&b.path))
+    });
+    Some(shared)
+}
 
-    let mut dynamic_only_in_b: Vec<DiffPackage> = dyn_keys_b
-        .difference(&dyn_keys_a)
-        .map(|&name| DiffPackage {
-            name: name.to_string(),
-            size: b.dynamic_packages[name],
+/// Modules that import `target` over any kind of edge: its direct importers,
+/// or with `transitive` every module with an import path to it. The target
+/// itself is excluded even when it sits on a cycle. `reverse_adj` holds each
+/// module's incoming edges, indexed by `ModuleId`. Sorted by path.
+#[must_use]
+pub fn find_dependents(
+    graph: &ModuleGraph,
+    reverse_adj: &[Vec<EdgeId>],
+    target: ModuleId,
+    transitive: bool,
+) -> Vec<ModuleId> {
+    let mut seen = vec![false; graph.module_count()];
+    seen[target.0 as usize] = true;
+    let mut dependents = Vec::new();
+    let mut queue = VecDeque::from([target]);
+    while let Some(mid) = queue.pop_front() {
+        for &eid in &reverse_adj[mid.0 as usize] {
+            let from = graph.edge(eid).from;
+            if !seen[from.0 as usize] {
+                seen[from.0 as usize] = true;
+                dependents.push(from);
+                if transitive {
+                    queue.push_back(from);
+                }
+            }
+        }
+    }
+    dependents.sort_by(|a, b| graph.module(*a).path.cmp(&graph.module(*b).path));
+    dependents
+}
+
+/// Rank the modules reachable from `entry` by transitive dependent count,
+/// the change-risk counterpart to weight. Only reachable modules and the
+/// edges a trace follows (static, plus dynamic with `include_dynamic`) are
+/// counted. `reverse_adj` holds each module's incoming edges, indexed by
+/// `ModuleId`. Ties go to more direct importers.
+#[must_use]
+#[allow(clippy::cast_sign_loss)]
+pub fn most_depended_on(
+    graph: &ModuleGraph,
+    reverse_adj: &[Vec<EdgeId>],
+    entry: ModuleId,
+    include_dynamic: bool,
+    top_n: i32,
+) -> Vec<DependentCount> {
+    let bfs = bfs_reachable(graph, entry, None, None);
+    let mut reachable = vec![false; graph.module_count()];
+    let mut members = bfs.static_set;
+    if include_dynamic {
+        members.extend(bfs.dynamic_set);
+    }
+    for &mid in &members {
+        reachable[mid.0 as usize] = true;
+    }
+    let follows =
+        |kind: EdgeKind| kind == EdgeKind::Static || (include_dynamic && kind == EdgeKind::Dynamic);
+
+    // Visit stamps, reused across the per-module reverse walks
+    let mut seen = vec![u32::MAX; graph.module_count()];
+    let mut queue: VecDeque<ModuleId> = VecDeque::new();
+    let mut counts: Vec<DependentCount> = Vec::with_capacity(members.len());
+    for (stamp, &target) in (0u32..).zip(&members) {
+        seen[target.0 as usize] = stamp;
+        queue.push_back(target);
+        let mut dependents = 0;
+        while let Some(mid) = queue.pop_front() {
+            for &eid in &reverse_adj[mid.0 as usize] {
+                let edge = graph.edge(eid);
+                let from = edge.from.0 as usize;
+                if reachable[from] && seen[from] != stamp && follows(edge.kind) {
+                    seen[from] = stamp;
+                    dependents += 1;
+                    queue.push_back(edge.from);
+                }
+            }
+        }
+        let direct: HashSet<ModuleId> = reverse_adj[target.0 as usize]
+            .iter()
+            .map(|&eid| graph.edge(eid))
+            .filter(|e| e.from != target && reachable[e.from.0 as usize] && follows(e.kind))
+            .map(|e| e.from)
+            .collect();
+        counts.push(DependentCount {
+            module: target,
+            dependents,
+            direct_importers: direct.len(),
+        });
+    }
+
+    counts.sort_by(|a, b| {
+        b.dependents
+            .cmp(&a.dependents)
+            .then(b.direct_importers.cmp(&a.direct_importers))
+            .then_with(|| {
+                graph
+                    .module(a.module)
+                    .path
+                    .cmp(&graph.module(b.module).path)
+            })
+    });
+    if top_n >= 0 {
+        counts.truncate(top_n as usize);
+    }
+    counts
+}
+
+/// Minimal snapshot of a trace result for before/after comparison.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct TraceSnapshot {
+    pub entry: String,
+    pub static_weight: u64,
+    pub packages: HashMap<String, u64>,
+    #[serde(default)]
+    pub dynamic_weight: u64,
+    #[serde(default)]
+    pub dynamic_packages: HashMap<String, u64>,
+    /// Static module sizes keyed by path relative to the project root.
+    /// Filled by [`TraceSnapshot::with_modules`]; empty in older snapshots.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub modules: HashMap<String, u64>,
+    /// Dynamic import edges among reachable modules, sorted. Filled by
+    /// [`TraceSnapshot::with_modules`]; empty in older snapshots.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dynamic_imports: Vec<DynamicImport>,
+}
+
+/// A dynamic import edge, as paths relative to the project root.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct DynamicImport {
+    pub from: String,
+    pub to: String,
+}
+
+impl TraceSnapshot {
+    /// Record every statically reachable module and every reachable dynamic
+    /// import, for module-level diffs.
+    #[must_use]
+    pub fn with_modules(mut self, graph: &ModuleGraph, entry: ModuleId, root: &Path) -> Self {
+        self.dynamic_imports = dynamic_imports(graph, entry, root);
+        let reachable = graph.reachable_set(&[entry], |e| e.kind == EdgeKind::Static);
+        self.modules = graph
+            .modules
+            .iter()
+            .filter(|m| reachable[m.id.0 as usize])
+            .map(|m| {
+                let rel = m.path.strip_prefix(root).unwrap_or(&m.path);
+                (rel.to_string_lossy().into_owned(), m.size_bytes)
+            })
+            .collect();
+        self
+    }
+}
+
+impl TraceResult {
+    pub fn to_snapshot(&self, entry: &str) -> TraceSnapshot {
+        TraceSnapshot {
+            entry: entry.to_string(),
+            static_weight: self.static_weight,
+            packages: self.all_packages.clone(),
+            dynamic_weight: self.dynamic_only_weight,
+            dynamic_packages: self.dynamic_packages.clone(),
+            modules: HashMap::new(),
+            dynamic_imports: Vec::new(),
+        }
+    }
+}
+
+/// Every dynamic import edge whose importer is reachable from `entry`
+/// (through static or dynamic edges), sorted and deduplicated.
+pub fn dynamic_imports(graph: &ModuleGraph, entry: ModuleId, root: &Path) -> Vec<DynamicImport> {
+    let reachable = graph.reachable_set(&[entry], |e| {
+        matches!(e.kind, EdgeKind::Static | EdgeKind::Dynamic)
+    });
+    let rel = |id: ModuleId| {
+        let path = &graph.module(id).path;
+        path.strip_prefix(root)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .into_owned()
+    };
+    let imports: BTreeSet<DynamicImport> = graph
+        .edges
+        .iter()
+        .filter(|e| e.kind == EdgeKind::Dynamic && reachable[e.from.0 as usize])
+        .map(|e| DynamicImport {
+            from: rel(e.from),
+            to: rel(e.to),
         })
         .collect();
-    dynamic_only_in_b.sort_by(|x, y| y.size.cmp(&x.size));
+    imports.into_iter().collect()
+}
+
+/// Dynamic imports added and removed relative to a baseline snapshot.
+/// A removed edge was either dropped or turned into a static import.
+#[derive(Debug, Default)]
+#[non_exhaustive]
+pub struct DynamicImportDiff {
+    pub added: Vec<DynamicImport>,
+    pub removed: Vec<DynamicImport>,
+}
+
+#[must_use]
+pub fn diff_dynamic_imports(
+    baseline: &[DynamicImport],
+    current: &[DynamicImport],
+) -> DynamicImportDiff {
+    let before: BTreeSet<&DynamicImport> = baseline.iter().collect();
+    let after: BTreeSet<&DynamicImport> = current.iter().collect();
+    DynamicImportDiff {
+        added: after.difference(&before).map(|&d| d.clone()).collect(),
+        removed: before.difference(&after).map(|&d| d.clone()).collect(),
+    }
+}
+
+/// A package (or, for module-level diffs, a module path) that appears in
+/// only one side of a diff, with its size.
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct DiffPackage {
+    pub name: String,
+    pub size: u64,
+}
+
+/// Compute a diff between two trace snapshots.
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct DiffResult {
+    pub entry_a_weight: u64,
+    pub entry_b_weight: u64,
+    pub weight_delta: i64,
+    pub dynamic_a_weight: u64,
+    pub dynamic_b_weight: u64,
+    pub dynamic_weight_delta: i64,
+    pub shared_count: usize,
+    pub only_in_a: Vec<DiffPackage>,
+    pub only_in_b: Vec<DiffPackage>,
+    pub dynamic_only_in_a: Vec<DiffPackage>,
+    pub dynamic_only_in_b: Vec<DiffPackage>,
+    /// Modules removed and added; empty unless both snapshots list modules.
+    pub modules_only_in_a: Vec<DiffPackage>,
+    pub modules_only_in_b: Vec<DiffPackage>,
+}
+
+#[must_use]
+#[allow(clippy::cast_possible_wrap)]
+pub fn diff_snapshots(a: &TraceSnapshot, b: &TraceSnapshot) -> DiffResult {
+    let shared_count = a
+        .packages
+        .keys()
+        .filter(|k| b.packages.contains_key(*k))
+        .count();
+    // A snapshot saved without a module list would make every module of
+    // the other side look added or removed.
+    let has_modules = !a.modules.is_empty() && !b.modules.is_empty();
 
     DiffResult {
         entry_a_weight: a.static_weight,
@@ -778,14 +2286,38 @@ pub fn diff_snapshots(a: &TraceSnapshot, b: &TraceSnapshot) -> DiffResult {
         dynamic_a_weight: a.dynamic_weight,
         dynamic_b_weight: b.dynamic_weight,
         dynamic_weight_delta: b.dynamic_weight as i64 - a.dynamic_weight as i64,
-        shared_count: keys_a.intersection(&keys_b).count(),
-        only_in_a,
-        only_in_b,
-        dynamic_only_in_a,
-        dynamic_only_in_b,
+        shared_count,
+        only_in_a: only_in(&a.packages, &b.packages),
+        only_in_b: only_in(&b.packages, &a.packages),
+        dynamic_only_in_a: only_in(&a.dynamic_packages, &b.dynamic_packages),
+        dynamic_only_in_b: only_in(&b.dynamic_packages, &a.dynamic_packages),
+        modules_only_in_a: if has_modules {
+            only_in(&a.modules, &b.modules)
+        } else {
+            Vec::new()
+        },
+        modules_only_in_b: if has_modules {
+            only_in(&b.modules, &a.modules)
+        } else {
+            Vec::new()
+        },
     }
 }
 
+/// Entries of `a` missing from `b`, largest first.
+fn only_in(a: &HashMap<String, u64>, b: &HashMap<String, u64>) -> Vec<DiffPackage> {
+    let mut only: Vec<DiffPackage> = a
+        .iter()
+        .filter(|(name, _)| !b.contains_key(*name))
+        .map(|(name, &size)| DiffPackage {
+            name: name.clone(),
+            size,
+        })
+        .collect();
+    only.sort_by(|x, y| y.size.cmp(&x.size).then_with(|| x.name.cmp(&y.name)));
+    only
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -845,6 +2377,324 @@ mod tests {
         assert_eq!(result.dynamic_only_module_count, 1);
     }
 
+    #[test]
+    fn trace_counts_lazy_load_boundaries() {
+        // entry(10) -static-> app(20); entry -dynamic-> page(100) -static-> chart(500)
+        // app -dynamic-> page again, app -dynamic-> app (already eager, not a boundary)
+        // page -dynamic-> modal(1000): nested, behind a lazy chunk
+        let graph = make_graph(
+            &[
+                ("entry.ts", 10, None),
+                ("app.ts", 20, None),
+                ("page.ts", 100, None),
+                ("chart.ts", 500, None),
+                ("modal.ts", 1000, None),
+            ],
+            &[
+                (0, 1, EdgeKind::Static),
+                (0, 2, EdgeKind::Dynamic),
+                (1, 2, EdgeKind::Dynamic),
+                (0, 1, EdgeKind::Dynamic),
+                (2, 3, EdgeKind::Static),
+                (2, 4, EdgeKind::Dynamic),
+            ],
+        );
+        let result = trace(&graph, ModuleId(0), &TraceOptions::default());
+        assert_eq!(result.lazy_boundary_count, 2);
+        assert_eq!(
+            result.lazy_boundary_weight, 600,
+            "page + chart, counted once"
+        );
+        assert_eq!(result.dynamic_only_weight, 1600);
+    }
+
+    #[test]
+    fn dynamic_only_excludes_the_static_startup_set() {
+        // entry -static-> app -static-> shared; entry -dynamic-> page
+        // page -static-> shared (already loaded) and chart
+        let graph = make_graph(
+            &[
+                ("entry.ts", 10, None),
+                ("app.ts", 20, None),
+                ("shared.ts", 40, None),
+                ("page.ts", 100, None),
+                ("chart.ts", 500, None),
+            ],
+            &[
+                (0, 1, EdgeKind::Static),
+                (1, 2, EdgeKind::Static),
+                (0, 3, EdgeKind::Dynamic),
+                (1, 3, EdgeKind::Dynamic),
+                (3, 2, EdgeKind::Static),
+                (3, 4, EdgeKind::Static),
+            ],
+        );
+        let result = dynamic_only(&graph, ModuleId(0), &TraceOptions::default());
+        assert_eq!(result.modules, [ModuleId(4), ModuleId(3)]);
+        assert_eq!(result.weight, 600, "shared.ts is loaded at startup");
+        let boundaries: Vec<_> = result
+            .boundaries
+            .iter()
+            .map(|b| (b.importer, b.target, b.weight, b.module_count))
+            .collect();
+        assert_eq!(
+            boundaries,
+            [
+                (ModuleId(0), ModuleId(3), 600, 2),
+                (ModuleId(1), ModuleId(3), 600, 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn package_savings_count_what_only_the_package_reaches() {
+        // entry -> app -> big/a, entry -> util -> big/b; both big files
+        // import helper/x; big/a and entry both import shared/s
+        let graph = make_graph(
+            &[
+                ("entry.ts", 10, None),
+                ("app.ts", 20, None),
+                ("util.ts", 30, None),
+                ("node_modules/big/a.js", 100, Some("big")),
+                ("node_modules/big/b.js", 100, Some("big")),
+                ("node_modules/helper/x.js", 500, Some("helper")),
+                ("node_modules/shared/s.js", 50, Some("shared")),
+            ],
+            &[
+                (0, 1, EdgeKind::Static),
+                (0, 2, EdgeKind::Static),
+                (1, 3, EdgeKind::Static),
+                (2, 4, EdgeKind::Static),
+                (3, 5, EdgeKind::Static),
+                (4, 5, EdgeKind::Static),
+                (3, 6, EdgeKind::Static),
+                (0, 6, EdgeKind::Static),
+            ],
+        );
+        // Per module, helper/x is reached through two big files, so only
+        // the entry dominates it
+        let idom = dominators(&graph, ModuleId(0));
+        assert_eq!(idom[0], None);
+        assert_eq!(idom[3], Some(ModuleId(1)));
+        assert_eq!(idom[5], Some(ModuleId(0)));
+
+        let savings = package_savings(&graph, ModuleId(0), false, -1);
+        let rows: Vec<(&str, u64, u64)> = savings
+            .iter()
+            .map(|p| (p.name.as_str(), p.total_size, p.dominated_size))
+            .collect();
+        assert_eq!(
+            rows,
+            [("big", 200, 700), ("helper", 500, 500), ("shared", 50, 50)]
+        );
+        assert_eq!(package_savings(&graph, ModuleId(0), false, 1).len(), 1);
+    }
+
+    #[test]
+    fn type_only_modules_excluded_from_static_count_by_default() {
+        // entry(10) -static-> app(20) -type-> types(300) -static-> zod-types(400)
+        // entry -type-> app again: already reached at runtime, not type-only
+        let graph = make_graph(
+            &[
+                ("entry.ts", 10, None),
+                ("app.ts", 20, None),
+                ("types.ts", 300, None),
+                ("node_modules/zod/index.d.ts", 400, Some("zod")),
+            ],
+            &[
+                (0, 1, EdgeKind::Static),
+                (0, 1, EdgeKind::TypeOnly),
+                (1, 2, EdgeKind::TypeOnly),
+                (2, 3, EdgeKind::Static),
+            ],
+        );
+        let result = trace(&graph, ModuleId(0), &TraceOptions::default());
+        assert_eq!(result.static_module_count, 2);
+        assert_eq!(result.static_weight, 30);
+        assert_eq!(result.type_only_module_count, 2);
+        assert_eq!(result.type_only_weight, 700);
+        assert!(result.all_packages.is_empty());
+        let counts = trace_counts(&graph, ModuleId(0), &TraceOptions::default());
+        assert_eq!(
+            (counts.static_module_count, counts.type_only_module_count),
+            (2, 2)
+        );
+
+        let opts = TraceOptions {
+            count_type_only: true,
+            ..TraceOptions::default()
+        };
+        let result = trace(&graph, ModuleId(0), &opts);
+        assert_eq!(result.static_module_count, 4);
+        assert_eq!(result.static_weight, 730);
+        assert_eq!(result.type_only_module_count, 0);
+        assert!(result.all_packages.contains_key("zod"));
+        let counts = trace_counts(&graph, ModuleId(0), &opts);
+        assert_eq!(counts.static_weight, 730);
+        assert_eq!(counts.package_count, 1);
+    }
+
+    #[test]
+    fn trace_without_drops_exclusive_subtree() {
+        // entry(10) -> chart(100) -> d3(400), entry -> util(20), chart -> util
+        let graph = make_graph(
+            &[
+                ("entry.ts", 10, None),
+                ("chart.ts", 100, None),
+                ("d3.js", 400, None),
+                ("util.ts", 20, None),
+            ],
+            &[
+                (0, 1, EdgeKind::Static),
+                (1, 2, EdgeKind::Static),
+                (0, 3, EdgeKind::Static),
+                (1, 3, EdgeKind::Static),
+            ],
+        );
+        let before = trace(&graph, ModuleId(0), &TraceOptions::default());
+        let after = trace_without(&graph, ModuleId(0), ModuleId(1));
+        let exclusive = compute_exclusive_weights(&graph, ModuleId(0), false, None);
+
+        assert_eq!(before.static_weight - after.static_weight, exclusive[1]);
+        assert_eq!(
+            after.static_weight, 30,
+            "util stays: entry imports it directly"
+        );
+        assert_eq!(after.static_module_count, 2);
+    }
+
+    #[test]
+    fn trace_assets_not_counted_as_code_weight() {
+        // A(100) -static-> B(200), A -asset-> styles.css(5000), B -asset-> logo.svg(800)
+        let graph = make_graph(
+            &[
+                ("a.ts", 100, None),
+                ("b.ts", 200, None),
+                ("styles.css", 5000, None),
+                ("logo.svg", 800, None),
+            ],
+            &[
+                (0, 1, EdgeKind::Static),
+                (0, 2, EdgeKind::Asset),
+                (1, 3, EdgeKind::Asset),
+            ],
+        );
+        let result = trace(&graph, ModuleId(0), &TraceOptions::default());
+        assert_eq!(result.static_weight, 300);
+        assert_eq!(result.static_module_count, 2);
+        assert_eq!(result.asset_weight, 5800);
+        assert_eq!(result.assets, vec![ModuleId(2), ModuleId(3)]);
+        assert!(
+            result
+                .modules_by_cost
+                .iter()
+                .all(|mc| mc.module_id != ModuleId(2))
+        );
+    }
+
+    #[test]
+    fn trace_counts_match_full_trace() {
+        // A(100) -> B(200, zod) -> C(300, zod), A -dynamic-> D(400, react)
+        let graph = make_graph(
+            &[
+                ("a.ts", 100, None),
+                ("b.ts", 200, Some("zod")),
+                ("c.ts", 300, Some("zod")),
+                ("d.ts", 400, Some("react")),
+            ],
+            &[
+                (0, 1, EdgeKind::Static),
+                (1, 2, EdgeKind::Static),
+                (0, 3, EdgeKind::Dynamic),
+            ],
+        );
+        for include_dynamic in [false, true] {
+            let opts = TraceOptions {
+                include_dynamic,
+                ..TraceOptions::default()
+            };
+            let full = trace(&graph, ModuleId(0), &opts);
+            let counts = trace_counts(&graph, ModuleId(0), &opts);
+            assert_eq!(counts.static_weight, full.static_weight);
+            assert_eq!(counts.static_module_count, full.static_module_count);
+            assert_eq!(counts.dynamic_only_weight, full.dynamic_only_weight);
+            assert_eq!(
+                counts.dynamic_only_module_count,
+                full.dynamic_only_module_count
+            );
+            assert_eq!(counts.package_count, full.all_packages.len());
+        }
+    }
+
+    #[test]
+    fn trace_dynamic_depth_limits_dynamic_levels() {
+        // A -dynamic-> B -static-> C -dynamic-> D
+        let graph = make_graph(
+            &[
+                ("a.ts", 100, None),
+                ("b.ts", 200, None),
+                ("c.ts", 300, None),
+                ("d.ts", 400, None),
+            ],
+            &[
+                (0, 1, EdgeKind::Dynamic),
+                (1, 2, EdgeKind::Static),
+                (2, 3, EdgeKind::Dynamic),
+            ],
+        );
+        let opts = TraceOptions {
+            include_dynamic: true,
+            dynamic_depth: Some(1),
+            ..TraceOptions::default()
+        };
+        let result = trace(&graph, ModuleId(0), &opts);
+        // B and its static child C are in; the second-level dynamic D is not
+        assert_eq!(result.static_weight, 600);
+        assert_eq!(result.static_module_count, 3);
+        assert!(
+            result
+                .modules_by_cost
+                .iter()
+                .all(|mc| mc.module_id != ModuleId(3))
+        );
+        let b = result
+            .modules_by_cost
+            .iter()
+            .find(|mc| mc.module_id == ModuleId(1))
+            .unwrap();
+        assert_eq!(b.exclusive_size, 500, "D must not count toward B's subtree");
+
+        let unbounded = trace(
+            &graph,
+            ModuleId(0),
+            &TraceOptions {
+                include_dynamic: true,
+                ..TraceOptions::default()
+            },
+        );
+        assert_eq!(unbounded.static_weight, 1000);
+    }
+
+    #[test]
+    fn trace_dynamic_depth_uses_shallowest_path() {
+        // A -dynamic-> B -dynamic-> C, and A -dynamic-> C directly
+        let graph = make_graph(
+            &[("a.ts", 1, None), ("b.ts", 10, None), ("c.ts", 100, None)],
+            &[
+                (0, 1, EdgeKind::Dynamic),
+                (1, 2, EdgeKind::Dynamic),
+                (0, 2, EdgeKind::Dynamic),
+            ],
+        );
+        let opts = TraceOptions {
+            dynamic_depth: Some(1),
+            ..TraceOptions::default()
+        };
+        let result = trace(&graph, ModuleId(0), &opts);
+        assert_eq!(result.dynamic_only_weight, 110);
+        assert_eq!(result.dynamic_only_module_count, 2);
+    }
+
     #[test]
     fn trace_include_dynamic() {
         // A(100) -dynamic-> B(200)
@@ -856,28 +2706,149 @@ mod tests {
             include_dynamic: true,
             top_n: 10,
             ignore: Vec::new(),
+            dynamic_depth: None,
+            exclude: None,
+            count_type_only: false,
+            max_package_depth: None,
+        };
+        let result = trace(&graph, ModuleId(0), &opts);
+        // B should appear in modules_by_cost when include_dynamic is set
+        assert!(
+            result
+                .modules_by_cost
+                .iter()
+                .any(|m| m.module_id == ModuleId(1))
+        );
+    }
+
+    // --- Chain finding ---
+
+    #[test]
+    fn chain_linear_path() {
+        // A -> B -> C -> zod
+        let graph = make_graph(
+            &[
+                ("a.ts", 100, None),
+                ("b.ts", 100, None),
+                ("c.ts", 100, None),
+                ("node_modules/zod/index.js", 500, Some("zod")),
+            ],
+            &[
+                (0, 1, EdgeKind::Static),
+                (1, 2, EdgeKind::Static),
+                (2, 3, EdgeKind::Static),
+            ],
+        );
+        let chains = find_all_chains(
+            &graph,
+            ModuleId(0),
+            &ChainTarget::Package("zod".to_string()),
+            false,
+        );
+        assert_eq!(chains.len(), 1);
+        assert_eq!(
+            chains[0],
+            vec![ModuleId(0), ModuleId(1), ModuleId(2), ModuleId(3)]
+        );
+    }
+
+    #[test]
+    fn chain_dedup_same_package_path() {
+        // Two paths to zod that differ only by internal zod file:
+        // A -> B -> zod/index.js
+        // A -> B -> zod/lib.js
+        // These should dedup to one chain at the package level.
+        let graph = make_graph(
+            &[
+                ("a.ts", 100, None),
+                ("b.ts", 100, None),
+                ("node_modules/zod/index.js", 250, Some("zod")),
+                ("node_modules/zod/lib.js", 250, Some("zod")),
+            ],
+            &[
+                (0, 1, EdgeKind::Static),
+                (1, 2, EdgeKind::Static),
+                (1, 3, EdgeKind::Static),
+            ],
+        );
+        let chains = find_all_chains(
+            &graph,
+            ModuleId(0),
+            &ChainTarget::Package("zod".to_string()),
+            false,
+        );
+        assert_eq!(chains.len(), 1);
+    }
+
+    #[test]
+    fn import_depths_are_shortest_hops() {
+        // A -> B -> C, A -> C (dynamic), C -> D
+        let graph = make_graph(
+            &[
+                ("a.ts", 100, None),
+                ("b.ts", 100, None),
+                ("c.ts", 100, None),
+                ("d.ts", 100, None),
+            ],
+            &[
+                (0, 1, EdgeKind::Static),
+                (1, 2, EdgeKind::Static),
+                (0, 2, EdgeKind::Dynamic),
+                (2, 3, EdgeKind::Static),
+            ],
+        );
+        assert_eq!(import_depths(&graph, ModuleId(0), false), vec![0, 1, 2, 3]);
+        assert_eq!(import_depths(&graph, ModuleId(0), true), vec![0, 1, 1, 2]);
+        assert_eq!(
+            import_depths(&graph, ModuleId(1), false),
+            vec![u32::MAX, 0, 1, 2]
+        );
+    }
+
+    #[test]
+    fn max_package_depth_stops_at_direct_dependencies() {
+        // index -> a (dep) -> a/inner (same package) -> b (a's own dep)
+        let graph = make_graph(
+            &[
+                ("index.ts", 10, None),
+                ("node_modules/a/index.js", 100, Some("a")),
+                ("node_modules/a/inner.js", 100, Some("a")),
+                ("node_modules/b/index.js", 500, Some("b")),
+            ],
+            &[
+                (0, 1, EdgeKind::Static),
+                (1, 2, EdgeKind::Static),
+                (2, 3, EdgeKind::Static),
+            ],
+        );
+        assert_eq!(package_depths(&graph, ModuleId(0)), vec![0, 1, 1, 2]);
+
+        let opts = TraceOptions {
+            max_package_depth: Some(1),
+            ..Default::default()
         };
         let result = trace(&graph, ModuleId(0), &opts);
-        // B should appear in modules_by_cost when include_dynamic is set
-        assert!(
-            result
-                .modules_by_cost
-                .iter()
-                .any(|m| m.module_id == ModuleId(1))
-        );
-    }
+        assert_eq!(result.static_weight, 210);
+        assert!(!result.reachable.contains(&ModuleId(3)));
+        assert!(!result.all_packages.contains_key("b"));
+        assert_eq!(result.unexpanded_packages, vec!["b".to_string()]);
 
-    // --- Chain finding ---
+        let result = trace(&graph, ModuleId(0), &TraceOptions::default());
+        assert_eq!(result.static_weight, 710);
+        assert!(result.unexpanded_packages.is_empty());
+    }
 
     #[test]
-    fn chain_linear_path() {
-        // A -> B -> C -> zod
+    #[allow(clippy::float_cmp)]
+    fn sample_estimate_weights_deep_modules_up() {
+        // A -> B -> C -> D: B is always found, C only if B was parsed, D
+        // only if B and C were
         let graph = make_graph(
             &[
                 ("a.ts", 100, None),
                 ("b.ts", 100, None),
                 ("c.ts", 100, None),
-                ("node_modules/zod/index.js", 500, Some("zod")),
+                ("d.ts", 100, None),
             ],
             &[
                 (0, 1, EdgeKind::Static),
@@ -885,45 +2856,55 @@ mod tests {
                 (2, 3, EdgeKind::Static),
             ],
         );
-        let chains = find_all_chains(
-            &graph,
-            ModuleId(0),
-            &ChainTarget::Package("zod".to_string()),
-            false,
-        );
-        assert_eq!(chains.len(), 1);
-        assert_eq!(
-            chains[0],
-            vec![ModuleId(0), ModuleId(1), ModuleId(2), ModuleId(3)]
-        );
+        let all: Vec<ModuleId> = (0..4).map(ModuleId).collect();
+        let estimate = estimate_from_sample(&graph, ModuleId(0), &all, 0.5);
+        assert_eq!(estimate.weight, 100.0 + 100.0 + 200.0 + 400.0);
+        assert_eq!(estimate.module_count, 8.0);
+
+        let exact = estimate_from_sample(&graph, ModuleId(0), &all, 1.0);
+        assert_eq!(exact.weight, 400.0);
     }
 
     #[test]
-    fn chain_dedup_same_package_path() {
-        // Two paths to zod that differ only by internal zod file:
-        // A -> B -> zod/index.js
-        // A -> B -> zod/lib.js
-        // These should dedup to one chain at the package level.
+    fn chain_min_hops_skips_direct_import() {
+        // A -> zod (1 hop), A -> B -> C -> zod (3 hops)
         let graph = make_graph(
             &[
                 ("a.ts", 100, None),
                 ("b.ts", 100, None),
-                ("node_modules/zod/index.js", 250, Some("zod")),
-                ("node_modules/zod/lib.js", 250, Some("zod")),
+                ("c.ts", 100, None),
+                ("node_modules/zod/index.js", 500, Some("zod")),
             ],
             &[
+                (0, 3, EdgeKind::Static),
                 (0, 1, EdgeKind::Static),
                 (1, 2, EdgeKind::Static),
-                (1, 3, EdgeKind::Static),
+                (2, 3, EdgeKind::Static),
             ],
         );
-        let chains = find_all_chains(
-            &graph,
-            ModuleId(0),
-            &ChainTarget::Package("zod".to_string()),
-            false,
+        let zod = ChainTarget::Package("zod".to_string());
+        let shortest = find_all_chains(&graph, ModuleId(0), &zod, false);
+        assert_eq!(shortest, vec![vec![ModuleId(0), ModuleId(3)]]);
+
+        let long = find_chains_min_hops(&graph, ModuleId(0), &zod, false, 2);
+        assert_eq!(
+            long,
+            vec![vec![ModuleId(0), ModuleId(1), ModuleId(2), ModuleId(3)]]
         );
-        assert_eq!(chains.len(), 1);
+    }
+
+    #[test]
+    fn chain_min_hops_none_long_enough() {
+        // A -> zod only
+        let graph = make_graph(
+            &[
+                ("a.ts", 100, None),
+                ("node_modules/zod/index.js", 500, Some("zod")),
+            ],
+            &[(0, 1, EdgeKind::Static)],
+        );
+        let zod = ChainTarget::Package("zod".to_string());
+        assert!(find_chains_min_hops(&graph, ModuleId(0), &zod, false, 2).is_empty());
     }
 
     #[test]
@@ -1097,6 +3078,8 @@ mod tests {
                 .collect(),
             dynamic_weight: 0,
             dynamic_packages: HashMap::new(),
+            modules: HashMap::new(),
+            dynamic_imports: Vec::new(),
         }
     }
 
@@ -1119,9 +3102,82 @@ mod tests {
                 .iter()
                 .map(|(k, v)| ((*k).to_string(), *v))
                 .collect(),
+            modules: HashMap::new(),
+            dynamic_imports: Vec::new(),
         }
     }
 
+    #[test]
+    fn snapshot_modules_follow_static_edges_only() {
+        let graph = make_graph(
+            &[
+                ("/p/index.ts", 10, None),
+                ("/p/a.ts", 20, None),
+                ("/p/lazy.ts", 30, None),
+            ],
+            &[(0, 1, EdgeKind::Static), (0, 2, EdgeKind::Dynamic)],
+        );
+        let snapshot = snap("index.ts", 30, &[]).with_modules(&graph, ModuleId(0), Path::new("/p"));
+        let mut modules: Vec<_> = snapshot.modules.into_iter().collect();
+        modules.sort();
+        assert_eq!(
+            modules,
+            vec![("a.ts".to_string(), 20), ("index.ts".to_string(), 10)]
+        );
+    }
+
+    #[test]
+    fn dynamic_import_diff_reports_added_and_removed_edges() {
+        let nodes = [
+            ("/p/index.ts", 10, None),
+            ("/p/a.ts", 20, None),
+            ("/p/lazy.ts", 30, None),
+            ("/p/chart.ts", 40, None),
+        ];
+        let baseline = make_graph(
+            &nodes,
+            &[(0, 1, EdgeKind::Static), (0, 2, EdgeKind::Dynamic)],
+        );
+        // lazy.ts became a static import; a.ts gained a dynamic import
+        let current = make_graph(
+            &nodes,
+            &[
+                (0, 1, EdgeKind::Static),
+                (0, 2, EdgeKind::Static),
+                (1, 3, EdgeKind::Dynamic),
+            ],
+        );
+        let root = Path::new("/p");
+        let before = snap("index.ts", 30, &[]).with_modules(&baseline, ModuleId(0), root);
+        let after = dynamic_imports(&current, ModuleId(0), root);
+        let edge = |from: &str, to: &str| DynamicImport {
+            from: from.into(),
+            to: to.into(),
+        };
+        assert_eq!(before.dynamic_imports, vec![edge("index.ts", "lazy.ts")]);
+
+        let diff = diff_dynamic_imports(&before.dynamic_imports, &after);
+        assert_eq!(diff.added, vec![edge("a.ts", "chart.ts")]);
+        assert_eq!(diff.removed, vec![edge("index.ts", "lazy.ts")]);
+    }
+
+    #[test]
+    fn diff_snapshots_modules_need_both_sides() {
+        let mut a = snap("a.ts", 10, &[]);
+        a.modules = [("index.ts".to_string(), 10)].into_iter().collect();
+        let mut b = snap("b.ts", 30, &[]);
+        let diff = diff_snapshots(&a, &b);
+        assert!(diff.modules_only_in_a.is_empty() && diff.modules_only_in_b.is_empty());
+
+        b.modules = [("index.ts".to_string(), 10), ("new.ts".to_string(), 20)]
+            .into_iter()
+            .collect();
+        let diff = diff_snapshots(&a, &b);
+        assert!(diff.modules_only_in_a.is_empty());
+        assert_eq!(diff.modules_only_in_b.len(), 1);
+        assert_eq!(diff.modules_only_in_b[0].name, "new.ts");
+    }
+
     #[test]
     fn diff_snapshots_computes_sets() {
         let a = snap(
@@ -1189,7 +3245,7 @@ mod tests {
             ],
             &[(0, 1, EdgeKind::Static), (1, 2, EdgeKind::Static)],
         );
-        let weights = compute_exclusive_weights(&graph, ModuleId(0), false);
+        let weights = compute_exclusive_weights(&graph, ModuleId(0), false, None);
         assert_eq!(weights[0], 600); // entry: entire graph
         assert_eq!(weights[1], 500); // a: a + b
         assert_eq!(weights[2], 300); // b: just b
@@ -1214,7 +3270,7 @@ mod tests {
                 (2, 3, EdgeKind::Static),
             ],
         );
-        let weights = compute_exclusive_weights(&graph, ModuleId(0), false);
+        let weights = compute_exclusive_weights(&graph, ModuleId(0), false, None);
         assert_eq!(weights[0], 1100); // entry: everything
         assert_eq!(weights[1], 200); // a: only itself (D shared)
         assert_eq!(weights[2], 300); // b: only itself (D shared)
@@ -1242,7 +3298,7 @@ mod tests {
                 (1, 4, EdgeKind::Static),
             ],
         );
-        let weights = compute_exclusive_weights(&graph, ModuleId(0), false);
+        let weights = compute_exclusive_weights(&graph, ModuleId(0), false, None);
         assert_eq!(weights[0], 1700); // entry: everything
         assert_eq!(weights[1], 800); // a: a(200) + e(600), not d
         assert_eq!(weights[2], 300); // b: only itself
@@ -1271,17 +3327,104 @@ mod tests {
             ],
         );
         // Static only: B unreachable, C exclusively through A
-        let static_weights = compute_exclusive_weights(&graph, ModuleId(0), false);
+        let static_weights = compute_exclusive_weights(&graph, ModuleId(0), false, None);
         assert_eq!(static_weights[1], 600); // a: a(200) + c(400)
 
         // With dynamic: C shared between A and B
-        let all_weights = compute_exclusive_weights(&graph, ModuleId(0), true);
+        let all_weights = compute_exclusive_weights(&graph, ModuleId(0), true, None);
         assert_eq!(all_weights[1], 200); // a: only itself (c shared with b)
         assert_eq!(all_weights[2], 300); // b: only itself (c shared with a)
     }
 
     // --- Ignore filter ---
 
+    fn exclude_graph() -> ModuleGraph {
+        // entry -> lib.ts -> @aws-sdk/client -> tiny
+        //       -> app.ts
+        make_graph(
+            &[
+                ("entry.ts", 100, None),
+                ("lib.ts", 20, None),
+                (
+                    "node_modules/@aws-sdk/client/index.js",
+                    1000,
+                    Some("@aws-sdk/client"),
+                ),
+                ("node_modules/tiny/index.js", 10, Some("tiny")),
+                ("app.ts", 50, None),
+            ],
+            &[
+                (0, 1, EdgeKind::Static),
+                (1, 2, EdgeKind::Static),
+                (2, 3, EdgeKind::Static),
+                (0, 4, EdgeKind::Static),
+            ],
+        )
+    }
+
+    fn trace_excluding(graph: &ModuleGraph, patterns: &[&str], subtree: bool) -> TraceResult {
+        let patterns: Vec<String> = patterns.iter().map(|p| (*p).to_string()).collect();
+        let opts = TraceOptions {
+            exclude: Some(Exclude::new(&patterns, Path::new(""), subtree).unwrap()),
+            ..TraceOptions::default()
+        };
+        trace(graph, ModuleId(0), &opts)
+    }
+
+    #[test]
+    fn exclude_drops_matches_but_follows_their_imports() {
+        let graph = exclude_graph();
+        let result = trace_excluding(&graph, &["@aws-sdk/*"], false);
+        assert_eq!(result.static_weight, 100 + 20 + 10 + 50);
+        assert_eq!(result.static_module_count, 4);
+        let packages: Vec<&str> = result
+            .heavy_packages
+            .iter()
+            .map(|p| p.name.as_str())
+            .collect();
+        assert_eq!(packages, ["tiny"]);
+        // lib.ts still dominates `tiny`, but not the excluded SDK's bytes
+        let lib = result
+            .modules_by_cost
+            .iter()
+            .find(|m| m.module_id == ModuleId(1))
+            .unwrap();
+        assert_eq!(lib.exclusive_size, 20 + 10);
+
+        let counts = trace_counts(
+            &graph,
+            ModuleId(0),
+            &TraceOptions {
+                exclude: Some(
+                    Exclude::new(&["@aws-sdk/*".to_string()], Path::new(""), false).unwrap(),
+                ),
+                ..TraceOptions::default()
+            },
+        );
+        assert_eq!(counts.static_weight, result.static_weight);
+        assert_eq!(counts.package_count, 1);
+    }
+
+    #[test]
+    fn exclude_subtree_prunes_everything_behind_matches() {
+        let graph = exclude_graph();
+        let result = trace_excluding(&graph, &["node_modules/**"], true);
+        assert_eq!(result.static_weight, 100 + 20 + 50);
+        assert!(result.heavy_packages.is_empty());
+        let lib = result
+            .modules_by_cost
+            .iter()
+            .find(|m| m.module_id == ModuleId(1))
+            .unwrap();
+        assert_eq!(lib.exclusive_size, 20);
+    }
+
+    #[test]
+    fn exclude_rejects_invalid_glob() {
+        let err = Exclude::new(&["src/[".to_string()], Path::new(""), false).unwrap_err();
+        assert!(matches!(err, Error::InvalidExclude(_)), "{err}");
+    }
+
     #[test]
     fn trace_ignore_filters_heavy_packages() {
         let graph = make_graph(
@@ -1301,6 +3444,10 @@ mod tests {
             include_dynamic: false,
             top_n: 10,
             ignore: vec!["pkg-c".to_string()],
+            dynamic_depth: None,
+            exclude: None,
+            count_type_only: false,
+            max_package_depth: None,
         };
         let result = trace(&graph, ModuleId(0), &opts);
         let names: Vec<&str> = result
@@ -1323,6 +3470,10 @@ mod tests {
             include_dynamic: false,
             top_n: 10,
             ignore: vec!["big-pkg".to_string()],
+            dynamic_depth: None,
+            exclude: None,
+            count_type_only: false,
+            max_package_depth: None,
         };
         let result = trace(&graph, ModuleId(0), &opts);
         assert!(result.heavy_packages.is_empty());
@@ -1341,7 +3492,7 @@ mod tests {
             ],
             &[(0, 1, EdgeKind::Static), (1, 2, EdgeKind::Static)],
         );
-        let target_id = graph.path_to_id[&PathBuf::from("b.ts")];
+        let target_id = graph.module_id(&PathBuf::from("b.ts")).unwrap();
         let chains = find_all_chains(&graph, ModuleId(0), &ChainTarget::Module(target_id), false);
         assert_eq!(chains.len(), 1);
         assert_eq!(chains[0].len(), 3);
@@ -1358,14 +3509,14 @@ mod tests {
             ],
             &[(0, 1, EdgeKind::Static), (1, 2, EdgeKind::Static)],
         );
-        let target_id = graph.path_to_id[&PathBuf::from("target.ts")];
+        let target_id = graph.module_id(&PathBuf::from("target.ts")).unwrap();
         let target = ChainTarget::Module(target_id);
         let chains = find_all_chains(&graph, ModuleId(0), &target, false);
         let cuts = find_cut_modules(&graph, &chains, ModuleId(0), &target, 10, false);
         assert_eq!(cuts.len(), 1);
         assert_eq!(
             cuts[0].module_id,
-            graph.path_to_id[&PathBuf::from("bridge.ts")]
+            graph.module_id(&PathBuf::from("bridge.ts")).unwrap()
         );
     }
 
@@ -1407,4 +3558,304 @@ mod tests {
         let result = trace(&graph, ModuleId(0), &opts);
         assert_eq!(result.heavy_packages.len(), 0);
     }
+
+    // --- Barrel penalty ---
+
+    #[test]
+    fn barrel_penalty_flags_large_unused_subtree() {
+        // entry -> utils/index.ts (barrel) -> {small, big-a -> big-a-dep, big-b}
+        let graph = make_graph(
+            &[
+                ("src/main.ts", 100, None),
+                ("src/utils/index.ts", 50, None),
+                ("src/utils/small.ts", 1_000, None),
+                ("src/utils/big-a.ts", 10_000, None),
+                ("src/utils/big-a-dep.ts", 10_000, None),
+                ("src/utils/big-b.ts", 10_000, None),
+            ],
+            &[
+                (0, 1, EdgeKind::Static),
+                (1, 2, EdgeKind::Static),
+                (1, 3, EdgeKind::Static),
+                (3, 4, EdgeKind::Static),
+                (1, 5, EdgeKind::Static),
+            ],
+        );
+        let penalties = barrel_penalties(&graph, ModuleId(0), -1);
+        assert_eq!(penalties.len(), 1);
+        let p = penalties[0];
+        assert_eq!(p.importer, ModuleId(0));
+        assert_eq!(p.barrel, ModuleId(1));
+        assert_eq!(p.barrel_weight, 31_050);
+        assert_eq!(p.single_weight, 10_000);
+        assert!(p.amplification >= BARREL_PENALTY_THRESHOLD);
+    }
+
+    #[test]
+    fn barrel_penalty_ignores_small_fanout_and_non_index() {
+        // index.ts with only 2 imports, and a non-index module with 3
+        let graph = make_graph(
+            &[
+                ("main.ts", 1, None),
+                ("lib/index.ts", 1, None),
+                ("hub.ts", 1, None),
+                ("a.ts", 10, None),
+                ("b.ts", 10, None),
+                ("c.ts", 10, None),
+            ],
+            &[
+                (0, 1, EdgeKind::Static),
+                (0, 2, EdgeKind::Static),
+                (1, 3, EdgeKind::Static),
+                (1, 4, EdgeKind::Static),
+                (2, 3, EdgeKind::Static),
+                (2, 4, EdgeKind::Static),
+                (2, 5, EdgeKind::Static),
+            ],
+        );
+        assert!(barrel_penalties(&graph, ModuleId(0), -1).is_empty());
+    }
+
+    // --- Most depended-on ---
+
+    fn reverse_adj(graph: &ModuleGraph) -> Vec<Vec<EdgeId>> {
+        let mut rev = vec![Vec::new(); graph.module_count()];
+        for edge in &graph.edges {
+            rev[edge.to.0 as usize].push(edge.id);
+        }
+        rev
+    }
+
+    #[test]
+    fn most_depended_on_ranks_shared_utility_first() {
+        // entry -> {a, b, c}; a, b, c -> utils; a -> b;
+        // lazy -dyn-> utils is only counted with include_dynamic
+        let graph = make_graph(
+            &[
+                ("entry.ts", 1, None),
+                ("a.ts", 1, None),
+                ("b.ts", 1, None),
+                ("c.ts", 1, None),
+                ("utils.ts", 1, None),
+                ("lazy.ts", 1, None),
+            ],
+            &[
+                (0, 1, EdgeKind::Static),
+                (0, 2, EdgeKind::Static),
+                (0, 3, EdgeKind::Static),
+                (1, 4, EdgeKind::Static),
+                (2, 4, EdgeKind::Static),
+                (3, 4, EdgeKind::Static),
+                (1, 2, EdgeKind::Static),
+                (0, 5, EdgeKind::Dynamic),
+                (5, 4, EdgeKind::Dynamic),
+            ],
+        );
+        let rev = reverse_adj(&graph);
+
+        let ranked = most_depended_on(&graph, &rev, ModuleId(0), false, 2);
+        assert_eq!(ranked.len(), 2);
+        assert_eq!(ranked[0].module, ModuleId(4));
+        assert_eq!((ranked[0].dependents, ranked[0].direct_importers), (4, 3));
+        assert_eq!(ranked[1].module, ModuleId(2));
+        assert_eq!((ranked[1].dependents, ranked[1].direct_importers), (2, 2));
+
+        let ranked = most_depended_on(&graph, &rev, ModuleId(0), true, -1);
+        let utils = ranked.iter().find(|d| d.module == ModuleId(4)).unwrap();
+        assert_eq!((utils.dependents, utils.direct_importers), (5, 4));
+        assert_eq!(ranked.len(), 6);
+    }
+
+    #[test]
+    fn shared_with_package_reports_module_reached_from_both_sides() {
+        // entry -> util, entry -> vendor/index -> util (aliased local import),
+        // vendor/index -> vendor/lib; entry -> other.ts -> zod
+        let graph = make_graph(
+            &[
+                ("/p/entry.ts", 10, None),
+                ("/p/src/util.ts", 100, None),
+                ("/p/node_modules/vendor/index.js", 20, Some("vendor")),
+                ("/p/node_modules/vendor/lib.js", 30, Some("vendor")),
+                ("/p/src/other.ts", 40, None),
+                ("/p/node_modules/zod/index.js", 50, Some("zod")),
+            ],
+            &[
+                (0, 1, EdgeKind::Static),
+                (0, 2, EdgeKind::Static),
+                (2, 1, EdgeKind::Static),
+                (2, 3, EdgeKind::Static),
+                (0, 4, EdgeKind::Static),
+                (4, 5, EdgeKind::Static),
+            ],
+        );
+        assert_eq!(
+            shared_with_package(&graph, ModuleId(0), "vendor", false),
+            Some(vec![ModuleId(1)])
+        );
+        assert_eq!(
+            shared_with_package(&graph, ModuleId(0), "zod", false),
+            Some(vec![])
+        );
+        assert_eq!(
+            shared_with_package(&graph, ModuleId(0), "react", false),
+            None
+        );
+    }
+
+    #[test]
+    fn duplicate_packages_groups_copies_by_install_dir() {
+        // entry -> lodash@4 (top level) and entry -> legacy -> its own nested
+        // lodash@3; @scope/ui has one copy
+        let graph = make_graph(
+            &[
+                ("/p/index.ts", 10, None),
+                ("/p/node_modules/lodash/index.js", 500, Some("lodash")),
+                ("/p/node_modules/lodash/fp.js", 100, Some("lodash")),
+                ("/p/node_modules/legacy/index.js", 20, Some("legacy")),
+                (
+                    "/p/node_modules/legacy/node_modules/lodash/index.js",
+                    300,
+                    Some("lodash"),
+                ),
+                ("/p/node_modules/@scope/ui/index.js", 40, Some("@scope/ui")),
+            ],
+            &[
+                (0, 1, EdgeKind::Static),
+                (1, 2, EdgeKind::Static),
+                (0, 3, EdgeKind::Static),
+                (3, 4, EdgeKind::Static),
+                (0, 5, EdgeKind::Static),
+            ],
+        );
+        let dups = duplicate_packages(&graph, ModuleId(0), false);
+        assert_eq!(dups.len(), 1);
+        assert_eq!(dups[0].name, "lodash");
+        let copies: Vec<(&Path, u32, u64, ModuleId)> = dups[0]
+            .copies
+            .iter()
+            .map(|c| (c.dir.as_path(), c.file_count, c.total_size, c.first_module))
+            .collect();
+        assert_eq!(
+            copies,
+            vec![
+                (Path::new("/p/node_modules/lodash"), 2, 600, ModuleId(1)),
+                (
+                    Path::new("/p/node_modules/legacy/node_modules/lodash"),
+                    1,
+                    300,
+                    ModuleId(4)
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn find_dependents_direct_and_transitive() {
+        // entry -> a -> legacy; b -type-> legacy; c -> b; legacy -> a (cycle)
+        let graph = make_graph(
+            &[
+                ("entry.ts", 1, None),
+                ("c.ts", 1, None),
+                ("b.ts", 1, None),
+                ("a.ts", 1, None),
+                ("legacy.ts", 1, None),
+            ],
+            &[
+                (0, 3, EdgeKind::Static),
+                (3, 4, EdgeKind::Static),
+                (2, 4, EdgeKind::TypeOnly),
+                (1, 2, EdgeKind::Static),
+                (4, 3, EdgeKind::Static),
+            ],
+        );
+        let rev = reverse_adj(&graph);
+        assert_eq!(
+            find_dependents(&graph, &rev, ModuleId(4), false),
+            vec![ModuleId(3), ModuleId(2)]
+        );
+        assert_eq!(
+            find_dependents(&graph, &rev, ModuleId(4), true),
+            vec![ModuleId(3), ModuleId(2), ModuleId(1), ModuleId(0)]
+        );
+        assert!(find_dependents(&graph, &rev, ModuleId(0), true).is_empty());
+    }
+
+    // --- Cycles ---
+
+    #[test]
+    fn find_cycles_separates_static_and_dynamic() {
+        // entry -> a <-> b (static); entry -> c -dyn-> d -> c; e -> e
+        let graph = make_graph(
+            &[
+                ("entry.ts", 1, None),
+                ("a.ts", 1, None),
+                ("b.ts", 1, None),
+                ("c.ts", 1, None),
+                ("d.ts", 1, None),
+                ("e.ts", 1, None),
+            ],
+            &[
+                (0, 1, EdgeKind::Static),
+                (1, 2, EdgeKind::Static),
+                (2, 1, EdgeKind::Static),
+                (0, 3, EdgeKind::Static),
+                (3, 4, EdgeKind::Dynamic),
+                (4, 3, EdgeKind::Static),
+                (0, 5, EdgeKind::Static),
+                (5, 5, EdgeKind::Static),
+            ],
+        );
+        let normalize = |mut cycles: Vec<Vec<ModuleId>>| {
+            for c in &mut cycles {
+                c.sort_unstable_by_key(|m| m.0);
+            }
+            cycles.sort_unstable_by_key(|c| c[0].0);
+            cycles
+        };
+        assert_eq!(
+            normalize(find_cycles(&graph, ModuleId(0), false)),
+            vec![vec![ModuleId(1), ModuleId(2)], vec![ModuleId(5)]]
+        );
+        assert_eq!(
+            normalize(find_cycles(&graph, ModuleId(0), true)),
+            vec![
+                vec![ModuleId(1), ModuleId(2)],
+                vec![ModuleId(3), ModuleId(4)],
+                vec![ModuleId(5)]
+            ]
+        );
+    }
+
+    #[test]
+    fn find_all_cycles_flags_type_only_cycles() {
+        // entry -> a <-> b (static); entry -> c -type-> d -> c
+        let graph = make_graph(
+            &[
+                ("entry.ts", 1, None),
+                ("a.ts", 1, None),
+                ("b.ts", 1, None),
+                ("c.ts", 1, None),
+                ("d.ts", 1, None),
+            ],
+            &[
+                (0, 1, EdgeKind::Static),
+                (1, 2, EdgeKind::Static),
+                (2, 1, EdgeKind::Static),
+                (0, 3, EdgeKind::Static),
+                (3, 4, EdgeKind::TypeOnly),
+                (4, 3, EdgeKind::Static),
+            ],
+        );
+        let mut cycles = find_all_cycles(&graph, ModuleId(0));
+        cycles.sort_unstable_by_key(|c| c.modules[0].0);
+        assert_eq!(cycles.len(), 2);
+
+        assert_eq!(cycles[0].modules, [ModuleId(1), ModuleId(2)]);
+        assert!(!cycles[0].type_only);
+        let closing = graph.edge(cycles[0].closing_edges[0]);
+        assert_eq!((closing.from, closing.to), (ModuleId(2), ModuleId(1)));
+
+        assert_eq!(cycles[1].modules, [ModuleId(3), ModuleId(4)]);
+        assert!(cycles[1].type_only);
+    }
 }
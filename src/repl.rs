@@ -15,6 +15,7 @@ use rustyline::validate::Validator;
 
 use crate::error::Error;
 use crate::graph::EdgeKind;
+use crate::loader::LoadOptions;
 use crate::query::{self, ChainTarget};
 use crate::report::{self, StderrColor};
 use crate::session::Session;
@@ -230,9 +231,14 @@ impl Helper for ChainsawHelper {}
 // ---------------------------------------------------------------------------
 
 /// Run the interactive REPL loop.
-pub fn run(entry: &Path, no_color: bool, sc: StderrColor) -> Result<(), Error> {
+pub fn run(
+    entry: &Path,
+    load_opts: &LoadOptions,
+    no_color: bool,
+    sc: StderrColor,
+) -> Result<(), Error> {
     let start = std::time::Instant::now();
-    let mut session = Session::open(entry, false)?;
+    let mut session = Session::open_with(entry, load_opts)?;
 
     report::print_load_status(
         session.from_cache(),
@@ -366,7 +372,7 @@ fn dispatch_chain(session: &Session, target: &str, color: bool, sc: StderrColor)
         eprintln!("{} target is the entry point itself", sc.error("error:"));
         return;
     }
-    let report = session.chain_report(target, false);
+    let report = session.chain_report(target, false, 0);
     print!("{}", report.to_terminal(color));
 }
 
@@ -389,7 +395,7 @@ fn dispatch_diff(session: &Session, path: &str, color: bool, sc: StderrColor) {
 }
 
 fn dispatch_packages(session: &Session, color: bool) {
-    let report = session.packages_report(report::DEFAULT_TOP);
+    let report = session.packages_report(report::DEFAULT_TOP, false);
     print!("{}", report.to_terminal(color));
 }
 
@@ -406,6 +412,7 @@ fn dispatch_imports(session: &Session, path: &str, sc: StderrColor) {
                     EdgeKind::Static => "",
                     EdgeKind::Dynamic => " (dynamic)",
                     EdgeKind::TypeOnly => " (type-only)",
+                    EdgeKind::Asset => " (asset)",
                 };
                 println!("  {rel}{suffix}");
             }
@@ -427,6 +434,7 @@ fn dispatch_importers(session: &Session, path: &str, sc: StderrColor) {
                     EdgeKind::Static => "",
                     EdgeKind::Dynamic => " (dynamic)",
                     EdgeKind::TypeOnly => " (type-only)",
+                    EdgeKind::Asset => " (asset)",
                 };
                 println!("  {rel}{suffix}");
             }
@@ -444,6 +452,15 @@ fn dispatch_info(session: &Session, name: &str, sc: StderrColor) {
                 info.total_reachable_files,
                 report::format_size(info.total_reachable_size)
             );
+            let deps = session.package_dependencies(name);
+            if !deps.is_empty() {
+                println!(
+                    "  pulls in {} more in {} package{}",
+                    report::format_size(deps.iter().map(|d| d.1).sum()),
+                    deps.len(),
+                    if deps.len() == 1 { "" } else { "s" }
+                );
+            }
         }
         None => eprintln!("{} package '{name}' not found", sc.error("error:")),
     }
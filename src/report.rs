@@ -1,6 +1,7 @@
 //! Human-readable output formatting for trace results, diffs, and package lists.
 
-use std::collections::HashMap;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt::Write as _;
 use std::io::IsTerminal;
 use std::path::{Component, Path, PathBuf};
@@ -8,7 +9,9 @@ use std::path::{Component, Path, PathBuf};
 use serde::Serialize;
 
 use crate::graph::{ModuleGraph, ModuleId};
-use crate::query::DiffResult;
+use crate::lang::{CandidateOutcome, ResolveStrategy};
+use crate::query::{DiffPackage, DiffResult};
+use crate::walker::ResolutionStats;
 
 /// Default number of heavy dependencies to display.
 pub const DEFAULT_TOP: i32 = 10;
@@ -39,6 +42,24 @@ const fn plural(n: u64) -> &'static str {
     if n == 1 { "" } else { "s" }
 }
 
+/// `1240` as `1,240`.
+fn group_digits(n: usize) -> String {
+    let digits = n.to_string();
+    let mut out = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, ch) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            out.push(',');
+        }
+        out.push(ch);
+    }
+    out
+}
+
+#[allow(clippy::trivially_copy_pass_by_ref)] // serde skip_serializing_if signature
+const fn is_zero(n: &usize) -> bool {
+    *n == 0
+}
+
 #[derive(Clone, Copy)]
 struct C {
     color: bool,
@@ -174,6 +195,390 @@ pub fn format_size(bytes: u64) -> String {
     }
 }
 
+/// Shorten `name` to `width` characters by replacing its middle with
+/// `...`, so a table column stays aligned while the scope and file name at
+/// either end stay readable.
+pub fn middle_ellipsis(name: &str, width: usize) -> Cow<'_, str> {
+    let len = name.chars().count();
+    if len <= width {
+        return Cow::Borrowed(name);
+    }
+    let keep = width.saturating_sub(3);
+    let head = keep.div_ceil(2);
+    let tail = keep / 2;
+    let mut out: String = name.chars().take(head).collect();
+    out.push_str(&"..."[..width.min(3)]);
+    out.extend(name.chars().skip(len - tail));
+    Cow::Owned(out)
+}
+
+/// Border style for `--format table`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableBorders {
+    /// Unicode box-drawing characters.
+    Unicode,
+    /// `+`, `-` and `|`, for terminals and fonts without box-drawing glyphs.
+    Ascii,
+}
+
+impl TableBorders {
+    /// The horizontal and vertical lines, then the left, middle and right
+    /// joints of the top, header-separator and bottom rules.
+    fn chars(self) -> (char, char, [[char; 3]; 3]) {
+        match self {
+            Self::Unicode => (
+                '\u{2500}',
+                '\u{2502}',
+                [
+                    ['\u{250c}', '\u{252c}', '\u{2510}'],
+                    ['\u{251c}', '\u{253c}', '\u{2524}'],
+                    ['\u{2514}', '\u{2534}', '\u{2518}'],
+                ],
+            ),
+            Self::Ascii => ('-', '|', [['+'; 3]; 3]),
+        }
+    }
+}
+
+/// Draw `rows` under `headers` as a bordered table, indented like the plain
+/// sections. Each column is as wide as its longest cell; columns flagged in
+/// `right_align` (sizes, counts) are right-aligned.
+fn write_table(
+    out: &mut String,
+    borders: TableBorders,
+    headers: &[&str],
+    right_align: &[bool],
+    rows: &[Vec<String>],
+) {
+    let (horizontal, vertical, joints) = borders.chars();
+    let widths: Vec<usize> = headers
+        .iter()
+        .enumerate()
+        .map(|(i, header)| {
+            rows.iter()
+                .map(|row| row[i].chars().count())
+                .fold(header.chars().count(), usize::max)
+        })
+        .collect();
+    let rule = |out: &mut String, [left, mid, right]: [char; 3]| {
+        let segments: Vec<String> = widths
+            .iter()
+            .map(|w| horizontal.to_string().repeat(w + 2))
+            .collect();
+        writeln!(out, "  {left}{}{right}", segments.join(&mid.to_string())).unwrap();
+    };
+    let line = |out: &mut String, cells: &[&str]| {
+        write!(out, "  {vertical}").unwrap();
+        for ((cell, &width), &right) in cells.iter().zip(&widths).zip(right_align) {
+            if right {
+                write!(out, " {cell:>width$} {vertical}").unwrap();
+            } else {
+                write!(out, " {cell:<width$} {vertical}").unwrap();
+            }
+        }
+        writeln!(out).unwrap();
+    };
+    rule(out, joints[0]);
+    line(out, headers);
+    rule(out, joints[1]);
+    for row in rows {
+        let cells: Vec<&str> = row.iter().map(String::as_str).collect();
+        line(out, &cells);
+    }
+    rule(out, joints[2]);
+}
+
+/// Render the module table as delimited text with a header line.
+///
+/// With `quote` (CSV), fields containing the delimiter, a double quote, or a
+/// line break are quoted per RFC 4180. Without it (TSV), fields are written
+/// raw, with the delimiter and line breaks replaced by spaces so every row
+/// stays on one line.
+pub fn print_trace_delimited(rows: &[ModuleRow], delimiter: char, quote: bool) -> String {
+    let field = |s: &str| -> String {
+        let special = |ch: char| ch == delimiter || ch == '\n' || ch == '\r';
+        if quote {
+            if s.contains(|ch: char| special(ch) || ch == '"') {
+                format!("\"{}\"", s.replace('"', "\"\""))
+            } else {
+                s.to_string()
+            }
+        } else {
+            s.replace(special, " ")
+        }
+    };
+    let d = delimiter;
+    let mut out = String::new();
+    writeln!(out, "path{d}package{d}size{d}exclusive_size{d}depth").unwrap();
+    for row in rows {
+        writeln!(
+            out,
+            "{}{d}{}{d}{}{d}{}{d}{}",
+            field(&row.path),
+            field(row.package.as_deref().unwrap_or("")),
+            row.size_bytes,
+            row.exclusive_size_bytes,
+            row.depth
+        )
+        .unwrap();
+    }
+    out
+}
+
+/// Render folded stacks (`frame;frame;frame size`), the input format of
+/// `flamegraph.pl` and `inferno-flamegraph`. Frames are sanitized so a `;`
+/// in a path cannot split a frame.
+pub fn print_folded_stacks(stacks: &[FoldedStack]) -> String {
+    let mut out = String::new();
+    for stack in stacks {
+        let frames: Vec<String> = stack.frames.iter().map(|f| f.replace(';', "_")).collect();
+        writeln!(out, "{} {}", frames.join(";"), stack.size_bytes).unwrap();
+    }
+    out
+}
+
+/// Render the import graph as Graphviz DOT. Dynamic imports are dashed.
+/// With `cluster_by_package`, each package's modules are boxed in a
+/// `subgraph cluster_*` labeled with the package name, and the project's
+/// own files are boxed by top-level directory.
+pub fn print_dot(graph: &DotGraph, cluster_by_package: bool) -> String {
+    let escape = |s: &str| s.replace('\\', "\\\\").replace('"', "\\\"");
+    let quote = |s: &str| format!("\"{}\"", escape(s));
+    let node = |n: &DotNode| {
+        format!(
+            "{} [label=\"{}\\n{}\"]",
+            quote(&n.path),
+            escape(&n.path),
+            format_size(n.size_bytes)
+        )
+    };
+    let mut out = String::new();
+    writeln!(out, "digraph imports {{").unwrap();
+    writeln!(out, "  node [shape=box];").unwrap();
+    if cluster_by_package {
+        // Packages first, then source directories; files at the project
+        // root stay outside any cluster
+        let mut clusters: BTreeMap<(bool, &str), Vec<&DotNode>> = BTreeMap::new();
+        let mut loose = Vec::new();
+        for n in &graph.nodes {
+            match (&n.package, n.path.split_once('/')) {
+                (Some(pkg), _) => clusters.entry((false, pkg)).or_default().push(n),
+                (None, Some((dir, _))) => clusters.entry((true, dir)).or_default().push(n),
+                (None, None) => loose.push(n),
+            }
+        }
+        for (i, ((is_source, name), nodes)) in clusters.iter().enumerate() {
+            writeln!(out, "  subgraph cluster_{i} {{").unwrap();
+            let label = if *is_source {
+                format!("{name}/")
+            } else {
+                (*name).to_string()
+            };
+            writeln!(out, "    label={};", quote(&label)).unwrap();
+            for n in nodes {
+                writeln!(out, "    {};", node(n)).unwrap();
+            }
+            writeln!(out, "  }}").unwrap();
+        }
+        for n in loose {
+            writeln!(out, "  {};", node(n)).unwrap();
+        }
+    } else {
+        for n in &graph.nodes {
+            writeln!(out, "  {};", node(n)).unwrap();
+        }
+    }
+    for e in &graph.edges {
+        let style = if e.kind == "dynamic" {
+            " [style=dashed]"
+        } else {
+            ""
+        };
+        writeln!(out, "  {} -> {}{style};", quote(&e.from), quote(&e.to)).unwrap();
+    }
+    writeln!(out, "}}").unwrap();
+    out
+}
+
+/// Render a Mermaid `graph LR` block for embedding in Markdown. Node IDs
+/// are the labels with everything but ASCII letters and digits replaced,
+/// packages get rounded boxes, and dynamic imports are dotted arrows.
+pub fn print_mermaid(graph: &MermaidGraph) -> String {
+    let mut ids: HashMap<&str, String> = HashMap::new();
+    let mut taken: HashMap<String, usize> = HashMap::new();
+    for n in &graph.nodes {
+        let base: String = n
+            .label
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        // `end` and friends are Mermaid keywords; a prefix keeps IDs apart from them
+        let mut id = format!("m_{base}");
+        let seen = taken.entry(id.clone()).or_insert(0);
+        *seen += 1;
+        if *seen > 1 {
+            id = format!("{id}_{seen}");
+        }
+        ids.insert(&n.label, id);
+    }
+    let mut out = String::new();
+    writeln!(out, "graph LR").unwrap();
+    if graph.omitted > 0 {
+        writeln!(
+            out,
+            "  %% {} more node{} omitted: only the entry, its direct imports and the heaviest packages are drawn",
+            graph.omitted,
+            plural(graph.omitted as u64),
+        )
+        .unwrap();
+    }
+    for n in &graph.nodes {
+        let label = format!(
+            "{}<br/>{}",
+            n.label.replace('"', "#quot;"),
+            format_size(n.size_bytes)
+        );
+        let (open, close) = if n.is_package { ("(", ")") } else { ("[", "]") };
+        writeln!(out, "  {}{open}\"{label}\"{close}", ids[n.label.as_str()]).unwrap();
+    }
+    for e in &graph.edges {
+        let arrow = if e.kind == "dynamic" { "-.->" } else { "-->" };
+        writeln!(
+            out,
+            "  {} {arrow} {}",
+            ids[e.from.as_str()],
+            ids[e.to.as_str()]
+        )
+        .unwrap();
+    }
+    out
+}
+
+/// Outcome of one CI gate (`--max-weight`, `--fail-on-cycle`, ...).
+#[derive(Debug, Clone)]
+pub struct GateResult {
+    pub name: String,
+    /// Why the gate failed; `None` when it passed.
+    pub failure: Option<String>,
+}
+
+/// Render gate outcomes as a `JUnit` XML report, one `<testcase>` per gate,
+/// so CI test dashboards can show dependency-policy failures.
+pub fn print_junit(suite: &str, gates: &[GateResult]) -> String {
+    let failures = gates.iter().filter(|g| g.failure.is_some()).count();
+    let suite = xml_escape(suite);
+    let mut out = String::new();
+    writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#).unwrap();
+    writeln!(
+        out,
+        r#"<testsuites name="chainsaw" tests="{}" failures="{failures}">"#,
+        gates.len()
+    )
+    .unwrap();
+    writeln!(
+        out,
+        r#"  <testsuite name="{suite}" tests="{}" failures="{failures}">"#,
+        gates.len()
+    )
+    .unwrap();
+    for gate in gates {
+        let name = xml_escape(&gate.name);
+        match &gate.failure {
+            None => writeln!(out, r#"    <testcase name="{name}" classname="{suite}"/>"#).unwrap(),
+            Some(message) => {
+                let message = xml_escape(message);
+                let summary = message.lines().next().unwrap_or_default();
+                writeln!(out, r#"    <testcase name="{name}" classname="{suite}">"#).unwrap();
+                writeln!(
+                    out,
+                    r#"      <failure message="{summary}">{message}</failure>"#
+                )
+                .unwrap();
+                writeln!(out, "    </testcase>").unwrap();
+            }
+        }
+    }
+    writeln!(out, "  </testsuite>").unwrap();
+    writeln!(out, "</testsuites>").unwrap();
+    out
+}
+
+fn xml_escape(s: &str) -> Cow<'_, str> {
+    if !s.contains(['&', '<', '>', '"', '\'']) {
+        return Cow::Borrowed(s);
+    }
+    let mut out = String::with_capacity(s.len());
+    for ch in s.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            _ => out.push(ch),
+        }
+    }
+    Cow::Owned(out)
+}
+
+/// Fields stamped into every JSON report printed by the CLI, so results from
+/// many invocations (e.g. a CI matrix) can be correlated.
+#[derive(Debug, Clone, Serialize)]
+pub struct JsonMeta {
+    /// Caller-supplied `--label`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// RFC 3339 UTC time the report was generated.
+    pub generated_at: String,
+}
+
+impl JsonMeta {
+    pub fn now(label: Option<String>) -> Self {
+        let secs = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        Self {
+            label,
+            generated_at: utc_timestamp(secs),
+        }
+    }
+
+    /// Serialize `report` as pretty JSON with these fields appended to its
+    /// top-level object.
+    pub fn wrap<T: Serialize>(&self, report: &T) -> String {
+        #[derive(Serialize)]
+        struct WithMeta<'a, T> {
+            #[serde(flatten)]
+            report: &'a T,
+            #[serde(flatten)]
+            meta: &'a JsonMeta,
+        }
+        serde_json::to_string_pretty(&WithMeta { report, meta: self }).unwrap()
+    }
+}
+
+/// Format seconds since the Unix epoch as `YYYY-MM-DDTHH:MM:SSZ`
+/// (Howard Hinnant's `civil_from_days`).
+#[allow(clippy::cast_possible_wrap)]
+fn utc_timestamp(secs: u64) -> String {
+    let (days, rem) = (secs / 86_400, secs % 86_400);
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60
+    )
+}
+
 pub fn relative_path(path: &Path, root: &Path) -> String {
     path.strip_prefix(root)
         .unwrap_or(path)
@@ -191,7 +596,7 @@ pub(crate) fn display_name(graph: &ModuleGraph, mid: ModuleId, root: &Path) -> S
 /// Path relative to the package directory (e.g. `dateutil/__init__.py`).
 /// Handles scoped packages (`@scope/name` spans two path components).
 /// Falls back to the file name if the package name isn't found in path components.
-fn package_relative_path(path: &Path, package_name: &str) -> String {
+pub(crate) fn package_relative_path(path: &Path, package_name: &str) -> String {
     let components: Vec<_> = path.components().collect();
     // Scan backwards to find the last match — avoids false matches from
     // workspace dirs that share a package name (e.g. pnpm store paths
@@ -265,21 +670,82 @@ pub struct TraceReport {
     pub static_module_count: usize,
     pub dynamic_only_weight_bytes: u64,
     pub dynamic_only_module_count: usize,
+    /// Modules reached only through type-only imports, left out of the
+    /// static totals unless `--count-type-only`.
+    pub type_only_weight_bytes: u64,
+    pub type_only_module_count: usize,
     pub heavy_packages: Vec<PackageEntry>,
     pub modules_by_cost: Vec<ModuleEntry>,
     /// Total modules with non-zero exclusive weight (before truncation).
     pub total_modules_with_cost: usize,
+    /// `--group-by-package`: reachable modules rolled up by package, shown
+    /// in place of the module table.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub packages_by_cost: Vec<PackageCostEntry>,
+    /// Total packages in `packages_by_cost` (before truncation).
+    #[serde(skip_serializing_if = "is_zero")]
+    pub total_packages_with_cost: usize,
+    /// Total size of imported assets (not included in `static_weight_bytes`).
+    pub asset_weight_bytes: u64,
+    pub assets: Vec<AssetEntry>,
+    /// Total imported assets (before truncation).
+    pub total_assets: usize,
+    /// Dynamic imports from eagerly loaded code into not-yet-loaded modules.
+    pub lazy_boundaries: usize,
+    /// Weight loaded through those boundaries.
+    pub lazy_weight_bytes: u64,
+    /// How the graph's imports resolved when it was built.
+    pub resolution: ResolutionStats,
+    /// `--sample`: totals extrapolated from the files that were parsed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub estimate: Option<WeightEstimate>,
+    /// The `--max-package-depth` limit, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_package_depth: Option<u32>,
+    /// Packages past `max_package_depth` that were imported but not
+    /// expanded, so their weight is not counted.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub unexpanded_packages: Vec<String>,
     /// Whether dynamic imports were included in the trace.
     #[serde(skip)]
     pub include_dynamic: bool,
     /// The `--top` value (0 = hide heavy deps section entirely).
     #[serde(skip)]
     pub top: i32,
+    /// Show a line-count column in the module table (`--show-lines`).
+    #[serde(skip)]
+    pub show_lines: bool,
+    /// `--format table`: draw the package and module sections as bordered
+    /// tables instead of fixed-width columns.
+    #[serde(skip)]
+    pub table: Option<TableBorders>,
+    /// Whether `--max-weight` / `--max-modules` was exceeded; `None` when
+    /// no budget was given.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub budget_exceeded: Option<bool>,
+}
+
+/// Static weight extrapolated from a sampled build (`trace --sample`). The
+/// observed totals in [`TraceReport`] only count what was parsed, so they
+/// are a lower bound.
+#[derive(Debug, Clone, Serialize)]
+pub struct WeightEstimate {
+    /// Always `true`: these totals are estimates, not measurements.
+    pub approximate: bool,
+    pub sample_fraction: f64,
+    pub seed: u64,
+    /// Files reached but left unparsed by the sample.
+    pub sampled_out_files: usize,
+    pub estimated_weight_bytes: u64,
+    pub estimated_module_count: usize,
 }
 
 #[derive(Debug, Clone, Serialize)]
 pub struct PackageEntry {
     pub name: String,
+    /// Installed version, when read from package.json (`--only-packages`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
     pub total_size_bytes: u64,
     pub file_count: u32,
     pub chain: Vec<String>,
@@ -289,6 +755,29 @@ pub struct PackageEntry {
 pub struct ModuleEntry {
     pub path: String,
     pub exclusive_size_bytes: u64,
+    /// Line count (0 for unparsed files; misleading for minified code).
+    pub lines: u32,
+}
+
+/// Package name for first-party modules in [`PackageCostEntry`].
+pub const LOCAL_PACKAGE: &str = "(local)";
+
+/// A package's share of the traced weight: the summed size of its reachable
+/// modules and the largest of them.
+#[derive(Debug, Clone, Serialize)]
+pub struct PackageCostEntry {
+    /// Package name, or [`LOCAL_PACKAGE`] for first-party modules.
+    pub package: String,
+    pub size_bytes: u64,
+    pub module_count: usize,
+    pub largest_module: String,
+    pub largest_module_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AssetEntry {
+    pub path: String,
+    pub size_bytes: u64,
 }
 
 /// Display-ready chain result. Produced by `Session::chain_report()`.
@@ -296,9 +785,33 @@ pub struct ModuleEntry {
 pub struct ChainReport {
     pub target: String,
     pub found_in_graph: bool,
+    /// Minimum hop count requested (`--min-hops`); 0 means no minimum.
+    pub min_hops: u32,
+    /// Chains exist, but all are shorter than `min_hops`.
+    pub all_below_min_hops: bool,
     pub chain_count: usize,
     pub hop_count: usize,
     pub chains: Vec<Vec<String>>,
+    /// Per chain, the import edge behind each hop. JSON only, unless
+    /// `show_specifiers` is set.
+    pub hops: Vec<Vec<ChainHop>>,
+    /// Label each hop with the specifier written in source (`--show-specifiers`).
+    #[serde(skip)]
+    pub show_specifiers: bool,
+}
+
+/// One `from -> to` step of a chain and the import statement behind it.
+#[derive(Debug, Clone, Serialize)]
+pub struct ChainHop {
+    pub from: String,
+    pub to: String,
+    /// `static`, `dynamic`, `type-only`, or `asset`.
+    pub kind: &'static str,
+    pub specifier: String,
+    /// 1-based line of the import in `from`, when the specifier can be
+    /// found in its source as a quoted string.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<u32>,
 }
 
 /// Display-ready cut result. Produced by `Session::cut_report()`.
@@ -335,6 +848,9 @@ pub struct DiffReport {
     pub only_in_b: Vec<DiffPackageEntry>,
     pub dynamic_only_in_a: Vec<DiffPackageEntry>,
     pub dynamic_only_in_b: Vec<DiffPackageEntry>,
+    /// Per-module additions and removals (`--module-level`).
+    #[serde(flatten)]
+    pub module_changes: Option<ModuleChangeset>,
     /// Max packages to show per section (-1 for all).
     #[serde(skip)]
     pub limit: i32,
@@ -346,11 +862,72 @@ pub struct DiffPackageEntry {
     pub size: u64,
 }
 
+/// Modules added and removed between two snapshots, with a one-line
+/// summary ready to paste into a PR comment.
+#[derive(Debug, Clone, Serialize)]
+pub struct ModuleChangeset {
+    pub summary: String,
+    pub added_modules: Vec<DiffPackageEntry>,
+    pub removed_modules: Vec<DiffPackageEntry>,
+}
+
+impl ModuleChangeset {
+    pub fn from_diff(diff: &DiffResult) -> Self {
+        let entries = |modules: &[DiffPackage]| -> Vec<DiffPackageEntry> {
+            modules
+                .iter()
+                .map(|m| DiffPackageEntry {
+                    name: m.name.clone(),
+                    size: m.size,
+                })
+                .collect()
+        };
+        let added_modules = entries(&diff.modules_only_in_b);
+        let removed_modules = entries(&diff.modules_only_in_a);
+        Self {
+            summary: module_summary(&added_modules, &removed_modules, diff.weight_delta),
+            added_modules,
+            removed_modules,
+        }
+    }
+}
+
+/// "This change adds 3 modules (+42 KB) and removes 1 (-8 KB), net +34 KB".
+/// The net figure is the static weight delta, so it also covers modules
+/// that changed size.
+fn module_summary(added: &[DiffPackageEntry], removed: &[DiffPackageEntry], net: i64) -> String {
+    let total = |entries: &[DiffPackageEntry]| format_size(entries.iter().map(|e| e.size).sum());
+    let plural = |n: usize| if n == 1 { "" } else { "s" };
+    let added_part = if added.is_empty() {
+        "adds no modules".to_string()
+    } else {
+        format!(
+            "adds {} module{} (+{})",
+            added.len(),
+            plural(added.len()),
+            total(added)
+        )
+    };
+    let removed_part = match (removed.len(), added.is_empty()) {
+        (0, _) => "removes none".to_string(),
+        (n, true) => format!("removes {n} module{} (-{})", plural(n), total(removed)),
+        (n, false) => format!("removes {n} (-{})", total(removed)),
+    };
+    let sign = if net >= 0 { "+" } else { "-" };
+    format!(
+        "This change {added_part} and {removed_part}, net {sign}{}",
+        format_size(net.unsigned_abs())
+    )
+}
+
 /// Display-ready packages list. Produced by `Session::packages_report()`.
 #[derive(Debug, Clone, Serialize)]
 pub struct PackagesReport {
     pub package_count: usize,
     pub packages: Vec<PackageListEntry>,
+    /// List each package's sub-dependencies under it (`--with-deps`).
+    #[serde(skip)]
+    pub with_deps: bool,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -358,167 +935,1384 @@ pub struct PackageListEntry {
     pub name: String,
     pub size: u64,
     pub files: u32,
+    /// Weight of the other packages this one pulls in. Only computed
+    /// with `--with-deps`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dependency_size: Option<u64>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub dependencies: Vec<DependencyEntry>,
 }
 
-// ---------------------------------------------------------------------------
-// Report rendering
-// ---------------------------------------------------------------------------
+#[derive(Debug, Clone, Serialize)]
+pub struct DependencyEntry {
+    pub name: String,
+    pub size: u64,
+}
 
-impl TraceReport {
-    pub fn to_json(&self) -> String {
-        serde_json::to_string_pretty(self).unwrap()
-    }
+/// Display-ready barrel import amplification. Produced by
+/// `Session::barrel_report()`.
+#[derive(Debug, Clone, Serialize)]
+pub struct BarrelReport {
+    pub entry: String,
+    pub barrels: Vec<BarrelEntry>,
+}
 
-    pub fn to_terminal(&self, color: bool) -> String {
-        let c = C { color };
-        let mut out = String::new();
-        writeln!(out, "{}", self.entry).unwrap();
+#[derive(Debug, Clone, Serialize)]
+pub struct BarrelEntry {
+    pub importer: String,
+    pub barrel: String,
+    pub barrel_weight_bytes: u64,
+    pub single_module_bytes: u64,
+    pub amplification: f64,
+    /// Amplification is at or above the flagging threshold.
+    pub high: bool,
+}
 
-        let suffix = if self.include_dynamic {
-            ", static + dynamic"
-        } else {
-            ""
-        };
-        let weight = format_size(self.static_weight_bytes);
-        let modules = format!(
-            "{} module{}{}",
-            self.static_module_count,
-            plural(self.static_module_count as u64),
-            suffix,
-        );
-        let label = if self.include_dynamic {
-            "Total transitive weight:"
-        } else {
-            "Static transitive weight:"
-        };
-        writeln!(out, "{} {weight} ({modules})", c.bold_green(label)).unwrap();
+/// Modules ranked by how many reachable modules depend on them
+/// (`--most-depended-on`). Produced by `Session::depended_on_report()`.
+#[derive(Debug, Clone, Serialize)]
+pub struct DependedOnReport {
+    pub entry: String,
+    pub modules: Vec<DependedOnEntry>,
+}
 
-        if !self.include_dynamic && self.dynamic_only_module_count > 0 {
-            writeln!(
-                out,
-                "{} {} ({} module{}, not loaded at startup)",
-                c.bold_green("Dynamic-only weight:"),
-                format_size(self.dynamic_only_weight_bytes),
-                self.dynamic_only_module_count,
-                plural(self.dynamic_only_module_count as u64)
-            )
-            .unwrap();
-        }
+#[derive(Debug, Clone, Serialize)]
+pub struct DependedOnEntry {
+    pub module: String,
+    /// Modules that import this one directly or transitively.
+    pub dependents: usize,
+    pub direct_importers: usize,
+}
 
-        if self.top != 0 {
-            writeln!(out).unwrap();
-            let deps_label = if self.include_dynamic {
-                "Heavy dependencies (static + dynamic):"
-            } else {
-                "Heavy dependencies (static):"
-            };
-            writeln!(out, "{}", c.bold_green(deps_label)).unwrap();
-            if self.heavy_packages.is_empty() {
-                writeln!(
-                    out,
-                    "  (none \u{2014} all reachable modules are first-party)"
-                )
-                .unwrap();
-            } else {
-                for pkg in &self.heavy_packages {
-                    writeln!(
-                        out,
-                        "  {:<35} {}  {} file{}",
-                        pkg.name,
-                        format_size(pkg.total_size_bytes),
-                        pkg.file_count,
-                        plural(u64::from(pkg.file_count))
-                    )
-                    .unwrap();
-                    if pkg.chain.len() > 1 {
-                        writeln!(out, "    -> {}", pkg.chain.join(" -> ")).unwrap();
-                    }
-                }
-            }
-            writeln!(out).unwrap();
-        }
+/// Import cycles reachable from an entry (`chainsaw cycles`). Produced by
+/// `Session::cycles_report()`.
+#[derive(Debug, Clone, Serialize)]
+pub struct CyclesReport {
+    pub entry: String,
+    /// Runtime cycles first, then type-only ones.
+    pub cycles: Vec<CycleEntry>,
+}
 
-        if !self.modules_by_cost.is_empty() {
-            writeln!(
-                out,
-                "{}",
-                c.bold_green("Modules (sorted by exclusive weight):")
-            )
-            .unwrap();
-            for mc in &self.modules_by_cost {
-                writeln!(
-                    out,
-                    "  {:<55} {}",
-                    mc.path,
-                    format_size(mc.exclusive_size_bytes)
-                )
-                .unwrap();
-            }
-            if self.total_modules_with_cost > self.modules_by_cost.len() {
-                let remaining = self.total_modules_with_cost - self.modules_by_cost.len();
-                writeln!(
-                    out,
-                    "  ... and {remaining} more module{}",
-                    plural(remaining as u64)
-                )
-                .unwrap();
-            }
-        }
+#[derive(Debug, Clone, Serialize)]
+pub struct CycleEntry {
+    /// Members of the strongly connected component, starting where the
+    /// loop closes.
+    pub modules: Vec<String>,
+    /// Imports from a member back to the first module.
+    pub closing_edges: Vec<CycleEdge>,
+    /// Only `import type` edges close the loop, so it is gone at runtime.
+    pub type_only: bool,
+}
 
-        out
-    }
+#[derive(Debug, Clone, Serialize)]
+pub struct CycleEdge {
+    pub from: String,
+    pub to: String,
+    /// `static`, `dynamic`, or `type-only`.
+    pub kind: &'static str,
+    pub specifier: String,
 }
 
-impl ChainReport {
-    pub fn to_json(&self) -> String {
-        serde_json::to_string_pretty(self).unwrap()
+/// Project files the entry never imports (`chainsaw deadcode`). Produced by
+/// `Session::deadcode_report()`.
+#[derive(Debug, Clone, Serialize)]
+pub struct DeadcodeReport {
+    pub entry: String,
+    pub dead_bytes: u64,
+    pub dead_file_count: usize,
+    /// Test files left out because they are entry points of their own
+    /// (0 with `--include-tests`).
+    pub tests_skipped: usize,
+    /// Declaration files (`*.d.ts`) left out because the compiler loads them
+    /// without an import (0 with `--include-declarations`).
+    pub declarations_skipped: usize,
+    /// Heaviest directory first.
+    pub directories: Vec<DeadDirectory>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DeadDirectory {
+    /// Relative to the project root; `.` for the root itself.
+    pub dir: String,
+    pub size_bytes: u64,
+    /// Sorted by path.
+    pub files: Vec<DeadFileEntry>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DeadFileEntry {
+    pub path: String,
+    pub size_bytes: u64,
+}
+
+/// Result of `chainsaw prune-cache`.
+#[derive(Debug, Clone, Serialize)]
+pub struct PruneReport {
+    pub cache_file: String,
+    pub entries_removed: usize,
+    pub entries_kept: usize,
+    pub graph_discarded: bool,
+    pub bytes_before: u64,
+    pub bytes_after: u64,
+}
+
+/// What removing each package would save (`--savings`). Produced by
+/// `Session::savings_report()`.
+#[derive(Debug, Clone, Serialize)]
+pub struct SavingsReport {
+    pub entry: String,
+    pub static_weight_bytes: u64,
+    /// Largest savings first.
+    pub packages: Vec<SavingsEntry>,
+    /// Whether dynamic imports were included in the trace.
+    #[serde(skip)]
+    pub include_dynamic: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SavingsEntry {
+    pub name: String,
+    /// The package's own files.
+    pub size_bytes: u64,
+    /// Its own files plus everything only it pulls in.
+    pub savings_bytes: u64,
+}
+
+/// Modules loaded only through dynamic imports (`--only-dynamic`).
+/// Produced by `Session::dynamic_only_report()`.
+#[derive(Debug, Clone, Serialize)]
+pub struct DynamicOnlyReport {
+    pub entry: String,
+    /// What loads at startup, for scale.
+    pub static_weight_bytes: u64,
+    pub dynamic_only_weight_bytes: u64,
+    pub dynamic_only_module_count: usize,
+    /// Largest first, truncated to `--top-modules`.
+    pub modules: Vec<DynamicModuleEntry>,
+    /// Heaviest first, truncated to `--top`.
+    pub boundaries: Vec<LazyBoundaryEntry>,
+    /// Total boundaries (before truncation).
+    pub boundary_count: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DynamicModuleEntry {
+    pub path: String,
+    pub size_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LazyBoundaryEntry {
+    /// The statically loaded module with the `import()`.
+    pub importer: String,
+    pub target: String,
+    /// The target plus what it imports statically that startup hasn't loaded.
+    pub weight_bytes: u64,
+    pub module_count: usize,
+}
+
+/// Packages installed more than once (`--duplicates`). Produced by
+/// `Session::duplicates_report()`.
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicatesReport {
+    pub entry: String,
+    /// Largest combined size first.
+    pub packages: Vec<DuplicatePackageEntry>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicatePackageEntry {
+    pub name: String,
+    /// Largest copy first.
+    pub copies: Vec<DuplicateCopy>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateCopy {
+    /// From the copy's package.json; `None` when unavailable.
+    pub version: Option<String>,
+    /// Install directory, relative to the project root.
+    pub path: String,
+    pub size_bytes: u64,
+    pub file_count: u32,
+    /// One shortest import chain from the entry to this copy.
+    pub chain: Vec<String>,
+}
+
+/// Modules reached both from a package and from the entry's own code
+/// (`--shared-with`). Produced by `Session::shared_modules_report()`.
+#[derive(Debug, Clone, Serialize)]
+pub struct SharedModulesReport {
+    pub entry: String,
+    pub package: String,
+    /// Whether the entry loads the package at all.
+    pub package_found: bool,
+    /// Largest first.
+    pub modules: Vec<SharedModuleEntry>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SharedModuleEntry {
+    pub path: String,
+    /// `None` for the project's own files.
+    pub package: Option<String>,
+    pub size_bytes: u64,
+}
+
+/// Modules that import a file (`chainsaw dependents`). Produced by
+/// `Session::dependents_report()`.
+#[derive(Debug, Clone, Serialize)]
+pub struct DependentsReport {
+    pub target: String,
+    /// Modules with an import edge to the target, sorted by path.
+    pub direct: Vec<String>,
+    /// With `--transitive`: every module with an import path to the
+    /// target, sorted by path.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transitive: Option<Vec<String>>,
+}
+
+/// Lightweight software bill of materials: the third-party packages an entry
+/// point actually loads. Produced by `Session::sbom_report()`.
+#[derive(Debug, Clone, Serialize)]
+pub struct SbomReport {
+    pub entry: String,
+    pub packages: Vec<SbomPackage>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SbomPackage {
+    pub name: String,
+    /// From the package's package.json; `None` when unavailable.
+    pub version: Option<String>,
+    /// `"static"`, `"dynamic"`, or `"type-only"`: the strongest way the
+    /// package is reached from the entry.
+    pub reach: &'static str,
+    pub file_count: u32,
+    pub size_bytes: u64,
+}
+
+impl SbomReport {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+}
+
+/// One module's dominator-tree stack for `--format flamegraph`. Produced by
+/// `Session::folded_stacks()`.
+#[derive(Debug, Clone)]
+pub struct FoldedStack {
+    /// Entry first, this module last.
+    pub frames: Vec<String>,
+    /// The module's own size; ancestors' sizes are on their own stacks.
+    pub size_bytes: u64,
+}
+
+/// The reachable import graph for `--format dot`. Produced by
+/// `Session::dot_graph()`.
+#[derive(Debug, Clone)]
+pub struct DotGraph {
+    /// Sorted by path.
+    pub nodes: Vec<DotNode>,
+    pub edges: Vec<DotEdge>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DotNode {
+    /// Path relative to the project root; also the node ID.
+    pub path: String,
+    /// `None` for the project's own files.
+    pub package: Option<String>,
+    pub size_bytes: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct DotEdge {
+    pub from: String,
+    pub to: String,
+    /// `static` or `dynamic`.
+    pub kind: &'static str,
+}
+
+/// The trimmed import graph for `--format mermaid`. Produced by
+/// `Session::mermaid_graph()`.
+#[derive(Debug, Clone)]
+pub struct MermaidGraph {
+    /// Sorted by label.
+    pub nodes: Vec<MermaidNode>,
+    /// Between node labels.
+    pub edges: Vec<DotEdge>,
+    /// Reachable files and packages left out of `nodes`.
+    pub omitted: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct MermaidNode {
+    /// Package name, or path relative to the project root for the project's
+    /// own files.
+    pub label: String,
+    pub is_package: bool,
+    /// Summed over the package's reachable modules for a package.
+    pub size_bytes: u64,
+}
+
+/// One row of the module table export (`--format csv|tsv`). Produced by
+/// `Session::module_rows()`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ModuleRow {
+    pub path: String,
+    pub package: Option<String>,
+    pub size_bytes: u64,
+    pub exclusive_size_bytes: u64,
+    /// Fewest import hops from the entry point.
+    pub depth: u32,
+}
+
+/// How each import specifier in the graph was resolved. Produced by
+/// `Session::resolve_report()`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ResolveReport {
+    pub entry: String,
+    pub imports: Vec<ResolveEntry>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ResolveEntry {
+    /// Importing file, relative to the project root.
+    pub from: String,
+    pub specifier: String,
+    pub strategy: ResolveStrategy,
+    /// Resolved file, relative to the project root. `None` when unresolved
+    /// or a built-in.
+    pub resolved: Option<String>,
+}
+
+/// Every candidate path tried for one specifier and why each failed.
+/// Produced by `Session::explain_unresolved()`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ExplainReport {
+    pub specifier: String,
+    /// File the lookup starts from (the first importer of the specifier, or
+    /// the entry), relative to the project root.
+    pub from: String,
+    /// The candidate that exists, if any.
+    pub resolved: Option<String>,
+    pub attempts: Vec<ExplainAttempt>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExplainAttempt {
+    /// Candidate path, relative to the project root.
+    pub candidate: String,
+    pub outcome: CandidateOutcome,
+}
+
+/// Per-package weight and cross-package imports across a monorepo's
+/// workspaces (`--aggregate-monorepo`). Produced by `session::monorepo_report()`.
+#[derive(Debug, Clone, Serialize)]
+pub struct MonorepoReport {
+    /// Directory holding the workspaces config.
+    pub root: String,
+    /// Heaviest first; packages without an entry last.
+    pub packages: Vec<WorkspaceCost>,
+    /// Workspace package pairs where one imports the other, sorted by name.
+    pub cross_edges: Vec<WorkspaceEdge>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkspaceCost {
+    pub name: String,
+    /// Package directory, relative to the monorepo root.
+    pub dir: String,
+    /// Entry file, relative to the monorepo root. `None` when the package
+    /// has no `main` or index file, in which case it was not traced.
+    pub entry: Option<String>,
+    pub static_weight_bytes: u64,
+    pub static_module_count: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkspaceEdge {
+    pub from: String,
+    pub to: String,
+    /// Import edges from files of `from` to files of `to`, of any kind.
+    pub import_count: usize,
+}
+
+/// Static totals before and after detaching one module (`--without`).
+/// Produced by `Session::without_report()`.
+#[derive(Debug, Clone, Serialize)]
+pub struct WithoutReport {
+    pub entry: String,
+    pub removed: String,
+    pub before_weight_bytes: u64,
+    pub before_module_count: usize,
+    pub after_weight_bytes: u64,
+    pub after_module_count: usize,
+}
+
+/// Display-ready headline totals. Produced by `Session::count_report()`.
+#[derive(Debug, Clone, Serialize)]
+pub struct CountReport {
+    pub entry: String,
+    pub static_weight_bytes: u64,
+    pub static_module_count: usize,
+    pub dynamic_only_weight_bytes: u64,
+    pub dynamic_only_module_count: usize,
+    pub type_only_weight_bytes: u64,
+    pub type_only_module_count: usize,
+    pub package_count: usize,
+    /// Whether dynamic imports were included in the trace.
+    #[serde(skip)]
+    pub include_dynamic: bool,
+    /// Whether `--max-weight` / `--max-modules` was exceeded; `None` when
+    /// no budget was given.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub budget_exceeded: Option<bool>,
+}
+
+// ---------------------------------------------------------------------------
+// Report rendering
+// ---------------------------------------------------------------------------
+
+impl TraceReport {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+
+    /// The `Code splitting:` line, omitted without lazy-load boundaries.
+    fn write_code_splitting(&self, out: &mut String, c: C) {
+        if self.lazy_boundaries == 0 {
+            return;
+        }
+        writeln!(
+            out,
+            "{} {} lazy-load boundar{} gating {}",
+            c.bold_green("Code splitting:"),
+            self.lazy_boundaries,
+            if self.lazy_boundaries == 1 {
+                "y"
+            } else {
+                "ies"
+            },
+            format_size(self.lazy_weight_bytes),
+        )
+        .unwrap();
+    }
+
+    /// The `--sample` estimate and how it was made.
+    fn write_estimate(&self, out: &mut String, c: C) {
+        let Some(e) = &self.estimate else {
+            return;
+        };
+        writeln!(
+            out,
+            "{} ~{} (~{} module{}, extrapolated; can be far off)",
+            c.bold_green("Estimated weight:"),
+            format_size(e.estimated_weight_bytes),
+            group_digits(e.estimated_module_count),
+            plural(e.estimated_module_count as u64),
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "  approximate: --sample {} --seed {} left {} file{} unparsed; \
+             the weight above counts only what was reached",
+            e.sample_fraction,
+            e.seed,
+            group_digits(e.sampled_out_files),
+            plural(e.sampled_out_files as u64),
+        )
+        .unwrap();
+    }
+
+    /// Packages `--max-package-depth` stopped at, omitted when it cut nothing.
+    fn write_unexpanded(&self, out: &mut String, c: C) {
+        let (Some(depth), false) = (self.max_package_depth, self.unexpanded_packages.is_empty())
+        else {
+            return;
+        };
+        let shown = &self.unexpanded_packages[..self.unexpanded_packages.len().min(5)];
+        let more = self.unexpanded_packages.len() - shown.len();
+        writeln!(
+            out,
+            "{} {} deeper package{} not expanded (--max-package-depth {depth}): {}{}",
+            c.bold_green("Package depth:"),
+            self.unexpanded_packages.len(),
+            plural(self.unexpanded_packages.len() as u64),
+            shown.join(", "),
+            if more > 0 {
+                format!(", +{more} more")
+            } else {
+                String::new()
+            },
+        )
+        .unwrap();
+    }
+
+    /// The `Resolution:` summary line, omitted when nothing was imported.
+    fn write_resolution(&self, out: &mut String, c: C) {
+        let r = &self.resolution;
+        if r.imports == 0 && r.unresolvable_dynamic == 0 {
+            return;
+        }
+        writeln!(
+            out,
+            "{} {} resolved, {} builtin{}, {} unresolved, {} dynamic-unresolvable",
+            c.bold_green("Resolution:"),
+            group_digits(r.resolved),
+            group_digits(r.builtin),
+            plural(r.builtin as u64),
+            group_digits(r.unresolved),
+            group_digits(r.unresolvable_dynamic),
+        )
+        .unwrap();
+    }
+
+    pub fn to_terminal(&self, color: bool) -> String {
+        let c = C { color };
+        let mut out = String::new();
+        writeln!(out, "{}", self.entry).unwrap();
+
+        let suffix = if self.include_dynamic {
+            ", static + dynamic"
+        } else {
+            ""
+        };
+        let weight = format_size(self.static_weight_bytes);
+        let modules = format!(
+            "{} module{}{}",
+            self.static_module_count,
+            plural(self.static_module_count as u64),
+            suffix,
+        );
+        let label = if self.include_dynamic {
+            "Total transitive weight:"
+        } else {
+            "Static transitive weight:"
+        };
+        writeln!(out, "{} {weight} ({modules})", c.bold_green(label)).unwrap();
+
+        if !self.include_dynamic && self.dynamic_only_module_count > 0 {
+            writeln!(
+                out,
+                "{} {} ({} module{}, not loaded at startup)",
+                c.bold_green("Dynamic-only weight:"),
+                format_size(self.dynamic_only_weight_bytes),
+                self.dynamic_only_module_count,
+                plural(self.dynamic_only_module_count as u64)
+            )
+            .unwrap();
+        }
+        if self.type_only_module_count > 0 {
+            writeln!(
+                out,
+                "{} {} module{} ({}, erased at runtime)",
+                c.bold_green("Type-only:"),
+                self.type_only_module_count,
+                plural(self.type_only_module_count as u64),
+                format_size(self.type_only_weight_bytes),
+            )
+            .unwrap();
+        }
+        self.write_code_splitting(&mut out, c);
+        self.write_estimate(&mut out, c);
+        self.write_unexpanded(&mut out, c);
+        self.write_resolution(&mut out, c);
+
+        if self.top != 0 {
+            writeln!(out).unwrap();
+            let deps_label = if self.include_dynamic {
+                "Heavy dependencies (static + dynamic):"
+            } else {
+                "Heavy dependencies (static):"
+            };
+            writeln!(out, "{}", c.bold_green(deps_label)).unwrap();
+            if self.heavy_packages.is_empty() {
+                writeln!(
+                    out,
+                    "  (none \u{2014} all reachable modules are first-party)"
+                )
+                .unwrap();
+            } else if let Some(borders) = self.table {
+                self.write_heavy_packages_table(&mut out, borders);
+            } else {
+                for pkg in &self.heavy_packages {
+                    let name = match &pkg.version {
+                        Some(v) => format!("{}@{v}", pkg.name),
+                        None => pkg.name.clone(),
+                    };
+                    writeln!(
+                        out,
+                        "  {:<35} {}  {} file{}",
+                        middle_ellipsis(&name, 35),
+                        format_size(pkg.total_size_bytes),
+                        pkg.file_count,
+                        plural(u64::from(pkg.file_count))
+                    )
+                    .unwrap();
+                    if pkg.chain.len() > 1 {
+                        writeln!(out, "    -> {}", pkg.chain.join(" -> ")).unwrap();
+                    }
+                }
+            }
+            writeln!(out).unwrap();
+        }
+
+        self.write_assets(&mut out, c);
+
+        self.write_modules(&mut out, c);
+
+        out
+    }
+
+    fn write_heavy_packages_table(&self, out: &mut String, borders: TableBorders) {
+        let with_chain = self.heavy_packages.iter().any(|p| p.chain.len() > 1);
+        let rows: Vec<Vec<String>> = self
+            .heavy_packages
+            .iter()
+            .map(|pkg| {
+                let mut row = vec![
+                    match &pkg.version {
+                        Some(v) => format!("{}@{v}", pkg.name),
+                        None => pkg.name.clone(),
+                    },
+                    format_size(pkg.total_size_bytes),
+                    pkg.file_count.to_string(),
+                ];
+                if with_chain {
+                    row.push(if pkg.chain.len() > 1 {
+                        pkg.chain.join(" -> ")
+                    } else {
+                        String::new()
+                    });
+                }
+                row
+            })
+            .collect();
+        let (headers, right_align): (&[&str], &[bool]) = if with_chain {
+            (
+                &["Package", "Size", "Files", "Imported via"],
+                &[false, true, true, false],
+            )
+        } else {
+            (&["Package", "Size", "Files"], &[false, true, true])
+        };
+        write_table(out, borders, headers, right_align, &rows);
+    }
+
+    fn write_packages_by_cost(&self, out: &mut String, c: C) {
+        writeln!(out, "{}", c.bold_green("Packages (sorted by size):")).unwrap();
+        if let Some(borders) = self.table {
+            let rows: Vec<Vec<String>> = self
+                .packages_by_cost
+                .iter()
+                .map(|pc| {
+                    vec![
+                        pc.package.clone(),
+                        format_size(pc.size_bytes),
+                        pc.module_count.to_string(),
+                        format!(
+                            "{} ({})",
+                            pc.largest_module,
+                            format_size(pc.largest_module_bytes)
+                        ),
+                    ]
+                })
+                .collect();
+            write_table(
+                out,
+                borders,
+                &["Package", "Size", "Modules", "Largest"],
+                &[false, true, true, false],
+                &rows,
+            );
+        } else {
+            for pc in &self.packages_by_cost {
+                writeln!(
+                    out,
+                    "  {:<35} {:>8}  {} module{}, largest {} ({})",
+                    middle_ellipsis(&pc.package, 35),
+                    format_size(pc.size_bytes),
+                    pc.module_count,
+                    plural(pc.module_count as u64),
+                    pc.largest_module,
+                    format_size(pc.largest_module_bytes),
+                )
+                .unwrap();
+            }
+        }
+        if self.total_packages_with_cost > self.packages_by_cost.len() {
+            let remaining = self.total_packages_with_cost - self.packages_by_cost.len();
+            writeln!(
+                out,
+                "  ... and {remaining} more package{}",
+                plural(remaining as u64)
+            )
+            .unwrap();
+        }
+    }
+
+    fn write_modules(&self, out: &mut String, c: C) {
+        if !self.packages_by_cost.is_empty() {
+            self.write_packages_by_cost(out, c);
+            return;
+        }
+        if self.modules_by_cost.is_empty() {
+            return;
+        }
+        writeln!(
+            out,
+            "{}",
+            c.bold_green("Modules (sorted by exclusive weight):")
+        )
+        .unwrap();
+        if let Some(borders) = self.table {
+            let rows: Vec<Vec<String>> = self
+                .modules_by_cost
+                .iter()
+                .map(|mc| {
+                    let mut row = vec![mc.path.clone(), format_size(mc.exclusive_size_bytes)];
+                    if self.show_lines {
+                        row.push(mc.lines.to_string());
+                    }
+                    row
+                })
+                .collect();
+            let (headers, right_align): (&[&str], &[bool]) = if self.show_lines {
+                (&["Module", "Exclusive", "Lines"], &[false, true, true])
+            } else {
+                (&["Module", "Exclusive"], &[false, true])
+            };
+            write_table(out, borders, headers, right_align, &rows);
+        } else {
+            for mc in &self.modules_by_cost {
+                if self.show_lines {
+                    writeln!(
+                        out,
+                        "  {:<55} {:>8}  {:>7} lines",
+                        middle_ellipsis(&mc.path, 55),
+                        format_size(mc.exclusive_size_bytes),
+                        mc.lines
+                    )
+                    .unwrap();
+                } else {
+                    writeln!(
+                        out,
+                        "  {:<55} {}",
+                        middle_ellipsis(&mc.path, 55),
+                        format_size(mc.exclusive_size_bytes)
+                    )
+                    .unwrap();
+                }
+            }
+        }
+        if self.total_modules_with_cost > self.modules_by_cost.len() {
+            let remaining = self.total_modules_with_cost - self.modules_by_cost.len();
+            writeln!(
+                out,
+                "  ... and {remaining} more module{}",
+                plural(remaining as u64)
+            )
+            .unwrap();
+        }
+        if self.show_lines
+            && self
+                .modules_by_cost
+                .iter()
+                .any(|m| m.path.contains("node_modules"))
+        {
+            writeln!(
+                out,
+                "  {}",
+                c.dim("(line counts of minified node_modules files are not meaningful)")
+            )
+            .unwrap();
+        }
+    }
+
+    fn write_assets(&self, out: &mut String, c: C) {
+        if self.assets.is_empty() {
+            return;
+        }
+        writeln!(
+            out,
+            "{} {} ({} file{}, not counted above)",
+            c.bold_green("Assets:"),
+            format_size(self.asset_weight_bytes),
+            self.total_assets,
+            plural(self.total_assets as u64)
+        )
+        .unwrap();
+        for asset in &self.assets {
+            writeln!(
+                out,
+                "  {:<55} {}",
+                middle_ellipsis(&asset.path, 55),
+                format_size(asset.size_bytes)
+            )
+            .unwrap();
+        }
+        if self.total_assets > self.assets.len() {
+            let remaining = self.total_assets - self.assets.len();
+            writeln!(
+                out,
+                "  ... and {remaining} more asset{}",
+                plural(remaining as u64)
+            )
+            .unwrap();
+        }
+        writeln!(out).unwrap();
+    }
+}
+
+impl ChainReport {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+
+    pub fn to_terminal(&self, color: bool) -> String {
+        let c = C { color };
+        let mut out = String::new();
+
+        if self.chains.is_empty() {
+            if self.all_below_min_hops {
+                writeln!(
+                    out,
+                    "All chains to \"{}\" are shorter than {} hops.",
+                    self.target, self.min_hops
+                )
+                .unwrap();
+            } else if self.found_in_graph {
+                writeln!(
+                    out,
+                    "\"{}\" exists in the graph but is not reachable from this entry point.",
+                    self.target
+                )
+                .unwrap();
+            } else {
+                writeln!(
+                    out,
+                    "\"{}\" is not in the dependency graph. Check the spelling or verify it's installed.",
+                    self.target
+                )
+                .unwrap();
+            }
+            return out;
+        }
+
+        writeln!(
+            out,
+            "{}\n",
+            c.bold_green(&format!(
+                "{} chain{} to \"{}\" ({} hop{}):",
+                self.chain_count,
+                if self.chain_count == 1 { "" } else { "s" },
+                self.target,
+                self.hop_count,
+                if self.hop_count == 1 { "" } else { "s" },
+            )),
+        )
+        .unwrap();
+        for (i, chain) in self.chains.iter().enumerate() {
+            let hops = self
+                .hops
+                .get(i)
+                .filter(|hops| self.show_specifiers && hops.len() + 1 == chain.len());
+            let line = match hops {
+                Some(hops) => {
+                    let mut line = chain[0].clone();
+                    for hop in hops {
+                        write!(
+                            line,
+                            " {} {}",
+                            c.dim(&format!("--({})-->", hop.specifier)),
+                            hop.to
+                        )
+                        .unwrap();
+                    }
+                    line
+                }
+                None => chain.join(" -> "),
+            };
+            writeln!(out, "  {}. {line}", i + 1).unwrap();
+        }
+
+        out
+    }
+}
+
+impl CutReport {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+
+    pub fn to_terminal(&self, color: bool) -> String {
+        let c = C { color };
+        let mut out = String::new();
+
+        if self.chain_count == 0 {
+            if self.found_in_graph {
+                writeln!(
+                    out,
+                    "\"{}\" exists in the graph but is not reachable from this entry point.",
+                    self.target
+                )
+                .unwrap();
+            } else {
+                writeln!(
+                    out,
+                    "\"{}\" is not in the dependency graph. Check the spelling or verify it's installed.",
+                    self.target
+                )
+                .unwrap();
+            }
+            return out;
+        }
+
+        if self.cut_points.is_empty() {
+            if self.direct_import {
+                writeln!(
+                    out,
+                    "Entry file directly imports \"{}\" \u{2014} remove the import to sever the dependency.",
+                    self.target
+                )
+                .unwrap();
+            } else {
+                writeln!(
+                    out,
+                    "No single cut point can sever all {} chain{} to \"{}\".",
+                    self.chain_count,
+                    if self.chain_count == 1 { "" } else { "s" },
+                    self.target,
+                )
+                .unwrap();
+                writeln!(
+                    out,
+                    "Each chain takes a different path \u{2014} multiple fixes needed."
+                )
+                .unwrap();
+            }
+            return out;
+        }
+
+        writeln!(
+            out,
+            "{}\n",
+            c.bold_green(&format!(
+                "{} cut point{} to sever all {} chain{} to \"{}\":",
+                self.cut_points.len(),
+                if self.cut_points.len() == 1 { "" } else { "s" },
+                self.chain_count,
+                if self.chain_count == 1 { "" } else { "s" },
+                self.target,
+            )),
+        )
+        .unwrap();
+        for cut in &self.cut_points {
+            if self.chain_count == 1 {
+                writeln!(
+                    out,
+                    "  {:<45} {:>8}",
+                    middle_ellipsis(&cut.module, 45),
+                    format_size(cut.exclusive_size_bytes),
+                )
+                .unwrap();
+            } else {
+                writeln!(
+                    out,
+                    "  {:<45} {:>8}  (breaks {}/{} chains)",
+                    middle_ellipsis(&cut.module, 45),
+                    format_size(cut.exclusive_size_bytes),
+                    cut.chains_broken,
+                    self.chain_count
+                )
+                .unwrap();
+            }
+        }
+
+        out
+    }
+}
+
+impl DiffReport {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+
+    pub fn from_diff(diff: &DiffResult, entry_a: &str, entry_b: &str, limit: i32) -> Self {
+        Self {
+            entry_a: entry_a.to_string(),
+            entry_b: entry_b.to_string(),
+            weight_a: diff.entry_a_weight,
+            weight_b: diff.entry_b_weight,
+            weight_delta: diff.weight_delta,
+            dynamic_weight_a: diff.dynamic_a_weight,
+            dynamic_weight_b: diff.dynamic_b_weight,
+            dynamic_weight_delta: diff.dynamic_weight_delta,
+            shared_count: diff.shared_count,
+            only_in_a: diff
+                .only_in_a
+                .iter()
+                .map(|p| DiffPackageEntry {
+                    name: p.name.clone(),
+                    size: p.size,
+                })
+                .collect(),
+            only_in_b: diff
+                .only_in_b
+                .iter()
+                .map(|p| DiffPackageEntry {
+                    name: p.name.clone(),
+                    size: p.size,
+                })
+                .collect(),
+            dynamic_only_in_a: diff
+                .dynamic_only_in_a
+                .iter()
+                .map(|p| DiffPackageEntry {
+                    name: p.name.clone(),
+                    size: p.size,
+                })
+                .collect(),
+            dynamic_only_in_b: diff
+                .dynamic_only_in_b
+                .iter()
+                .map(|p| DiffPackageEntry {
+                    name: p.name.clone(),
+                    size: p.size,
+                })
+                .collect(),
+            module_changes: None,
+            limit,
+        }
+    }
+
+    #[allow(clippy::cast_sign_loss, clippy::too_many_lines)]
+    pub fn to_terminal(&self, color: bool) -> String {
+        let c = C { color };
+        let mut out = String::new();
+        writeln!(out, "Diff: {} vs {}", self.entry_a, self.entry_b).unwrap();
+        writeln!(out).unwrap();
+        writeln!(out, "  {:<40} {}", self.entry_a, format_size(self.weight_a)).unwrap();
+        writeln!(out, "  {:<40} {}", self.entry_b, format_size(self.weight_b)).unwrap();
+        let sign = if self.weight_delta >= 0 { "+" } else { "-" };
+        writeln!(
+            out,
+            "  {:<40} {sign}{}",
+            "Delta",
+            format_size(self.weight_delta.unsigned_abs())
+        )
+        .unwrap();
+
+        let has_dynamic = self.dynamic_weight_a > 0 || self.dynamic_weight_b > 0;
+        if has_dynamic {
+            writeln!(out).unwrap();
+            writeln!(
+                out,
+                "  {:<40} {}",
+                "Dynamic-only (before)",
+                format_size(self.dynamic_weight_a)
+            )
+            .unwrap();
+            writeln!(
+                out,
+                "  {:<40} {}",
+                "Dynamic-only (after)",
+                format_size(self.dynamic_weight_b)
+            )
+            .unwrap();
+            let dyn_sign = if self.dynamic_weight_delta >= 0 {
+                "+"
+            } else {
+                "-"
+            };
+            writeln!(
+                out,
+                "  {:<40} {dyn_sign}{}",
+                "Dynamic delta",
+                format_size(self.dynamic_weight_delta.unsigned_abs())
+            )
+            .unwrap();
+        }
+
+        writeln!(out).unwrap();
+
+        let limit = self.limit;
+        let show_count = |total: usize| -> usize {
+            if limit < 0 {
+                total
+            } else {
+                total.min(limit as usize)
+            }
+        };
+
+        if !self.only_in_a.is_empty() {
+            let show = show_count(self.only_in_a.len());
+            writeln!(out, "{}", c.red(&format!("Only in {}:", self.entry_a))).unwrap();
+            for pkg in &self.only_in_a[..show] {
+                writeln!(
+                    out,
+                    "{}",
+                    c.red(&format!(
+                        "  - {:<35} {}",
+                        middle_ellipsis(&pkg.name, 35),
+                        format_size(pkg.size)
+                    ))
+                )
+                .unwrap();
+            }
+            let remaining = self.only_in_a.len() - show;
+            if remaining > 0 {
+                writeln!(out, "{}", c.dim(&format!("  - ... and {remaining} more"))).unwrap();
+            }
+        }
+        if !self.only_in_b.is_empty() {
+            let show = show_count(self.only_in_b.len());
+            writeln!(out, "{}", c.green(&format!("Only in {}:", self.entry_b))).unwrap();
+            for pkg in &self.only_in_b[..show] {
+                writeln!(
+                    out,
+                    "{}",
+                    c.green(&format!(
+                        "  + {:<35} {}",
+                        middle_ellipsis(&pkg.name, 35),
+                        format_size(pkg.size)
+                    ))
+                )
+                .unwrap();
+            }
+            let remaining = self.only_in_b.len() - show;
+            if remaining > 0 {
+                writeln!(out, "{}", c.dim(&format!("  + ... and {remaining} more"))).unwrap();
+            }
+        }
+
+        if !self.dynamic_only_in_a.is_empty() {
+            let show = show_count(self.dynamic_only_in_a.len());
+            writeln!(
+                out,
+                "{}",
+                c.red(&format!("Dynamic only in {}:", self.entry_a))
+            )
+            .unwrap();
+            for pkg in &self.dynamic_only_in_a[..show] {
+                writeln!(
+                    out,
+                    "{}",
+                    c.red(&format!(
+                        "  - {:<35} {}",
+                        middle_ellipsis(&pkg.name, 35),
+                        format_size(pkg.size)
+                    ))
+                )
+                .unwrap();
+            }
+            let remaining = self.dynamic_only_in_a.len() - show;
+            if remaining > 0 {
+                writeln!(out, "{}", c.dim(&format!("  - ... and {remaining} more"))).unwrap();
+            }
+        }
+        if !self.dynamic_only_in_b.is_empty() {
+            let show = show_count(self.dynamic_only_in_b.len());
+            writeln!(
+                out,
+                "{}",
+                c.green(&format!("Dynamic only in {}:", self.entry_b))
+            )
+            .unwrap();
+            for pkg in &self.dynamic_only_in_b[..show] {
+                writeln!(
+                    out,
+                    "{}",
+                    c.green(&format!(
+                        "  + {:<35} {}",
+                        middle_ellipsis(&pkg.name, 35),
+                        format_size(pkg.size)
+                    ))
+                )
+                .unwrap();
+            }
+            let remaining = self.dynamic_only_in_b.len() - show;
+            if remaining > 0 {
+                writeln!(out, "{}", c.dim(&format!("  + ... and {remaining} more"))).unwrap();
+            }
+        }
+
+        if let Some(changes) = &self.module_changes {
+            for (title, sign, modules) in [
+                ("Modules removed:", '-', &changes.removed_modules),
+                ("Modules added:", '+', &changes.added_modules),
+            ] {
+                if modules.is_empty() {
+                    continue;
+                }
+                let paint = |s: &str| if sign == '-' { c.red(s) } else { c.green(s) };
+                let show = show_count(modules.len());
+                writeln!(out, "{}", paint(title)).unwrap();
+                for m in &modules[..show] {
+                    writeln!(
+                        out,
+                        "{}",
+                        paint(&format!(
+                            "  {sign} {:<35} {}",
+                            middle_ellipsis(&m.name, 35),
+                            format_size(m.size)
+                        ))
+                    )
+                    .unwrap();
+                }
+                let remaining = modules.len() - show;
+                if remaining > 0 {
+                    writeln!(
+                        out,
+                        "{}",
+                        c.dim(&format!("  {sign} ... and {remaining} more"))
+                    )
+                    .unwrap();
+                }
+            }
+            writeln!(out, "{}", changes.summary).unwrap();
+        }
+
+        if self.shared_count > 0 {
+            writeln!(
+                out,
+                "{}",
+                c.dim(&format!(
+                    "Shared: {} package{}",
+                    self.shared_count,
+                    if self.shared_count == 1 { "" } else { "s" }
+                ))
+            )
+            .unwrap();
+        }
+
+        out
+    }
+}
+
+impl PackagesReport {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
     }
 
+    #[allow(clippy::cast_sign_loss)]
     pub fn to_terminal(&self, color: bool) -> String {
         let c = C { color };
         let mut out = String::new();
 
-        if self.chains.is_empty() {
-            if self.found_in_graph {
-                writeln!(
-                    out,
-                    "\"{}\" exists in the graph but is not reachable from this entry point.",
-                    self.target
-                )
-                .unwrap();
-            } else {
-                writeln!(
+        if self.packages.is_empty() {
+            writeln!(
+                out,
+                "No third-party packages found in the dependency graph."
+            )
+            .unwrap();
+            return out;
+        }
+
+        writeln!(
+            out,
+            "{}\n",
+            c.bold_green(&format!(
+                "{} package{}:",
+                self.package_count,
+                plural(self.package_count as u64)
+            ))
+        )
+        .unwrap();
+        for pkg in &self.packages {
+            write!(
+                out,
+                "  {:<40} {:>8}  {} file{}",
+                middle_ellipsis(&pkg.name, 40),
+                format_size(pkg.size),
+                pkg.files,
+                plural(u64::from(pkg.files))
+            )
+            .unwrap();
+            if self.with_deps
+                && let Some(dependency_size) = pkg.dependency_size
+                && !pkg.dependencies.is_empty()
+            {
+                write!(
                     out,
-                    "\"{}\" is not in the dependency graph. Check the spelling or verify it's installed.",
-                    self.target
+                    "  {}",
+                    c.dim(&format!(
+                        "(+{} in {} dep{})",
+                        format_size(dependency_size),
+                        pkg.dependencies.len(),
+                        plural(pkg.dependencies.len() as u64)
+                    ))
                 )
                 .unwrap();
             }
+            writeln!(out).unwrap();
+            if self.with_deps {
+                for dep in &pkg.dependencies {
+                    writeln!(
+                        out,
+                        "      {:<36} {:>8}",
+                        middle_ellipsis(&dep.name, 36),
+                        format_size(dep.size)
+                    )
+                    .unwrap();
+                }
+            }
+        }
+        if self.package_count > self.packages.len() {
+            let remaining = self.package_count - self.packages.len();
+            writeln!(
+                out,
+                "  ... and {remaining} more package{}",
+                plural(remaining as u64)
+            )
+            .unwrap();
+        }
+
+        out
+    }
+}
+
+impl BarrelReport {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+
+    pub fn to_terminal(&self, color: bool) -> String {
+        let c = C { color };
+        let mut out = String::new();
+
+        if self.barrels.is_empty() {
+            writeln!(out, "No barrel imports found from {}.", self.entry).unwrap();
             return out;
         }
 
         writeln!(
             out,
             "{}\n",
-            c.bold_green(&format!(
-                "{} chain{} to \"{}\" ({} hop{}):",
-                self.chain_count,
-                if self.chain_count == 1 { "" } else { "s" },
-                self.target,
-                self.hop_count,
-                if self.hop_count == 1 { "" } else { "s" },
-            )),
+            c.bold_green("Barrel imports (barrel subtree vs. largest single module):")
         )
         .unwrap();
-        for (i, chain) in self.chains.iter().enumerate() {
-            writeln!(out, "  {}. {}", i + 1, chain.join(" -> ")).unwrap();
+        for b in &self.barrels {
+            let ratio = format!("{:.1}x", b.amplification);
+            writeln!(
+                out,
+                "  {} -> {}\n    {} loaded for ~{}  {}",
+                b.importer,
+                b.barrel,
+                format_size(b.barrel_weight_bytes),
+                format_size(b.single_module_bytes),
+                if b.high { c.red(&ratio) } else { ratio },
+            )
+            .unwrap();
         }
 
         out
     }
 }
 
-impl CutReport {
+impl DependedOnReport {
     pub fn to_json(&self) -> String {
         serde_json::to_string_pretty(self).unwrap()
     }
@@ -526,345 +2320,670 @@ impl CutReport {
     pub fn to_terminal(&self, color: bool) -> String {
         let c = C { color };
         let mut out = String::new();
+        writeln!(
+            out,
+            "{}\n",
+            c.bold_green(&format!("Most depended-on modules from {}:", self.entry))
+        )
+        .unwrap();
+        let width = self
+            .modules
+            .iter()
+            .map(|m| m.module.len())
+            .max()
+            .unwrap_or(0);
+        for m in &self.modules {
+            writeln!(
+                out,
+                "  {:<width$}  {:>5} dependent{} {}",
+                m.module,
+                m.dependents,
+                if m.dependents == 1 { " " } else { "s" },
+                c.dim(&format!("({} direct)", m.direct_importers)),
+            )
+            .unwrap();
+        }
+        out
+    }
+}
 
-        if self.chain_count == 0 {
-            if self.found_in_graph {
-                writeln!(
-                    out,
-                    "\"{}\" exists in the graph but is not reachable from this entry point.",
-                    self.target
-                )
-                .unwrap();
+impl PruneReport {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+
+    pub fn to_terminal(&self, color: bool) -> String {
+        let c = C { color };
+        let mut out = String::new();
+        writeln!(
+            out,
+            "{} {} stale entr{} from {} ({} kept)",
+            c.bold_green("Pruned"),
+            self.entries_removed,
+            if self.entries_removed == 1 {
+                "y"
             } else {
-                writeln!(
-                    out,
-                    "\"{}\" is not in the dependency graph. Check the spelling or verify it's installed.",
-                    self.target
-                )
-                .unwrap();
-            }
+                "ies"
+            },
+            self.cache_file,
+            self.entries_kept,
+        )
+        .unwrap();
+        if self.graph_discarded {
+            writeln!(out, "Dropped the cached graph: its entry file is gone").unwrap();
+        }
+        writeln!(
+            out,
+            "Reclaimed {} ({} -> {})",
+            format_size(self.bytes_before.saturating_sub(self.bytes_after)),
+            format_size(self.bytes_before),
+            format_size(self.bytes_after),
+        )
+        .unwrap();
+        out
+    }
+}
+
+impl SavingsReport {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+
+    pub fn to_terminal(&self, color: bool) -> String {
+        let c = C { color };
+        let mut out = String::new();
+        if self.packages.is_empty() {
+            writeln!(
+                out,
+                "No third-party packages reachable from {}.",
+                self.entry
+            )
+            .unwrap();
+            return out;
+        }
+        let kind = if self.include_dynamic {
+            "static + dynamic"
+        } else {
+            "static"
+        };
+        writeln!(
+            out,
+            "{}",
+            c.bold_green(&format!(
+                "Weight saved by removing each package (of {} {kind}):",
+                format_size(self.static_weight_bytes)
+            ))
+        )
+        .unwrap();
+        for pkg in &self.packages {
+            writeln!(
+                out,
+                "  {:<35} {:>9}  {}",
+                middle_ellipsis(&pkg.name, 35),
+                format_size(pkg.savings_bytes),
+                c.dim(&format!("own {}", format_size(pkg.size_bytes))),
+            )
+            .unwrap();
+        }
+        out
+    }
+}
+
+impl DynamicOnlyReport {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+
+    pub fn to_terminal(&self, color: bool) -> String {
+        let c = C { color };
+        let mut out = String::new();
+        writeln!(out, "{}", self.entry).unwrap();
+        writeln!(
+            out,
+            "{} {} ({} module{}, static startup is {})",
+            c.bold_green("Dynamic-only weight:"),
+            format_size(self.dynamic_only_weight_bytes),
+            self.dynamic_only_module_count,
+            plural(self.dynamic_only_module_count as u64),
+            format_size(self.static_weight_bytes),
+        )
+        .unwrap();
+        if self.dynamic_only_module_count == 0 {
             return out;
         }
 
-        if self.cut_points.is_empty() {
-            if self.direct_import {
-                writeln!(
-                    out,
-                    "Entry file directly imports \"{}\" \u{2014} remove the import to sever the dependency.",
-                    self.target
-                )
-                .unwrap();
-            } else {
+        if !self.boundaries.is_empty() {
+            writeln!(out).unwrap();
+            writeln!(out, "{}", c.bold_green("Lazy-load boundaries:")).unwrap();
+            for b in &self.boundaries {
                 writeln!(
                     out,
-                    "No single cut point can sever all {} chain{} to \"{}\".",
-                    self.chain_count,
-                    if self.chain_count == 1 { "" } else { "s" },
-                    self.target,
+                    "  {} -> {}  {} ({} module{})",
+                    b.importer,
+                    b.target,
+                    format_size(b.weight_bytes),
+                    b.module_count,
+                    plural(b.module_count as u64),
                 )
                 .unwrap();
+            }
+            if self.boundary_count > self.boundaries.len() {
+                let remaining = self.boundary_count - self.boundaries.len();
                 writeln!(
                     out,
-                    "Each chain takes a different path \u{2014} multiple fixes needed."
+                    "  ... and {remaining} more boundar{}",
+                    if remaining == 1 { "y" } else { "ies" }
                 )
                 .unwrap();
-            }
-            return out;
-        }
-
-        writeln!(
-            out,
-            "{}\n",
-            c.bold_green(&format!(
-                "{} cut point{} to sever all {} chain{} to \"{}\":",
-                self.cut_points.len(),
-                if self.cut_points.len() == 1 { "" } else { "s" },
-                self.chain_count,
-                if self.chain_count == 1 { "" } else { "s" },
-                self.target,
-            )),
-        )
-        .unwrap();
-        for cut in &self.cut_points {
-            if self.chain_count == 1 {
+            }
+        }
+
+        if !self.modules.is_empty() {
+            writeln!(out).unwrap();
+            writeln!(out, "{}", c.bold_green("Dynamic-only modules:")).unwrap();
+            for m in &self.modules {
                 writeln!(
                     out,
-                    "  {:<45} {:>8}",
-                    cut.module,
-                    format_size(cut.exclusive_size_bytes),
+                    "  {:<55} {}",
+                    middle_ellipsis(&m.path, 55),
+                    format_size(m.size_bytes)
                 )
                 .unwrap();
-            } else {
+            }
+            if self.dynamic_only_module_count > self.modules.len() {
+                let remaining = self.dynamic_only_module_count - self.modules.len();
                 writeln!(
                     out,
-                    "  {:<45} {:>8}  (breaks {}/{} chains)",
-                    cut.module,
-                    format_size(cut.exclusive_size_bytes),
-                    cut.chains_broken,
-                    self.chain_count
+                    "  ... and {remaining} more module{}",
+                    plural(remaining as u64)
                 )
                 .unwrap();
             }
         }
-
         out
     }
 }
 
-impl DiffReport {
+impl DuplicatesReport {
     pub fn to_json(&self) -> String {
         serde_json::to_string_pretty(self).unwrap()
     }
 
-    pub fn from_diff(diff: &DiffResult, entry_a: &str, entry_b: &str, limit: i32) -> Self {
-        Self {
-            entry_a: entry_a.to_string(),
-            entry_b: entry_b.to_string(),
-            weight_a: diff.entry_a_weight,
-            weight_b: diff.entry_b_weight,
-            weight_delta: diff.weight_delta,
-            dynamic_weight_a: diff.dynamic_a_weight,
-            dynamic_weight_b: diff.dynamic_b_weight,
-            dynamic_weight_delta: diff.dynamic_weight_delta,
-            shared_count: diff.shared_count,
-            only_in_a: diff
-                .only_in_a
-                .iter()
-                .map(|p| DiffPackageEntry {
-                    name: p.name.clone(),
-                    size: p.size,
-                })
-                .collect(),
-            only_in_b: diff
-                .only_in_b
-                .iter()
-                .map(|p| DiffPackageEntry {
-                    name: p.name.clone(),
-                    size: p.size,
-                })
-                .collect(),
-            dynamic_only_in_a: diff
-                .dynamic_only_in_a
-                .iter()
-                .map(|p| DiffPackageEntry {
-                    name: p.name.clone(),
-                    size: p.size,
-                })
-                .collect(),
-            dynamic_only_in_b: diff
-                .dynamic_only_in_b
-                .iter()
-                .map(|p| DiffPackageEntry {
-                    name: p.name.clone(),
-                    size: p.size,
-                })
-                .collect(),
-            limit,
-        }
-    }
-
-    #[allow(clippy::cast_sign_loss, clippy::too_many_lines)]
     pub fn to_terminal(&self, color: bool) -> String {
         let c = C { color };
         let mut out = String::new();
-        writeln!(out, "Diff: {} vs {}", self.entry_a, self.entry_b).unwrap();
-        writeln!(out).unwrap();
-        writeln!(out, "  {:<40} {}", self.entry_a, format_size(self.weight_a)).unwrap();
-        writeln!(out, "  {:<40} {}", self.entry_b, format_size(self.weight_b)).unwrap();
-        let sign = if self.weight_delta >= 0 { "+" } else { "-" };
+        if self.packages.is_empty() {
+            writeln!(out, "No duplicate packages reachable from {}.", self.entry).unwrap();
+            return out;
+        }
         writeln!(
             out,
-            "  {:<40} {sign}{}",
-            "Delta",
-            format_size(self.weight_delta.unsigned_abs())
+            "{}\n",
+            c.bold_green(&format!(
+                "{} package{} installed more than once, reachable from {}:",
+                self.packages.len(),
+                plural(self.packages.len() as u64),
+                self.entry
+            ))
         )
         .unwrap();
+        for pkg in &self.packages {
+            writeln!(out, "  {}", pkg.name).unwrap();
+            for copy in &pkg.copies {
+                writeln!(
+                    out,
+                    "    {:<10} {:>9}  {} file{}  {}",
+                    copy.version.as_deref().unwrap_or("?"),
+                    format_size(copy.size_bytes),
+                    copy.file_count,
+                    plural(u64::from(copy.file_count)),
+                    c.dim(&copy.path),
+                )
+                .unwrap();
+                if !copy.chain.is_empty() {
+                    writeln!(out, "      {}", c.dim(&copy.chain.join(" -> "))).unwrap();
+                }
+            }
+        }
+        out
+    }
+}
 
-        let has_dynamic = self.dynamic_weight_a > 0 || self.dynamic_weight_b > 0;
-        if has_dynamic {
-            writeln!(out).unwrap();
+impl SharedModulesReport {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+
+    pub fn to_terminal(&self, color: bool) -> String {
+        let c = C { color };
+        let mut out = String::new();
+        if !self.package_found {
             writeln!(
                 out,
-                "  {:<40} {}",
-                "Dynamic-only (before)",
-                format_size(self.dynamic_weight_a)
+                "Package '{}' is not loaded from {}.",
+                self.package, self.entry
             )
             .unwrap();
+            return out;
+        }
+        if self.modules.is_empty() {
             writeln!(
                 out,
-                "  {:<40} {}",
-                "Dynamic-only (after)",
-                format_size(self.dynamic_weight_b)
+                "No modules shared between {} and the code of {}.",
+                self.package, self.entry
             )
             .unwrap();
-            let dyn_sign = if self.dynamic_weight_delta >= 0 {
-                "+"
-            } else {
-                "-"
-            };
+            return out;
+        }
+        writeln!(
+            out,
+            "{}\n",
+            c.bold_green(&format!(
+                "{} module{} reached by both {} and the code of {}:",
+                self.modules.len(),
+                plural(self.modules.len() as u64),
+                self.package,
+                self.entry
+            ))
+        )
+        .unwrap();
+        let width = self.modules.iter().map(|m| m.path.len()).max().unwrap_or(0);
+        for m in &self.modules {
             writeln!(
                 out,
-                "  {:<40} {dyn_sign}{}",
-                "Dynamic delta",
-                format_size(self.dynamic_weight_delta.unsigned_abs())
+                "  {:<width$}  {:>9}",
+                m.path,
+                format_size(m.size_bytes)
             )
             .unwrap();
         }
+        out
+    }
+}
 
-        writeln!(out).unwrap();
-
-        let limit = self.limit;
-        let show_count = |total: usize| -> usize {
-            if limit < 0 {
-                total
-            } else {
-                total.min(limit as usize)
-            }
-        };
+impl DependentsReport {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
 
-        if !self.only_in_a.is_empty() {
-            let show = show_count(self.only_in_a.len());
-            writeln!(out, "{}", c.red(&format!("Only in {}:", self.entry_a))).unwrap();
-            for pkg in &self.only_in_a[..show] {
-                writeln!(
-                    out,
-                    "{}",
-                    c.red(&format!("  - {:<35} {}", pkg.name, format_size(pkg.size)))
-                )
-                .unwrap();
-            }
-            let remaining = self.only_in_a.len() - show;
-            if remaining > 0 {
-                writeln!(out, "{}", c.dim(&format!("  - ... and {remaining} more"))).unwrap();
-            }
+    pub fn to_terminal(&self, color: bool) -> String {
+        let c = C { color };
+        let mut out = String::new();
+        if self.direct.is_empty() {
+            writeln!(out, "Nothing imports {}.", self.target).unwrap();
+            return out;
         }
-        if !self.only_in_b.is_empty() {
-            let show = show_count(self.only_in_b.len());
-            writeln!(out, "{}", c.green(&format!("Only in {}:", self.entry_b))).unwrap();
-            for pkg in &self.only_in_b[..show] {
-                writeln!(
-                    out,
-                    "{}",
-                    c.green(&format!("  + {:<35} {}", pkg.name, format_size(pkg.size)))
-                )
-                .unwrap();
-            }
-            let remaining = self.only_in_b.len() - show;
-            if remaining > 0 {
-                writeln!(out, "{}", c.dim(&format!("  + ... and {remaining} more"))).unwrap();
+        let section = |out: &mut String, title: String, modules: &[String]| {
+            writeln!(out, "{}", c.bold_green(&title)).unwrap();
+            for module in modules {
+                writeln!(out, "  {module}").unwrap();
             }
+        };
+        section(
+            &mut out,
+            format!(
+                "{} direct importer{} of {}:",
+                self.direct.len(),
+                plural(self.direct.len() as u64),
+                self.target
+            ),
+            &self.direct,
+        );
+        if let Some(ref transitive) = self.transitive {
+            writeln!(out).unwrap();
+            section(
+                &mut out,
+                format!(
+                    "{} transitive dependent{}:",
+                    transitive.len(),
+                    plural(transitive.len() as u64)
+                ),
+                transitive,
+            );
         }
+        out
+    }
+}
 
-        if !self.dynamic_only_in_a.is_empty() {
-            let show = show_count(self.dynamic_only_in_a.len());
+impl DeadcodeReport {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+
+    pub fn to_terminal(&self, color: bool) -> String {
+        let c = C { color };
+        let mut out = String::new();
+        if self.dead_file_count == 0 {
+            writeln!(out, "No unreachable files from {}.", self.entry).unwrap();
+        } else {
             writeln!(
                 out,
                 "{}",
-                c.red(&format!("Dynamic only in {}:", self.entry_a))
+                c.bold_green(&format!(
+                    "{} unreachable file{} from {} ({}):",
+                    self.dead_file_count,
+                    plural(self.dead_file_count as u64),
+                    self.entry,
+                    format_size(self.dead_bytes),
+                ))
             )
             .unwrap();
-            for pkg in &self.dynamic_only_in_a[..show] {
+            for dir in &self.directories {
+                writeln!(out).unwrap();
                 writeln!(
                     out,
-                    "{}",
-                    c.red(&format!("  - {:<35} {}", pkg.name, format_size(pkg.size)))
+                    "  {}/  {} ({} file{})",
+                    dir.dir,
+                    format_size(dir.size_bytes),
+                    dir.files.len(),
+                    plural(dir.files.len() as u64),
                 )
                 .unwrap();
-            }
-            let remaining = self.dynamic_only_in_a.len() - show;
-            if remaining > 0 {
-                writeln!(out, "{}", c.dim(&format!("  - ... and {remaining} more"))).unwrap();
+                for file in &dir.files {
+                    writeln!(
+                        out,
+                        "    {:<53} {}",
+                        middle_ellipsis(&file.path, 53),
+                        format_size(file.size_bytes)
+                    )
+                    .unwrap();
+                }
             }
         }
-        if !self.dynamic_only_in_b.is_empty() {
-            let show = show_count(self.dynamic_only_in_b.len());
+        let skipped = [
+            (self.tests_skipped, "test file", "--include-tests"),
+            (
+                self.declarations_skipped,
+                "declaration file",
+                "--include-declarations",
+            ),
+        ];
+        if skipped.iter().any(|&(count, ..)| count > 0) {
+            writeln!(out).unwrap();
+        }
+        for (count, what, flag) in skipped.into_iter().filter(|&(count, ..)| count > 0) {
             writeln!(
                 out,
                 "{}",
-                c.green(&format!("Dynamic only in {}:", self.entry_b))
+                c.dim(&format!(
+                    "{count} {what}{} not checked ({flag} to list them)",
+                    plural(count as u64),
+                ))
             )
             .unwrap();
-            for pkg in &self.dynamic_only_in_b[..show] {
+        }
+        out
+    }
+}
+
+impl CyclesReport {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+
+    pub fn to_terminal(&self, color: bool) -> String {
+        let c = C { color };
+        let mut out = String::new();
+        if self.cycles.is_empty() {
+            writeln!(out, "No import cycles reachable from {}.", self.entry).unwrap();
+            return out;
+        }
+        let type_only = self.cycles.iter().filter(|cy| cy.type_only).count();
+        writeln!(
+            out,
+            "{}\n",
+            c.bold_green(&format!(
+                "{} import cycle{} from {} ({} runtime, {} type-only):",
+                self.cycles.len(),
+                plural(self.cycles.len() as u64),
+                self.entry,
+                self.cycles.len() - type_only,
+                type_only,
+            ))
+        )
+        .unwrap();
+        for (i, cycle) in self.cycles.iter().enumerate() {
+            let label = format!(
+                "{}, {} module{}",
+                if cycle.type_only {
+                    "type-only"
+                } else {
+                    "runtime"
+                },
+                cycle.modules.len(),
+                plural(cycle.modules.len() as u64),
+            );
+            let label = if cycle.type_only {
+                c.dim(&label)
+            } else {
+                c.red(&label)
+            };
+            writeln!(out, "  {}. {label}", i + 1).unwrap();
+            for module in &cycle.modules {
+                writeln!(out, "     {module}").unwrap();
+            }
+            for edge in &cycle.closing_edges {
                 writeln!(
                     out,
-                    "{}",
-                    c.green(&format!("  + {:<35} {}", pkg.name, format_size(pkg.size)))
+                    "     {}",
+                    c.dim(&format!(
+                        "closed by {} -> {} ({})",
+                        edge.from, edge.to, edge.kind
+                    ))
                 )
                 .unwrap();
             }
-            let remaining = self.dynamic_only_in_b.len() - show;
-            if remaining > 0 {
-                writeln!(out, "{}", c.dim(&format!("  + ... and {remaining} more"))).unwrap();
-            }
         }
+        out
+    }
+}
+
+impl ExplainReport {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+
+    pub fn to_terminal(&self, color: bool) -> String {
+        let c = C { color };
+        let mut out = String::new();
+        writeln!(
+            out,
+            "{}",
+            c.bold_green(&format!(
+                "Resolving \"{}\" from {}:",
+                self.specifier, self.from
+            ))
+        )
+        .unwrap();
+        if self.attempts.is_empty() {
+            writeln!(out, "  (no candidate trace for this language)").unwrap();
+            return out;
+        }
+        for attempt in &self.attempts {
+            let outcome = attempt.outcome.to_string();
+            let outcome = if attempt.outcome == CandidateOutcome::Found {
+                c.green(&outcome)
+            } else {
+                c.dim(&outcome)
+            };
+            writeln!(out, "  {:<55} {outcome}", attempt.candidate).unwrap();
+        }
+        match &self.resolved {
+            Some(path) => writeln!(out, "\nResolves to {path}").unwrap(),
+            None => writeln!(out, "\n{}", c.red("Unresolved: every candidate failed")).unwrap(),
+        }
+        out
+    }
+}
 
-        if self.shared_count > 0 {
+impl ResolveReport {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+
+    pub fn to_terminal(&self, color: bool) -> String {
+        let c = C { color };
+        let mut out = String::new();
+        writeln!(out, "{}", c.bold_green("Resolution report:")).unwrap();
+
+        let mut current: Option<&str> = None;
+        for imp in &self.imports {
+            if current != Some(imp.from.as_str()) {
+                writeln!(out, "\n{}", imp.from).unwrap();
+                current = Some(&imp.from);
+            }
+            let strategy = format!("{:<14}", imp.strategy.to_string());
+            let strategy = if imp.strategy == ResolveStrategy::Unresolved {
+                c.red(&strategy)
+            } else {
+                c.dim(&strategy)
+            };
             writeln!(
                 out,
-                "{}",
-                c.dim(&format!(
-                    "Shared: {} package{}",
-                    self.shared_count,
-                    if self.shared_count == 1 { "" } else { "s" }
-                ))
+                "  {:<40} {strategy} {}",
+                imp.specifier,
+                imp.resolved.as_deref().unwrap_or("-")
             )
             .unwrap();
         }
 
+        let mut counts: Vec<(ResolveStrategy, usize)> = Vec::new();
+        for imp in &self.imports {
+            match counts.iter_mut().find(|(s, _)| *s == imp.strategy) {
+                Some((_, n)) => *n += 1,
+                None => counts.push((imp.strategy, 1)),
+            }
+        }
+        counts.sort_by(|a, b| b.1.cmp(&a.1));
+        let summary: Vec<String> = counts.iter().map(|(s, n)| format!("{n} {s}")).collect();
+        writeln!(
+            out,
+            "\n{} import{}: {}",
+            self.imports.len(),
+            plural(self.imports.len() as u64),
+            summary.join(", ")
+        )
+        .unwrap();
         out
     }
 }
 
-impl PackagesReport {
+impl CountReport {
     pub fn to_json(&self) -> String {
         serde_json::to_string_pretty(self).unwrap()
     }
 
-    #[allow(clippy::cast_sign_loss)]
     pub fn to_terminal(&self, color: bool) -> String {
         let c = C { color };
+        let label = if self.include_dynamic {
+            "Total transitive weight:"
+        } else {
+            "Static transitive weight:"
+        };
         let mut out = String::new();
+        writeln!(
+            out,
+            "{} {} {} ({} module{}, {} package{})",
+            self.entry,
+            c.bold_green(label),
+            format_size(self.static_weight_bytes),
+            self.static_module_count,
+            plural(self.static_module_count as u64),
+            self.package_count,
+            plural(self.package_count as u64),
+        )
+        .unwrap();
+        out
+    }
+}
 
-        if self.packages.is_empty() {
-            writeln!(
-                out,
-                "No third-party packages found in the dependency graph."
-            )
-            .unwrap();
-            return out;
-        }
+impl MonorepoReport {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
 
+    pub fn to_terminal(&self, color: bool) -> String {
+        let c = C { color };
+        let mut out = String::new();
         writeln!(
             out,
-            "{}\n",
-            c.bold_green(&format!(
-                "{} package{}:",
-                self.package_count,
-                plural(self.package_count as u64)
-            ))
+            "{}",
+            c.bold_green(&format!("Workspace packages ({}):", self.root))
         )
         .unwrap();
+        let name_width = self
+            .packages
+            .iter()
+            .map(|p| p.name.len())
+            .max()
+            .unwrap_or(0);
         for pkg in &self.packages {
-            writeln!(
-                out,
-                "  {:<40} {:>8}  {} file{}",
-                pkg.name,
-                format_size(pkg.size),
-                pkg.files,
-                plural(u64::from(pkg.files))
-            )
-            .unwrap();
+            let weight = if pkg.entry.is_some() {
+                format!(
+                    "{:>9}  {} module{}",
+                    format_size(pkg.static_weight_bytes),
+                    pkg.static_module_count,
+                    plural(pkg.static_module_count as u64),
+                )
+            } else {
+                c.dim("(no entry)")
+            };
+            writeln!(out, "  {:<name_width$}  {weight}", pkg.name).unwrap();
         }
-        if self.package_count > self.packages.len() {
-            let remaining = self.package_count - self.packages.len();
+
+        writeln!(out, "\n{}", c.bold_green("Cross-package imports:")).unwrap();
+        if self.cross_edges.is_empty() {
+            writeln!(out, "  (none)").unwrap();
+        }
+        for edge in &self.cross_edges {
             writeln!(
                 out,
-                "  ... and {remaining} more package{}",
-                plural(remaining as u64)
+                "  {} -> {} {}",
+                edge.from,
+                edge.to,
+                c.dim(&format!(
+                    "({} import{})",
+                    edge.import_count,
+                    plural(edge.import_count as u64)
+                )),
             )
             .unwrap();
         }
+        out
+    }
+}
+
+impl WithoutReport {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
 
+    pub fn to_terminal(&self, color: bool) -> String {
+        let c = C { color };
+        let mut out = String::new();
+        writeln!(out, "{} without {}", self.entry, self.removed).unwrap();
+        let saved = self.before_weight_bytes - self.after_weight_bytes;
+        let dropped = self.before_module_count - self.after_module_count;
+        writeln!(
+            out,
+            "{} {} -> {} ({} module{} -> {})",
+            c.bold_green("Static transitive weight:"),
+            format_size(self.before_weight_bytes),
+            format_size(self.after_weight_bytes),
+            self.before_module_count,
+            plural(self.before_module_count as u64),
+            self.after_module_count,
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "{} {} ({} module{})",
+            c.bold_green("Saved:"),
+            c.green(&format!("-{}", format_size(saved))),
+            dropped,
+            plural(dropped as u64),
+        )
+        .unwrap();
         out
     }
 }
@@ -898,6 +3017,70 @@ mod tests {
         assert!(!should_use_color(true, false, false, true));
     }
 
+    #[test]
+    fn junit_reports_violated_budget_as_failing_testcase() {
+        let gates = [
+            GateResult {
+                name: "max-weight".into(),
+                failure: Some("static transitive weight 2.0 MB exceeds limit 1.0 MB".into()),
+            },
+            GateResult {
+                name: "cycles".into(),
+                failure: None,
+            },
+        ];
+        let xml = print_junit("src/<app>.ts", &gates);
+        assert!(xml.starts_with("<?xml"), "{xml}");
+        assert!(xml.contains(r#"tests="2" failures="1""#), "{xml}");
+        assert!(
+            xml.contains(
+                r#"<testcase name="max-weight" classname="src/&lt;app&gt;.ts">
+      <failure message="static transitive weight 2.0 MB exceeds limit 1.0 MB">"#
+            ),
+            "{xml}"
+        );
+        assert!(
+            xml.contains(r#"<testcase name="cycles" classname="src/&lt;app&gt;.ts"/>"#),
+            "{xml}"
+        );
+    }
+
+    #[test]
+    fn middle_ellipsis_fits_long_names_to_width() {
+        let name = "@very-long-scope/some-very-long-package-name";
+        let short = middle_ellipsis(name, 35);
+        assert_eq!(short.chars().count(), 35);
+        assert_eq!(short, "@very-long-scope...ong-package-name");
+        assert_eq!(middle_ellipsis("zod", 35), "zod");
+        assert_eq!(middle_ellipsis(name, 2), "..");
+    }
+
+    #[test]
+    fn group_digits_inserts_thousands_separators() {
+        assert_eq!(group_digits(0), "0");
+        assert_eq!(group_digits(999), "999");
+        assert_eq!(group_digits(1240), "1,240");
+        assert_eq!(group_digits(1_234_567), "1,234,567");
+    }
+
+    #[test]
+    fn packages_column_stays_aligned_for_long_names() {
+        let report = PackagesReport {
+            packages: vec![PackageListEntry {
+                name: "@very-long-scope/some-very-long-package-name".into(),
+                size: 1200,
+                files: 3,
+                dependencies: vec![],
+                dependency_size: None,
+            }],
+            package_count: 1,
+            with_deps: false,
+        };
+        let out = report.to_terminal(false);
+        let row = out.lines().find(|l| l.contains("...")).unwrap();
+        assert_eq!(row.find("1 KB"), Some(2 + 40 + 1 + 4), "{out}");
+    }
+
     #[test]
     fn package_relative_path_pnpm_store() {
         // pnpm store path where workspace dir matches package name
@@ -936,8 +3119,11 @@ mod tests {
             static_module_count: 5,
             dynamic_only_weight_bytes: 200,
             dynamic_only_module_count: 1,
+            type_only_weight_bytes: 0,
+            type_only_module_count: 0,
             heavy_packages: vec![PackageEntry {
                 name: "zod".into(),
+                version: None,
                 total_size_bytes: 500,
                 file_count: 3,
                 chain: vec!["src/index.ts".into(), "zod".into()],
@@ -945,26 +3131,162 @@ mod tests {
             modules_by_cost: vec![ModuleEntry {
                 path: "src/utils.ts".into(),
                 exclusive_size_bytes: 100,
+                lines: 42,
             }],
             total_modules_with_cost: 10,
+            packages_by_cost: vec![],
+            total_packages_with_cost: 0,
+            asset_weight_bytes: 0,
+            assets: vec![],
+            total_assets: 0,
+            lazy_boundaries: 0,
+            lazy_weight_bytes: 0,
+            resolution: ResolutionStats::default(),
+            estimate: None,
+            max_package_depth: None,
+            unexpanded_packages: Vec::new(),
             include_dynamic: false,
             top: 10,
+            show_lines: false,
+            table: None,
+            budget_exceeded: None,
         };
         let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
         assert!(json["entry"].is_string());
         assert!(json["static_weight_bytes"].is_number());
         assert!(json["heavy_packages"][0]["total_size_bytes"].is_number());
         assert!(json["modules_by_cost"][0]["exclusive_size_bytes"].is_number());
+        assert_eq!(json["modules_by_cost"][0]["lines"], 42);
         assert_eq!(json["total_modules_with_cost"], 10);
         // include_dynamic should not appear in JSON (serde skip)
         assert!(json.get("include_dynamic").is_none());
     }
 
+    #[test]
+    fn table_format_columns_fit_longest_entry() {
+        let module = |path: &str, size| ModuleEntry {
+            path: path.into(),
+            exclusive_size_bytes: size,
+            lines: 0,
+        };
+        let long_path = "node_modules/@very-long-scope/some-very-long-package-name/dist/index.js";
+        let report = TraceReport {
+            entry: "src/index.ts".into(),
+            static_weight_bytes: 1000,
+            static_module_count: 2,
+            dynamic_only_weight_bytes: 0,
+            dynamic_only_module_count: 0,
+            type_only_weight_bytes: 0,
+            type_only_module_count: 0,
+            heavy_packages: vec![],
+            modules_by_cost: vec![module("src/a.ts", 1_500_000), module(long_path, 20)],
+            total_modules_with_cost: 2,
+            packages_by_cost: vec![],
+            total_packages_with_cost: 0,
+            asset_weight_bytes: 0,
+            assets: vec![],
+            total_assets: 0,
+            lazy_boundaries: 0,
+            lazy_weight_bytes: 0,
+            resolution: ResolutionStats::default(),
+            estimate: None,
+            max_package_depth: None,
+            unexpanded_packages: Vec::new(),
+            include_dynamic: false,
+            top: 0,
+            show_lines: false,
+            table: Some(TableBorders::Ascii),
+            budget_exceeded: None,
+        };
+        let out = report.to_terminal(false);
+        let table: Vec<&str> = out
+            .lines()
+            .filter(|l| l.starts_with("  +") || l.starts_with("  |"))
+            .collect();
+        // Long paths are not truncated, and every row is as wide as the longest
+        let width = long_path.len() + 2;
+        assert_eq!(
+            table[0],
+            format!("  +{}+-----------+", "-".repeat(width)),
+            "{out}"
+        );
+        assert!(table.iter().all(|l| l.len() == table[0].len()), "{out}");
+        assert!(
+            out.contains(&format!("| {long_path} |      20 B |")),
+            "{out}"
+        );
+        assert!(out.contains("| src/a.ts "), "{out}");
+        assert!(out.contains("|    1.5 MB |"), "{out}");
+
+        let unicode = TraceReport {
+            table: Some(TableBorders::Unicode),
+            ..report
+        }
+        .to_terminal(false);
+        assert!(unicode.contains("\u{2502} Module"), "{unicode}");
+    }
+
+    #[test]
+    fn chain_report_all_below_min_hops() {
+        let report = ChainReport {
+            target: "zod".into(),
+            found_in_graph: true,
+            min_hops: 2,
+            all_below_min_hops: true,
+            chain_count: 0,
+            hop_count: 0,
+            chains: vec![],
+            hops: vec![],
+            show_specifiers: false,
+        };
+        let out = report.to_terminal(false);
+        assert!(out.contains("shorter than 2 hops"), "{out}");
+    }
+
+    #[test]
+    fn chain_report_annotates_hops_with_specifiers() {
+        let hop = |from: &str, to: &str, specifier: &str| ChainHop {
+            from: from.into(),
+            to: to.into(),
+            kind: "static",
+            specifier: specifier.into(),
+            line: None,
+        };
+        let mut report = ChainReport {
+            target: "zod".into(),
+            found_in_graph: true,
+            min_hops: 0,
+            all_below_min_hops: false,
+            chain_count: 1,
+            hop_count: 2,
+            chains: vec![vec!["a.ts".into(), "lib/b.ts".into(), "zod".into()]],
+            hops: vec![vec![
+                hop("a.ts", "lib/b.ts", "./lib/b"),
+                hop("lib/b.ts", "zod", "zod"),
+            ]],
+            show_specifiers: false,
+        };
+        assert!(
+            report
+                .to_terminal(false)
+                .contains("a.ts -> lib/b.ts -> zod")
+        );
+
+        report.show_specifiers = true;
+        let out = report.to_terminal(false);
+        assert!(
+            out.contains("a.ts --(./lib/b)--> lib/b.ts --(zod)--> zod"),
+            "{out}"
+        );
+    }
+
     #[test]
     fn chain_report_json_fields() {
         let report = ChainReport {
             target: "zod".into(),
             found_in_graph: true,
+            min_hops: 0,
+            all_below_min_hops: false,
             chain_count: 1,
             hop_count: 2,
             chains: vec![vec![
@@ -972,6 +3294,8 @@ mod tests {
                 "src/lib.ts".into(),
                 "zod".into(),
             ]],
+            hops: vec![],
+            show_specifiers: false,
         };
         let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
         assert!(json["target"].is_string());
@@ -1013,6 +3337,7 @@ mod tests {
             only_in_b: vec![],
             dynamic_only_in_a: vec![],
             dynamic_only_in_b: vec![],
+            module_changes: None,
             limit: 10,
         };
         let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
@@ -1021,6 +3346,32 @@ mod tests {
         assert!(json.get("limit").is_none());
     }
 
+    #[test]
+    fn packages_report_with_deps_lists_sub_packages() {
+        let mut report = PackagesReport {
+            package_count: 1,
+            packages: vec![PackageListEntry {
+                name: "tiny".into(),
+                size: 50,
+                files: 1,
+                dependency_size: Some(2_100_000),
+                dependencies: vec![DependencyEntry {
+                    name: "huge".into(),
+                    size: 2_100_000,
+                }],
+            }],
+            with_deps: false,
+        };
+        assert!(!report.to_terminal(false).contains("huge"));
+        report.with_deps = true;
+        let out = report.to_terminal(false);
+        assert!(out.contains("(+2.1 MB in 1 dep)"), "{out}");
+        assert!(out.contains("      huge"), "{out}");
+        let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+        assert_eq!(json["packages"][0]["dependency_size"], 2_100_000);
+        assert_eq!(json["packages"][0]["dependencies"][0]["name"], "huge");
+    }
+
     #[test]
     fn packages_report_json_fields() {
         let report = PackagesReport {
@@ -1029,13 +3380,17 @@ mod tests {
                 name: "zod".into(),
                 size: 500,
                 files: 3,
+                dependency_size: None,
+                dependencies: vec![],
             }],
+            with_deps: false,
         };
         let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
         assert_eq!(json["package_count"], 2);
         assert_eq!(json["packages"][0]["name"], "zod");
         assert_eq!(json["packages"][0]["size"], 500);
         assert_eq!(json["packages"][0]["files"], 3);
+        assert!(json["packages"][0].get("dependency_size").is_none());
     }
 
     #[test]
@@ -1046,11 +3401,27 @@ mod tests {
             static_module_count: 5,
             dynamic_only_weight_bytes: 0,
             dynamic_only_module_count: 0,
+            type_only_weight_bytes: 0,
+            type_only_module_count: 0,
             heavy_packages: vec![],
             modules_by_cost: vec![],
             total_modules_with_cost: 0,
+            packages_by_cost: vec![],
+            total_packages_with_cost: 0,
+            asset_weight_bytes: 0,
+            assets: vec![],
+            total_assets: 0,
+            lazy_boundaries: 0,
+            lazy_weight_bytes: 0,
+            resolution: ResolutionStats::default(),
+            estimate: None,
+            max_package_depth: None,
+            unexpanded_packages: Vec::new(),
             include_dynamic: false,
             top: 10,
+            show_lines: false,
+            table: None,
+            budget_exceeded: None,
         };
         let output = report.to_terminal(false);
         assert!(output.contains("src/index.ts"));
@@ -1058,6 +3429,50 @@ mod tests {
         assert!(output.contains("1 KB"));
     }
 
+    #[test]
+    fn trace_report_assets_section_separate_from_weight() {
+        let report = TraceReport {
+            entry: "src/index.ts".into(),
+            static_weight_bytes: 1000,
+            static_module_count: 5,
+            dynamic_only_weight_bytes: 0,
+            dynamic_only_module_count: 0,
+            type_only_weight_bytes: 0,
+            type_only_module_count: 0,
+            heavy_packages: vec![],
+            modules_by_cost: vec![],
+            total_modules_with_cost: 0,
+            packages_by_cost: vec![],
+            total_packages_with_cost: 0,
+            asset_weight_bytes: 250_000,
+            assets: vec![AssetEntry {
+                path: "src/styles.css".into(),
+                size_bytes: 250_000,
+            }],
+            total_assets: 1,
+            lazy_boundaries: 3,
+            lazy_weight_bytes: 1_200_000,
+            resolution: ResolutionStats::default(),
+            estimate: None,
+            max_package_depth: None,
+            unexpanded_packages: Vec::new(),
+            include_dynamic: false,
+            top: 10,
+            show_lines: false,
+            table: None,
+            budget_exceeded: None,
+        };
+        let output = report.to_terminal(false);
+        assert!(output.contains("Static transitive weight: 1 KB"));
+        assert!(output.contains("Assets: 250 KB (1 file, not counted above)"));
+        assert!(output.contains("Code splitting: 3 lazy-load boundaries gating 1.2 MB"));
+        assert!(output.contains("src/styles.css"));
+        let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+        assert_eq!(json["static_weight_bytes"], 1000);
+        assert_eq!(json["asset_weight_bytes"], 250_000);
+        assert_eq!(json["assets"][0]["size_bytes"], 250_000);
+    }
+
     #[test]
     fn trace_report_top_zero_hides_heavy_deps() {
         let report = TraceReport {
@@ -1066,11 +3481,27 @@ mod tests {
             static_module_count: 5,
             dynamic_only_weight_bytes: 0,
             dynamic_only_module_count: 0,
+            type_only_weight_bytes: 0,
+            type_only_module_count: 0,
             heavy_packages: vec![],
             modules_by_cost: vec![],
             total_modules_with_cost: 0,
+            packages_by_cost: vec![],
+            total_packages_with_cost: 0,
+            asset_weight_bytes: 0,
+            assets: vec![],
+            total_assets: 0,
+            lazy_boundaries: 0,
+            lazy_weight_bytes: 0,
+            resolution: ResolutionStats::default(),
+            estimate: None,
+            max_package_depth: None,
+            unexpanded_packages: Vec::new(),
             include_dynamic: false,
             top: 0,
+            show_lines: false,
+            table: None,
+            budget_exceeded: None,
         };
         let output = report.to_terminal(false);
         assert!(!output.contains("Heavy dependencies"));
@@ -1085,11 +3516,27 @@ mod tests {
             static_module_count: 5,
             dynamic_only_weight_bytes: 0,
             dynamic_only_module_count: 0,
+            type_only_weight_bytes: 0,
+            type_only_module_count: 0,
             heavy_packages: vec![],
             modules_by_cost: vec![],
             total_modules_with_cost: 0,
+            packages_by_cost: vec![],
+            total_packages_with_cost: 0,
+            asset_weight_bytes: 0,
+            assets: vec![],
+            total_assets: 0,
+            lazy_boundaries: 0,
+            lazy_weight_bytes: 0,
+            resolution: ResolutionStats::default(),
+            estimate: None,
+            max_package_depth: None,
+            unexpanded_packages: Vec::new(),
             include_dynamic: false,
             top: 0,
+            show_lines: false,
+            table: None,
+            budget_exceeded: None,
         };
         let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
         assert!(json.get("top").is_none());
@@ -1104,6 +3551,8 @@ mod tests {
             packages: [("zod".into(), 500)].into_iter().collect(),
             dynamic_weight: 0,
             dynamic_packages: HashMap::new(),
+            modules: HashMap::new(),
+            dynamic_imports: Vec::new(),
         };
         let b = TraceSnapshot {
             entry: "b.ts".into(),
@@ -1111,6 +3560,8 @@ mod tests {
             packages: [("chalk".into(), 300)].into_iter().collect(),
             dynamic_weight: 0,
             dynamic_packages: HashMap::new(),
+            modules: HashMap::new(),
+            dynamic_imports: Vec::new(),
         };
         let diff = query::diff_snapshots(&a, &b);
         let report = DiffReport::from_diff(&diff, "a.ts", "b.ts", 10);
@@ -1120,4 +3571,216 @@ mod tests {
         assert_eq!(report.only_in_a.len(), 1);
         assert_eq!(report.only_in_a[0].name, "zod");
     }
+
+    #[test]
+    fn diff_report_module_level_summary() {
+        use crate::query::{self, TraceSnapshot};
+        let snapshot = |weight: u64, modules: &[(&str, u64)]| TraceSnapshot {
+            entry: "index.ts".into(),
+            static_weight: weight,
+            packages: HashMap::new(),
+            dynamic_weight: 0,
+            dynamic_packages: HashMap::new(),
+            modules: modules.iter().map(|&(p, s)| (p.into(), s)).collect(),
+            dynamic_imports: Vec::new(),
+        };
+        let a = snapshot(20_000, &[("index.ts", 12_000), ("src/old.ts", 8_000)]);
+        let b = snapshot(
+            54_000,
+            &[
+                ("index.ts", 12_000),
+                ("src/a.ts", 30_000),
+                ("src/b.ts", 10_000),
+                ("src/c.ts", 2_000),
+            ],
+        );
+        let diff = query::diff_snapshots(&a, &b);
+        let mut report = DiffReport::from_diff(&diff, "before", "after", 10);
+        report.module_changes = Some(ModuleChangeset::from_diff(&diff));
+
+        let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+        assert_eq!(
+            json["summary"],
+            "This change adds 3 modules (+42 KB) and removes 1 (-8 KB), net +34 KB"
+        );
+        assert_eq!(json["added_modules"][0]["name"], "src/a.ts");
+        assert_eq!(json["removed_modules"][0]["name"], "src/old.ts");
+        let out = report.to_terminal(false);
+        assert!(out.contains("Modules added:"), "{out}");
+        assert!(out.contains("  - src/old.ts"), "{out}");
+
+        // Without --module-level the JSON shape is unchanged
+        let report = DiffReport::from_diff(&diff, "before", "after", 10);
+        let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+        assert!(json.get("summary").is_none());
+        assert!(json.get("added_modules").is_none());
+    }
+
+    #[test]
+    fn module_summary_handles_one_sided_changes() {
+        let entry = |size| DiffPackageEntry {
+            name: "m.ts".into(),
+            size,
+        };
+        assert_eq!(
+            module_summary(&[], &[entry(8_000)], -8_000),
+            "This change adds no modules and removes 1 module (-8 KB), net -8 KB"
+        );
+        assert_eq!(
+            module_summary(&[entry(500)], &[], 700),
+            "This change adds 1 module (+500 B) and removes none, net +700 B"
+        );
+    }
+
+    #[test]
+    fn count_report_has_no_detail_sections() {
+        let report = CountReport {
+            entry: "src/index.ts".into(),
+            static_weight_bytes: 1_500_000,
+            static_module_count: 42,
+            dynamic_only_weight_bytes: 0,
+            dynamic_only_module_count: 0,
+            type_only_weight_bytes: 0,
+            type_only_module_count: 0,
+            package_count: 3,
+            include_dynamic: false,
+            budget_exceeded: None,
+        };
+        let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+        assert_eq!(json["static_module_count"], 42);
+        assert_eq!(json["package_count"], 3);
+        assert!(json.get("heavy_packages").is_none());
+        assert!(json.get("modules_by_cost").is_none());
+
+        let out = report.to_terminal(false);
+        assert_eq!(
+            out,
+            "src/index.ts Static transitive weight: 1.5 MB (42 modules, 3 packages)\n"
+        );
+    }
+
+    #[test]
+    fn barrel_report_terminal_and_json() {
+        let report = BarrelReport {
+            entry: "src/main.ts".into(),
+            barrels: vec![BarrelEntry {
+                importer: "src/main.ts".into(),
+                barrel: "src/utils/index.ts".into(),
+                barrel_weight_bytes: 400_000,
+                single_module_bytes: 20_000,
+                amplification: 20.0,
+                high: true,
+            }],
+        };
+        let out = report.to_terminal(false);
+        assert!(out.contains("src/main.ts -> src/utils/index.ts"));
+        assert!(out.contains("400 KB loaded for ~20 KB  20.0x"));
+        let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+        assert_eq!(json["barrels"][0]["amplification"], 20.0);
+        assert_eq!(json["barrels"][0]["high"], true);
+    }
+
+    #[test]
+    fn json_meta_adds_label_and_timestamp() {
+        let report = CountReport {
+            entry: "src/index.ts".into(),
+            static_weight_bytes: 100,
+            static_module_count: 2,
+            dynamic_only_weight_bytes: 0,
+            dynamic_only_module_count: 0,
+            type_only_weight_bytes: 0,
+            type_only_module_count: 0,
+            package_count: 0,
+            include_dynamic: false,
+            budget_exceeded: None,
+        };
+        let meta = JsonMeta {
+            label: Some("node-20/linux".into()),
+            generated_at: "2026-01-31T12:00:00Z".into(),
+        };
+        let json: serde_json::Value = serde_json::from_str(&meta.wrap(&report)).unwrap();
+        assert_eq!(json["label"], "node-20/linux");
+        assert_eq!(json["generated_at"], "2026-01-31T12:00:00Z");
+        assert_eq!(json["static_weight_bytes"], 100);
+
+        let unlabeled = JsonMeta {
+            label: None,
+            ..meta
+        };
+        let json: serde_json::Value = serde_json::from_str(&unlabeled.wrap(&report)).unwrap();
+        assert!(json.get("label").is_none());
+        assert!(json["generated_at"].is_string());
+    }
+
+    #[test]
+    fn utc_timestamp_formats_rfc3339() {
+        assert_eq!(utc_timestamp(0), "1970-01-01T00:00:00Z");
+        assert_eq!(utc_timestamp(951_782_400), "2000-02-29T00:00:00Z");
+        assert_eq!(utc_timestamp(1_700_000_000), "2023-11-14T22:13:20Z");
+    }
+
+    #[test]
+    fn delimited_quotes_csv_but_not_tsv() {
+        let rows = vec![
+            ModuleRow {
+                path: "src/a,b.ts".into(),
+                package: None,
+                size_bytes: 10,
+                exclusive_size_bytes: 30,
+                depth: 1,
+            },
+            ModuleRow {
+                path: "node_modules/zod/index.js".into(),
+                package: Some("zod".into()),
+                size_bytes: 20,
+                exclusive_size_bytes: 20,
+                depth: 2,
+            },
+        ];
+
+        let csv = print_trace_delimited(&rows, ',', true);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "path,package,size,exclusive_size,depth");
+        assert_eq!(lines[1], "\"src/a,b.ts\",,10,30,1");
+        assert_eq!(lines[2], "node_modules/zod/index.js,zod,20,20,2");
+
+        let tsv = print_trace_delimited(&rows, '\t', false);
+        let lines: Vec<&str> = tsv.lines().collect();
+        assert_eq!(lines[0], "path\tpackage\tsize\texclusive_size\tdepth");
+        assert_eq!(lines[1], "src/a,b.ts\t\t10\t30\t1");
+    }
+
+    #[test]
+    fn delimited_escapes_quotes_and_sanitizes_raw_fields() {
+        let rows = vec![ModuleRow {
+            path: "src/\"odd\"\tname.ts".into(),
+            package: None,
+            size_bytes: 1,
+            exclusive_size_bytes: 1,
+            depth: 0,
+        }];
+        let csv = print_trace_delimited(&rows, ',', true);
+        assert_eq!(
+            csv.lines().nth(1),
+            Some("\"src/\"\"odd\"\"\tname.ts\",,1,1,0")
+        );
+        let tsv = print_trace_delimited(&rows, '\t', false);
+        assert_eq!(tsv.lines().nth(1), Some("src/\"odd\" name.ts\t\t1\t1\t0"));
+    }
+
+    #[test]
+    fn without_report_shows_saving() {
+        let report = WithoutReport {
+            entry: "src/index.ts".into(),
+            removed: "src/chart.ts".into(),
+            before_weight_bytes: 1_500_000,
+            before_module_count: 40,
+            after_weight_bytes: 300_000,
+            after_module_count: 12,
+        };
+        let out = report.to_terminal(false);
+        assert!(out.contains("src/index.ts without src/chart.ts"));
+        assert!(out.contains("1.5 MB -> 300 KB (40 modules -> 12)"));
+        assert!(out.contains("Saved: -1.2 MB (28 modules)"));
+    }
 }
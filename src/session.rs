@@ -4,18 +4,29 @@
 //! language server). It wraps graph loading, entry resolution, and keeps the
 //! background cache-write handle alive for the duration of the session.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
-use crate::cache::CacheWriteHandle;
+use crate::cache::{self, CacheMode, CacheWriteHandle, ParseCache};
 use crate::error::Error;
 use crate::graph::{EdgeId, EdgeKind, ModuleGraph, ModuleId, PackageInfo};
-use crate::loader;
+use crate::lang::{self, LanguageSupport};
+use crate::loader::{self, LoadOptions, WeightSource, WorkspaceGraph};
 use crate::query::{self, ChainTarget, CutModule, DiffResult, TraceOptions, TraceResult};
 use crate::report::{
-    self, ChainReport, CutEntry, CutReport, DiffReport, ModuleEntry, PackageEntry,
-    PackageListEntry, PackagesReport, TraceReport,
+    self, AssetEntry, BarrelEntry, BarrelReport, ChainHop, ChainReport, CountReport, CutEntry,
+    CutReport, CycleEdge, CycleEntry, CyclesReport, DeadDirectory, DeadFileEntry, DeadcodeReport,
+    DependedOnEntry, DependedOnReport, DependencyEntry, DependentsReport, DiffReport, DotEdge,
+    DotGraph, DotNode, DuplicateCopy, DuplicatePackageEntry, DuplicatesReport, DynamicModuleEntry,
+    DynamicOnlyReport, ExplainAttempt, ExplainReport, FoldedStack, LazyBoundaryEntry, MermaidGraph,
+    MermaidNode, ModuleChangeset, ModuleEntry, ModuleRow, MonorepoReport, PackageCostEntry,
+    PackageEntry, PackageListEntry, PackagesReport, ResolveEntry, ResolveReport, SavingsEntry,
+    SavingsReport, SbomPackage, SbomReport, SharedModuleEntry, SharedModulesReport, TraceReport,
+    WeightEstimate, WithoutReport, WorkspaceCost, WorkspaceEdge,
 };
+use crate::vfs::{OsVfs, Vfs};
+use crate::walker::{self, ResolutionStats};
 
 /// The result of resolving a `--chain`/`--cut` argument against the graph.
 ///
@@ -36,15 +47,145 @@ pub struct Session {
     reverse_adj: Vec<Vec<EdgeId>>,
     root: PathBuf,
     entry: PathBuf,
+    /// The entry as given, before symlinks are resolved. [`Session::refresh`]
+    /// reloads from it so root detection matches the first load.
+    requested_entry: PathBuf,
     entry_id: ModuleId,
     valid_extensions: &'static [&'static str],
     from_cache: bool,
     unresolvable_dynamic_count: usize,
     unresolvable_dynamic_files: Vec<(PathBuf, usize)>,
+    resolution: ResolutionStats,
     file_warnings: Vec<String>,
+    timed_out: bool,
+    sampled_out: usize,
+    load_opts: LoadOptions,
+    /// Set up by the first [`Session::update_files`], dropped when the
+    /// graph is reloaded.
+    patcher: Option<FilePatcher>,
     _cache_handle: CacheWriteHandle,
 }
 
+/// What [`Session::update_files`] keeps between calls: the parser and
+/// resolver, the parse cache holding each file's last imports, and the
+/// modules the entries reach over any edge.
+struct FilePatcher {
+    lang: Box<dyn LanguageSupport>,
+    cache: ParseCache,
+    reached: Vec<bool>,
+}
+
+/// One changed file, re-parsed and resolved by [`Session::update_files`].
+struct FileEdit {
+    module: ModuleId,
+    path: PathBuf,
+    size: u64,
+    mtime_nanos: Option<u128>,
+    line_count: u32,
+    parsed: lang::ParseResult,
+    resolved: Vec<Option<PathBuf>>,
+    /// Per import: the module it resolved to, `None` when unresolved.
+    targets: Vec<Option<ModuleId>>,
+    /// What the file counted toward the resolution stats before the edit.
+    old_stats: ResolutionStats,
+    content_hash: Option<u64>,
+}
+
+/// How one file's imports resolved, as it counts toward [`ResolutionStats`].
+fn file_resolution(
+    lang: &dyn LanguageSupport,
+    parsed: &lang::ParseResult,
+    resolved: &[Option<PathBuf>],
+) -> ResolutionStats {
+    let mut stats = ResolutionStats {
+        imports: parsed.imports.len(),
+        unresolvable_dynamic: parsed.unresolvable_dynamic,
+        ..ResolutionStats::default()
+    };
+    for (imp, resolved) in parsed.imports.iter().zip(resolved) {
+        match resolved {
+            Some(_) => stats.resolved += 1,
+            None if lang.is_builtin(&imp.specifier) => stats.builtin += 1,
+            None => stats.unresolved += 1,
+        }
+    }
+    stats
+}
+
+/// Update `reached`, the modules reachable from the graph's entries over
+/// any edge, after a reached module dropped its edges to `removed` and
+/// gained edges to `added`. Only modules downstream of those edges are
+/// revisited: each is let go, then taken back if an importer still reached
+/// (found through `reverse_adj`) leads to it. Returns the modules whose
+/// flag flipped.
+fn patch_reached(
+    graph: &ModuleGraph,
+    reverse_adj: &[Vec<EdgeId>],
+    reached: &mut [bool],
+    removed: &[ModuleId],
+    added: &[ModuleId],
+) -> Vec<ModuleId> {
+    let mut let_go = Vec::new();
+    let mut queue: VecDeque<ModuleId> = VecDeque::new();
+    for &mid in removed {
+        if std::mem::take(&mut reached[mid.0 as usize]) {
+            let_go.push(mid);
+            queue.push_back(mid);
+        }
+    }
+    while let Some(mid) = queue.pop_front() {
+        for &eid in graph.outgoing_edges(mid) {
+            let to = graph.edge(eid).to;
+            if std::mem::take(&mut reached[to.0 as usize]) {
+                let_go.push(to);
+                queue.push_back(to);
+            }
+        }
+    }
+
+    // Taken back from importers still reached, then from the new edges
+    let roots: Vec<ModuleId> = let_go
+        .iter()
+        .copied()
+        .filter(|&mid| {
+            graph.is_entry(mid)
+                || reverse_adj[mid.0 as usize]
+                    .iter()
+                    .any(|&eid| reached[graph.edge(eid).from.0 as usize])
+        })
+        .chain(added.iter().copied())
+        .collect();
+    let mut newly_reached = Vec::new();
+    for mid in roots {
+        if !std::mem::replace(&mut reached[mid.0 as usize], true) {
+            newly_reached.push(mid);
+            queue.push_back(mid);
+        }
+    }
+    while let Some(mid) = queue.pop_front() {
+        for &eid in graph.outgoing_edges(mid) {
+            let to = graph.edge(eid).to;
+            if !std::mem::replace(&mut reached[to.0 as usize], true) {
+                newly_reached.push(to);
+                queue.push_back(to);
+            }
+        }
+    }
+
+    let was_let_go: HashSet<ModuleId> = let_go.iter().copied().collect();
+
+    let mut flipped: Vec<ModuleId> = let_go
+        .into_iter()
+        .filter(|mid| !reached[mid.0 as usize])
+        .collect();
+    flipped.extend(
+        newly_reached
+            .into_iter()
+            .filter(|mid| !was_let_go.contains(mid)),
+    );
+    flipped
+}
+
 fn build_reverse_adj(graph: &ModuleGraph) -> Vec<Vec<EdgeId>> {
     let mut rev = vec![Vec::new(); graph.module_count()];
     for edge in &graph.edges {
@@ -58,12 +199,21 @@ impl Session {
     ///
     /// When `no_cache` is true the on-disk cache is bypassed entirely.
     pub fn open(entry: &Path, no_cache: bool) -> Result<Self, Error> {
-        let (loaded, cache_handle) = loader::load_graph(entry, no_cache)?;
+        let opts = LoadOptions {
+            no_cache,
+            ..LoadOptions::default()
+        };
+        Self::open_with(entry, &opts)
+    }
+
+    /// Like [`Session::open`], with full control over [`LoadOptions`]
+    /// (cache location, defines), which [`Session::refresh`] reuses.
+    pub fn open_with(entry: &Path, opts: &LoadOptions) -> Result<Self, Error> {
+        let (loaded, cache_handle) = loader::load_graph_with_options(entry, opts, Arc::new(OsVfs))?;
 
-        let entry_id = *loaded
+        let entry_id = loaded
             .graph
-            .path_to_id
-            .get(&loaded.entry)
+            .module_id(&loaded.entry)
             .ok_or_else(|| Error::EntryNotInGraph(loaded.entry.clone()))?;
 
         let reverse_adj = build_reverse_adj(&loaded.graph);
@@ -73,12 +223,21 @@ impl Session {
             reverse_adj,
             root: loaded.root,
             entry: loaded.entry,
+            requested_entry: entry.to_path_buf(),
             entry_id,
             valid_extensions: loaded.valid_extensions,
             from_cache: loaded.from_cache,
             unresolvable_dynamic_count: loaded.unresolvable_dynamic_count,
             unresolvable_dynamic_files: loaded.unresolvable_dynamic_files,
+            resolution: loaded.resolution,
             file_warnings: loaded.file_warnings,
+            timed_out: loaded.timed_out,
+            sampled_out: loaded.sampled_out,
+            load_opts: LoadOptions {
+                no_cache: false,
+                ..opts.clone()
+            },
+            patcher: None,
             _cache_handle: cache_handle,
         })
     }
@@ -98,7 +257,7 @@ impl Session {
             .canonicalize()
             .or_else(|_| self.root.join(file).canonicalize())
             .map_err(|e| Error::EntryNotFound(file.to_path_buf(), e))?;
-        let Some(&id) = self.graph.path_to_id.get(&canon) else {
+        let Some(id) = self.graph.module_id(&canon) else {
             return Err(Error::EntryNotInGraph(canon));
         };
         Ok((query::trace(&self.graph, id, opts), canon))
@@ -112,7 +271,7 @@ impl Session {
     pub fn resolve_target(&self, arg: &str) -> ResolvedTarget {
         if looks_like_path(arg, self.valid_extensions)
             && let Ok(target_path) = self.root.join(arg).canonicalize()
-            && let Some(&id) = self.graph.path_to_id.get(&target_path)
+            && let Some(id) = self.graph.module_id(&target_path)
         {
             let p = &self.graph.module(id).path;
             let label = p
@@ -193,7 +352,7 @@ impl Session {
             .canonicalize()
             .or_else(|_| self.root.join(other).canonicalize())
             .map_err(|e| Error::EntryNotFound(other.to_path_buf(), e))?;
-        let Some(&other_id) = self.graph.path_to_id.get(&other_canon) else {
+        let Some(other_id) = self.graph.module_id(&other_canon) else {
             return Err(Error::EntryNotInGraph(other_canon.clone()));
         };
         let snap_a = self.trace(opts).to_snapshot(&self.entry_label());
@@ -213,7 +372,7 @@ impl Session {
             .canonicalize()
             .or_else(|_| self.root.join(file).canonicalize())
             .map_err(|e| Error::EntryNotFound(file.to_path_buf(), e))?;
-        let Some(&id) = self.graph.path_to_id.get(&canon) else {
+        let Some(id) = self.graph.module_id(&canon) else {
             return Err(Error::EntryNotInGraph(canon));
         };
         let result = self
@@ -234,7 +393,7 @@ impl Session {
             .canonicalize()
             .or_else(|_| self.root.join(file).canonicalize())
             .map_err(|e| Error::EntryNotFound(file.to_path_buf(), e))?;
-        let Some(&id) = self.graph.path_to_id.get(&canon) else {
+        let Some(id) = self.graph.module_id(&canon) else {
             return Err(Error::EntryNotInGraph(canon));
         };
         let result = self.reverse_adj[id.0 as usize]
@@ -252,6 +411,11 @@ impl Session {
         self.graph.package_map.get(package_name)
     }
 
+    /// The packages `package_name` statically pulls in, heaviest first.
+    pub fn package_dependencies(&self, package_name: &str) -> Vec<(String, u64)> {
+        self.graph.transitive_dependencies(package_name)
+    }
+
     /// Display label for the current entry point, including the project
     /// directory name for disambiguation (e.g. `wrangler/src/index.ts`).
     pub fn entry_label(&self) -> String {
@@ -272,9 +436,10 @@ impl Session {
             .canonicalize()
             .or_else(|_| self.root.join(path).canonicalize())
             .map_err(|e| Error::EntryNotFound(path.to_path_buf(), e))?;
-        let Some(&id) = self.graph.path_to_id.get(&canon) else {
+        let Some(id) = self.graph.module_id(&canon) else {
             return Err(Error::EntryNotInGraph(canon));
         };
+        self.requested_entry.clone_from(&canon);
         self.entry = canon;
         self.entry_id = id;
         Ok(())
@@ -286,16 +451,23 @@ impl Session {
     /// changed since the last load).
     #[allow(clippy::used_underscore_binding)] // _cache_handle held for drop
     pub fn refresh(&mut self) -> Result<bool, Error> {
-        let (loaded, handle) = loader::load_graph(&self.entry, false)?;
-        let Some(&entry_id) = loaded.graph.path_to_id.get(&loaded.entry) else {
+        let (loaded, handle) = loader::load_graph_with_options(
+            &self.requested_entry,
+            &self.load_opts,
+            Arc::new(OsVfs),
+        )?;
+        let Some(entry_id) = loaded.graph.module_id(&loaded.entry) else {
             return Err(Error::EntryNotInGraph(loaded.entry));
         };
         // Detect structural change: cold build (not from cache) or module count
         // changed. When from_cache is true and module count matches, edges are
         // guaranteed identical (tier 1.5 only returns from_cache when imports
         // are unchanged), so we can reuse the existing reverse adjacency index.
-        let changed =
-            !loaded.from_cache || loaded.graph.module_count() != self.graph.module_count();
+        // Unless update_files patched edges since: the cache never saw those.
+        let patched = self.patcher.take().is_some();
+        let changed = patched
+            || !loaded.from_cache
+            || loaded.graph.module_count() != self.graph.module_count();
         if changed {
             self.reverse_adj = build_reverse_adj(&loaded.graph);
         } else {
@@ -313,11 +485,188 @@ impl Session {
         self.from_cache = loaded.from_cache;
         self.unresolvable_dynamic_count = loaded.unresolvable_dynamic_count;
         self.unresolvable_dynamic_files = loaded.unresolvable_dynamic_files;
+        self.resolution = loaded.resolution;
         self.file_warnings = loaded.file_warnings;
+        self.timed_out = loaded.timed_out;
+        self.sampled_out = loaded.sampled_out;
         self._cache_handle = handle;
         Ok(changed)
     }
 
+    /// Replace the graph with its [type graph](ModuleGraph::type_graph), so
+    /// every report covers only `import type` edges.
+    pub fn restrict_to_type_graph(&mut self) {
+        self.graph = self.graph.type_graph();
+        self.reverse_adj = build_reverse_adj(&self.graph);
+        self.patcher = None;
+    }
+
+    /// Apply edits to files already in the graph without reloading it, for
+    /// `--watch`. Each file is re-parsed and its imports re-resolved, its
+    /// edges are swapped in place, and only the reachability they change
+    /// and the packages owning a module that was edited, newly reached or
+    /// left behind are recomputed. Returns those packages, sorted.
+    ///
+    /// `None`, with the graph untouched, when the edits can't be patched
+    /// in: a file that is new, gone or unparseable, an import of a file not
+    /// in the graph yet, gzip weights, or a sampled or partial graph. Call
+    /// [`refresh`](Self::refresh) then.
+    #[allow(clippy::used_underscore_binding)] // _cache_handle held for drop
+    pub fn update_files(&mut self, changed: &[PathBuf]) -> Option<Vec<String>> {
+        if self.load_opts.sample.is_some()
+            || self.timed_out
+            || self.load_opts.weight_source == WeightSource::Gzip
+        {
+            return None;
+        }
+        let vfs: Arc<dyn Vfs> = Arc::new(OsVfs);
+        if self.patcher.is_none() {
+            // The load's cache write must land before it is read back
+            std::mem::replace(&mut self._cache_handle, CacheWriteHandle::none()).join();
+            let (lang, cache) =
+                loader::file_parser(&self.requested_entry, &self.load_opts, &vfs).ok()?;
+            let reached = self.graph.reachable_set(&self.graph.entries, |_| true);
+            self.patcher = Some(FilePatcher {
+                lang,
+                cache,
+                reached,
+            });
+        }
+        // Everything is parsed and resolved before the graph is touched
+        let edits = changed
+            .iter()
+            .map(|path| self.parse_edit(path, &*vfs))
+            .collect::<Option<Vec<FileEdit>>>()?;
+        let mut affected = HashSet::new();
+        for edit in edits {
+            self.apply_edit(edit, &mut affected);
+        }
+        let patcher = self.patcher.as_ref()?;
+        debug_assert_eq!(
+            patcher.reached,
+            self.graph.reachable_set(&self.graph.entries, |_| true),
+            "reachability drifted from a full walk after patching edges"
+        );
+        self.graph.recompute_packages(&affected, &patcher.reached);
+        let mut recomputed: Vec<String> = affected.into_iter().collect();
+        recomputed.sort();
+        Some(recomputed)
+    }
+
+    /// Re-parse and resolve `path` for [`update_files`](Self::update_files),
+    /// leaving the graph as it is.
+    fn parse_edit(&mut self, path: &Path, vfs: &dyn Vfs) -> Option<FileEdit> {
+        let patcher = self.patcher.as_mut()?;
+        let module = self.graph.module_id(path)?;
+        let path = self.graph.module(module).path.clone();
+        let (source, meta) = vfs.read_with_metadata(&path).ok()?;
+        let parsed = patcher.lang.parse(&path, &source).ok()?;
+        let resolved: Vec<Option<PathBuf>> = parsed
+            .imports
+            .iter()
+            .map(|imp| patcher.lang.resolve_import(&path, imp))
+            .collect();
+        let targets = resolved
+            .iter()
+            .map(|p| match p {
+                Some(p) => self.graph.module_id(p).map(Some),
+                None => Some(None),
+            })
+            .collect::<Option<Vec<Option<ModuleId>>>>()?;
+        let (old, old_resolved) = patcher.cache.lookup_resolved_unchecked(&path)?;
+        let old_stats = file_resolution(&*patcher.lang, old, old_resolved);
+        let content_hash = (patcher.cache.mode() == CacheMode::Hash)
+            .then(|| cache::content_hash(&path, source.as_bytes()));
+        Some(FileEdit {
+            module,
+            path,
+            size: meta.len,
+            mtime_nanos: meta.mtime_nanos,
+            line_count: walker::count_lines(&source),
+            parsed,
+            resolved,
+            targets,
+            old_stats,
+            content_hash,
+        })
+    }
+
+    /// Swap one file's size and edges into the graph, and add the packages
+    /// whose info it changes to `affected`.
+    fn apply_edit(&mut self, edit: FileEdit, affected: &mut HashSet<String>) {
+        let Some(patcher) = self.patcher.as_mut() else {
+            return;
+        };
+        let mid = edit.module;
+        let module = &mut self.graph.modules[mid.0 as usize];
+        module.size_bytes = edit.size;
+        module.line_count = edit.line_count;
+        affected.extend(module.package.clone());
+
+        let old_targets: HashSet<ModuleId> = self
+            .graph
+            .outgoing_edges(mid)
+            .iter()
+            .map(|&eid| self.graph.edge(eid).to)
+            .collect();
+        let imports: Vec<(ModuleId, EdgeKind, &str)> = edit
+            .parsed
+            .imports
+            .iter()
+            .zip(&edit.targets)
+            .filter_map(|(imp, &to)| Some((to?, imp.kind, imp.specifier.as_str())))
+            .collect();
+        let new_targets: HashSet<ModuleId> = imports.iter().map(|&(to, ..)| to).collect();
+        self.graph
+            .replace_edges(mid, &imports, &mut self.reverse_adj);
+        // Edges out of a module nothing reaches change no reachability
+        if patcher.reached[mid.0 as usize] {
+            let removed: Vec<ModuleId> = old_targets.difference(&new_targets).copied().collect();
+            let added: Vec<ModuleId> = new_targets.difference(&old_targets).copied().collect();
+            let flipped = patch_reached(
+                &self.graph,
+                &self.reverse_adj,
+                &mut patcher.reached,
+                &removed,
+                &added,
+            );
+            affected.extend(
+                flipped
+                    .into_iter()
+                    .filter_map(|m| self.graph.module(m).package.clone()),
+            );
+        }
+
+        let (old, new) = (
+            edit.old_stats,
+            file_resolution(&*patcher.lang, &edit.parsed, &edit.resolved),
+        );
+        let r = &mut self.resolution;
+        r.imports = r.imports - old.imports + new.imports;
+        r.resolved = r.resolved - old.resolved + new.resolved;
+        r.builtin = r.builtin - old.builtin + new.builtin;
+        r.unresolved = r.unresolved - old.unresolved + new.unresolved;
+        r.unresolvable_dynamic =
+            r.unresolvable_dynamic - old.unresolvable_dynamic + new.unresolvable_dynamic;
+        self.unresolvable_dynamic_count = r.unresolvable_dynamic;
+        self.unresolvable_dynamic_files
+            .retain(|(p, _)| *p != edit.path);
+        if new.unresolvable_dynamic > 0 {
+            self.unresolvable_dynamic_files
+                .push((edit.path.clone(), new.unresolvable_dynamic));
+        }
+        if let Some(mtime) = edit.mtime_nanos {
+            patcher.cache.insert(
+                edit.path,
+                edit.size,
+                mtime,
+                edit.parsed,
+                edit.resolved,
+                edit.content_hash,
+            );
+        }
+    }
+
     // -- report builders --
 
     /// Trace and produce a display-ready report.
@@ -326,6 +675,229 @@ impl Session {
         self.build_trace_report(&result, self.entry(), opts, top_modules)
     }
 
+    /// Every reachable module, sorted by exclusive weight, for the
+    /// delimited module table export.
+    pub fn module_rows(&self, opts: &TraceOptions) -> Vec<ModuleRow> {
+        let result = self.trace(opts);
+        let depths = query::import_depths(&self.graph, self.entry_id, opts.include_dynamic);
+        result
+            .modules_by_cost
+            .iter()
+            .map(|mc| {
+                let module = self.graph.module(mc.module_id);
+                ModuleRow {
+                    path: report::relative_path(&module.path, &self.root),
+                    package: module.package.clone(),
+                    size_bytes: module.size_bytes,
+                    exclusive_size_bytes: mc.exclusive_size,
+                    depth: depths[mc.module_id.0 as usize],
+                }
+            })
+            .collect()
+    }
+
+    /// Folded flamegraph stacks: one per reachable module, along its
+    /// dominator-tree path from the entry, sorted by stack.
+    pub fn folded_stacks(&self, opts: &TraceOptions) -> Vec<FoldedStack> {
+        let frame = |mid: ModuleId| {
+            let m = self.graph.module(mid);
+            m.package.as_ref().map_or_else(
+                || report::relative_path(&m.path, &self.root),
+                |pkg| report::package_relative_path(&m.path, pkg),
+            )
+        };
+        let mut stacks: Vec<FoldedStack> =
+            query::dominator_paths(&self.graph, self.entry_id, opts.include_dynamic)
+                .into_iter()
+                .filter_map(|path| {
+                    let size_bytes = self.graph.module(*path.last()?).size_bytes;
+                    (size_bytes > 0).then(|| FoldedStack {
+                        frames: path.into_iter().map(frame).collect(),
+                        size_bytes,
+                    })
+                })
+                .collect();
+        stacks.sort_by(|a, b| a.frames.cmp(&b.frames));
+        stacks
+    }
+
+    /// The modules reachable from the entry and the imports between them,
+    /// for `--format dot`. Past `collapse_depth` import hops, package
+    /// modules are drawn as one `<package>/…` node per collapsed subtree.
+    pub fn dot_graph(&self, opts: &TraceOptions, collapse_depth: Option<u32>) -> DotGraph {
+        let follows = |kind: EdgeKind| {
+            kind == EdgeKind::Static || (opts.include_dynamic && kind == EdgeKind::Dynamic)
+        };
+        let reachable = self
+            .graph
+            .reachable_set(&[self.entry_id], |e| follows(e.kind));
+        let collapsed = self.collapse_groups(follows, collapse_depth);
+        let label = |mid: ModuleId| match collapsed[mid.0 as usize] {
+            Some(pkg) => format!("{pkg}/…"),
+            None => report::relative_path(&self.graph.module(mid).path, &self.root),
+        };
+        let mut nodes: BTreeMap<String, DotNode> = BTreeMap::new();
+        for m in self
+            .graph
+            .modules
+            .iter()
+            .filter(|m| reachable[m.id.0 as usize])
+        {
+            let package = collapsed[m.id.0 as usize].or(m.package.as_deref());
+            nodes
+                .entry(label(m.id))
+                .or_insert_with_key(|path| DotNode {
+                    path: path.clone(),
+                    package: package.map(str::to_string),
+                    size_bytes: 0,
+                })
+                .size_bytes += m.size_bytes;
+        }
+        let mut edges: Vec<DotEdge> = self
+            .graph
+            .edges
+            .iter()
+            .filter(|e| follows(e.kind) && reachable[e.from.0 as usize])
+            // Imports inside a collapsed subtree are part of its node
+            .filter(|e| {
+                let group = |mid: ModuleId| collapsed[mid.0 as usize];
+                group(e.from).is_none() || group(e.from) != group(e.to)
+            })
+            .map(|e| DotEdge {
+                from: label(e.from),
+                to: label(e.to),
+                kind: edge_kind_label(e.kind),
+            })
+            .collect();
+        edges.sort_by(|a, b| (&a.from, &a.to, a.kind).cmp(&(&b.from, &b.to, b.kind)));
+        edges.dedup_by(|a, b| (&a.from, &a.to, a.kind) == (&b.from, &b.to, b.kind));
+        DotGraph {
+            nodes: nodes.into_values().collect(),
+            edges,
+        }
+    }
+
+    /// For `--collapse-depth`: the package each package module more than
+    /// `depth` import hops from the entry is rolled up into, which is the
+    /// package where its shortest chain first went past `depth`. `None` for
+    /// modules drawn on their own, and for every module without a `depth`.
+    fn collapse_groups(
+        &self,
+        follows: impl Fn(EdgeKind) -> bool,
+        depth: Option<u32>,
+    ) -> Vec<Option<&str>> {
+        let mut group = vec![None; self.graph.modules.len()];
+        let Some(depth) = depth else {
+            return group;
+        };
+        let mut hops = vec![u32::MAX; self.graph.modules.len()];
+        hops[self.entry_id.0 as usize] = 0;
+        let mut queue = VecDeque::from([self.entry_id]);
+        while let Some(mid) = queue.pop_front() {
+            for &eid in self.graph.outgoing_edges(mid) {
+                let edge = self.graph.edge(eid);
+                let to = edge.to.0 as usize;
+                if !follows(edge.kind) || hops[to] != u32::MAX {
+                    continue;
+                }
+                hops[to] = hops[mid.0 as usize] + 1;
+                if hops[to] > depth
+                    && let Some(pkg) = self.graph.module(edge.to).package.as_deref()
+                {
+                    group[to] = Some(group[mid.0 as usize].unwrap_or(pkg));
+                }
+                queue.push_back(edge.to);
+            }
+        }
+        group
+    }
+
+    /// The import graph for `--format mermaid`, trimmed to stay readable:
+    /// the entry, what it imports directly, and the heavy packages (as many
+    /// as `--top` shows). Each package is drawn as one node. Past
+    /// `collapse_depth` import hops, a package's dependencies are rolled up
+    /// into its node too.
+    pub fn mermaid_graph(&self, opts: &TraceOptions, collapse_depth: Option<u32>) -> MermaidGraph {
+        let follows = |kind: EdgeKind| {
+            kind == EdgeKind::Static || (opts.include_dynamic && kind == EdgeKind::Dynamic)
+        };
+        let reachable = self
+            .graph
+            .reachable_set(&[self.entry_id], |e| follows(e.kind));
+        let collapsed = self.collapse_groups(follows, collapse_depth);
+        // A package's modules all share its node, keyed by package name
+        let key = |mid: ModuleId| {
+            let m = self.graph.module(mid);
+            collapsed[mid.0 as usize]
+                .or(m.package.as_deref())
+                .map_or_else(
+                    || report::relative_path(&m.path, &self.root),
+                    str::to_string,
+                )
+        };
+
+        let mut kept: HashMap<String, MermaidNode> = HashMap::new();
+        let mut keep = |mid: ModuleId| {
+            let m = self.graph.module(mid);
+            kept.entry(key(mid)).or_insert_with(|| MermaidNode {
+                label: key(mid),
+                is_package: m.package.is_some(),
+                size_bytes: 0,
+            });
+        };
+        keep(self.entry_id);
+        for &eid in self.graph.outgoing_edges(self.entry_id) {
+            let edge = self.graph.edge(eid);
+            if follows(edge.kind) {
+                keep(edge.to);
+            }
+        }
+        for pkg in &self.trace(opts).heavy_packages {
+            keep(pkg.chain.last().copied().unwrap_or(self.entry_id));
+        }
+
+        let mut total = HashMap::new();
+        for m in self
+            .graph
+            .modules
+            .iter()
+            .filter(|m| reachable[m.id.0 as usize])
+        {
+            *total.entry(key(m.id)).or_insert(0u64) += m.size_bytes;
+        }
+        for (name, node) in &mut kept {
+            node.size_bytes = total.get(name).copied().unwrap_or_default();
+        }
+
+        let mut edges: BTreeMap<(String, String), &'static str> = BTreeMap::new();
+        for e in &self.graph.edges {
+            if !follows(e.kind) || !reachable[e.from.0 as usize] {
+                continue;
+            }
+            let (from, to) = (key(e.from), key(e.to));
+            if from == to || !kept.contains_key(&from) || !kept.contains_key(&to) {
+                continue;
+            }
+            let kind = edges.entry((from, to)).or_insert(edge_kind_label(e.kind));
+            // Drawn solid when any of the collapsed imports is static
+            if e.kind == EdgeKind::Static {
+                *kind = edge_kind_label(e.kind);
+            }
+        }
+
+        let omitted = total.len() - kept.len();
+        let mut nodes: Vec<MermaidNode> = kept.into_values().collect();
+        nodes.sort_by(|a, b| a.label.cmp(&b.label));
+        MermaidGraph {
+            nodes,
+            edges: edges
+                .into_iter()
+                .map(|((from, to), kind)| DotEdge { from, to, kind })
+                .collect(),
+            omitted,
+        }
+    }
+
     /// Trace from a different file and produce a display-ready report.
     pub fn trace_from_report(
         &self,
@@ -340,6 +912,28 @@ impl Session {
         ))
     }
 
+    /// Extrapolated totals for `result` when the graph was built from a
+    /// [`LoadOptions::sample`]. Depths are counted from the session entry,
+    /// where discovery started, whichever module was traced.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn sample_estimate(&self, result: &TraceResult) -> Option<WeightEstimate> {
+        let sample = self.load_opts.sample?;
+        let estimate = query::estimate_from_sample(
+            &self.graph,
+            self.entry_id,
+            &result.reachable,
+            sample.fraction,
+        );
+        Some(WeightEstimate {
+            approximate: true,
+            sample_fraction: sample.fraction,
+            seed: sample.seed,
+            sampled_out_files: self.sampled_out,
+            estimated_weight_bytes: estimate.weight.round() as u64,
+            estimated_module_count: estimate.module_count.round() as usize,
+        })
+    }
+
     #[allow(clippy::cast_sign_loss)]
     fn build_trace_report(
         &self,
@@ -353,6 +947,7 @@ impl Session {
             .iter()
             .map(|pkg| PackageEntry {
                 name: pkg.name.clone(),
+                version: None,
                 total_size_bytes: pkg.total_size,
                 file_count: pkg.file_count,
                 chain: report::chain_display_names(&self.graph, &pkg.chain, &self.root),
@@ -369,6 +964,23 @@ impl Session {
             .map(|mc| ModuleEntry {
                 path: report::relative_path(&self.graph.module(mc.module_id).path, &self.root),
                 exclusive_size_bytes: mc.exclusive_size,
+                lines: self.graph.module(mc.module_id).line_count,
+            })
+            .collect();
+
+        let asset_count = if opts.top_n < 0 {
+            result.assets.len()
+        } else {
+            result.assets.len().min(opts.top_n as usize)
+        };
+        let assets = result.assets[..asset_count]
+            .iter()
+            .map(|&mid| {
+                let m = self.graph.module(mid);
+                AssetEntry {
+                    path: report::relative_path(&m.path, &self.root),
+                    size_bytes: m.size_bytes,
+                }
             })
             .collect();
 
@@ -378,29 +990,631 @@ impl Session {
             static_module_count: result.static_module_count,
             dynamic_only_weight_bytes: result.dynamic_only_weight,
             dynamic_only_module_count: result.dynamic_only_module_count,
+            type_only_weight_bytes: result.type_only_weight,
+            type_only_module_count: result.type_only_module_count,
             heavy_packages,
             modules_by_cost,
             total_modules_with_cost: result.modules_by_cost.len(),
+            packages_by_cost: Vec::new(),
+            total_packages_with_cost: 0,
+            asset_weight_bytes: result.asset_weight,
+            assets,
+            total_assets: result.assets.len(),
+            lazy_boundaries: result.lazy_boundary_count,
+            lazy_weight_bytes: result.lazy_boundary_weight,
+            resolution: self.resolution,
+            estimate: self.sample_estimate(result),
+            max_package_depth: opts.max_package_depth,
+            unexpanded_packages: result.unexpanded_packages.clone(),
             include_dynamic: opts.include_dynamic,
             top: opts.top_n,
+            show_lines: false,
+            table: None,
+            budget_exceeded: None,
+        }
+    }
+
+    /// Like [`Session::trace_report`], with the module table replaced by
+    /// every reachable module rolled up by package: total size and largest
+    /// module per package, first-party code (entry included) under
+    /// [`report::LOCAL_PACKAGE`], so the rows add up to the traced weight.
+    /// `top_modules` caps the package rows.
+    #[allow(clippy::cast_sign_loss)]
+    pub fn package_cost_report(&self, opts: &TraceOptions, top_modules: i32) -> TraceReport {
+        let result = self.trace(opts);
+        let mut report = self.build_trace_report(&result, self.entry(), opts, 0);
+        let mut groups: HashMap<&str, PackageCostEntry> = HashMap::new();
+        for &mid in &result.reachable {
+            let m = self.graph.module(mid);
+            let name = m.package.as_deref().unwrap_or(report::LOCAL_PACKAGE);
+            let group = groups.entry(name).or_insert_with(|| PackageCostEntry {
+                package: name.to_string(),
+                size_bytes: 0,
+                module_count: 0,
+                largest_module: String::new(),
+                largest_module_bytes: 0,
+            });
+            group.size_bytes += m.size_bytes;
+            group.module_count += 1;
+            if group.largest_module.is_empty() || m.size_bytes > group.largest_module_bytes {
+                group.largest_module = m.package.as_ref().map_or_else(
+                    || report::relative_path(&m.path, &self.root),
+                    |pkg| report::package_relative_path(&m.path, pkg),
+                );
+                group.largest_module_bytes = m.size_bytes;
+            }
+        }
+        let mut packages: Vec<PackageCostEntry> = groups.into_values().collect();
+        packages.sort_by(|a, b| {
+            b.size_bytes
+                .cmp(&a.size_bytes)
+                .then_with(|| a.package.cmp(&b.package))
+        });
+        report.total_packages_with_cost = packages.len();
+        if top_modules >= 0 {
+            packages.truncate(top_modules as usize);
+        }
+        report.packages_by_cost = packages;
+        report
+    }
+
+    /// Third-party packages only: every reachable package with its version
+    /// and import chain, and no module or asset rows.
+    pub fn packages_only_report(&self, opts: &TraceOptions) -> TraceReport {
+        let mut report = self.trace_report(opts, 0);
+        report.assets.clear();
+        report.total_assets = 0;
+        for pkg in &mut report.heavy_packages {
+            pkg.version = self.package_version(&pkg.name);
+        }
+        report
+    }
+
+    /// Compute only the headline totals and produce a display-ready report.
+    pub fn count_report(&self, opts: &TraceOptions) -> CountReport {
+        let counts = query::trace_counts(&self.graph, self.entry_id, opts);
+        CountReport {
+            entry: report::relative_path(&self.entry, &self.root),
+            static_weight_bytes: counts.static_weight,
+            static_module_count: counts.static_module_count,
+            dynamic_only_weight_bytes: counts.dynamic_only_weight,
+            dynamic_only_module_count: counts.dynamic_only_module_count,
+            type_only_weight_bytes: counts.type_only_weight,
+            type_only_module_count: counts.type_only_module_count,
+            package_count: counts.package_count,
+            include_dynamic: opts.include_dynamic,
+            budget_exceeded: None,
+        }
+    }
+
+    /// Import cycles reachable from the entry, as paths relative to the
+    /// project root. Each cycle and the list are sorted for stable output.
+    pub fn import_cycles(&self, include_dynamic: bool) -> Vec<Vec<String>> {
+        let mut cycles: Vec<Vec<String>> =
+            query::find_cycles(&self.graph, self.entry_id, include_dynamic)
+                .into_iter()
+                .map(|cycle| {
+                    let mut paths: Vec<String> = cycle
+                        .iter()
+                        .map(|&mid| report::relative_path(&self.graph.module(mid).path, &self.root))
+                        .collect();
+                    paths.sort_unstable();
+                    paths
+                })
+                .collect();
+        cycles.sort_unstable();
+        cycles
+    }
+
+    /// Every import cycle reachable from the entry, type-only ones included
+    /// but flagged, and produce a display-ready report.
+    pub fn cycles_report(&self) -> CyclesReport {
+        let label = |mid: ModuleId| report::relative_path(&self.graph.module(mid).path, &self.root);
+        let mut cycles: Vec<CycleEntry> = query::find_all_cycles(&self.graph, self.entry_id)
+            .into_iter()
+            .map(|cycle| CycleEntry {
+                modules: cycle.modules.iter().map(|&mid| label(mid)).collect(),
+                closing_edges: cycle
+                    .closing_edges
+                    .iter()
+                    .map(|&eid| {
+                        let edge = self.graph.edge(eid);
+                        CycleEdge {
+                            from: label(edge.from),
+                            to: label(edge.to),
+                            kind: edge_kind_label(edge.kind),
+                            specifier: edge.specifier.clone(),
+                        }
+                    })
+                    .collect(),
+                type_only: cycle.type_only,
+            })
+            .collect();
+        cycles.sort_by(|a, b| (a.type_only, &a.modules).cmp(&(b.type_only, &b.modules)));
+        CyclesReport {
+            entry: report::relative_path(&self.entry, &self.root),
+            cycles,
+        }
+    }
+
+    /// Source files under the project root that the entry never imports,
+    /// grouped by directory. Reachability counts every import kind; files
+    /// matching `exclude` are left out, and so are test files unless
+    /// `include_tests` is set, since they are entry points of their own.
+    /// Declaration files (`global.d.ts`, `vite-env.d.ts`) are loaded by the
+    /// compiler without an import, so they are left out unless
+    /// `include_declarations` is set.
+    pub fn deadcode_report(
+        &self,
+        exclude: Option<&query::Exclude>,
+        include_tests: bool,
+        include_declarations: bool,
+    ) -> Result<DeadcodeReport, Error> {
+        let lang = self.language_support()?;
+        let opts = TraceOptions {
+            include_dynamic: true,
+            count_type_only: true,
+            ..TraceOptions::default()
+        };
+        let reachable: HashSet<&Path> = query::trace(&self.graph, self.entry_id, &opts)
+            .reachable
+            .iter()
+            .map(|&mid| self.graph.module(mid).path.as_path())
+            .collect();
+
+        let mut tests_skipped = 0;
+        let mut declarations_skipped = 0;
+        let mut by_dir: BTreeMap<String, Vec<DeadFileEntry>> = BTreeMap::new();
+        for (path, size_bytes) in project_files(&self.root, lang.extensions(), lang.skip_dirs()) {
+            if reachable.contains(path.as_path()) || exclude.is_some_and(|e| e.matches_path(&path))
+            {
+                continue;
+            }
+            if !include_tests && is_test_file(&path) {
+                tests_skipped += 1;
+                continue;
+            }
+            if !include_declarations && is_declaration_file(&path) {
+                declarations_skipped += 1;
+                continue;
+            }
+            let dir = path.parent().map_or_else(
+                || ".".to_string(),
+                |dir| report::relative_path(dir, &self.root),
+            );
+            by_dir.entry(dir).or_default().push(DeadFileEntry {
+                path: report::relative_path(&path, &self.root),
+                size_bytes,
+            });
+        }
+
+        let mut directories: Vec<DeadDirectory> = by_dir
+            .into_iter()
+            .map(|(dir, mut files)| {
+                files.sort_by(|a, b| a.path.cmp(&b.path));
+                DeadDirectory {
+                    dir: if dir.is_empty() { ".".to_string() } else { dir },
+                    size_bytes: files.iter().map(|f| f.size_bytes).sum(),
+                    files,
+                }
+            })
+            .collect();
+        directories.sort_by(|a, b| b.size_bytes.cmp(&a.size_bytes).then(a.dir.cmp(&b.dir)));
+        Ok(DeadcodeReport {
+            entry: report::relative_path(&self.entry, &self.root),
+            dead_bytes: directories.iter().map(|d| d.size_bytes).sum(),
+            dead_file_count: directories.iter().map(|d| d.files.len()).sum(),
+            tests_skipped,
+            declarations_skipped,
+            directories,
+        })
+    }
+
+    /// Packages installed more than once, with each copy's version, size,
+    /// and one import chain to it (`--duplicates`).
+    pub fn duplicates_report(&self, include_dynamic: bool) -> DuplicatesReport {
+        let packages = query::duplicate_packages(&self.graph, self.entry_id, include_dynamic)
+            .into_iter()
+            .map(|dup| DuplicatePackageEntry {
+                copies: dup
+                    .copies
+                    .iter()
+                    .map(|copy| {
+                        let chain = query::find_all_chains(
+                            &self.graph,
+                            self.entry_id,
+                            &ChainTarget::Module(copy.first_module),
+                            include_dynamic,
+                        );
+                        DuplicateCopy {
+                            version: package_version(
+                                &self.graph.module(copy.first_module).path,
+                                &dup.name,
+                            ),
+                            path: report::relative_path(&copy.dir, &self.root),
+                            size_bytes: copy.total_size,
+                            file_count: copy.file_count,
+                            chain: chain.first().map_or_else(Vec::new, |c| {
+                                report::chain_display_names(&self.graph, c, &self.root)
+                            }),
+                        }
+                    })
+                    .collect(),
+                name: dup.name,
+            })
+            .collect();
+        DuplicatesReport {
+            entry: report::relative_path(&self.entry, &self.root),
+            packages,
+        }
+    }
+
+    /// Reachable packages ranked by the weight that disappears with them
+    /// (`--savings`).
+    pub fn dynamic_only_report(&self, opts: &TraceOptions, top_modules: i32) -> DynamicOnlyReport {
+        let counts = query::trace_counts(&self.graph, self.entry_id, opts);
+        let result = query::dynamic_only(&self.graph, self.entry_id, opts);
+        let label = |mid: ModuleId| report::relative_path(&self.graph.module(mid).path, &self.root);
+        let limit = |n: i32, len: usize| usize::try_from(n).map_or(len, |n| n.min(len));
+        let modules = result
+            .modules
+            .iter()
+            .take(limit(top_modules, result.modules.len()))
+            .map(|&mid| DynamicModuleEntry {
+                path: label(mid),
+                size_bytes: self.graph.module(mid).size_bytes,
+            })
+            .collect();
+        let boundaries = result
+            .boundaries
+            .iter()
+            .take(limit(opts.top_n, result.boundaries.len()))
+            .map(|b| LazyBoundaryEntry {
+                importer: label(b.importer),
+                target: label(b.target),
+                weight_bytes: b.weight,
+                module_count: b.module_count,
+            })
+            .collect();
+        DynamicOnlyReport {
+            entry: report::relative_path(&self.entry, &self.root),
+            static_weight_bytes: counts.static_weight,
+            dynamic_only_weight_bytes: result.weight,
+            dynamic_only_module_count: result.modules.len(),
+            modules,
+            boundaries,
+            boundary_count: result.boundaries.len(),
+        }
+    }
+
+    pub fn savings_report(&self, opts: &TraceOptions) -> SavingsReport {
+        let counts = query::trace_counts(&self.graph, self.entry_id, opts);
+        let packages =
+            query::package_savings(&self.graph, self.entry_id, opts.include_dynamic, opts.top_n)
+                .into_iter()
+                .map(|pkg| SavingsEntry {
+                    name: pkg.name,
+                    size_bytes: pkg.total_size,
+                    savings_bytes: pkg.dominated_size,
+                })
+                .collect();
+        SavingsReport {
+            entry: report::relative_path(&self.entry, &self.root),
+            static_weight_bytes: counts.static_weight,
+            packages,
+            include_dynamic: opts.include_dynamic,
+        }
+    }
+
+    /// Modules that both `package` and the entry's own code reach
+    /// (`--shared-with`).
+    pub fn shared_modules_report(
+        &self,
+        package: &str,
+        include_dynamic: bool,
+    ) -> SharedModulesReport {
+        let shared =
+            query::shared_with_package(&self.graph, self.entry_id, package, include_dynamic);
+        SharedModulesReport {
+            entry: report::relative_path(&self.entry, &self.root),
+            package: package.to_string(),
+            package_found: shared.is_some(),
+            modules: shared
+                .unwrap_or_default()
+                .into_iter()
+                .map(|mid| {
+                    let module = self.graph.module(mid);
+                    SharedModuleEntry {
+                        path: report::relative_path(&module.path, &self.root),
+                        package: module.package.clone(),
+                        size_bytes: module.size_bytes,
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    /// Modules importing `file` directly and, with `transitive`, every module
+    /// that reaches it through imports of any kind.
+    pub fn dependents_report(
+        &self,
+        file: &Path,
+        transitive: bool,
+    ) -> Result<DependentsReport, Error> {
+        let canon = file
+            .canonicalize()
+            .or_else(|_| self.root.join(file).canonicalize())
+            .map_err(|e| Error::EntryNotFound(file.to_path_buf(), e))?;
+        let Some(target) = self.graph.module_id(&canon) else {
+            return Err(Error::EntryNotInGraph(canon));
+        };
+        let label = |ids: Vec<ModuleId>| -> Vec<String> {
+            ids.into_iter()
+                .map(|mid| report::relative_path(&self.graph.module(mid).path, &self.root))
+                .collect()
+        };
+        Ok(DependentsReport {
+            target: report::relative_path(&canon, &self.root),
+            direct: label(query::find_dependents(
+                &self.graph,
+                &self.reverse_adj,
+                target,
+                false,
+            )),
+            transitive: transitive.then(|| {
+                label(query::find_dependents(
+                    &self.graph,
+                    &self.reverse_adj,
+                    target,
+                    true,
+                ))
+            }),
+        })
+    }
+
+    /// Static totals before and after detaching `file` from the graph.
+    pub fn without_report(&self, file: &Path) -> Result<WithoutReport, Error> {
+        let canon = file
+            .canonicalize()
+            .or_else(|_| self.root.join(file).canonicalize())
+            .map_err(|e| Error::EntryNotFound(file.to_path_buf(), e))?;
+        let Some(removed) = self.graph.module_id(&canon) else {
+            return Err(Error::EntryNotInGraph(canon));
+        };
+        if removed == self.entry_id {
+            return Err(Error::TargetIsEntryPoint("--without".into()));
+        }
+        let before = query::trace_counts(&self.graph, self.entry_id, &TraceOptions::default());
+        let after = query::trace_without(&self.graph, self.entry_id, removed);
+        Ok(WithoutReport {
+            entry: report::relative_path(&self.entry, &self.root),
+            removed: report::relative_path(&canon, &self.root),
+            before_weight_bytes: before.static_weight,
+            before_module_count: before.static_module_count,
+            after_weight_bytes: after.static_weight,
+            after_module_count: after.static_module_count,
+        })
+    }
+
+    /// The `top` modules with the most transitive dependents among those
+    /// reachable from the entry.
+    pub fn depended_on_report(&self, include_dynamic: bool, top: i32) -> DependedOnReport {
+        let modules = query::most_depended_on(
+            &self.graph,
+            &self.reverse_adj,
+            self.entry_id,
+            include_dynamic,
+            top,
+        )
+        .into_iter()
+        .map(|d| {
+            let m = self.graph.module(d.module);
+            DependedOnEntry {
+                module: m.package.as_ref().map_or_else(
+                    || report::relative_path(&m.path, &self.root),
+                    |pkg| report::package_relative_path(&m.path, pkg),
+                ),
+                dependents: d.dependents,
+                direct_importers: d.direct_importers,
+            }
+        })
+        .collect();
+        DependedOnReport {
+            entry: report::relative_path(&self.entry, &self.root),
+            modules,
+        }
+    }
+
+    /// Measure barrel import amplification and produce a display-ready report.
+    pub fn barrel_report(&self, top: i32) -> BarrelReport {
+        let barrels = query::barrel_penalties(&self.graph, self.entry_id, top)
+            .iter()
+            .map(|p| BarrelEntry {
+                importer: report::display_name(&self.graph, p.importer, &self.root),
+                barrel: report::display_name(&self.graph, p.barrel, &self.root),
+                barrel_weight_bytes: p.barrel_weight,
+                single_module_bytes: p.single_weight,
+                amplification: p.amplification,
+                high: p.amplification >= query::BARREL_PENALTY_THRESHOLD,
+            })
+            .collect();
+        BarrelReport {
+            entry: report::relative_path(&self.entry, &self.root),
+            barrels,
+        }
+    }
+
+    /// Re-parse every module in the graph and report how each import
+    /// specifier resolves. Diagnostic only: this rebuilds the resolver and
+    /// re-reads sources, so it costs roughly a cold build.
+    pub fn resolve_report(&self) -> Result<ResolveReport, Error> {
+        let lang = self.language_support()?;
+        let mut imports = Vec::new();
+        for (path, parsed) in self.parsed_modules(&*lang) {
+            let Some(dir) = path.parent() else {
+                continue;
+            };
+            let from = report::relative_path(path, &self.root);
+            for imp in parsed.imports {
+                let (resolved, strategy) = lang.resolve_with_strategy(dir, &imp.specifier);
+                imports.push(ResolveEntry {
+                    from: from.clone(),
+                    specifier: imp.specifier,
+                    strategy,
+                    resolved: resolved.map(|p| report::relative_path(&p, &self.root)),
+                });
+            }
         }
+
+        Ok(ResolveReport {
+            entry: report::relative_path(&self.entry, &self.root),
+            imports,
+        })
+    }
+
+    /// Every candidate path tried when resolving `specifier`, and why each
+    /// failed. The lookup starts from the first module that imports it, or
+    /// from the entry when nothing does.
+    pub fn explain_unresolved(&self, specifier: &str) -> Result<ExplainReport, Error> {
+        let lang = self.language_support()?;
+        let from = self
+            .parsed_modules(&*lang)
+            .find(|(_, parsed)| parsed.imports.iter().any(|i| i.specifier == specifier))
+            .map_or(self.entry.as_path(), |(path, _)| path);
+        let dir = from.parent().unwrap_or(&self.root);
+        let attempts: Vec<ExplainAttempt> = lang
+            .explain_resolve(dir, specifier)
+            .into_iter()
+            .map(|a| ExplainAttempt {
+                candidate: report::relative_path(&a.candidate, &self.root),
+                outcome: a.outcome,
+            })
+            .collect();
+        Ok(ExplainReport {
+            specifier: specifier.to_string(),
+            from: report::relative_path(from, &self.root),
+            resolved: attempts
+                .iter()
+                .find(|a| a.outcome == lang::CandidateOutcome::Found)
+                .map(|a| a.candidate.clone()),
+            attempts,
+        })
+    }
+
+    /// A fresh language support for diagnostics that re-resolve imports.
+    fn language_support(&self) -> Result<Box<dyn lang::LanguageSupport>, Error> {
+        let vfs: Arc<dyn Vfs> = Arc::new(OsVfs);
+        let (_, kind) = lang::detect_project(&self.entry, &*vfs).ok_or_else(|| {
+            let ext = self
+                .entry
+                .extension()
+                .and_then(|e| e.to_str())
+                .map(String::from);
+            Error::UnsupportedFileType(ext)
+        })?;
+        loader::language_support(&self.root, kind, &self.load_opts, &vfs)
+    }
+
+    /// Re-parse every parseable module in the graph, skipping files that
+    /// can no longer be read or parsed.
+    fn parsed_modules<'a>(
+        &'a self,
+        lang: &'a dyn lang::LanguageSupport,
+    ) -> impl Iterator<Item = (&'a Path, lang::ParseResult)> + 'a {
+        let extensions = lang.extensions();
+        self.graph.modules.iter().filter_map(move |module| {
+            let parseable = module
+                .path
+                .extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| extensions.contains(&e));
+            if !parseable {
+                return None;
+            }
+            let source = OsVfs.read_to_string(&module.path).ok()?;
+            let parsed = lang.parse(&module.path, &source).ok()?;
+            Some((module.path.as_path(), parsed))
+        })
     }
 
     /// Find import chains and produce a display-ready report.
-    pub fn chain_report(&self, target_arg: &str, include_dynamic: bool) -> ChainReport {
+    ///
+    /// With `min_hops > 0`, only chains of at least that many hops are shown.
+    pub fn chain_report(
+        &self,
+        target_arg: &str,
+        include_dynamic: bool,
+        min_hops: u32,
+    ) -> ChainReport {
         let (resolved, chains) = self.chain(target_arg, include_dynamic);
+        let (chains, all_below_min_hops) = if chains.iter().all(|c| c.len() > min_hops as usize) {
+            (chains, false)
+        } else {
+            let longer = query::find_chains_min_hops(
+                &self.graph,
+                self.entry_id,
+                &resolved.target,
+                include_dynamic,
+                min_hops,
+            );
+            let all_below = longer.is_empty() && !chains.is_empty();
+            (longer, all_below)
+        };
+        let names: Vec<Vec<String>> = chains
+            .iter()
+            .map(|chain| report::chain_display_names(&self.graph, chain, &self.root))
+            .collect();
         ChainReport {
             target: resolved.label,
             found_in_graph: resolved.exists,
+            min_hops,
+            all_below_min_hops,
             chain_count: chains.len(),
             hop_count: chains.first().map_or(0, |c| c.len().saturating_sub(1)),
-            chains: chains
+            hops: chains
                 .iter()
-                .map(|chain| report::chain_display_names(&self.graph, chain, &self.root))
+                .zip(&names)
+                .map(|(chain, names)| self.chain_hops(chain, names, include_dynamic))
                 .collect(),
+            chains: names,
+            show_specifiers: false,
         }
     }
 
+    /// The import edge behind each hop of `chain`. When a module imports the
+    /// next one more than once, the edge the chain search follows wins.
+    fn chain_hops(
+        &self,
+        chain: &[ModuleId],
+        names: &[String],
+        include_dynamic: bool,
+    ) -> Vec<ChainHop> {
+        chain
+            .windows(2)
+            .zip(names.windows(2))
+            .filter_map(|(ids, labels)| {
+                let edge = self
+                    .graph
+                    .outgoing_edges(ids[0])
+                    .iter()
+                    .map(|&eid| self.graph.edge(eid))
+                    .filter(|e| e.to == ids[1])
+                    .min_by_key(|e| match e.kind {
+                        EdgeKind::Static => 0,
+                        EdgeKind::Dynamic if include_dynamic => 1,
+                        _ => 2,
+                    })?;
+                Some(ChainHop {
+                    from: labels[0].clone(),
+                    to: labels[1].clone(),
+                    kind: edge_kind_label(edge.kind),
+                    specifier: edge.specifier.clone(),
+                    line: import_line(&self.graph.module(ids[0]).path, &edge.specifier),
+                })
+            })
+            .collect()
+    }
+
     /// Find cut points and produce a display-ready report.
     pub fn cut_report(&self, target_arg: &str, top: i32, include_dynamic: bool) -> CutReport {
         let (resolved, chains, cuts) = self.cut(target_arg, top, include_dynamic);
@@ -433,10 +1647,37 @@ impl Session {
         Ok(DiffReport::from_diff(&diff, &entry_a, &entry_b, limit))
     }
 
-    /// List packages and produce a display-ready report.
-    #[allow(clippy::cast_sign_loss)]
-    pub fn packages_report(&self, top: i32) -> PackagesReport {
-        let mut packages: Vec<_> = self.graph.package_map.values().collect();
+    /// Diff the graph `cached` by an earlier run against this one, module by
+    /// module (`trace --diff-cache`).
+    pub fn cache_diff_report(
+        &self,
+        cached: &ModuleGraph,
+        opts: &TraceOptions,
+        limit: i32,
+    ) -> Result<DiffReport, Error> {
+        let cached_entry = cached
+            .module_id(&self.entry)
+            .ok_or_else(|| Error::NoCachedGraph(self.entry.clone()))?;
+        let label = self.entry_label();
+        let before = query::trace(cached, cached_entry, opts)
+            .to_snapshot(&label)
+            .with_modules(cached, cached_entry, &self.root);
+        let now = self.trace(opts).to_snapshot(&label).with_modules(
+            &self.graph,
+            self.entry_id,
+            &self.root,
+        );
+        let diff = query::diff_snapshots(&before, &now);
+        let mut report = DiffReport::from_diff(&diff, "cached", "current", limit);
+        report.module_changes = Some(ModuleChangeset::from_diff(&diff));
+        Ok(report)
+    }
+
+    /// List packages and produce a display-ready report. `with_deps` also
+    /// lists the packages each one pulls in, at one graph walk per package.
+    #[allow(clippy::cast_sign_loss)]
+    pub fn packages_report(&self, top: i32, with_deps: bool) -> PackagesReport {
+        let mut packages: Vec<_> = self.graph.package_map.values().collect();
         packages.sort_by(|a, b| b.total_reachable_size.cmp(&a.total_reachable_size));
         let total = packages.len();
         let display_count = if top < 0 {
@@ -449,15 +1690,60 @@ impl Session {
             package_count: total,
             packages: packages[..display_count]
                 .iter()
-                .map(|pkg| PackageListEntry {
-                    name: pkg.name.clone(),
-                    size: pkg.total_reachable_size,
-                    files: pkg.total_reachable_files,
+                .map(|pkg| {
+                    let dependencies: Vec<DependencyEntry> = if with_deps {
+                        self.graph
+                            .transitive_dependencies(&pkg.name)
+                            .into_iter()
+                            .map(|(name, size)| DependencyEntry { name, size })
+                            .collect()
+                    } else {
+                        Vec::new()
+                    };
+                    PackageListEntry {
+                        name: pkg.name.clone(),
+                        size: pkg.total_reachable_size,
+                        files: pkg.total_reachable_files,
+                        dependency_size: with_deps
+                            .then(|| dependencies.iter().map(|d| d.size).sum()),
+                        dependencies,
+                    }
                 })
                 .collect(),
+            with_deps,
         }
     }
 
+    /// Reachable third-party packages with versions and reach kind.
+    pub fn sbom_report(&self) -> SbomReport {
+        let packages = query::package_reach(&self.graph, self.entry_id)
+            .into_iter()
+            .map(|p| {
+                let version = self.package_version(&p.name);
+                SbomPackage {
+                    reach: match p.reach {
+                        EdgeKind::Dynamic => "dynamic",
+                        EdgeKind::TypeOnly => "type-only",
+                        _ => "static",
+                    },
+                    name: p.name,
+                    version,
+                    file_count: p.file_count,
+                    size_bytes: p.total_size,
+                }
+            })
+            .collect();
+        SbomReport {
+            entry: report::relative_path(&self.entry, &self.root),
+            packages,
+        }
+    }
+
+    fn package_version(&self, name: &str) -> Option<String> {
+        let info = self.graph.package_map.get(name)?;
+        package_version(&self.graph.module(info.entry_module).path, name)
+    }
+
     // -- accessors --
 
     pub fn graph(&self) -> &ModuleGraph {
@@ -492,9 +1778,99 @@ impl Session {
         &self.unresolvable_dynamic_files
     }
 
+    /// How the graph's imports resolved when it was built.
+    pub fn resolution(&self) -> ResolutionStats {
+        self.resolution
+    }
+
     pub fn file_warnings(&self) -> &[String] {
         &self.file_warnings
     }
+
+    /// The last load hit [`LoadOptions::timeout`], so the graph is partial.
+    pub fn timed_out(&self) -> bool {
+        self.timed_out
+    }
+}
+
+/// Trace each package of a monorepo from its entry and count the imports
+/// between packages (`--aggregate-monorepo`). A file belongs to the
+/// innermost workspace package directory containing it, or, when reached
+/// through a `node_modules` link, to the workspace package of that name.
+pub fn monorepo_report(ws: &WorkspaceGraph, opts: &TraceOptions) -> MonorepoReport {
+    let graph = &ws.graph;
+    let owners: Vec<Option<usize>> = graph
+        .modules
+        .iter()
+        .map(|m| {
+            ws.packages
+                .iter()
+                .enumerate()
+                .filter(|(_, (pkg, _))| {
+                    m.path
+                        .strip_prefix(&pkg.dir)
+                        .is_ok_and(|rel| !rel.components().any(|c| c.as_os_str() == "node_modules"))
+                })
+                .max_by_key(|(_, (pkg, _))| pkg.dir.as_os_str().len())
+                .map(|(i, _)| i)
+                .or_else(|| {
+                    let name = m.package.as_deref()?;
+                    ws.packages.iter().position(|(pkg, _)| pkg.name == name)
+                })
+        })
+        .collect();
+
+    let mut packages: Vec<WorkspaceCost> = ws
+        .packages
+        .iter()
+        .map(|(pkg, entry)| {
+            let counts = entry
+                .as_deref()
+                .and_then(|e| graph.module_id(e))
+                .map(|id| query::trace_counts(graph, id, opts));
+            WorkspaceCost {
+                name: pkg.name.clone(),
+                dir: report::relative_path(&pkg.dir, &ws.root),
+                entry: entry.as_deref().map(|e| report::relative_path(e, &ws.root)),
+                static_weight_bytes: counts.as_ref().map_or(0, |c| c.static_weight),
+                static_module_count: counts.as_ref().map_or(0, |c| c.static_module_count),
+            }
+        })
+        .collect();
+    packages.sort_by(|a, b| {
+        b.entry
+            .is_some()
+            .cmp(&a.entry.is_some())
+            .then(b.static_weight_bytes.cmp(&a.static_weight_bytes))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+
+    let mut pairs: BTreeMap<(&str, &str), usize> = BTreeMap::new();
+    for edge in &graph.edges {
+        if let (Some(from), Some(to)) = (owners[edge.from.0 as usize], owners[edge.to.0 as usize])
+            && from != to
+        {
+            let key = (
+                ws.packages[from].0.name.as_str(),
+                ws.packages[to].0.name.as_str(),
+            );
+            *pairs.entry(key).or_default() += 1;
+        }
+    }
+    let cross_edges = pairs
+        .into_iter()
+        .map(|((from, to), import_count)| WorkspaceEdge {
+            from: from.to_string(),
+            to: to.to_string(),
+            import_count,
+        })
+        .collect();
+
+    MonorepoReport {
+        root: ws.root.display().to_string(),
+        packages,
+        cross_edges,
+    }
 }
 
 /// Build a display label for an entry point that includes the project
@@ -508,6 +1884,41 @@ pub fn entry_label(path: &Path, root: &Path) -> String {
     )
 }
 
+/// Version of package `name` from the nearest enclosing package.json that
+/// declares that name. Skips nested manifests such as `dist/esm/package.json`.
+fn package_version(file: &Path, name: &str) -> Option<String> {
+    file.ancestors().skip(1).find_map(|dir| {
+        let content = std::fs::read_to_string(dir.join("package.json")).ok()?;
+        let json: serde_json::Value = serde_json::from_str(&content).ok()?;
+        if json.get("name")?.as_str()? != name {
+            return None;
+        }
+        json.get("version")?.as_str().map(str::to_string)
+    })
+}
+
+const fn edge_kind_label(kind: EdgeKind) -> &'static str {
+    match kind {
+        EdgeKind::Static => "static",
+        EdgeKind::Dynamic => "dynamic",
+        EdgeKind::TypeOnly => "type-only",
+        EdgeKind::Asset => "asset",
+    }
+}
+
+/// 1-based line of the first quoted occurrence of `specifier` in `file`.
+/// Edges don't record where the import was written, so this rereads the
+/// source; Python's unquoted `import a.b` is not found.
+fn import_line(file: &Path, specifier: &str) -> Option<u32> {
+    let source = std::fs::read_to_string(file).ok()?;
+    let quoted = ['"', '\'', '`'].map(|q| format!("{q}{specifier}{q}"));
+    let offset = quoted
+        .iter()
+        .filter_map(|q| source.find(q.as_str()))
+        .min()?;
+    u32::try_from(source[..offset].matches('\n').count() + 1).ok()
+}
+
 /// Determine whether a chain/cut argument looks like a file path
 /// (as opposed to a package name).
 pub fn looks_like_path(arg: &str, extensions: &[&str]) -> bool {
@@ -519,6 +1930,64 @@ pub fn looks_like_path(arg: &str, extensions: &[&str]) -> bool {
                 .is_some_and(|(_, suffix)| extensions.contains(&suffix)))
 }
 
+/// Files under `root` with one of `extensions`, and their sizes. Hidden and
+/// gitignored entries and `skip_dirs` are not descended into.
+fn project_files(
+    root: &Path,
+    extensions: &[&str],
+    skip_dirs: &'static [&'static str],
+) -> Vec<(PathBuf, u64)> {
+    ignore::WalkBuilder::new(root)
+        .require_git(false)
+        .filter_entry(move |entry| {
+            !entry.file_type().is_some_and(|t| t.is_dir())
+                || !entry
+                    .file_name()
+                    .to_str()
+                    .is_some_and(|name| skip_dirs.contains(&name))
+        })
+        .build()
+        .flatten()
+        .filter(|entry| entry.file_type().is_some_and(|t| t.is_file()))
+        .filter(|entry| {
+            entry
+                .path()
+                .extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| extensions.contains(&e))
+        })
+        .filter_map(|entry| {
+            let size = entry.metadata().ok()?.len();
+            Some((entry.into_path(), size))
+        })
+        .collect()
+}
+
+/// `*.test.ts`, `*.spec.js`, `test_*.py`, `*_test.py`: run by a test
+/// runner rather than imported.
+fn is_test_file(path: &Path) -> bool {
+    let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+        return false;
+    };
+    let stem = name.split('.').next().unwrap_or(name);
+    let python = path.extension().is_some_and(|e| e == "py");
+    name.contains(".test.")
+        || name.contains(".spec.")
+        || (python && (stem.starts_with("test_") || stem.ends_with("_test")))
+}
+
+/// `*.d.ts`, `*.d.mts`, `*.d.cts`: type declarations, which ambient ones
+/// supply to the compiler without being imported.
+fn is_declaration_file(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(|name| {
+            [".d.ts", ".d.mts", ".d.cts"]
+                .iter()
+                .any(|s| name.ends_with(s))
+        })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -582,6 +2051,92 @@ mod tests {
         assert!(diff.entry_a_weight >= diff.entry_b_weight);
     }
 
+    #[test]
+    fn cache_diff_reports_module_added_since_last_run() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        std::fs::write(root.join("package.json"), r#"{"name":"test"}"#).unwrap();
+        let entry = root.join("index.ts");
+        std::fs::write(&entry, r#"import "./a";"#).unwrap();
+        std::fs::write(root.join("a.ts"), "export {};").unwrap();
+        std::fs::write(root.join("b.ts"), "export const b = 1;").unwrap();
+        drop(Session::open(&entry, false).unwrap());
+
+        std::fs::write(&entry, "import \"./a\";\nimport \"./b\";").unwrap();
+        let cached = loader::load_cached_graph(&entry, &LoadOptions::default())
+            .unwrap()
+            .unwrap();
+        let session = Session::open(&entry, false).unwrap();
+        let report = session
+            .cache_diff_report(&cached, &TraceOptions::default(), -1)
+            .unwrap();
+        let changes = report.module_changes.unwrap();
+        let added: Vec<&str> = changes
+            .added_modules
+            .iter()
+            .map(|m| m.name.as_str())
+            .collect();
+        assert_eq!(added, ["b.ts"]);
+        assert!(changes.removed_modules.is_empty());
+        assert!(report.weight_delta > 0);
+    }
+
+    #[test]
+    fn cached_glob_importer_sees_added_file() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        std::fs::write(root.join("package.json"), r#"{"name":"test"}"#).unwrap();
+        let entry = root.join("index.ts");
+        std::fs::write(&entry, r#"const pages = import.meta.glob("./pages/*.ts");"#).unwrap();
+        std::fs::create_dir(root.join("pages")).unwrap();
+        std::fs::write(root.join("pages/a.ts"), "export {};").unwrap();
+        std::fs::write(root.join("pages/b.ts"), "export {};").unwrap();
+        assert_eq!(
+            Session::open(&entry, false).unwrap().graph().module_count(),
+            3
+        );
+
+        // index.ts is untouched; only the directory it globs gains a file
+        std::fs::write(root.join("pages/c.ts"), "export {};").unwrap();
+        let session = Session::open(&entry, false).unwrap();
+        assert!(
+            session
+                .graph()
+                .module_id(&root.join("pages/c.ts"))
+                .is_some()
+        );
+        assert_eq!(session.graph().module_count(), 4);
+    }
+
+    #[test]
+    fn type_graph_follows_only_type_imports() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        std::fs::write(root.join("package.json"), r#"{"name":"test"}"#).unwrap();
+        for pkg in ["types-only", "runtime-only"] {
+            let dir = root.join("node_modules").join(pkg);
+            std::fs::create_dir_all(&dir).unwrap();
+            std::fs::write(
+                dir.join("package.json"),
+                format!(r#"{{"name":"{pkg}","main":"index.js"}}"#),
+            )
+            .unwrap();
+            std::fs::write(dir.join("index.js"), "module.exports = {};").unwrap();
+        }
+        let entry = root.join("index.ts");
+        std::fs::write(
+            &entry,
+            "import type { T } from \"types-only\";\nimport \"runtime-only\";",
+        )
+        .unwrap();
+
+        let mut session = Session::open(&entry, true).unwrap();
+        session.restrict_to_type_graph();
+        let result = session.trace(&TraceOptions::default());
+        let packages: Vec<&str> = result.all_packages.keys().map(String::as_str).collect();
+        assert_eq!(packages, ["types-only"]);
+    }
+
     #[test]
     fn packages_returns_package_map() {
         let (_tmp, entry) = test_project();
@@ -672,6 +2227,147 @@ mod tests {
         assert!(importers[0].0.ends_with("index.ts"));
     }
 
+    #[test]
+    fn timeout_returns_partial_uncached_graph() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        std::fs::write(root.join("package.json"), r#"{"name":"test"}"#).unwrap();
+        let entry = root.join("index.ts");
+        std::fs::write(&entry, r#"import "./a";"#).unwrap();
+        std::fs::write(root.join("a.ts"), r#"import "./b";"#).unwrap();
+        std::fs::write(root.join("b.ts"), r#"import "./c";"#).unwrap();
+        std::fs::write(root.join("c.ts"), "export {};").unwrap();
+
+        let opts = LoadOptions {
+            timeout: Some(std::time::Duration::ZERO),
+            ..LoadOptions::default()
+        };
+        let session = Session::open_with(&entry, &opts).unwrap();
+        assert!(session.timed_out());
+        // Only the entry was parsed; its import is a leaf
+        assert!(session.graph().module_id(&root.join("a.ts")).is_some());
+        assert!(session.graph().module_id(&root.join("c.ts")).is_none());
+        drop(session);
+        assert!(!root.join(".chainsaw.cache").exists());
+
+        let session = Session::open(&entry, false).unwrap();
+        assert!(!session.timed_out());
+        assert!(session.graph().module_id(&root.join("c.ts")).is_some());
+    }
+
+    #[test]
+    #[allow(clippy::cast_precision_loss)]
+    fn sample_estimate_tracks_exact_weight_on_shared_dag() {
+        // Every page imports two of four shared modules, which share a core
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        std::fs::write(root.join("package.json"), r#"{"name":"test"}"#).unwrap();
+        let pages: Vec<String> = (0..12).map(|i| format!("import './page{i}';")).collect();
+        let entry = root.join("index.ts");
+        std::fs::write(&entry, pages.join("\n")).unwrap();
+        for i in 0..12 {
+            std::fs::write(
+                root.join(format!("page{i}.ts")),
+                format!(
+                    "import './shared{}';\nimport './shared{}';",
+                    i % 4,
+                    (i + 1) % 4
+                ),
+            )
+            .unwrap();
+        }
+        for i in 0..4 {
+            std::fs::write(
+                root.join(format!("shared{i}.ts")),
+                format!(
+                    "import './core';\nexport const s{i} = '{}';",
+                    "x".repeat(200)
+                ),
+            )
+            .unwrap();
+        }
+        std::fs::write(
+            root.join("core.ts"),
+            format!("export const c = '{}';", "y".repeat(400)),
+        )
+        .unwrap();
+
+        let exact = Session::open(&entry, true)
+            .unwrap()
+            .trace_report(&TraceOptions::default(), 10)
+            .static_weight_bytes as f64;
+        let seeds = 40;
+        let mut total = 0.0;
+        for seed in 0..seeds {
+            let opts = LoadOptions {
+                sample: Some(crate::walker::Sample {
+                    fraction: 0.5,
+                    seed,
+                }),
+                ..LoadOptions::default()
+            };
+            let report = Session::open_with(&entry, &opts)
+                .unwrap()
+                .trace_report(&TraceOptions::default(), 10);
+            let estimate = report.estimate.unwrap().estimated_weight_bytes;
+            assert!(estimate >= report.static_weight_bytes, "seed {seed}");
+            total += estimate as f64;
+        }
+        let mean = total / seeds as f64;
+        // Unparsed pages hide which shared modules they import, so it leans high
+        assert!(
+            (mean - exact).abs() < exact * 0.3,
+            "exact {exact}, mean {mean}"
+        );
+    }
+
+    #[test]
+    fn sampled_trace_reports_approximate_estimate() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        std::fs::write(root.join("package.json"), r#"{"name":"test"}"#).unwrap();
+        let entry = root.join("index.ts");
+        let imports: Vec<String> = (0..20).map(|i| format!(r#"import "./m{i}";"#)).collect();
+        std::fs::write(&entry, imports.join("\n")).unwrap();
+        for i in 0..20 {
+            std::fs::write(
+                root.join(format!("m{i}.ts")),
+                format!(r#"import "./leaf{i}";"#),
+            )
+            .unwrap();
+            std::fs::write(root.join(format!("leaf{i}.ts")), "export const x = 1;").unwrap();
+        }
+
+        let opts = LoadOptions {
+            sample: Some(crate::walker::Sample {
+                fraction: 0.5,
+                seed: 7,
+            }),
+            ..LoadOptions::default()
+        };
+        let session = Session::open_with(&entry, &opts).unwrap();
+        let report = session.trace_report(&TraceOptions::default(), 10);
+        let estimate = report
+            .estimate
+            .clone()
+            .expect("sampled run has an estimate");
+        assert!(estimate.approximate);
+        assert!(estimate.sampled_out_files > 0);
+        assert!(report.static_module_count < 41);
+        assert!(estimate.estimated_weight_bytes >= report.static_weight_bytes);
+        drop(session);
+        assert!(!root.join(".chainsaw.cache").exists());
+
+        // Same seed, same files parsed
+        let session = Session::open_with(&entry, &opts).unwrap();
+        let again = session.trace_report(&TraceOptions::default(), 10);
+        assert_eq!(again.static_weight_bytes, report.static_weight_bytes);
+        assert_eq!(
+            again.estimate.unwrap().estimated_weight_bytes,
+            estimate.estimated_weight_bytes
+        );
+    }
+
     #[test]
     fn set_entry_switches_entry_point() {
         let tmp = tempfile::tempdir().unwrap();
@@ -717,6 +2413,66 @@ mod tests {
         assert_eq!(session.graph().module_count(), 3);
     }
 
+    #[test]
+    fn update_files_recomputes_only_affected_package() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        std::fs::write(root.join("package.json"), r#"{"name":"app"}"#).unwrap();
+        for (name, source) in [
+            ("zod", "export const z = 1;"),
+            ("lodash", "export const l = 1;"),
+        ] {
+            let dir = root.join("node_modules").join(name);
+            std::fs::create_dir_all(&dir).unwrap();
+            std::fs::write(
+                dir.join("package.json"),
+                format!(r#"{{"name":"{name}","main":"index.js"}}"#),
+            )
+            .unwrap();
+            std::fs::write(dir.join("index.js"), source).unwrap();
+        }
+        std::fs::write(root.join("a.ts"), "import 'zod';").unwrap();
+        std::fs::write(root.join("b.ts"), "import 'lodash';").unwrap();
+        let entry = root.join("index.ts");
+        std::fs::write(&entry, "import './a';\nimport './b';").unwrap();
+        let b = root.join("b.ts");
+        let opts = TraceOptions::default();
+        let mut session = Session::open(&entry, true).unwrap();
+
+        // Dropping lodash leaves zod alone
+        std::fs::write(&b, "import fs from 'fs';").unwrap();
+        let recomputed = session.update_files(std::slice::from_ref(&b)).unwrap();
+        assert_eq!(recomputed, ["lodash"]);
+        assert!(!session.graph().package_map.contains_key("lodash"));
+        assert!(session.graph().package_map.contains_key("zod"));
+        let fresh = Session::open(&entry, true).unwrap();
+        assert_eq!(
+            session.trace(&opts).static_weight,
+            fresh.trace(&opts).static_weight
+        );
+        assert_eq!(session.resolution(), fresh.resolution());
+
+        // A first-party edit that keeps its imports recomputes no package
+        std::fs::write(&b, "import fs from 'fs';\nexport const b = 2;").unwrap();
+        let recomputed = session.update_files(std::slice::from_ref(&b)).unwrap();
+        assert!(recomputed.is_empty());
+
+        std::fs::write(&b, "import 'lodash';").unwrap();
+        let recomputed = session.update_files(std::slice::from_ref(&b)).unwrap();
+        assert_eq!(recomputed, ["lodash"]);
+        assert_eq!(
+            session.graph().package_map["lodash"].total_reachable_size,
+            19
+        );
+
+        // A file the graph hasn't seen needs a reload
+        std::fs::write(root.join("c.ts"), "export {};").unwrap();
+        std::fs::write(&b, "import './c';").unwrap();
+        assert!(session.update_files(std::slice::from_ref(&b)).is_none());
+        session.refresh().unwrap();
+        assert!(session.graph().module_id(&root.join("c.ts")).is_some());
+    }
+
     #[test]
     fn entry_label_includes_project_dir() {
         let (_tmp, entry) = test_project();
@@ -749,12 +2505,36 @@ mod tests {
     fn chain_report_resolves_to_strings() {
         let (_tmp, entry) = test_project();
         let session = Session::open(&entry, true).unwrap();
-        let report = session.chain_report("a.ts", false);
+        let report = session.chain_report("a.ts", false, 0);
         assert!(report.found_in_graph);
         assert_eq!(report.chain_count, 1);
         assert!(report.chains[0].iter().any(|s| s.contains("a.ts")));
     }
 
+    #[test]
+    fn chain_report_hops_carry_edge_details() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        std::fs::write(root.join("package.json"), r#"{"name":"test"}"#).unwrap();
+        let entry = root.join("index.ts");
+        std::fs::write(&entry, "// entry\nimport { a } from './a';\n").unwrap();
+        std::fs::write(root.join("a.ts"), r#"export { b as a } from "./b";"#).unwrap();
+        std::fs::write(root.join("b.ts"), "export const b = 1;").unwrap();
+
+        let session = Session::open(&entry, true).unwrap();
+        let report = session.chain_report("b.ts", false, 0);
+        let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+        let hops = json["hops"][0].as_array().unwrap();
+        assert_eq!(hops.len(), 2);
+        assert_eq!(hops[0]["from"], "index.ts");
+        assert_eq!(hops[0]["to"], "a.ts");
+        assert_eq!(hops[0]["kind"], "static");
+        assert_eq!(hops[0]["specifier"], "./a");
+        assert_eq!(hops[0]["line"], 2);
+        assert_eq!(hops[1]["specifier"], "./b");
+        assert_eq!(hops[1]["line"], 1);
+    }
+
     #[test]
     fn cut_report_direct_import() {
         let (_tmp, entry) = test_project();
@@ -770,8 +2550,799 @@ mod tests {
     fn packages_report_empty_for_first_party() {
         let (_tmp, entry) = test_project();
         let session = Session::open(&entry, true).unwrap();
-        let report = session.packages_report(report::DEFAULT_TOP);
+        let report = session.packages_report(report::DEFAULT_TOP, false);
         assert_eq!(report.package_count, 0);
         assert!(report.packages.is_empty());
     }
+
+    #[test]
+    fn package_cost_report_sums_member_modules() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        std::fs::write(root.join("package.json"), r#"{"name":"test"}"#).unwrap();
+        let lib = root.join("node_modules/lib");
+        std::fs::create_dir_all(&lib).unwrap();
+        std::fs::write(
+            lib.join("package.json"),
+            r#"{"name":"lib","main":"index.js"}"#,
+        )
+        .unwrap();
+        std::fs::write(lib.join("index.js"), "require('./big'); // index").unwrap();
+        std::fs::write(lib.join("big.js"), "x".repeat(500)).unwrap();
+        std::fs::write(root.join("a.ts"), "export const x = 1;").unwrap();
+        let entry = root.join("index.ts");
+        std::fs::write(&entry, "import './a';\nimport 'lib';\n").unwrap();
+
+        let session = Session::open(&entry, true).unwrap();
+        let opts = TraceOptions::default();
+        let graph = session.graph();
+        let sum = |pkg: Option<&str>| -> u64 {
+            graph
+                .modules
+                .iter()
+                .filter(|m| m.package.as_deref() == pkg)
+                .map(|m| m.size_bytes)
+                .sum()
+        };
+
+        let report = session.package_cost_report(&opts, -1);
+        assert_eq!(report.total_packages_with_cost, 2);
+        let [first, second] = report.packages_by_cost.as_slice() else {
+            panic!("{:?}", report.packages_by_cost);
+        };
+        assert_eq!(first.package, "lib");
+        assert_eq!(first.module_count, 2);
+        assert_eq!(first.size_bytes, sum(Some("lib")));
+        assert_eq!(first.largest_module, "lib/big.js");
+        assert_eq!(first.largest_module_bytes, 500);
+        assert_eq!(second.package, report::LOCAL_PACKAGE);
+        assert_eq!(second.module_count, 2);
+        assert_eq!(second.size_bytes, sum(None));
+        assert_eq!(
+            first.size_bytes + second.size_bytes,
+            report.static_weight_bytes
+        );
+
+        let top = session.package_cost_report(&opts, 1);
+        assert_eq!(top.packages_by_cost.len(), 1);
+        assert!(top.to_terminal(false).contains("... and 1 more package"));
+    }
+
+    #[test]
+    fn explain_unresolved_starts_from_importer() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        std::fs::write(root.join("package.json"), r#"{"name":"test"}"#).unwrap();
+        std::fs::create_dir_all(root.join("lib")).unwrap();
+        std::fs::write(root.join("lib/x.ts"), "import './missing';").unwrap();
+        let entry = root.join("index.ts");
+        std::fs::write(&entry, "import './lib/x';").unwrap();
+
+        let session = Session::open(&entry, true).unwrap();
+        let report = session.explain_unresolved("./missing").unwrap();
+        assert_eq!(report.from, "lib/x.ts");
+        assert_eq!(report.resolved, None);
+        assert!(
+            report
+                .attempts
+                .iter()
+                .any(|a| a.candidate == "lib/missing.ts")
+        );
+        assert!(report.to_terminal(false).contains("Unresolved"));
+    }
+
+    #[test]
+    fn resolve_report_classifies_relative_and_bare_imports() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        std::fs::write(root.join("package.json"), r#"{"name":"test"}"#).unwrap();
+        let zod = root.join("node_modules/zod");
+        std::fs::create_dir_all(&zod).unwrap();
+        std::fs::write(
+            zod.join("package.json"),
+            r#"{"name":"zod","main":"index.js"}"#,
+        )
+        .unwrap();
+        std::fs::write(zod.join("index.js"), "").unwrap();
+        std::fs::write(root.join("a.ts"), "export const x = 1;").unwrap();
+        let entry = root.join("index.ts");
+        std::fs::write(
+            &entry,
+            "import { x } from './a';\nimport { z } from 'zod';\nimport fs from 'fs';\nimport m from 'missing';\n",
+        )
+        .unwrap();
+
+        let session = Session::open(&entry, true).unwrap();
+        let report = session.resolve_report().unwrap();
+        let find = |spec: &str| {
+            report
+                .imports
+                .iter()
+                .find(|i| i.specifier == spec)
+                .unwrap_or_else(|| panic!("{spec} not in report"))
+        };
+
+        let rel = find("./a");
+        assert_eq!(rel.strategy, lang::ResolveStrategy::Relative);
+        assert_eq!(rel.from, "index.ts");
+        assert_eq!(rel.resolved.as_deref(), Some("a.ts"));
+
+        let bare = find("zod");
+        assert_eq!(bare.strategy, lang::ResolveStrategy::NodeModules);
+        assert_eq!(bare.resolved.as_deref(), Some("node_modules/zod/index.js"));
+
+        assert_eq!(find("fs").strategy, lang::ResolveStrategy::Builtin);
+        let missing = find("missing");
+        assert_eq!(missing.strategy, lang::ResolveStrategy::Unresolved);
+        assert!(missing.resolved.is_none());
+    }
+
+    #[test]
+    fn module_rows_include_depth_and_package() {
+        let (_tmp, entry) = test_project();
+        let session = Session::open(&entry, true).unwrap();
+        let rows = session.module_rows(&TraceOptions::default());
+        // The entry itself is not listed, matching the module table
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].path, "a.ts");
+        assert_eq!(rows[0].depth, 1);
+        assert_eq!(rows[0].package, None);
+        assert_eq!(rows[0].size_bytes, "export const x = 1;".len() as u64);
+    }
+
+    #[test]
+    fn packages_only_report_lists_every_package_without_source_rows() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        std::fs::write(root.join("package.json"), r#"{"name":"app"}"#).unwrap();
+        for (name, version) in [("zod", "3.22.4"), ("lodash", "4.17.21")] {
+            let dir = root.join("node_modules").join(name);
+            std::fs::create_dir_all(dir.join("dist/esm")).unwrap();
+            std::fs::write(
+                dir.join("package.json"),
+                format!(r#"{{"name":"{name}","version":"{version}","main":"dist/esm/index.js"}}"#),
+            )
+            .unwrap();
+            // Nested manifest without a name must not hide the real one
+            std::fs::write(dir.join("dist/esm/package.json"), r#"{"type":"module"}"#).unwrap();
+            std::fs::write(dir.join("dist/esm/index.js"), "export {};").unwrap();
+        }
+        std::fs::write(root.join("a.ts"), "import 'lodash';\nimport './a.css';").unwrap();
+        std::fs::write(root.join("a.css"), "body {}").unwrap();
+        let entry = root.join("index.ts");
+        std::fs::write(&entry, "import './a';\nimport 'zod';").unwrap();
+
+        let session = Session::open(&entry, true).unwrap();
+        let opts = TraceOptions {
+            top_n: -1,
+            ..TraceOptions::default()
+        };
+        let report = session.packages_only_report(&opts);
+        assert!(report.modules_by_cost.is_empty());
+        assert!(report.assets.is_empty());
+
+        let mut packages: Vec<(&str, Option<&str>)> = report
+            .heavy_packages
+            .iter()
+            .map(|p| (p.name.as_str(), p.version.as_deref()))
+            .collect();
+        packages.sort_unstable();
+        assert_eq!(
+            packages,
+            vec![("lodash", Some("4.17.21")), ("zod", Some("3.22.4"))]
+        );
+        let lodash = report
+            .heavy_packages
+            .iter()
+            .find(|p| p.name == "lodash")
+            .unwrap();
+        assert_eq!(lodash.chain, vec!["index.ts", "a.ts", "lodash"]);
+
+        let out = report.to_terminal(false);
+        assert!(out.contains("zod@3.22.4"));
+        assert!(!out.contains("Modules (sorted"));
+        assert!(!out.contains("a.css"));
+    }
+
+    #[test]
+    fn json5_config_import_is_counted_asset_leaf() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        let config = "{ port: 8080, // comment\n}";
+        std::fs::write(root.join("config.json5"), config).unwrap();
+        let entry = root.join("index.ts");
+        std::fs::write(&entry, "import config from './config.json5';").unwrap();
+
+        let session = Session::open(&entry, true).unwrap();
+        let report = session.trace_report(&TraceOptions::default(), -1);
+        assert_eq!(report.static_module_count, 1);
+        assert_eq!(report.asset_weight_bytes, config.len() as u64);
+        assert_eq!(report.assets.len(), 1);
+        assert_eq!(report.assets[0].path, "config.json5");
+    }
+
+    #[test]
+    fn css_and_svg_imports_are_weighed_but_not_parsed() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        let css = "@import './theme.css';\nbody { margin: 0 }";
+        let svg = "<svg><text>import './not-code'</text></svg>";
+        std::fs::write(root.join("styles.css"), css).unwrap();
+        std::fs::write(root.join("theme.css"), "body {}").unwrap();
+        std::fs::write(root.join("logo.svg"), svg).unwrap();
+        let entry = root.join("index.ts");
+        std::fs::write(
+            &entry,
+            "import './styles.css';\nimport logo from './logo.svg';",
+        )
+        .unwrap();
+
+        let session = Session::open(&entry, true).unwrap();
+        let report = session.trace_report(&TraceOptions::default(), -1);
+        let assets: Vec<(&str, u64)> = report
+            .assets
+            .iter()
+            .map(|a| (a.path.as_str(), a.size_bytes))
+            .collect();
+        assert_eq!(
+            assets,
+            [
+                ("logo.svg", svg.len() as u64),
+                ("styles.css", css.len() as u64)
+            ]
+        );
+        assert!(session.graph().module_id(&root.join("theme.css")).is_none());
+        assert_eq!(report.static_module_count, 1);
+    }
+
+    #[test]
+    fn duplicates_report_lists_each_version_with_chain() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        std::fs::write(root.join("package.json"), r#"{"name":"app"}"#).unwrap();
+        for (dir, name, version, source) in [
+            ("node_modules/lodash", "lodash", "4.17.21", "export {};"),
+            ("node_modules/legacy", "legacy", "1.0.0", "import 'lodash';"),
+            (
+                "node_modules/legacy/node_modules/lodash",
+                "lodash",
+                "3.10.1",
+                "export {};",
+            ),
+        ] {
+            let dir = root.join(dir);
+            std::fs::create_dir_all(&dir).unwrap();
+            std::fs::write(
+                dir.join("package.json"),
+                format!(r#"{{"name":"{name}","version":"{version}","main":"index.js"}}"#),
+            )
+            .unwrap();
+            std::fs::write(dir.join("index.js"), source).unwrap();
+        }
+        let entry = root.join("index.ts");
+        std::fs::write(&entry, "import 'lodash';\nimport 'legacy';").unwrap();
+
+        let session = Session::open(&entry, true).unwrap();
+        let report = session.duplicates_report(false);
+        assert_eq!(report.packages.len(), 1);
+        let lodash = &report.packages[0];
+        assert_eq!(lodash.name, "lodash");
+        let mut copies: Vec<(&str, Option<&str>, &[String])> = lodash
+            .copies
+            .iter()
+            .map(|c| (c.path.as_str(), c.version.as_deref(), c.chain.as_slice()))
+            .collect();
+        copies.sort_by_key(|c| c.0);
+        assert_eq!(
+            copies,
+            vec![
+                (
+                    "node_modules/legacy/node_modules/lodash",
+                    Some("3.10.1"),
+                    &["index.ts".to_string(), "legacy".into(), "lodash".into()][..]
+                ),
+                (
+                    "node_modules/lodash",
+                    Some("4.17.21"),
+                    &["index.ts".to_string(), "lodash".into()][..]
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn python_entry_builds_module_graph() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        std::fs::write(root.join("pyproject.toml"), "").unwrap();
+        std::fs::create_dir_all(root.join("app")).unwrap();
+        std::fs::write(root.join("app/__init__.py"), "").unwrap();
+        std::fs::write(root.join("app/util.py"), "import json\n").unwrap();
+        std::fs::write(root.join("app/models.py"), "from .util import x\n").unwrap();
+        let entry = root.join("app/main.py");
+        std::fs::write(&entry, "import app.models\nfrom app.util import x\n").unwrap();
+
+        let session = Session::open(&entry, true).unwrap();
+        let mut modules: Vec<String> = session
+            .graph()
+            .modules
+            .iter()
+            .map(|m| report::relative_path(&m.path, &root))
+            .collect();
+        modules.sort();
+        assert!(
+            ["app/main.py", "app/models.py", "app/util.py"]
+                .iter()
+                .all(|m| modules.iter().any(|p| p == m)),
+            "{modules:?}"
+        );
+        assert!(session.trace(&TraceOptions::default()).static_module_count >= 3);
+    }
+
+    #[test]
+    fn shebang_entry_keeps_imports_and_line_numbers() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        std::fs::write(root.join("package.json"), r#"{"name":"cli"}"#).unwrap();
+        std::fs::write(root.join("run.js"), "export const run = 1;").unwrap();
+        let entry = root.join("cli.js");
+        std::fs::write(
+            &entry,
+            "#!/usr/bin/env node\nimport { run } from './run.js';\n",
+        )
+        .unwrap();
+
+        let session = Session::open(&entry, true).unwrap();
+        let module = &session.graph().modules[session.entry_id().0 as usize];
+        assert_eq!(module.line_count, 2);
+        let report = session.chain_report("run.js", false, 0);
+        assert_eq!(report.hops[0].len(), 1);
+        assert_eq!(report.hops[0][0].specifier, "./run.js");
+        assert_eq!(report.hops[0][0].line, Some(2));
+    }
+
+    #[test]
+    fn sbom_report_records_version_and_reach_kind() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        std::fs::write(root.join("package.json"), r#"{"name":"app"}"#).unwrap();
+        for (name, version) in [("zod", "3.22.4"), ("chart", "1.0.0")] {
+            let dir = root.join("node_modules").join(name);
+            std::fs::create_dir_all(&dir).unwrap();
+            std::fs::write(
+                dir.join("package.json"),
+                format!(r#"{{"name":"{name}","version":"{version}","main":"index.js"}}"#),
+            )
+            .unwrap();
+            std::fs::write(dir.join("index.js"), "export {};").unwrap();
+        }
+        let entry = root.join("index.ts");
+        std::fs::write(&entry, "import 'zod';\nconst c = import('chart');").unwrap();
+
+        let session = Session::open(&entry, true).unwrap();
+        let report = session.sbom_report();
+        assert_eq!(report.entry, "index.ts");
+        let mut packages: Vec<(&str, Option<&str>, &str, u32)> = report
+            .packages
+            .iter()
+            .map(|p| (p.name.as_str(), p.version.as_deref(), p.reach, p.file_count))
+            .collect();
+        packages.sort_unstable();
+        assert_eq!(
+            packages,
+            vec![
+                ("chart", Some("1.0.0"), "dynamic", 1),
+                ("zod", Some("3.22.4"), "static", 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn directory_entry_traces_its_index_file() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        std::fs::write(root.join("package.json"), r#"{"name":"app"}"#).unwrap();
+        let src = root.join("src");
+        std::fs::create_dir_all(&src).unwrap();
+        std::fs::write(src.join("index.ts"), "import './a';").unwrap();
+        std::fs::write(src.join("a.ts"), "export const a = 1;").unwrap();
+
+        let session = Session::open(&src, true).unwrap();
+        assert_eq!(session.entry(), src.join("index.ts"));
+        assert_eq!(session.graph().module_count(), 2);
+
+        // package.json `main` wins over the index convention
+        let lib = root.join("lib");
+        std::fs::create_dir_all(&lib).unwrap();
+        std::fs::write(lib.join("package.json"), r#"{"main":"start.js"}"#).unwrap();
+        std::fs::write(lib.join("start.js"), "").unwrap();
+        std::fs::write(lib.join("index.js"), "").unwrap();
+        let session = Session::open(&lib, true).unwrap();
+        assert_eq!(session.entry(), lib.join("start.js"));
+
+        let empty = root.join("empty");
+        std::fs::create_dir_all(&empty).unwrap();
+        assert!(matches!(
+            Session::open(&empty, true),
+            Err(Error::EntryIsDirectory(_))
+        ));
+    }
+
+    #[test]
+    fn dot_output_clusters_modules_by_package() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        std::fs::write(root.join("package.json"), r#"{"name":"app"}"#).unwrap();
+        let zod = root.join("node_modules/zod");
+        std::fs::create_dir_all(zod.join("lib")).unwrap();
+        std::fs::write(
+            zod.join("package.json"),
+            r#"{"name":"zod","main":"lib/index.js"}"#,
+        )
+        .unwrap();
+        std::fs::write(zod.join("lib/index.js"), "import './util.js';").unwrap();
+        std::fs::write(zod.join("lib/util.js"), "export {};").unwrap();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(root.join("src/a.ts"), "import 'zod';").unwrap();
+        std::fs::write(root.join("src/lazy.ts"), "export {};").unwrap();
+        let entry = root.join("index.ts");
+        std::fs::write(&entry, "import './src/a';\nimport('./src/lazy');").unwrap();
+
+        let session = Session::open(&entry, true).unwrap();
+        let opts = TraceOptions {
+            include_dynamic: true,
+            ..TraceOptions::default()
+        };
+        let dot = report::print_dot(&session.dot_graph(&opts, None), true);
+        assert!(dot.starts_with("digraph imports {"), "{dot}");
+        assert!(
+            dot.contains(
+                "subgraph cluster_0 {\n    label=\"zod\";\n    \"node_modules/zod/lib/index.js\""
+            ),
+            "{dot}"
+        );
+        assert!(dot.contains("label=\"src/\";"), "{dot}");
+        assert!(
+            dot.contains("\"index.ts\" -> \"src/lazy.ts\" [style=dashed];"),
+            "{dot}"
+        );
+        assert!(
+            dot.contains("\"node_modules/zod/lib/index.js\" -> \"node_modules/zod/lib/util.js\";")
+        );
+
+        let flat = report::print_dot(&session.dot_graph(&TraceOptions::default(), None), false);
+        assert!(!flat.contains("subgraph"));
+        assert!(!flat.contains("lazy.ts"));
+    }
+
+    #[test]
+    fn collapse_depth_rolls_deep_package_subtree_into_one_node() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        std::fs::write(root.join("package.json"), r#"{"name":"app"}"#).unwrap();
+        for (name, main, source) in [
+            ("big", "index.js", "import './lib/x.js';"),
+            ("dep", "index.js", "export const dep = 1;"),
+        ] {
+            let dir = root.join("node_modules").join(name);
+            std::fs::create_dir_all(dir.join("lib")).unwrap();
+            std::fs::write(
+                dir.join("package.json"),
+                format!(r#"{{"name":"{name}","main":"{main}"}}"#),
+            )
+            .unwrap();
+            std::fs::write(dir.join(main), source).unwrap();
+        }
+        std::fs::write(root.join("node_modules/big/lib/x.js"), "import 'dep';").unwrap();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(root.join("src/a.ts"), "import 'big';").unwrap();
+        let entry = root.join("index.ts");
+        std::fs::write(&entry, "import './src/a';").unwrap();
+
+        let session = Session::open(&entry, true).unwrap();
+        let opts = TraceOptions::default();
+        // big/index.js is two imports from the entry; lib/x.js and dep are deeper
+        let graph = session.dot_graph(&opts, Some(2));
+        let paths: Vec<&str> = graph.nodes.iter().map(|n| n.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            ["big/…", "index.ts", "node_modules/big/index.js", "src/a.ts"]
+        );
+        let rolled = ("import 'dep';".len() + "export const dep = 1;".len()) as u64;
+        assert_eq!(graph.nodes[0].size_bytes, rolled);
+        assert_eq!(graph.nodes[0].package.as_deref(), Some("big"));
+        let dot = report::print_dot(&graph, false);
+        assert!(
+            dot.contains(r#""node_modules/big/index.js" -> "big/…";"#),
+            "{dot}"
+        );
+        assert!(!dot.contains(r#""big/…" ->"#), "{dot}");
+
+        // Without collapsing, dep is a node of its own
+        let mermaid = report::print_mermaid(&session.mermaid_graph(&opts, None));
+        assert!(mermaid.contains("m_dep("), "{mermaid}");
+        let mermaid = report::print_mermaid(&session.mermaid_graph(&opts, Some(2)));
+        assert!(!mermaid.contains("m_dep"), "{mermaid}");
+        let big = rolled + "import './lib/x.js';".len() as u64;
+        assert!(
+            mermaid.contains(&format!("m_big(\"big<br/>{}\")", report::format_size(big))),
+            "{mermaid}"
+        );
+    }
+
+    #[test]
+    fn mermaid_draws_entry_imports_and_heavy_packages() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        std::fs::write(root.join("package.json"), r#"{"name":"app"}"#).unwrap();
+        let zod = root.join("node_modules/zod");
+        std::fs::create_dir_all(zod.join("lib")).unwrap();
+        std::fs::write(
+            zod.join("package.json"),
+            r#"{"name":"zod","main":"lib/index.js"}"#,
+        )
+        .unwrap();
+        std::fs::write(zod.join("lib/index.js"), "import './util.js';").unwrap();
+        std::fs::write(zod.join("lib/util.js"), "export {};").unwrap();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(root.join("src/a-b.ts"), "import 'zod';\nimport './deep';").unwrap();
+        std::fs::write(root.join("src/deep.ts"), "export {};").unwrap();
+        std::fs::write(root.join("src/lazy.ts"), "export {};").unwrap();
+        let entry = root.join("index.ts");
+        std::fs::write(&entry, "import './src/a-b';\nimport('./src/lazy');").unwrap();
+
+        let session = Session::open(&entry, true).unwrap();
+        let opts = TraceOptions {
+            include_dynamic: true,
+            ..TraceOptions::default()
+        };
+        let mermaid = report::print_mermaid(&session.mermaid_graph(&opts, None));
+        let lines: Vec<&str> = mermaid.lines().collect();
+        assert_eq!(lines[0], "graph LR", "{mermaid}");
+        // src/deep.ts is neither a direct import nor in a package
+        assert!(
+            lines[1].starts_with("  %% 1 more node omitted"),
+            "{mermaid}"
+        );
+        assert!(!mermaid.contains("deep"), "{mermaid}");
+        // Sanitized IDs; zod is one node however many files it has
+        assert!(
+            mermaid.contains("  m_src_a_b_ts[\"src/a-b.ts<br/>"),
+            "{mermaid}"
+        );
+        assert!(mermaid.contains("  m_zod(\"zod<br/>"), "{mermaid}");
+        assert!(
+            mermaid.contains("  m_index_ts --> m_src_a_b_ts\n"),
+            "{mermaid}"
+        );
+        assert!(
+            mermaid.contains("  m_index_ts -.-> m_src_lazy_ts\n"),
+            "{mermaid}"
+        );
+        assert!(mermaid.contains("  m_src_a_b_ts --> m_zod\n"), "{mermaid}");
+        assert!(!mermaid.contains("m_zod -->"), "{mermaid}");
+    }
+
+    #[test]
+    fn folded_stacks_follow_dominator_tree() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        std::fs::write(root.join("package.json"), r#"{"name":"app"}"#).unwrap();
+        let zod = root.join("node_modules/zod");
+        std::fs::create_dir_all(zod.join("lib")).unwrap();
+        std::fs::write(
+            zod.join("package.json"),
+            r#"{"name":"zod","main":"lib/index.js"}"#,
+        )
+        .unwrap();
+        std::fs::write(zod.join("lib/index.js"), "export {};").unwrap();
+        let a = "import 'zod';";
+        let b = "export const b = 1;";
+        let index = "import './a';\nimport './b';\nimport 'zod';";
+        std::fs::write(root.join("a.ts"), a).unwrap();
+        std::fs::write(root.join("b.ts"), b).unwrap();
+        let entry = root.join("index.ts");
+        std::fs::write(&entry, index).unwrap();
+
+        let session = Session::open(&entry, true).unwrap();
+        let out = report::print_folded_stacks(&session.folded_stacks(&TraceOptions::default()));
+        // zod is imported by both index.ts and a.ts, so it hangs off the entry
+        assert_eq!(
+            out,
+            format!(
+                "index.ts {}\nindex.ts;a.ts {}\nindex.ts;b.ts {}\nindex.ts;zod/lib/index.js {}\n",
+                index.len(),
+                a.len(),
+                b.len(),
+                "export {};".len()
+            )
+        );
+    }
+
+    #[test]
+    fn deadcode_lists_files_the_entry_never_imports() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        std::fs::write(root.join("package.json"), r#"{"name":"app"}"#).unwrap();
+        std::fs::create_dir_all(root.join("src/old")).unwrap();
+        std::fs::create_dir_all(root.join("scripts")).unwrap();
+        std::fs::create_dir_all(root.join("node_modules/zod")).unwrap();
+        std::fs::write(root.join("src/used.ts"), "export {};").unwrap();
+        std::fs::write(root.join("src/lazy.ts"), "export {};").unwrap();
+        std::fs::write(root.join("src/old/unused.ts"), "export const x = 1;").unwrap();
+        std::fs::write(root.join("src/used.test.ts"), "import './used';").unwrap();
+        std::fs::write(root.join("scripts/build.ts"), "").unwrap();
+        std::fs::write(root.join("src/vite-env.d.ts"), "").unwrap();
+        std::fs::write(root.join("node_modules/zod/index.js"), "").unwrap();
+        let entry = root.join("src/index.ts");
+        std::fs::write(&entry, "import './used'; import('./lazy');").unwrap();
+        let session = Session::open(&entry, true).unwrap();
+
+        let exclude = query::Exclude::new(&["scripts/**".to_string()], &root, false).unwrap();
+        let report = session
+            .deadcode_report(Some(&exclude), false, false)
+            .unwrap();
+        let dead: Vec<&str> = report
+            .directories
+            .iter()
+            .flat_map(|d| d.files.iter().map(|f| f.path.as_str()))
+            .collect();
+        assert_eq!(dead, vec!["src/old/unused.ts"]);
+        assert_eq!(report.dead_bytes, 19);
+        assert_eq!(report.tests_skipped, 1);
+        assert_eq!(report.declarations_skipped, 1);
+
+        let report = session.deadcode_report(None, true, true).unwrap();
+        let dirs: Vec<&str> = report.directories.iter().map(|d| d.dir.as_str()).collect();
+        // Heaviest directory first
+        assert_eq!(dirs, vec!["src/old", "src", "scripts"]);
+        assert_eq!(report.directories[1].files[0].path, "src/used.test.ts");
+        assert_eq!(report.directories[1].files[1].path, "src/vite-env.d.ts");
+        assert_eq!(report.tests_skipped, 0);
+        assert_eq!(report.declarations_skipped, 0);
+    }
+
+    #[test]
+    fn import_cycles_static_vs_dynamic_only() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        std::fs::write(root.join("package.json"), r#"{"name":"app"}"#).unwrap();
+        // c -> d is dynamic, so the c/d cycle only closes with dynamic edges
+        std::fs::write(root.join("c.ts"), "import('./d');").unwrap();
+        std::fs::write(root.join("d.ts"), "import './c';").unwrap();
+        let entry = root.join("index.ts");
+        std::fs::write(&entry, "import './c';").unwrap();
+        let session = Session::open(&entry, true).unwrap();
+        assert!(session.import_cycles(false).is_empty());
+        assert_eq!(session.import_cycles(true), vec![vec!["c.ts", "d.ts"]]);
+
+        std::fs::write(root.join("c.ts"), "import './d';").unwrap();
+        let session = Session::open(&entry, true).unwrap();
+        assert_eq!(session.import_cycles(false), vec![vec!["c.ts", "d.ts"]]);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn symlinked_entry_keeps_project_root() {
+        let tmp = tempfile::tempdir().unwrap();
+        let base = tmp.path().canonicalize().unwrap();
+        // The real file lives in another project with its own package.json
+        let shared = base.join("shared");
+        std::fs::create_dir_all(&shared).unwrap();
+        std::fs::write(shared.join("package.json"), r#"{"name":"shared"}"#).unwrap();
+        std::fs::write(shared.join("cli.ts"), "export {};").unwrap();
+        let app = base.join("app");
+        std::fs::create_dir_all(app.join("bin")).unwrap();
+        std::fs::write(app.join("package.json"), r#"{"name":"app"}"#).unwrap();
+        let link = app.join("bin/cli.ts");
+        std::os::unix::fs::symlink(shared.join("cli.ts"), &link).unwrap();
+
+        let mut session = Session::open(&link, true).unwrap();
+        assert_eq!(session.root(), app);
+        assert_eq!(session.entry(), shared.join("cli.ts"));
+        session.refresh().unwrap();
+        assert_eq!(session.root(), app);
+
+        // A link that stays inside the project keeps the nearest root
+        std::fs::write(app.join("real.ts"), "export {};").unwrap();
+        let inner = app.join("bin/inner.ts");
+        std::os::unix::fs::symlink(app.join("real.ts"), &inner).unwrap();
+        let session = Session::open(&inner, true).unwrap();
+        assert_eq!(session.root(), app);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn monorepo_report_lists_cross_package_edges() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        std::fs::write(
+            root.join("package.json"),
+            r#"{"workspaces":["packages/*"]}"#,
+        )
+        .unwrap();
+        let app = root.join("packages/app");
+        let lib = root.join("packages/lib");
+        std::fs::create_dir_all(app.join("src")).unwrap();
+        std::fs::create_dir_all(&lib).unwrap();
+        std::fs::write(
+            app.join("package.json"),
+            r#"{"name":"@my/app","main":"src/index.ts"}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            app.join("src/index.ts"),
+            "import { x } from '@my/lib';\nimport './util';",
+        )
+        .unwrap();
+        std::fs::write(app.join("src/util.ts"), "export {};").unwrap();
+        std::fs::write(lib.join("package.json"), r#"{"name":"@my/lib"}"#).unwrap();
+        std::fs::write(lib.join("index.ts"), "export const x = 1;").unwrap();
+        // How npm/yarn/pnpm link workspace packages
+        std::fs::create_dir_all(root.join("node_modules/@my")).unwrap();
+        std::os::unix::fs::symlink(&lib, root.join("node_modules/@my/lib")).unwrap();
+
+        let ws =
+            loader::load_workspace_graph(&app, &LoadOptions::default(), Arc::new(OsVfs)).unwrap();
+        assert_eq!(ws.root, root);
+        assert_eq!(ws.graph.entries.len(), 2);
+
+        let report = monorepo_report(&ws, &TraceOptions::default());
+        let names: Vec<&str> = report.packages.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["@my/app", "@my/lib"]);
+        assert_eq!(report.packages[0].static_module_count, 3);
+        assert_eq!(report.cross_edges.len(), 1);
+        let edge = &report.cross_edges[0];
+        assert_eq!(
+            (edge.from.as_str(), edge.to.as_str()),
+            ("@my/app", "@my/lib")
+        );
+        assert_eq!(edge.import_count, 1);
+    }
+
+    #[test]
+    fn published_entry_preferred_over_package_src() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        std::fs::write(root.join("package.json"), r#"{"name":"app"}"#).unwrap();
+        let ui = root.join("node_modules/ui");
+        std::fs::create_dir_all(ui.join("src")).unwrap();
+        std::fs::create_dir_all(ui.join("dist")).unwrap();
+        std::fs::write(
+            ui.join("package.json"),
+            r#"{"name":"ui","main":"dist/index.js","module":"src/index.js"}"#,
+        )
+        .unwrap();
+        std::fs::write(ui.join("src/index.js"), "export * from './button.js';").unwrap();
+        std::fs::write(ui.join("src/button.js"), "export const Button = 1;").unwrap();
+        std::fs::write(ui.join("dist/index.js"), "exports.Button=1;").unwrap();
+        let entry = root.join("index.ts");
+        std::fs::write(&entry, "import { Button } from 'ui';").unwrap();
+
+        let traced = |prefer: bool| {
+            let opts = LoadOptions {
+                prefer_published_entry: prefer,
+                ..LoadOptions::default()
+            };
+            let session = Session::open_with(&entry, &opts).unwrap();
+            let mut files: Vec<String> = session
+                .graph()
+                .modules
+                .iter()
+                .map(|m| report::relative_path(&m.path, &root))
+                .collect();
+            files.sort();
+            files
+        };
+        assert_eq!(
+            traced(false),
+            [
+                "index.ts",
+                "node_modules/ui/src/button.js",
+                "node_modules/ui/src/index.js"
+            ]
+        );
+        // Uses the cache written above; the setting change must invalidate it
+        assert_eq!(traced(true), ["index.ts", "node_modules/ui/dist/index.js"]);
+    }
 }
@@ -1,69 +1,195 @@
 //! Concurrent dependency graph construction.
 //!
-//! Starting from an entry file, discovers all reachable modules by parsing
+//! Starting from one or more entry files, discovers all reachable modules by parsing
 //! imports and resolving them against the filesystem in parallel using a
 //! lock-free work queue and rayon thread pool.
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
-use std::sync::Mutex;
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
 
 use crossbeam_queue::SegQueue;
-use dashmap::DashSet;
+use dashmap::{DashMap, DashSet};
+use ignore::Match;
+use ignore::gitignore::Gitignore;
 use rayon::slice::ParallelSliceMut;
+use serde::{Deserialize, Serialize};
 
-use crate::cache::ParseCache;
+use crate::cache::{self, CacheMode, ParseCache};
 use crate::graph::ModuleGraph;
-use crate::lang::{LanguageSupport, RawImport};
+use crate::lang::{LanguageSupport, ParseResult, RawImport};
 use crate::vfs::Vfs;
 
+/// Count lines the way editors do: a trailing newline does not start a
+/// new line, and a final line without one still counts.
+#[allow(clippy::cast_possible_truncation)]
+pub(crate) fn count_lines(source: &str) -> u32 {
+    let newlines = source.bytes().filter(|&b| b == b'\n').count();
+    let unterminated = !source.is_empty() && !source.ends_with('\n');
+    (newlines + usize::from(unterminated)) as u32
+}
+
 fn is_parseable(path: &Path, extensions: &[&str]) -> bool {
     path.extension()
         .and_then(|e| e.to_str())
         .is_some_and(|ext| extensions.contains(&ext))
 }
 
+/// Parse only a seeded random share of the discovered files (`trace
+/// --sample`). Files left out still appear in the graph, with their size,
+/// but their imports are not followed. Which files are kept depends only on
+/// the seed and each file's path under the project root, so a run is
+/// reproducible.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sample {
+    /// Share of files to parse, in `(0, 1]`.
+    pub fraction: f64,
+    pub seed: u64,
+}
+
+impl Sample {
+    /// Whether `path` is one of the files parsed under this sample.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn keeps(&self, path: &Path, root: &Path) -> bool {
+        let rel = path.strip_prefix(root).unwrap_or(path);
+        let hash = cache::fnv1a_extend(0xcbf2_9ce4_8422_2325, rel.as_os_str().as_encoded_bytes());
+        // splitmix64 finalizer: spreads the seed over every bit
+        let mut z = hash ^ self.seed.wrapping_mul(0x9e37_79b9_7f4a_7c15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^= z >> 31;
+        ((z >> 11) as f64 / (1u64 << 53) as f64) < self.fraction
+    }
+}
+
+/// `.gitignore` rules for project files, loaded lazily per directory.
+///
+/// Only files under the project root that don't belong to an installed
+/// package are checked: `node_modules/` and virtualenvs are usually
+/// gitignored themselves, but their packages are still traced.
+struct GitignoreFilter<'a> {
+    root: &'a Path,
+    lang: &'a dyn LanguageSupport,
+    /// Each directory's own `.gitignore`, if it has one.
+    dirs: DashMap<PathBuf, Option<Arc<Gitignore>>>,
+}
+
+impl<'a> GitignoreFilter<'a> {
+    fn new(root: &'a Path, lang: &'a dyn LanguageSupport) -> Self {
+        Self {
+            root,
+            lang,
+            dirs: DashMap::new(),
+        }
+    }
+
+    fn is_ignored(&self, path: &Path) -> bool {
+        if !path.starts_with(self.root) || self.lang.package_name(path).is_some() {
+            return false;
+        }
+        // The deepest .gitignore with a matching rule wins, as in git.
+        for dir in path.ancestors().skip(1) {
+            if let Some(gitignore) = self.gitignore_in(dir) {
+                match gitignore.matched_path_or_any_parents(path, false) {
+                    Match::Ignore(_) => return true,
+                    Match::Whitelist(_) => return false,
+                    Match::None => {}
+                }
+            }
+            if dir == self.root {
+                break;
+            }
+        }
+        false
+    }
+
+    fn gitignore_in(&self, dir: &Path) -> Option<Arc<Gitignore>> {
+        // Clone out of the map before inserting (DashMap shard deadlock).
+        if let Some(cached) = self.dirs.get(dir).map(|e| e.value().clone()) {
+            return cached;
+        }
+        let file = dir.join(".gitignore");
+        let gitignore = file
+            .is_file()
+            .then(|| Arc::new(Gitignore::new(&file).0))
+            .filter(|g| !g.is_empty());
+        self.dirs.insert(dir.to_path_buf(), gitignore.clone());
+        gitignore
+    }
+}
+
 /// Result of discovering a single file during concurrent traversal.
 struct FileResult {
     path: PathBuf,
     size: u64,
     /// File modification time captured during read (avoids re-stat in cache insert).
     mtime_nanos: Option<u128>,
+    /// Set under [`CacheMode::Hash`].
+    content_hash: Option<u64>,
+    line_count: u32,
     package: Option<String>,
     imports: Vec<(RawImport, Option<PathBuf>)>,
     unresolvable_dynamic: usize,
+    glob_dirs: Vec<PathBuf>,
 }
 
 struct DiscoverResult {
     files: Vec<FileResult>,
     warnings: Vec<String>,
+    /// Resolved imports that were gitignored: neither parsed nor added to
+    /// the graph.
+    ignored: HashSet<PathBuf>,
+    /// Parseable files reached but left unparsed by the sample.
+    sampled_out: usize,
+    timed_out: bool,
 }
 
 /// Phase 1: Concurrent file discovery using a lock-free work queue.
 /// Returns all discovered files with their parsed imports and resolved paths.
-#[allow(clippy::too_many_lines)]
+/// Past `deadline`, workers stop after their current file and the files
+/// found so far are returned with `timed_out` set. When `hash_content` is
+/// set, files whose content hash is in `by_content` skip parsing. With
+/// `gitignore`, imports of gitignored project files are not followed.
+/// With `sample`, imports of files it leaves out are not followed either.
+#[allow(clippy::too_many_lines, clippy::too_many_arguments)]
 fn concurrent_discover(
-    entry: &Path,
+    entries: &[PathBuf],
     root: &Path,
     lang: &dyn LanguageSupport,
     vfs: &dyn Vfs,
+    deadline: Option<Instant>,
+    hash_content: bool,
+    by_content: &HashMap<u64, &ParseResult>,
+    gitignore: Option<&GitignoreFilter>,
+    sample: Option<Sample>,
 ) -> DiscoverResult {
     let queue: SegQueue<PathBuf> = SegQueue::new();
     let seen: DashSet<PathBuf> = DashSet::new();
+    let ignored: DashSet<PathBuf> = DashSet::new();
+    let sampled_out: DashSet<PathBuf> = DashSet::new();
     let results: Mutex<Vec<FileResult>> = Mutex::new(Vec::new());
     let warnings: SegQueue<String> = SegQueue::new();
-    let active = AtomicUsize::new(1); // entry file is active
+    let active = AtomicUsize::new(0);
+    let timed_out = AtomicBool::new(false);
     let extensions = lang.extensions();
 
-    queue.push(entry.to_path_buf());
-    seen.insert(entry.to_path_buf());
+    for entry in entries {
+        if seen.insert(entry.clone()) {
+            active.fetch_add(1, Ordering::AcqRel);
+            queue.push(entry.clone());
+        }
+    }
 
     rayon::scope(|s| {
         for _ in 0..rayon::current_num_threads() {
             s.spawn(|_| {
                 let mut spin_count: u32 = 0;
                 loop {
+                    if timed_out.load(Ordering::Relaxed) {
+                        return;
+                    }
                     if let Some(path) = queue.pop() {
                         spin_count = 0;
                         let (source, meta) = match vfs.read_with_metadata(&path) {
@@ -76,16 +202,23 @@ fn concurrent_discover(
                         };
                         let mtime_nanos = meta.mtime_nanos;
                         let size = meta.len;
+                        let line_count = count_lines(&source);
+                        let content_hash =
+                            hash_content.then(|| cache::content_hash(&path, source.as_bytes()));
 
-                        let result = match lang.parse(&path, &source) {
-                            Ok(r) => r,
-                            Err(e) => {
-                                warnings.push(e.to_string());
-                                active.fetch_sub(1, Ordering::AcqRel);
-                                continue;
-                            }
+                        let cached = content_hash.and_then(|h| by_content.get(&h));
+                        let result = match cached {
+                            Some(&r) => r.clone(),
+                            None => match lang.parse(&path, &source) {
+                                Ok(r) => r,
+                                Err(e) => {
+                                    warnings.push(e.to_string());
+                                    active.fetch_sub(1, Ordering::AcqRel);
+                                    continue;
+                                }
+                            },
                         };
-                        let package = if path == entry {
+                        let package = if entries.contains(&path) {
                             None
                         } else {
                             lang.package_name(&path)
@@ -93,14 +226,28 @@ fn concurrent_discover(
                         };
 
                         // Resolve imports and discover new files
-                        #[allow(clippy::or_fun_call)]
-                        let dir = path.parent().unwrap_or(Path::new("."));
                         let imports: Vec<(RawImport, Option<PathBuf>)> = result
                             .imports
                             .into_iter()
                             .map(|imp| {
-                                let resolved = lang.resolve(dir, &imp.specifier);
+                                let resolved = lang.resolve_import(&path, &imp);
                                 if let Some(ref p) = resolved
+                                    && !seen.contains(p)
+                                    && !ignored.contains(p)
+                                    && gitignore.is_some_and(|g| g.is_ignored(p))
+                                {
+                                    ignored.insert(p.clone());
+                                }
+                                if let Some(ref p) = resolved
+                                    && !ignored.contains(p)
+                                    && is_parseable(p, extensions)
+                                    && sample.is_some_and(|s| !s.keeps(p, root))
+                                {
+                                    sampled_out.insert(p.clone());
+                                }
+                                if let Some(ref p) = resolved
+                                    && !ignored.contains(p)
+                                    && !sampled_out.contains(p)
                                     && is_parseable(p, extensions)
                                     && seen.insert(p.clone())
                                 {
@@ -115,12 +262,20 @@ fn concurrent_discover(
                             path,
                             size,
                             mtime_nanos,
+                            content_hash,
+                            line_count,
                             package,
                             imports,
                             unresolvable_dynamic: result.unresolvable_dynamic,
+                            glob_dirs: result.glob_dirs,
                         };
                         results.lock().unwrap().push(file_result);
 
+                        if deadline.is_some_and(|d| Instant::now() >= d) {
+                            timed_out.store(true, Ordering::Relaxed);
+                            return;
+                        }
+
                         if active.fetch_sub(1, Ordering::AcqRel) == 1 {
                             // This was the last active item; all work is done
                             return;
@@ -142,7 +297,27 @@ fn concurrent_discover(
     let mut files = results.into_inner().unwrap();
     files.par_sort_unstable_by(|a, b| a.path.cmp(&b.path));
     let warnings = std::iter::from_fn(|| warnings.pop()).collect();
-    DiscoverResult { files, warnings }
+    DiscoverResult {
+        files,
+        warnings,
+        ignored: ignored.into_iter().collect(),
+        sampled_out: sampled_out.len(),
+        timed_out: timed_out.into_inner(),
+    }
+}
+
+/// How the imports of every parsed file resolved: a quick read on whether
+/// the graph is complete. `imports` is `resolved + builtin + unresolved`;
+/// non-literal dynamic imports have no specifier and are counted apart.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResolutionStats {
+    pub imports: usize,
+    /// Resolved to a file, gitignored ones included.
+    pub resolved: usize,
+    /// Runtime built-ins such as `fs`, which have no file.
+    pub builtin: usize,
+    pub unresolved: usize,
+    pub unresolvable_dynamic: usize,
 }
 
 /// Result of building a module graph.
@@ -150,46 +325,134 @@ fn concurrent_discover(
 #[non_exhaustive]
 pub struct BuildResult {
     pub graph: ModuleGraph,
+    pub resolution: ResolutionStats,
     /// Files containing dynamic imports with non-literal arguments, with counts.
     pub unresolvable_dynamic: Vec<(PathBuf, usize)>,
     /// Import specifiers that failed to resolve (for cache invalidation).
     pub unresolved_specifiers: Vec<String>,
     /// Warnings from files that could not be opened, read, or parsed.
     pub file_warnings: Vec<String>,
+    /// Discovery hit its deadline; the graph holds only the files parsed by
+    /// then (their unparsed imports appear as leaves).
+    pub timed_out: bool,
+    /// Files left unparsed by a [`Sample`]; they appear as leaves.
+    pub sampled_out: usize,
 }
 
 /// Build a complete `ModuleGraph` from the given entry point.
 /// Phase 1 concurrently discovers files using a lock-free work queue.
 /// Phase 2 serially constructs the graph from sorted discovery results.
+/// Imports of project files matched by a `.gitignore` are not followed.
 pub fn build_graph(
     entry: &Path,
     root: &Path,
     lang: &dyn LanguageSupport,
     cache: &mut ParseCache,
     vfs: &dyn Vfs,
+) -> BuildResult {
+    build_graph_until(entry, root, lang, cache, vfs, None, true, None, false)
+}
+
+/// Like [`build_graph`], giving up on discovery at `deadline` and
+/// returning the partial graph (see [`BuildResult::timed_out`]). Unless
+/// `gitignore` is set, gitignored files are traced like any other. With
+/// `sample`, only the files it keeps are parsed (see [`Sample`]). With
+/// `fold_case`, paths differing only in case are one module.
+#[allow(clippy::too_many_arguments)]
+pub fn build_graph_until(
+    entry: &Path,
+    root: &Path,
+    lang: &dyn LanguageSupport,
+    cache: &mut ParseCache,
+    vfs: &dyn Vfs,
+    deadline: Option<Instant>,
+    gitignore: bool,
+    sample: Option<Sample>,
+    fold_case: bool,
+) -> BuildResult {
+    build_from(
+        &[entry.to_path_buf()],
+        root,
+        lang,
+        cache,
+        vfs,
+        deadline,
+        gitignore,
+        sample,
+        fold_case,
+    )
+}
+
+/// Build one graph reachable from any of `entries`, all of which are
+/// marked as [entries](ModuleGraph::entries). Modules shared between them
+/// are discovered and parsed once.
+pub fn build_graph_multi(
+    entries: &[PathBuf],
+    root: &Path,
+    lang: &dyn LanguageSupport,
+    cache: &mut ParseCache,
+    vfs: &dyn Vfs,
+    gitignore: bool,
+    fold_case: bool,
+) -> BuildResult {
+    build_from(
+        entries, root, lang, cache, vfs, None, gitignore, None, fold_case,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn build_from(
+    entries: &[PathBuf],
+    root: &Path,
+    lang: &dyn LanguageSupport,
+    cache: &mut ParseCache,
+    vfs: &dyn Vfs,
+    deadline: Option<Instant>,
+    gitignore: bool,
+    sample: Option<Sample>,
+    fold_case: bool,
 ) -> BuildResult {
     // Phase 1: Concurrent discovery (lock-free work queue)
-    let discovered = concurrent_discover(entry, root, lang, vfs);
+    let hash_content = cache.mode() == CacheMode::Hash;
+    let by_content = cache.parses_by_content();
+    let filter = gitignore.then(|| GitignoreFilter::new(root, lang));
+    let discovered = concurrent_discover(
+        entries,
+        root,
+        lang,
+        vfs,
+        deadline,
+        hash_content,
+        &by_content,
+        filter.as_ref(),
+        sample,
+    );
+    drop(by_content);
+    let timed_out = discovered.timed_out;
     let file_results = discovered.files;
 
     // Phase 2: Serial graph construction from sorted results
-    let mut graph = ModuleGraph::new();
+    let mut graph = ModuleGraph::with_case_folding(fold_case);
     let mut unresolvable_files: Vec<(PathBuf, usize)> = Vec::new();
     let mut unresolved: HashSet<String> = HashSet::new();
+    let mut resolution = ResolutionStats::default();
 
     // First pass: add all modules (deterministic order from sorted results)
     for fr in &file_results {
-        graph.add_module(fr.path.clone(), fr.size, fr.package.clone());
+        let id = graph.add_module(fr.path.clone(), fr.size, fr.package.clone());
+        graph.modules[id.0 as usize].line_count = fr.line_count;
     }
 
     // Second pass: add edges, collect diagnostics, and populate parse cache.
     // Consumes file_results by value to avoid redundant clones.
     for fr in file_results {
-        let source_id = graph.path_to_id[&fr.path];
+        let source_id = graph.module_id(&fr.path).expect("added in first pass");
 
         if fr.unresolvable_dynamic > 0 {
             unresolvable_files.push((fr.path.clone(), fr.unresolvable_dynamic));
         }
+        resolution.imports += fr.imports.len();
+        resolution.unresolvable_dynamic += fr.unresolvable_dynamic;
 
         // Separate imports into raw imports and resolved paths.
         // Edge processing borrows from these; cache takes ownership.
@@ -198,8 +461,14 @@ pub fn build_graph(
 
         for (raw_import, resolved_path) in raw_imports.iter().zip(resolved_paths.iter()) {
             match resolved_path {
+                Some(_) => resolution.resolved += 1,
+                None if lang.is_builtin(&raw_import.specifier) => resolution.builtin += 1,
+                None => resolution.unresolved += 1,
+            }
+            match resolved_path {
+                Some(p) if discovered.ignored.contains(p) => {}
                 Some(p) => {
-                    if let Some(&target_id) = graph.path_to_id.get(p) {
+                    if let Some(target_id) = graph.module_id(p) {
                         graph.add_edge(
                             source_id,
                             target_id,
@@ -229,21 +498,36 @@ pub fn build_graph(
             }
         }
 
-        let result = crate::lang::ParseResult {
-            imports: raw_imports,
-            unresolvable_dynamic: fr.unresolvable_dynamic,
-        };
         if let Some(mtime) = fr.mtime_nanos {
-            cache.insert(fr.path, fr.size, mtime, result, resolved_paths);
+            cache.insert(
+                fr.path,
+                fr.size,
+                mtime,
+                ParseResult {
+                    imports: raw_imports,
+                    unresolvable_dynamic: fr.unresolvable_dynamic,
+                    glob_dirs: fr.glob_dirs,
+                },
+                resolved_paths,
+                fr.content_hash,
+            );
         }
     }
 
+    for entry in entries {
+        if let Some(entry_id) = graph.module_id(entry) {
+            graph.mark_entry(entry_id);
+        }
+    }
     graph.compute_package_info();
     BuildResult {
         graph,
+        resolution,
         unresolvable_dynamic: unresolvable_files,
         unresolved_specifiers: unresolved.into_iter().collect(),
         file_warnings: discovered.warnings,
+        timed_out,
+        sampled_out: discovered.sampled_out,
     }
 }
 
@@ -253,6 +537,108 @@ mod tests {
     use crate::lang::typescript::TypeScriptSupport;
     use crate::vfs::OsVfs;
     use std::fs;
+    use std::sync::Arc;
+
+    #[test]
+    fn merged_builds_record_both_entries() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        fs::write(root.join("web.ts"), r#"import "./shared";"#).unwrap();
+        fs::write(root.join("worker.ts"), r#"import "./shared";"#).unwrap();
+        fs::write(root.join("shared.ts"), "export {};").unwrap();
+
+        let lang = TypeScriptSupport::new(&root);
+        let mut cache = ParseCache::new();
+        let mut graph = build_graph(&root.join("web.ts"), &root, &lang, &mut cache, &OsVfs).graph;
+        let worker = build_graph(&root.join("worker.ts"), &root, &lang, &mut cache, &OsVfs).graph;
+        assert_eq!(worker.entries.len(), 1);
+        graph.merge(&worker);
+
+        let web = graph.module_id(&root.join("web.ts")).unwrap();
+        let worker = graph.module_id(&root.join("worker.ts")).unwrap();
+        let shared = graph.module_id(&root.join("shared.ts")).unwrap();
+        assert_eq!(graph.entries, vec![web, worker]);
+        assert!(graph.is_entry(web) && graph.is_entry(worker));
+        assert!(!graph.is_entry(shared));
+    }
+
+    #[test]
+    fn resolution_stats_add_up() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        fs::write(root.join("package.json"), r#"{"name":"app"}"#).unwrap();
+        fs::write(
+            root.join("entry.ts"),
+            "import './a'; import fs from 'fs'; import 'missing-pkg';\n\
+             const name = 'b'; import(`./${name}`);",
+        )
+        .unwrap();
+        fs::write(
+            root.join("a.ts"),
+            "import path from 'node:path'; import './gone'; export {};",
+        )
+        .unwrap();
+
+        let lang = TypeScriptSupport::new(&root);
+        let mut cache = ParseCache::new();
+        let result = build_graph(&root.join("entry.ts"), &root, &lang, &mut cache, &OsVfs);
+        let stats = result.resolution;
+        assert_eq!(
+            stats,
+            ResolutionStats {
+                imports: 5,
+                resolved: 1,
+                builtin: 2,
+                unresolved: 2,
+                unresolvable_dynamic: 1,
+            }
+        );
+        assert_eq!(
+            stats.imports,
+            stats.resolved + stats.builtin + stats.unresolved
+        );
+    }
+
+    #[test]
+    fn hash_mode_reuses_parse_of_identical_content() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        let source = "export {};";
+        fs::write(root.join("entry.ts"), r#"import "./shared";"#).unwrap();
+        fs::write(root.join("shared.ts"), source).unwrap();
+        fs::write(root.join("extra.ts"), "").unwrap();
+
+        // A parse of the same bytes cached under another path. Its import
+        // is fake, so seeing it in the graph proves shared.ts wasn't parsed.
+        let mut cache = ParseCache::new();
+        cache.set_mode(CacheMode::Hash);
+        let result = ParseResult {
+            imports: vec![RawImport {
+                specifier: "./extra".into(),
+                kind: crate::graph::EdgeKind::Static,
+                require: false,
+            }],
+            unresolvable_dynamic: 0,
+            glob_dirs: Vec::new(),
+        };
+        let hash = cache::content_hash(&root.join("shared.ts"), source.as_bytes());
+        cache.insert(
+            root.join("copy/shared.ts"),
+            10,
+            0,
+            result,
+            vec![],
+            Some(hash),
+        );
+
+        let lang = TypeScriptSupport::new(&root);
+        let graph = build_graph(&root.join("entry.ts"), &root, &lang, &mut cache, &OsVfs).graph;
+        assert!(graph.module_id(&root.join("extra.ts")).is_some());
+
+        let mut cache = ParseCache::new();
+        let graph = build_graph(&root.join("entry.ts"), &root, &lang, &mut cache, &OsVfs).graph;
+        assert!(graph.module_id(&root.join("extra.ts")).is_none());
+    }
 
     #[test]
     fn parse_failure_not_retried() {
@@ -278,4 +664,138 @@ mod tests {
             "broken.ts should appear at most once, found {entry_count}"
         );
     }
+
+    #[test]
+    fn define_drops_dev_only_require() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+
+        fs::write(
+            root.join("entry.js"),
+            r#"if (process.env.NODE_ENV === "development") { require("./devtools"); }
+require("./app");"#,
+        )
+        .unwrap();
+        fs::write(root.join("devtools.js"), "").unwrap();
+        fs::write(root.join("app.js"), "").unwrap();
+
+        let defines = vec![("NODE_ENV".to_string(), "production".to_string())];
+        let lang = TypeScriptSupport::with_defines(&root, Arc::new(OsVfs), defines);
+        let mut cache = ParseCache::new();
+        let result = build_graph(&root.join("entry.js"), &root, &lang, &mut cache, &OsVfs);
+        assert!(result.graph.module_id(&root.join("app.js")).is_some());
+        assert!(result.graph.module_id(&root.join("devtools.js")).is_none());
+
+        let lang = TypeScriptSupport::new(&root);
+        let mut cache = ParseCache::new();
+        let result = build_graph(&root.join("entry.js"), &root, &lang, &mut cache, &OsVfs);
+        assert!(result.graph.module_id(&root.join("devtools.js")).is_some());
+    }
+
+    #[test]
+    fn count_lines_handles_trailing_newline() {
+        assert_eq!(count_lines(""), 0);
+        assert_eq!(count_lines("a"), 1);
+        assert_eq!(count_lines("a\n"), 1);
+        assert_eq!(count_lines("a\nb"), 2);
+        assert_eq!(count_lines("a\n\nb\n"), 3);
+    }
+
+    #[test]
+    fn modules_record_line_count() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        fs::write(
+            root.join("entry.ts"),
+            "import { x } from \"./lib\";\n\nconsole.log(x);\n",
+        )
+        .unwrap();
+        fs::write(root.join("lib.ts"), "export const x = 1;").unwrap();
+
+        let lang = TypeScriptSupport::new(&root);
+        let mut cache = ParseCache::new();
+        let result = build_graph(&root.join("entry.ts"), &root, &lang, &mut cache, &OsVfs);
+        let graph = result.graph;
+        let entry = graph.module_id(&root.join("entry.ts")).unwrap();
+        let lib = graph.module_id(&root.join("lib.ts")).unwrap();
+        assert_eq!(graph.module(entry).line_count, 3);
+        assert_eq!(graph.module(lib).line_count, 1);
+    }
+
+    #[test]
+    fn gitignored_project_files_not_followed() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        fs::write(root.join("package.json"), r#"{"name":"app"}"#).unwrap();
+        fs::write(root.join(".gitignore"), "dist/\nnode_modules/\n").unwrap();
+        fs::create_dir_all(root.join("dist")).unwrap();
+        fs::write(root.join("dist/bundle.js"), "import './chunk.js';").unwrap();
+        fs::write(root.join("dist/chunk.js"), "export {};").unwrap();
+        let zod = root.join("node_modules/zod");
+        fs::create_dir_all(&zod).unwrap();
+        fs::write(
+            zod.join("package.json"),
+            r#"{"name":"zod","main":"index.js"}"#,
+        )
+        .unwrap();
+        fs::write(zod.join("index.js"), "export {};").unwrap();
+        fs::write(
+            root.join("entry.ts"),
+            "import './dist/bundle.js';\nimport 'zod';",
+        )
+        .unwrap();
+
+        let lang = TypeScriptSupport::new(&root);
+        let entry = root.join("entry.ts");
+        let graph = build_graph(&entry, &root, &lang, &mut ParseCache::new(), &OsVfs).graph;
+        assert!(graph.module_id(&root.join("dist/bundle.js")).is_none());
+        assert!(graph.module_id(&root.join("dist/chunk.js")).is_none());
+        assert!(graph.module_id(&zod.join("index.js")).is_some());
+        assert_eq!(graph.modules.len(), 2);
+
+        let graph = build_graph_until(
+            &entry,
+            &root,
+            &lang,
+            &mut ParseCache::new(),
+            &OsVfs,
+            None,
+            false,
+            None,
+            false,
+        )
+        .graph;
+        assert!(graph.module_id(&root.join("dist/chunk.js")).is_some());
+        assert_eq!(graph.modules.len(), 4);
+    }
+
+    #[test]
+    fn case_insensitive_paths_merge_case_variants() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        fs::write(
+            root.join("entry.ts"),
+            "import \"./Util\";\nimport \"./util\";",
+        )
+        .unwrap();
+        fs::write(root.join("Util.ts"), "export {};").unwrap();
+        fs::write(root.join("util.ts"), "export {};").unwrap();
+
+        let lang = TypeScriptSupport::new(&root);
+        let entry = root.join("entry.ts");
+        let build = |fold_case| {
+            let mut cache = ParseCache::new();
+            build_graph_until(
+                &entry, &root, &lang, &mut cache, &OsVfs, None, true, None, fold_case,
+            )
+            .graph
+        };
+        assert_eq!(build(false).modules.len(), 3);
+        let folded = build(true);
+        assert_eq!(folded.modules.len(), 2);
+        assert_eq!(
+            folded.module_id(&root.join("UTIL.ts")),
+            folded.module_id(&root.join("util.ts"))
+        );
+    }
 }
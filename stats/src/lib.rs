@@ -38,6 +38,28 @@ pub fn median(data: &[f64]) -> f64 {
     }
 }
 
+/// The `p`th percentile (0 to 100) of a sorted copy of data, linearly
+/// interpolated between the two nearest order statistics. `percentile(data,
+/// 50.0)` equals `median(data)`.
+///
+/// # Panics
+///
+/// Panics if data is empty, any element is NaN, or `p` is outside 0..=100.
+#[must_use]
+pub fn percentile(data: &[f64], p: f64) -> f64 {
+    assert!((0.0..=100.0).contains(&p), "percentile {p} outside 0..=100");
+    let mut sorted = data.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    #[allow(clippy::cast_precision_loss)]
+    let rank = p / 100.0 * (sorted.len() - 1) as f64;
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let lo = rank.floor() as usize;
+    let hi = (lo + 1).min(sorted.len() - 1);
+    #[allow(clippy::cast_precision_loss)]
+    let frac = rank - lo as f64;
+    sorted[lo] + (sorted[hi] - sorted[lo]) * frac
+}
+
 /// Median absolute deviation: median of |`x_i` - median(x)|.
 /// Robust measure of spread with 50% breakdown point.
 #[must_use]
@@ -165,6 +187,30 @@ pub fn trimmed_mean(data: &[f64], fraction: f64) -> f64 {
     mean(&trim(data, fraction))
 }
 
+/// Winsorize: clamp the bottom and top `fraction` of values to the nearest
+/// value that survives trimming. Unlike [`trim`], nothing is discarded, so
+/// the length and order of `data` are preserved (Yuen's test uses the
+/// Winsorized variance).
+///
+/// # Panics
+///
+/// Panics if any element is NaN.
+#[must_use]
+pub fn winsorize(data: &[f64], fraction: f64) -> Vec<f64> {
+    let kept = trim(data, fraction);
+    let (Some(&lo), Some(&hi)) = (kept.first(), kept.last()) else {
+        return data.to_vec();
+    };
+    data.iter().map(|x| x.clamp(lo, hi)).collect()
+}
+
+/// Winsorized mean: the mean after clamping the bottom and top `fraction`
+/// of values to the trimming boundaries. Robust to outliers.
+#[must_use]
+pub fn winsorized_mean(data: &[f64], fraction: f64) -> f64 {
+    mean(&winsorize(data, fraction))
+}
+
 /// Welch's t-test for two independent samples with unequal variance.
 /// Returns two-tailed p-value.
 #[must_use]
@@ -193,6 +239,228 @@ pub fn welch_t_test(baseline: &[f64], candidate: &[f64]) -> f64 {
     2.0 * student_t_cdf(-t.abs(), df)
 }
 
+/// Mann-Whitney U test: a rank-based, non-parametric alternative to
+/// [`welch_t_test`] for skewed or heavy-tailed samples. Returns the
+/// two-sided p-value from the normal approximation, with tied values given
+/// their average rank, the tie-corrected variance, and a continuity
+/// correction. Returns 1.0 when either sample is empty or every value is
+/// tied, since there is then nothing to tell the samples apart by.
+///
+/// # Panics
+///
+/// Panics if any element is NaN.
+#[must_use]
+pub fn mann_whitney_u(baseline: &[f64], candidate: &[f64]) -> f64 {
+    let (n1, n2) = (baseline.len(), candidate.len());
+    if n1 == 0 || n2 == 0 {
+        return 1.0;
+    }
+    let mut pooled: Vec<(f64, bool)> = baseline
+        .iter()
+        .map(|&x| (x, true))
+        .chain(candidate.iter().map(|&x| (x, false)))
+        .collect();
+    pooled.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+
+    // Rank sum of the baseline, and Σ(t³ - t) over groups of t tied values
+    let mut rank_sum = 0.0;
+    let mut tie_term = 0.0;
+    let mut i = 0;
+    while i < pooled.len() {
+        let j = i + pooled[i..]
+            .iter()
+            .take_while(|p| p.0 == pooled[i].0)
+            .count();
+        #[allow(clippy::cast_precision_loss)]
+        let (avg_rank, t) = ((i + 1 + j) as f64 / 2.0, (j - i) as f64);
+        rank_sum += avg_rank * pooled[i..j].iter().filter(|p| p.1).count() as f64;
+        tie_term += t.powi(3) - t;
+        i = j;
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    let (n1, n2) = (n1 as f64, n2 as f64);
+    let n = n1 + n2;
+    let u = rank_sum - n1 * (n1 + 1.0) / 2.0;
+    let mu = n1 * n2 / 2.0;
+    let var = n1 * n2 / 12.0 * ((n + 1.0) - tie_term / (n * (n - 1.0)));
+    if var <= 0.0 {
+        return 1.0;
+    }
+    let z = ((u - mu).abs() - 0.5).max(0.0) / var.sqrt();
+    (2.0 * normal_cdf(-z)).min(1.0)
+}
+
+/// Percentile bootstrap confidence interval for the relative change in mean,
+/// `(mean(candidate) - mean(baseline)) / mean(baseline)`, at level
+/// `1 - alpha`. Each of `iters` rounds resamples both groups with
+/// replacement; the bounds are the `alpha / 2` and `1 - alpha / 2`
+/// percentiles of the resampled changes. The resampling is seeded, so the
+/// same inputs always give the same interval. A one-element group (e.g. a
+/// stored mean) is held fixed. Returns NaN bounds when either group is
+/// empty or `iters` is 0.
+///
+/// # Panics
+///
+/// Panics if any element is NaN or `alpha` is outside 0..=1.
+#[must_use]
+pub fn bootstrap_ci(baseline: &[f64], candidate: &[f64], iters: usize, alpha: f64) -> (f64, f64) {
+    if baseline.is_empty() || candidate.is_empty() || iters == 0 {
+        return (f64::NAN, f64::NAN);
+    }
+    let mut rng = 12345_u64;
+    let mut resample_mean = |data: &[f64]| {
+        let sum: f64 = (0..data.len())
+            .map(|_| {
+                rng = rng.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1);
+                #[allow(clippy::cast_possible_truncation)]
+                data[((rng >> 33) % data.len() as u64) as usize]
+            })
+            .sum();
+        #[allow(clippy::cast_precision_loss)]
+        let n = data.len() as f64;
+        sum / n
+    };
+    let changes: Vec<f64> = (0..iters)
+        .map(|_| {
+            let b = resample_mean(baseline);
+            let c = resample_mean(candidate);
+            (c - b) / b
+        })
+        .collect();
+    (
+        percentile(&changes, alpha / 2.0 * 100.0),
+        percentile(&changes, (1.0 - alpha / 2.0) * 100.0),
+    )
+}
+
+/// One-sample t-test of `data` against a known mean `mu` (e.g. a stored
+/// baseline mean with no samples behind it). Returns two-tailed p-value.
+#[must_use]
+pub fn one_sample_t_test(data: &[f64], mu: f64) -> f64 {
+    #[allow(clippy::cast_precision_loss)]
+    let n = data.len() as f64;
+    let m = mean(data);
+    let v = variance(data);
+
+    // Zero variance: the sample either sits exactly on mu or is infinitely
+    // far from it in standard-error units.
+    if v == 0.0 {
+        return if m == mu { 1.0 } else { 0.0 };
+    }
+
+    let t = (m - mu) / (v / n).sqrt();
+    2.0 * student_t_cdf(-t.abs(), n - 1.0)
+}
+
+/// Two-sided `confidence` interval (e.g. 0.95) for the mean of `data`,
+/// from Student's t-distribution with n - 1 degrees of freedom:
+/// mean ± `t_{(1+c)/2}` · s / √n. Needs at least two samples; with fewer the
+/// bounds are NaN.
+#[must_use]
+pub fn confidence_interval_mean(data: &[f64], confidence: f64) -> (f64, f64) {
+    #[allow(clippy::cast_precision_loss)]
+    let n = data.len() as f64;
+    let m = mean(data);
+    let t = -student_t_quantile((1.0 - confidence) / 2.0, n - 1.0);
+    let half = t * (variance(data) / n).sqrt();
+    (m - half, m + half)
+}
+
+/// Samples per group needed for a two-sided two-sample test at level `alpha`
+/// to detect a relative change of `mde_pct` (e.g. 0.02 for 2%) of `mean` with
+/// probability `power`, given per-sample standard deviation `baseline_std`.
+///
+/// Normal approximation: n = 2 (`z_{1-α/2}` + `z_power`)² σ² / δ², rounded up
+/// and at least 2. Returns `usize::MAX` if the effect is zero or not finite.
+#[must_use]
+pub fn required_samples(
+    baseline_std: f64,
+    mean: f64,
+    mde_pct: f64,
+    alpha: f64,
+    power: f64,
+) -> usize {
+    let delta = (mde_pct * mean).abs();
+    if delta == 0.0 || !delta.is_finite() {
+        return usize::MAX;
+    }
+    let z = normal_quantile(1.0 - alpha / 2.0) + normal_quantile(power);
+    let n = 2.0 * (z * baseline_std / delta).powi(2);
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let n = n.ceil() as usize;
+    n.max(2)
+}
+
+/// Inverse CDF of the standard normal distribution (Acklam's rational
+/// approximation, relative error < 1.2e-9). `p` must be in (0, 1).
+#[allow(clippy::suboptimal_flops, clippy::many_single_char_names)]
+fn normal_quantile(p: f64) -> f64 {
+    const A: [f64; 6] = [
+        -3.969_683_028_665_376e1,
+        2.209_460_984_245_205e2,
+        -2.759_285_104_469_687e2,
+        1.383_577_518_672_69e2,
+        -3.066_479_806_614_716e1,
+        2.506_628_277_459_239,
+    ];
+    const B: [f64; 5] = [
+        -5.447_609_879_822_406e1,
+        1.615_858_368_580_409e2,
+        -1.556_989_798_598_866e2,
+        6.680_131_188_771_972e1,
+        -1.328_068_155_288_572e1,
+    ];
+    const C: [f64; 6] = [
+        -7.784_894_002_430_293e-3,
+        -3.223_964_580_411_365e-1,
+        -2.400_758_277_161_838,
+        -2.549_732_539_343_734,
+        4.374_664_141_464_968,
+        2.938_163_982_698_783,
+    ];
+    const D: [f64; 4] = [
+        7.784_695_709_041_462e-3,
+        3.224_671_290_700_398e-1,
+        2.445_134_137_142_996,
+        3.754_408_661_907_416,
+    ];
+    const P_LOW: f64 = 0.024_25;
+
+    let tail = |q: f64| {
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    };
+    if p < P_LOW {
+        tail((-2.0 * p.ln()).sqrt())
+    } else if p > 1.0 - P_LOW {
+        -tail((-2.0 * (1.0 - p).ln()).sqrt())
+    } else {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    }
+}
+
+/// CDF of the standard normal distribution, from the complementary error
+/// function (Chebyshev fit, fractional error < 1.2e-7).
+#[allow(clippy::suboptimal_flops)]
+fn normal_cdf(z: f64) -> f64 {
+    let x = -z / std::f64::consts::SQRT_2;
+    let t = 1.0 / (1.0 + 0.5 * x.abs());
+    let poly = -1.265_512_23
+        + t * (1.000_023_68
+            + t * (0.374_091_96
+                + t * (0.096_784_18
+                    + t * (-0.186_288_06
+                        + t * (0.278_868_07
+                            + t * (-1.135_203_98
+                                + t * (1.488_515_87 + t * (-0.822_152_23 + t * 0.170_872_77))))))));
+    let erfc = t * (-x * x + poly).exp();
+    0.5 * if x >= 0.0 { erfc } else { 2.0 - erfc }
+}
+
 /// CDF of Student's t-distribution.
 /// P(T <= t) for t < 0 using the regularized incomplete beta function.
 #[allow(clippy::suboptimal_flops)]
@@ -201,6 +469,29 @@ fn student_t_cdf(t: f64, df: f64) -> f64 {
     0.5 * regularized_beta(x, df / 2.0, 0.5)
 }
 
+/// Inverse of [`student_t_cdf`] for `p` in (0, 0.5): the t <= 0 with
+/// P(T <= t) = p. Bisection, since the CDF is monotonic and there is no
+/// closed form.
+fn student_t_quantile(p: f64, df: f64) -> f64 {
+    if p.is_nan() || df.is_nan() || df < 1.0 {
+        return f64::NAN;
+    }
+    let (mut lo, mut hi) = (-1.0, 0.0);
+    while student_t_cdf(lo, df) > p {
+        hi = lo;
+        lo *= 2.0;
+    }
+    for _ in 0..100 {
+        let mid = (lo + hi) / 2.0;
+        if student_t_cdf(mid, df) > p {
+            hi = mid;
+        } else {
+            lo = mid;
+        }
+    }
+    (lo + hi) / 2.0
+}
+
 /// Regularized incomplete beta function `I_x(a, b)`.
 #[allow(clippy::suboptimal_flops)]
 fn regularized_beta(x: f64, a: f64, b: f64) -> f64 {
@@ -476,6 +767,33 @@ mod tests {
         assert!((tm - 3.0).abs() < 1e-10, "expected 3.0, got {tm}");
     }
 
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn winsorize_clamps_instead_of_discarding() {
+        let data = vec![100.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, -50.0];
+        // 10%: one value per tail. trim drops them; winsorize clamps them
+        // to the surviving extremes (2 and 9) in place.
+        assert_eq!(trim(&data, 0.10).len(), 8);
+        let w = winsorize(&data, 0.10);
+        assert_eq!(w.len(), data.len());
+        assert_eq!(w, vec![9.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 2.0]);
+
+        // [2..9] plus 2 and 9 → sum 55 → mean 5.5, same as the trimmed mean
+        // here only because the clamped values are symmetric.
+        let wm = winsorized_mean(&data, 0.10);
+        assert!((wm - 5.5).abs() < 1e-10, "expected 5.5, got {wm}");
+        assert!((trimmed_mean(&data, 0.10) - 5.5).abs() < 1e-10);
+        assert!(mean(&data) > 9.0);
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn winsorize_no_clamp_on_small_samples() {
+        let data = vec![5.0, 1.0, 3.0];
+        assert_eq!(winsorize(&data, 0.10), data);
+        assert!(winsorize(&[], 0.10).is_empty());
+    }
+
     #[test]
     #[allow(clippy::float_cmp)]
     fn cv_of_constant_data_is_zero() {
@@ -551,6 +869,106 @@ mod tests {
         assert_eq!(median(&[42.0]), 42.0);
     }
 
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn percentile_interpolates_between_order_statistics() {
+        let data = [4.0, 1.0, 3.0, 2.0];
+        assert_eq!(percentile(&data, 0.0), 1.0);
+        assert_eq!(percentile(&data, 100.0), 4.0);
+        // rank = 0.5 * 3 = 1.5 → halfway between 2 and 3
+        assert_eq!(percentile(&data, 50.0), 2.5);
+        // rank = 0.95 * 3 = 2.85 → 3 + 0.85 * (4 - 3)
+        assert!((percentile(&data, 95.0) - 3.85).abs() < 1e-10);
+    }
+
+    #[test]
+    fn percentile_50_matches_median() {
+        for data in [
+            &[3.0, 1.0, 2.0][..],
+            &[5.0, 1.0, 9.0, 2.0, 7.0, 3.0],
+            &[42.0],
+        ] {
+            assert!((percentile(data, 50.0) - median(data)).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "outside 0..=100")]
+    fn percentile_rejects_out_of_range() {
+        let _ = percentile(&[1.0, 2.0], 150.0);
+    }
+
+    #[test]
+    fn mann_whitney_identical_samples() {
+        let data = [10.0, 12.0, 11.0, 13.0, 9.0, 14.0, 10.5, 12.5];
+        let p = mann_whitney_u(&data, &data);
+        assert!(p > 0.95, "identical samples should give p≈1, got {p}");
+    }
+
+    #[test]
+    fn mann_whitney_clear_separation() {
+        let baseline: Vec<f64> = (0..20).map(|i| 100.0 + f64::from(i) * 0.1).collect();
+        let candidate: Vec<f64> = (0..20).map(|i| 110.0 + f64::from(i) * 0.1).collect();
+        let p = mann_whitney_u(&baseline, &candidate);
+        assert!(
+            p < 1e-6,
+            "non-overlapping samples should give p≪0.01, got {p}"
+        );
+    }
+
+    #[test]
+    fn mann_whitney_tie_heavy() {
+        // Average ranks and the tie-corrected variance: U = 16, z ≈ 2.639
+        let baseline = [1.0, 1.0, 1.0, 1.0, 2.0, 2.0, 2.0, 2.0, 3.0, 3.0];
+        let candidate = [2.0, 2.0, 2.0, 3.0, 3.0, 3.0, 3.0, 4.0, 4.0, 4.0];
+        let p = mann_whitney_u(&baseline, &candidate);
+        assert!((p - 0.008_323).abs() < 1e-5, "got {p}");
+        // Symmetric in its arguments
+        assert!((mann_whitney_u(&candidate, &baseline) - p).abs() < 1e-12);
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn mann_whitney_degenerate_inputs() {
+        assert_eq!(mann_whitney_u(&[], &[1.0, 2.0]), 1.0);
+        assert_eq!(mann_whitney_u(&[5.0, 5.0], &[5.0, 5.0, 5.0]), 1.0);
+        let p = mann_whitney_u(&[1.0], &[2.0]);
+        assert!((0.0..=1.0).contains(&p), "{p}");
+    }
+
+    #[test]
+    fn bootstrap_ci_brackets_the_change() {
+        let baseline = synthetic_samples(100.0, 2.0, 50);
+        let candidate = synthetic_samples(110.0, 2.0, 50);
+        let (lo, hi) = bootstrap_ci(&baseline, &candidate, 2000, 0.01);
+        assert!(lo < 0.10 && 0.10 < hi, "({lo}, {hi})");
+        assert!(
+            lo > 0.05,
+            "10% shift with 2% noise excludes small changes: {lo}"
+        );
+        assert_eq!(bootstrap_ci(&baseline, &candidate, 2000, 0.01), (lo, hi));
+
+        // Wider confidence, wider interval
+        let (lo95, hi95) = bootstrap_ci(&baseline, &candidate, 2000, 0.05);
+        assert!(lo <= lo95 && hi95 <= hi);
+    }
+
+    #[test]
+    fn bootstrap_ci_holds_single_value_fixed() {
+        let candidate = synthetic_samples(120.0, 1.0, 30);
+        let (lo, hi) = bootstrap_ci(&[100.0], &candidate, 1000, 0.05);
+        assert!(lo > 0.18 && hi < 0.22, "({lo}, {hi})");
+
+        let same = [5.0; 10];
+        assert_eq!(bootstrap_ci(&same, &same, 100, 0.05), (0.0, 0.0));
+    }
+
+    #[test]
+    fn bootstrap_ci_degenerate_inputs() {
+        assert!(bootstrap_ci(&[], &[1.0], 100, 0.05).0.is_nan());
+        assert!(bootstrap_ci(&[1.0], &[1.0], 0, 0.05).1.is_nan());
+    }
+
     #[test]
     fn mad_symmetric_data() {
         // [1, 2, 3, 4, 5] → median=3, deviations=[2,1,0,1,2] → MAD=1
@@ -673,4 +1091,75 @@ mod tests {
             "expected cv ~0.1, got {result}"
         );
     }
+
+    #[test]
+    fn normal_quantile_matches_tables() {
+        assert!(normal_quantile(0.5).abs() < 1e-9);
+        assert!((normal_quantile(0.975) - 1.959_964).abs() < 1e-6);
+        assert!((normal_quantile(0.8) - 0.841_621).abs() < 1e-6);
+        assert!((normal_quantile(0.005) + 2.575_829).abs() < 1e-6);
+    }
+
+    #[test]
+    fn required_samples_textbook_values() {
+        // Cohen's d = 0.5, alpha = 0.05, power = 0.80: 63 per group (z approximation)
+        assert_eq!(required_samples(1.0, 10.0, 0.05, 0.05, 0.80), 63);
+        // d = 0.5, power = 0.90: 85 per group
+        assert_eq!(required_samples(10.0, 100.0, 0.05, 0.05, 0.90), 85);
+        // d = 1.0, power = 0.80: 16 per group
+        assert_eq!(required_samples(2.0, 100.0, 0.02, 0.05, 0.80), 16);
+    }
+
+    #[test]
+    fn required_samples_edge_cases() {
+        // Tiny noise still needs two samples to estimate variance
+        assert_eq!(required_samples(0.0, 100.0, 0.02, 0.01, 0.8), 2);
+        assert_eq!(required_samples(1.0, 100.0, 0.0, 0.01, 0.8), usize::MAX);
+        // Halving the detectable effect quadruples the sample size
+        let n1 = required_samples(1.0, 100.0, 0.04, 0.01, 0.8);
+        let n2 = required_samples(1.0, 100.0, 0.02, 0.01, 0.8);
+        assert!((4 * n1).abs_diff(n2) <= 4, "{n1} vs {n2}");
+    }
+
+    #[test]
+    fn one_sample_detects_shift_from_stored_mean() {
+        let data = synthetic_samples(110.0, 1.0, 30);
+        let p = one_sample_t_test(&data, 100.0);
+        assert!(p < 0.001, "10% shift should be significant, got {p}");
+
+        let p = one_sample_t_test(&data, mean(&data));
+        assert!(p > 0.99, "sample mean itself should not differ, got {p}");
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn one_sample_known_value_and_zero_variance() {
+        // mean 3, sd sqrt(2.5), n 5: t = 2/(sqrt(2.5)/sqrt(5)) = 2.828, df 4
+        let p = one_sample_t_test(&[1.0, 2.0, 3.0, 4.0, 5.0], 1.0);
+        assert!((p - 0.0474).abs() < 1e-3, "got {p}");
+
+        assert_eq!(one_sample_t_test(&[5.0; 10], 5.0), 1.0);
+        assert_eq!(one_sample_t_test(&[5.0; 10], 4.0), 0.0);
+    }
+
+    #[test]
+    fn confidence_interval_known_values() {
+        // mean 3, se sqrt(2.5 / 5), t(0.975, 4) = 2.776445
+        let (lo, hi) = confidence_interval_mean(&[1.0, 2.0, 3.0, 4.0, 5.0], 0.95);
+        assert!((lo - 1.036_757).abs() < 1e-5, "got {lo}");
+        assert!((hi - 4.963_243).abs() < 1e-5, "got {hi}");
+
+        // t(0.975, 1) = 12.7062: mean 11, se 1
+        let (lo, hi) = confidence_interval_mean(&[10.0, 12.0], 0.95);
+        assert!((lo - (11.0 - 12.706_205)).abs() < 1e-4, "got {lo}");
+        assert!((hi - (11.0 + 12.706_205)).abs() < 1e-4, "got {hi}");
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn confidence_interval_degenerate_samples() {
+        assert_eq!(confidence_interval_mean(&[5.0; 10], 0.95), (5.0, 5.0));
+        let (lo, hi) = confidence_interval_mean(&[5.0], 0.95);
+        assert!(lo.is_nan() && hi.is_nan());
+    }
 }
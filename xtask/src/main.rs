@@ -22,7 +22,23 @@ enum Command {
         #[arg(long)]
         baseline: Option<String>,
 
-        /// Specific benchmark names to check (only with --baseline)
+        /// Compare against the committed means in perf/results.json instead of
+        /// a criterion baseline run (one-sample t-test per benchmark)
+        #[arg(long, conflicts_with = "baseline")]
+        baseline_auto: bool,
+
+        /// Write the criterion means from the last cargo bench to
+        /// perf/results.json, the baseline for --baseline-auto
+        #[arg(long, conflicts_with_all = ["baseline", "baseline_auto"])]
+        save_results: bool,
+
+        /// Significance test for each verdict: noise-aware Welch's t-test, or the
+        /// Mann-Whitney U rank test for skewed timings
+        #[arg(long, value_enum, default_value_t, conflicts_with = "baseline_auto")]
+        test: perf_judge::SignificanceTest,
+
+        /// Specific benchmark names to check (only with --baseline, --baseline-auto
+        /// or --save-results)
         benchmarks: Vec<String>,
     },
     /// Pre-commit hook: run checks on feature branches, perf attestation gate on main
@@ -47,9 +63,19 @@ fn main() {
     match cli.command {
         Command::PerfValidate {
             baseline,
+            baseline_auto,
+            save_results,
+            test,
             benchmarks,
         } => {
-            std::process::exit(perf_validate::run(baseline.as_deref(), &benchmarks));
+            let code = if save_results {
+                perf_validate::save_results(&benchmarks)
+            } else if baseline_auto {
+                perf_validate::run_against_stored(&benchmarks)
+            } else {
+                perf_validate::run(baseline.as_deref(), &benchmarks, test)
+            };
+            std::process::exit(code);
         }
         Command::PreCommit => {
             std::process::exit(hooks::pre_commit());
@@ -1,10 +1,24 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use stats::{
-    NOISE_FLOOR_MIN, REGRESSION_THRESHOLD, TRIM_FRACTION, VERDICT_P, format_time, mean,
-    noise_aware_welch_t_test, noise_floor, session_bias_adjust, trim,
+    NOISE_FLOOR_MIN, REGRESSION_THRESHOLD, TRIM_FRACTION, VERDICT_P, bootstrap_ci,
+    confidence_interval_mean, format_time, mann_whitney_u, mean, noise_aware_welch_t_test,
+    noise_floor, one_sample_t_test, percentile, required_samples, session_bias_adjust, trim,
+    variance,
 };
+use std::collections::BTreeMap;
 use std::path::Path;
 
+/// Power at which a benchmark should detect a `REGRESSION_THRESHOLD` change.
+/// Below the matching sample count, a real regression is likely to pass.
+const DETECTION_POWER: f64 = 0.80;
+
+/// Confidence level of the `mean ± ci` shown for each side.
+const CI_CONFIDENCE: f64 = 0.95;
+
+/// Resamples behind each change interval. The interval is at the verdict
+/// level (`1 - VERDICT_P`), so its tails rest on ~10 resamples each.
+const BOOTSTRAP_ITERS: usize = 2000;
+
 #[derive(Deserialize)]
 struct CriterionSample {
     iters: Vec<f64>,
@@ -15,17 +29,47 @@ pub struct BenchResult {
     pub name: String,
     pub baseline_mean: f64,
     pub candidate_mean: f64,
+    /// Half-width of the `CI_CONFIDENCE` interval around `baseline_mean`.
+    /// NaN when there are no baseline samples (stored means, New).
+    pub baseline_ci: f64,
+    /// Half-width of the interval around `candidate_mean`. NaN for Removed.
+    pub candidate_ci: f64,
+    /// Untrimmed candidate median and 95th percentile, for the tail the
+    /// trimmed mean hides. NaN for Removed.
+    pub candidate_p50: f64,
+    pub candidate_p95: f64,
     pub raw_change_pct: f64,
     pub adjusted_change_pct: f64,
+    /// Bootstrap interval around `adjusted_change_pct` at the verdict level.
+    /// A regression fails only when `ci_low` clears the threshold. NaN for
+    /// New and Removed.
+    pub ci_low: f64,
+    pub ci_high: f64,
     pub p_value: f64,
     pub verdict: Verdict,
 }
 
+/// Significance test behind each paired verdict (`--test`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum SignificanceTest {
+    /// Welch's t-test with the session noise floor added to the variance
+    #[default]
+    Welch,
+    /// Mann-Whitney U rank test, for skewed or bursty timings
+    Mwu,
+}
+
 #[derive(Debug)]
 pub enum Verdict {
     Pass,
     Faster,
     Fail,
+    /// Benchmark has candidate data but no baseline (newly added).
+    /// Only `candidate_mean` is meaningful; there is nothing to regress against.
+    New,
+    /// Benchmark has baseline data but no candidate (removed or renamed).
+    /// Only `baseline_mean` is meaningful.
+    Removed,
 }
 
 impl Verdict {
@@ -40,6 +84,8 @@ impl std::fmt::Display for Verdict {
             Self::Pass => write!(f, "pass"),
             Self::Faster => write!(f, "faster"),
             Self::Fail => write!(f, "FAIL"),
+            Self::New => write!(f, "new"),
+            Self::Removed => write!(f, "removed"),
         }
     }
 }
@@ -50,14 +96,26 @@ struct LoadedBench {
     candidate_trimmed: Vec<f64>,
     baseline_mean: f64,
     candidate_mean: f64,
+    candidate_p50: f64,
+    candidate_p95: f64,
     raw_change_pct: f64,
 }
 
 /// Judge criterion benchmark directories using the unified noise-aware pipeline.
-/// Same algorithm as benchmarks.rs: session bias → noise floor → noise-aware t-test.
-pub fn judge(dirs: &[String], baseline_name: &str, criterion_dir: &Path) -> Vec<BenchResult> {
-    // Phase 1: load all samples, compute raw changes
+/// Same algorithm as benchmarks.rs: session bias → noise floor → noise-aware t-test,
+/// plus a bootstrap interval for the change that must clear the threshold to fail.
+/// With [`SignificanceTest::Mwu`] the last step is a rank test of the same
+/// drift-adjusted samples instead, which does not assume normal timings.
+pub fn judge(
+    dirs: &[String],
+    baseline_name: &str,
+    criterion_dir: &Path,
+    test: SignificanceTest,
+) -> Vec<BenchResult> {
+    // Phase 1: load all samples, compute raw changes. Benchmarks with only
+    // one side present are reported as New/Removed and skip the statistics.
     let mut loaded = Vec::new();
+    let mut unpaired = Vec::new();
     for dir in dirs {
         let path = Path::new(dir);
         let name = extract_bench_name(path);
@@ -65,21 +123,30 @@ pub fn judge(dirs: &[String], baseline_name: &str, criterion_dir: &Path) -> Vec<
         let baseline_path = path.join(format!("{baseline_name}/sample.json"));
         let candidate_path = path.join("new/sample.json");
 
-        if !baseline_path.exists() || !candidate_path.exists() {
-            eprintln!("  SKIP {name}: missing baseline or candidate data");
-            continue;
-        }
-
-        let baseline = match load_samples(&baseline_path) {
-            Ok(s) => s,
-            Err(e) => {
-                eprintln!("  ERROR {name}: {e}");
+        let (baseline, candidate) = match (baseline_path.exists(), candidate_path.exists()) {
+            (true, true) => (load_samples(&baseline_path), load_samples(&candidate_path)),
+            (false, true) => {
+                match load_samples(&candidate_path) {
+                    Ok(c) => unpaired.push(unpaired_result(name, Verdict::New, &c)),
+                    Err(e) => eprintln!("  ERROR {name}: {e}"),
+                }
+                continue;
+            }
+            (true, false) => {
+                match load_samples(&baseline_path) {
+                    Ok(b) => unpaired.push(unpaired_result(name, Verdict::Removed, &b)),
+                    Err(e) => eprintln!("  ERROR {name}: {e}"),
+                }
+                continue;
+            }
+            (false, false) => {
+                eprintln!("  SKIP {name}: missing baseline and candidate data");
                 continue;
             }
         };
-        let candidate = match load_samples(&candidate_path) {
-            Ok(s) => s,
-            Err(e) => {
+        let (baseline, candidate) = match (baseline, candidate) {
+            (Ok(b), Ok(c)) => (b, c),
+            (Err(e), _) | (_, Err(e)) => {
                 eprintln!("  ERROR {name}: {e}");
                 continue;
             }
@@ -91,18 +158,36 @@ pub fn judge(dirs: &[String], baseline_name: &str, criterion_dir: &Path) -> Vec<
         let candidate_mean = mean(&candidate_trimmed);
         let raw_change_pct = (candidate_mean - baseline_mean) / baseline_mean;
 
+        let needed = required_samples(
+            variance(&baseline_trimmed).sqrt(),
+            baseline_mean,
+            REGRESSION_THRESHOLD,
+            VERDICT_P,
+            DETECTION_POWER,
+        );
+        if candidate.len() < needed {
+            eprintln!(
+                "  WARN {name}: {} samples, ~{needed} needed to detect a {:.0}% regression \
+                 (raise sample_size or reduce noise)",
+                candidate.len(),
+                REGRESSION_THRESHOLD * 100.0,
+            );
+        }
+
         loaded.push(LoadedBench {
             name,
             baseline_trimmed,
             candidate_trimmed,
             baseline_mean,
             candidate_mean,
+            candidate_p50: percentile(&candidate, 50.0),
+            candidate_p95: percentile(&candidate, 95.0),
             raw_change_pct,
         });
     }
 
     if loaded.is_empty() {
-        return Vec::new();
+        return unpaired;
     }
 
     // Phase 2: session bias correction + noise floor estimation
@@ -131,32 +216,195 @@ pub fn judge(dirs: &[String], baseline_name: &str, criterion_dir: &Path) -> Vec<
         let drift_ns = drift * l.baseline_mean;
         let adjusted_candidate: Vec<f64> =
             l.candidate_trimmed.iter().map(|x| x - drift_ns).collect();
-        let p_value =
-            noise_aware_welch_t_test(&l.baseline_trimmed, &adjusted_candidate, effective_sigma);
-        let adjusted_change = adjusted_changes[i];
-
-        let verdict = if p_value < VERDICT_P && adjusted_change > REGRESSION_THRESHOLD {
-            Verdict::Fail
-        } else if p_value < VERDICT_P && adjusted_change < -REGRESSION_THRESHOLD {
-            Verdict::Faster
-        } else {
-            Verdict::Pass
+        let p_value = match test {
+            SignificanceTest::Welch => {
+                noise_aware_welch_t_test(&l.baseline_trimmed, &adjusted_candidate, effective_sigma)
+            }
+            SignificanceTest::Mwu => mann_whitney_u(&l.baseline_trimmed, &adjusted_candidate),
         };
+        let adjusted_change = adjusted_changes[i];
+        let (ci_low, ci_high) = bootstrap_ci(
+            &l.baseline_trimmed,
+            &adjusted_candidate,
+            BOOTSTRAP_ITERS,
+            VERDICT_P,
+        );
 
         results.push(BenchResult {
             name: l.name.clone(),
             baseline_mean: l.baseline_mean,
             candidate_mean: l.candidate_mean,
+            baseline_ci: ci_half_width(&l.baseline_trimmed),
+            candidate_ci: ci_half_width(&l.candidate_trimmed),
+            candidate_p50: l.candidate_p50,
+            candidate_p95: l.candidate_p95,
             raw_change_pct: l.raw_change_pct,
             adjusted_change_pct: adjusted_change,
+            ci_low,
+            ci_high,
             p_value,
-            verdict,
+            verdict: classify(p_value, adjusted_change, ci_low),
         });
     }
 
+    results.extend(unpaired);
     results
 }
 
+/// Judge candidate samples against stored baseline means (`--baseline-auto`).
+///
+/// With no baseline samples there is nothing to estimate session drift or a
+/// noise floor from, so each benchmark gets a one-sample t-test of its
+/// trimmed candidate samples against the stored mean. Benchmarks missing
+/// from `means` are reported as New.
+pub fn judge_against_means(dirs: &[String], means: &BTreeMap<String, f64>) -> Vec<BenchResult> {
+    let mut results = Vec::new();
+    for dir in dirs {
+        let path = Path::new(dir);
+        let name = extract_bench_name(path);
+        let candidate = match load_samples(&path.join("new/sample.json")) {
+            Ok(c) => c,
+            Err(e) => {
+                eprintln!("  ERROR {name}: {e}");
+                continue;
+            }
+        };
+        let Some(&baseline_mean) = means.get(&name) else {
+            results.push(unpaired_result(name, Verdict::New, &candidate));
+            continue;
+        };
+
+        let candidate_trimmed = trim(&candidate, TRIM_FRACTION);
+        let candidate_mean = mean(&candidate_trimmed);
+        let change = (candidate_mean - baseline_mean) / baseline_mean;
+        let p_value = one_sample_t_test(&candidate_trimmed, baseline_mean);
+        let (ci_low, ci_high) = bootstrap_ci(
+            &[baseline_mean],
+            &candidate_trimmed,
+            BOOTSTRAP_ITERS,
+            VERDICT_P,
+        );
+
+        results.push(BenchResult {
+            name,
+            baseline_mean,
+            candidate_mean,
+            baseline_ci: f64::NAN,
+            candidate_ci: ci_half_width(&candidate_trimmed),
+            candidate_p50: percentile(&candidate, 50.0),
+            candidate_p95: percentile(&candidate, 95.0),
+            raw_change_pct: change,
+            adjusted_change_pct: change,
+            ci_low,
+            ci_high,
+            p_value,
+            verdict: classify(p_value, change, ci_low),
+        });
+    }
+    results
+}
+
+/// Committed results file of the form
+/// `{"benchmarks": {"<name>": {"mean_ns": <f64>}}}`.
+#[derive(Serialize, Deserialize)]
+struct StoredResults {
+    benchmarks: BTreeMap<String, StoredBench>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredBench {
+    mean_ns: f64,
+}
+
+/// Load stored baseline means from a committed results file.
+pub fn load_stored_means(path: &Path) -> Result<BTreeMap<String, f64>, String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("failed to read {}: {e}", path.display()))?;
+    let stored: StoredResults = serde_json::from_str(&content)
+        .map_err(|e| format!("failed to parse {}: {e}", path.display()))?;
+    Ok(stored
+        .benchmarks
+        .into_iter()
+        .map(|(name, b)| (name, b.mean_ns))
+        .collect())
+}
+
+/// Write `means` as a results file [`load_stored_means`] reads back.
+pub fn save_stored_means(path: &Path, means: &BTreeMap<String, f64>) -> Result<(), String> {
+    let stored = StoredResults {
+        benchmarks: means
+            .iter()
+            .map(|(name, &mean_ns)| (name.clone(), StoredBench { mean_ns }))
+            .collect(),
+    };
+    let mut json = serde_json::to_string_pretty(&stored).map_err(|e| format!("json: {e}"))?;
+    json.push('\n');
+    std::fs::write(path, json).map_err(|e| format!("failed to write {}: {e}", path.display()))
+}
+
+/// Trimmed mean of each benchmark's criterion `new` samples, the figure
+/// `judge_against_means` compares against a stored mean.
+pub fn candidate_means(dirs: &[String]) -> Result<BTreeMap<String, f64>, String> {
+    dirs.iter()
+        .map(|dir| {
+            let path = Path::new(dir);
+            let samples = load_samples(&path.join("new/sample.json"))?;
+            Ok((
+                extract_bench_name(path),
+                mean(&trim(&samples, TRIM_FRACTION)),
+            ))
+        })
+        .collect()
+}
+
+fn ci_half_width(samples: &[f64]) -> f64 {
+    let (lo, hi) = confidence_interval_mean(samples, CI_CONFIDENCE);
+    (hi - lo) / 2.0
+}
+
+/// A regression fails only when the whole change interval lies above the
+/// threshold, not just the point estimate, so borderline results pass.
+fn classify(p_value: f64, change: f64, ci_low: f64) -> Verdict {
+    if p_value < VERDICT_P && change > REGRESSION_THRESHOLD && ci_low > REGRESSION_THRESHOLD {
+        Verdict::Fail
+    } else if p_value < VERDICT_P && change < -REGRESSION_THRESHOLD {
+        Verdict::Faster
+    } else {
+        Verdict::Pass
+    }
+}
+
+/// Result for a benchmark present on only one side. The missing side's mean
+/// and all comparison fields are NaN.
+fn unpaired_result(name: String, verdict: Verdict, samples: &[f64]) -> BenchResult {
+    let trimmed = trim(samples, TRIM_FRACTION);
+    let m = mean(&trimmed);
+    let ci = ci_half_width(&trimmed);
+    let ((baseline_mean, baseline_ci), (candidate_mean, candidate_ci)) = match verdict {
+        Verdict::Removed => ((m, ci), (f64::NAN, f64::NAN)),
+        _ => ((f64::NAN, f64::NAN), (m, ci)),
+    };
+    let (candidate_p50, candidate_p95) = match verdict {
+        Verdict::Removed => (f64::NAN, f64::NAN),
+        _ => (percentile(samples, 50.0), percentile(samples, 95.0)),
+    };
+    BenchResult {
+        name,
+        baseline_mean,
+        candidate_mean,
+        baseline_ci,
+        candidate_ci,
+        candidate_p50,
+        candidate_p95,
+        raw_change_pct: f64::NAN,
+        adjusted_change_pct: f64::NAN,
+        ci_low: f64::NAN,
+        ci_high: f64::NAN,
+        p_value: f64::NAN,
+        verdict,
+    }
+}
+
 fn load_sigma_env(criterion_dir: &Path, baseline_name: &str) -> Option<f64> {
     #[derive(Deserialize)]
     struct SigmaEnv {
@@ -168,23 +416,70 @@ fn load_sigma_env(criterion_dir: &Path, baseline_name: &str) -> Option<f64> {
     Some(data.sigma_env)
 }
 
+/// `mean ± ci` for one side of a result; just the mean when there is no
+/// interval, `-` when the side is missing.
+fn mean_with_ci(mean: f64, ci: f64) -> String {
+    if mean.is_nan() {
+        "-".into()
+    } else if ci.is_nan() {
+        format_time(mean)
+    } else {
+        format!("{} ± {}", format_time(mean), format_time(ci))
+    }
+}
+
+/// `p50 / p95` of the candidate samples, `-` when the side is missing.
+fn candidate_percentiles(p50: f64, p95: f64) -> String {
+    if p50.is_nan() {
+        "-".into()
+    } else {
+        format!("{} / {}", format_time(p50), format_time(p95))
+    }
+}
+
+/// `[low, high]` change interval in percent, `-` without one.
+fn change_interval(low: f64, high: f64) -> String {
+    if low.is_nan() {
+        "-".into()
+    } else {
+        format!("[{:+.1}%, {:+.1}%]", low * 100.0, high * 100.0)
+    }
+}
+
 /// Print a results table to stdout.
 pub fn print_results(results: &[BenchResult]) {
     if results.is_empty() {
         return;
     }
     println!(
-        "{:<35} {:>12} {:>12} {:>8} {:>8} {:>8}  Verdict",
-        "Benchmark", "Baseline", "Candidate", "Adj", "Raw", "p-value"
+        "{:<35} {:>22} {:>22} {:>17} {:>8} {:>17} {:>8} {:>8}  Verdict",
+        "Benchmark", "Baseline", "Candidate", "Cand p50 / p95", "Adj", "Adj CI", "Raw", "p-value"
     );
-    println!("{}", "-".repeat(95));
+    println!("{}", "-".repeat(151));
     for r in results {
+        if matches!(r.verdict, Verdict::New | Verdict::Removed) {
+            println!(
+                "{:<35} {:>22} {:>22} {:>17} {:>8} {:>17} {:>8} {:>8}  {}",
+                r.name,
+                mean_with_ci(r.baseline_mean, r.baseline_ci),
+                mean_with_ci(r.candidate_mean, r.candidate_ci),
+                candidate_percentiles(r.candidate_p50, r.candidate_p95),
+                "-",
+                "-",
+                "-",
+                "-",
+                r.verdict,
+            );
+            continue;
+        }
         println!(
-            "{:<35} {:>12} {:>12} {:>+7.1}% {:>+7.1}% {:>8.4}  {}",
+            "{:<35} {:>22} {:>22} {:>17} {:>+7.1}% {:>17} {:>+7.1}% {:>8.4}  {}",
             r.name,
-            format_time(r.baseline_mean),
-            format_time(r.candidate_mean),
+            mean_with_ci(r.baseline_mean, r.baseline_ci),
+            mean_with_ci(r.candidate_mean, r.candidate_ci),
+            candidate_percentiles(r.candidate_p50, r.candidate_p95),
             r.adjusted_change_pct * 100.0,
+            change_interval(r.ci_low, r.ci_high),
             r.raw_change_pct * 100.0,
             r.p_value,
             r.verdict,
@@ -265,7 +560,7 @@ mod tests {
             bench_a.to_string_lossy().to_string(),
             bench_b.to_string_lossy().to_string(),
         ];
-        let results = judge(&dirs, "main", tmp.path());
+        let results = judge(&dirs, "main", tmp.path(), SignificanceTest::Welch);
 
         assert_eq!(results.len(), 2);
 
@@ -282,6 +577,36 @@ mod tests {
             results[1].verdict
         );
         assert!(results[1].raw_change_pct > 0.10);
+        assert!(
+            results[1].ci_low > REGRESSION_THRESHOLD
+                && results[1].ci_low <= results[1].adjusted_change_pct
+                && results[1].adjusted_change_pct <= results[1].ci_high,
+            "[{}, {}]",
+            results[1].ci_low,
+            results[1].ci_high
+        );
+
+        // ±1ns uniform noise over 40 trimmed samples: a sub-ns interval
+        for r in &results {
+            assert!(
+                r.baseline_ci > 0.0 && r.baseline_ci < 1.0,
+                "{}",
+                r.baseline_ci
+            );
+            assert!(
+                r.candidate_ci > 0.0 && r.candidate_ci < 1.0,
+                "{}",
+                r.candidate_ci
+            );
+            assert!(
+                (r.candidate_p50 - r.candidate_mean).abs() < 1.0
+                    && r.candidate_p50 <= r.candidate_p95,
+                "p50 {} p95 {} mean {}",
+                r.candidate_p50,
+                r.candidate_p95,
+                r.candidate_mean
+            );
+        }
     }
 
     #[test]
@@ -299,7 +624,7 @@ mod tests {
             pass_dir.to_string_lossy().to_string(),
             fail_dir.to_string_lossy().to_string(),
         ];
-        let results = judge(&dirs, "main", tmp.path());
+        let results = judge(&dirs, "main", tmp.path(), SignificanceTest::Welch);
         let failed: Vec<&str> = results
             .iter()
             .filter(|r| r.verdict.is_fail())
@@ -309,6 +634,39 @@ mod tests {
         assert_eq!(failed, vec!["slow_bench"]);
     }
 
+    #[test]
+    fn mwu_test_flags_regressions_by_rank() {
+        let tmp = tempfile::tempdir().unwrap();
+        let pass_dir = tmp.path().join("fast_bench");
+        let fail_dir = tmp.path().join("slow_bench");
+
+        write_criterion_sample(&pass_dir, "main", 100.0, 1.0, 50);
+        write_criterion_sample(&pass_dir, "new", 95.0, 1.0, 50); // faster
+        write_criterion_sample(&fail_dir, "main", 100.0, 1.0, 50);
+        write_criterion_sample(&fail_dir, "new", 110.0, 1.0, 50); // 10% regression
+
+        let dirs = vec![
+            pass_dir.to_string_lossy().to_string(),
+            fail_dir.to_string_lossy().to_string(),
+        ];
+        let results = judge(&dirs, "main", tmp.path(), SignificanceTest::Mwu);
+        assert!(
+            matches!(results[0].verdict, Verdict::Faster),
+            "{:?}",
+            results[0].verdict
+        );
+        assert!(results[1].verdict.is_fail(), "{:?}", results[1].verdict);
+        assert!(results[1].p_value < VERDICT_P);
+    }
+
+    #[test]
+    fn regression_fails_only_when_interval_clears_threshold() {
+        // Significant 3% slowdown, but the interval reaches down to 1.5%
+        assert!(matches!(classify(0.001, 0.03, 0.015), Verdict::Pass));
+        assert!(classify(0.001, 0.03, 0.025).is_fail());
+        assert!(matches!(classify(0.5, 0.03, 0.025), Verdict::Pass));
+    }
+
     #[test]
     fn outlier_skewed_baseline_does_not_false_positive() {
         // Reproduce the ts_resolve bug: 50-sample baseline with fast outliers
@@ -327,7 +685,7 @@ mod tests {
         write_raw_sample(&bench, "new", &candidate);
 
         let dirs = vec![bench.to_string_lossy().to_string()];
-        let results = judge(&dirs, "main", tmp.path());
+        let results = judge(&dirs, "main", tmp.path(), SignificanceTest::Welch);
 
         assert_eq!(results.len(), 1);
         assert!(
@@ -381,4 +739,111 @@ mod tests {
         )
         .unwrap();
     }
+
+    #[test]
+    fn new_benchmark_reported_without_regression_check() {
+        let tmp = tempfile::tempdir().unwrap();
+        let existing = tmp.path().join("existing");
+        let added = tmp.path().join("added");
+
+        write_criterion_sample(&existing, "main", 100.0, 1.0, 50);
+        write_criterion_sample(&existing, "new", 100.0, 1.0, 50);
+        write_criterion_sample(&added, "new", 500.0, 1.0, 50); // no baseline
+
+        let dirs = vec![
+            existing.to_string_lossy().to_string(),
+            added.to_string_lossy().to_string(),
+        ];
+        let results = judge(&dirs, "main", tmp.path(), SignificanceTest::Welch);
+
+        assert_eq!(results.len(), 2);
+        let new = results.iter().find(|r| r.name == "added").unwrap();
+        assert!(matches!(new.verdict, Verdict::New), "{:?}", new.verdict);
+        assert!(!new.verdict.is_fail());
+        assert!((new.candidate_mean - 500.0).abs() < 5.0);
+        assert!(new.baseline_mean.is_nan());
+    }
+
+    #[test]
+    fn removed_benchmark_is_noted() {
+        let tmp = tempfile::tempdir().unwrap();
+        let gone = tmp.path().join("gone");
+        write_criterion_sample(&gone, "main", 100.0, 1.0, 50); // no candidate
+
+        let dirs = vec![gone.to_string_lossy().to_string()];
+        let results = judge(&dirs, "main", tmp.path(), SignificanceTest::Welch);
+
+        assert_eq!(results.len(), 1);
+        assert!(matches!(results[0].verdict, Verdict::Removed));
+        assert!(!results[0].verdict.is_fail());
+        assert!((results[0].baseline_mean - 100.0).abs() < 5.0);
+        assert!(results[0].candidate_mean.is_nan());
+    }
+
+    #[test]
+    fn judge_against_stored_means() {
+        let tmp = tempfile::tempdir().unwrap();
+        let steady = tmp.path().join("steady");
+        let slower = tmp.path().join("slower");
+        let added = tmp.path().join("added");
+        write_criterion_sample(&steady, "new", 100.0, 1.0, 50);
+        write_criterion_sample(&slower, "new", 115.0, 1.0, 50);
+        write_criterion_sample(&added, "new", 100.0, 1.0, 50);
+
+        let results_json = tmp.path().join("results.json");
+        std::fs::write(
+            &results_json,
+            r#"{"benchmarks": {"steady": {"mean_ns": 100.0}, "slower": {"mean_ns": 100.0}}}"#,
+        )
+        .unwrap();
+        let means = load_stored_means(&results_json).unwrap();
+
+        let dirs: Vec<String> = [&steady, &slower, &added]
+            .iter()
+            .map(|d| d.to_string_lossy().to_string())
+            .collect();
+        let results = judge_against_means(&dirs, &means);
+
+        assert_eq!(results.len(), 3);
+        assert!(
+            matches!(results[0].verdict, Verdict::Pass),
+            "{:?}",
+            results[0].verdict
+        );
+        assert!(results[1].verdict.is_fail(), "{:?}", results[1].verdict);
+        assert!((results[1].raw_change_pct - 0.15).abs() < 0.01);
+        assert!(matches!(results[2].verdict, Verdict::New));
+    }
+
+    #[test]
+    fn saved_means_load_back() {
+        let tmp = tempfile::tempdir().unwrap();
+        let bench = tmp.path().join("steady");
+        write_criterion_sample(&bench, "new", 100.0, 1.0, 50);
+
+        let means = candidate_means(&[bench.to_string_lossy().to_string()]).unwrap();
+        let path = tmp.path().join("results.json");
+        save_stored_means(&path, &means).unwrap();
+
+        let loaded = load_stored_means(&path).unwrap();
+        assert_eq!(loaded.keys().collect::<Vec<_>>(), ["steady"]);
+        assert!((loaded["steady"] - 100.0).abs() < 1.0);
+    }
+
+    #[test]
+    fn load_stored_means_reports_bad_file() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("results.json");
+        assert!(
+            load_stored_means(&path)
+                .unwrap_err()
+                .contains("failed to read")
+        );
+        std::fs::write(&path, "{}").unwrap();
+        assert!(
+            load_stored_means(&path)
+                .unwrap_err()
+                .contains("failed to parse")
+        );
+    }
 }
@@ -1,4 +1,4 @@
-use crate::perf_judge::{self, BenchResult};
+use crate::perf_judge::{self, BenchResult, SignificanceTest};
 use crate::registry::Registry;
 use serde::Serialize;
 use std::collections::BTreeSet;
@@ -22,7 +22,7 @@ struct Attestation {
 ///   against the named baseline. No attestation written.
 ///
 /// Both modes use confirmation runs to eliminate false positives.
-pub fn run(baseline: Option<&str>, benchmark_args: &[String]) -> i32 {
+pub fn run(baseline: Option<&str>, benchmark_args: &[String], test: SignificanceTest) -> i32 {
     let root = project_root();
 
     let Some(registry) = Registry::load(&root) else {
@@ -73,23 +73,19 @@ pub fn run(baseline: Option<&str>, benchmark_args: &[String]) -> i32 {
             .exists();
         let has_candidate = bench_dir.join("new/sample.json").exists();
 
-        if !has_baseline || !has_candidate {
-            missing.push((bench.clone(), has_baseline, has_candidate));
-        } else {
+        // Candidate-only benchmarks are new and baseline-only ones removed:
+        // judge reports them without a regression check.
+        if has_baseline || has_candidate {
             dirs.push(bench_dir.to_string_lossy().to_string());
+        } else {
+            missing.push(bench);
         }
     }
 
     if !missing.is_empty() {
         eprintln!("Missing criterion data:");
-        for (bench, has_baseline, has_candidate) in &missing {
-            let what = match (has_baseline, has_candidate) {
-                (false, false) => "no baseline or candidate".to_string(),
-                (false, true) => format!("no baseline (run --save-baseline {baseline_name})"),
-                (true, false) => "no candidate (run cargo bench)".to_string(),
-                _ => unreachable!(),
-            };
-            eprintln!("  - {bench}: {what}");
+        for bench in &missing {
+            eprintln!("  - {bench}: no baseline or candidate");
         }
         eprintln!();
         eprintln!(
@@ -102,11 +98,11 @@ pub fn run(baseline: Option<&str>, benchmark_args: &[String]) -> i32 {
     }
 
     // Judge
-    let results = perf_judge::judge(&dirs, baseline_name, &criterion_dir);
+    let results = perf_judge::judge(&dirs, baseline_name, &criterion_dir, test);
     perf_judge::print_results(&results);
 
     // Confirmation runs for any failures
-    if let Some(exit) = confirm_failures(&results, &criterion_dir, &root, baseline_name) {
+    if let Some(exit) = confirm_failures(&results, &criterion_dir, &root, baseline_name, test) {
         return exit;
     }
 
@@ -125,6 +121,118 @@ pub fn run(baseline: Option<&str>, benchmark_args: &[String]) -> i32 {
     0
 }
 
+/// Committed baseline means used by `--baseline-auto`.
+const STORED_RESULTS: &str = "perf/results.json";
+
+/// Run perf-validate against the committed means in `perf/results.json`
+/// (`--baseline-auto`). Checks all registry benchmarks, or those specified,
+/// using the criterion `new` samples from the last `cargo bench`. No
+/// confirmation runs and no attestation.
+pub fn run_against_stored(benchmark_args: &[String]) -> i32 {
+    let root = project_root();
+
+    let Some(registry) = Registry::load(&root) else {
+        eprintln!("No perf.toml found. Nothing to validate.");
+        return 0;
+    };
+    let means = match perf_judge::load_stored_means(&root.join(STORED_RESULTS)) {
+        Ok(m) => m,
+        Err(e) => {
+            eprintln!("Cannot use stored baseline: {e}");
+            return 1;
+        }
+    };
+
+    let required: BTreeSet<String> = if benchmark_args.is_empty() {
+        registry.all_benchmarks()
+    } else {
+        benchmark_args.iter().cloned().collect()
+    };
+    println!("Benchmarks to validate (baseline: {STORED_RESULTS}):");
+    for bench in &required {
+        println!("  - {bench}");
+    }
+    println!();
+
+    let criterion_dir = root.join("target/criterion");
+    let (present, missing): (Vec<_>, Vec<_>) = required
+        .iter()
+        .partition(|b| criterion_dir.join(b).join("new/sample.json").exists());
+    if !missing.is_empty() {
+        eprintln!("Missing criterion data:");
+        for bench in &missing {
+            eprintln!("  - {bench}: no candidate (run cargo bench)");
+        }
+        return 1;
+    }
+    let dirs: Vec<String> = present
+        .iter()
+        .map(|b| criterion_dir.join(b).to_string_lossy().to_string())
+        .collect();
+
+    let results = perf_judge::judge_against_means(&dirs, &means);
+    perf_judge::print_results(&results);
+
+    let failed = results.iter().filter(|r| r.verdict.is_fail()).count();
+    if failed > 0 {
+        eprintln!("\n{failed} regression(s) against stored baseline.");
+        return 1;
+    }
+    println!("\nAll benchmarks passed.");
+    0
+}
+
+/// Store the criterion `new` means from the last `cargo bench` in
+/// `perf/results.json` (`--save-results`), for all registry benchmarks or
+/// those specified, as the baseline `--baseline-auto` compares against.
+pub fn save_results(benchmark_args: &[String]) -> i32 {
+    let root = project_root();
+
+    let Some(registry) = Registry::load(&root) else {
+        eprintln!("No perf.toml found. Nothing to save.");
+        return 0;
+    };
+    let required: BTreeSet<String> = if benchmark_args.is_empty() {
+        registry.all_benchmarks()
+    } else {
+        benchmark_args.iter().cloned().collect()
+    };
+
+    let criterion_dir = root.join("target/criterion");
+    let (present, missing): (Vec<_>, Vec<_>) = required
+        .iter()
+        .partition(|b| criterion_dir.join(b).join("new/sample.json").exists());
+    if !missing.is_empty() {
+        eprintln!("Missing criterion data:");
+        for bench in &missing {
+            eprintln!("  - {bench}: no candidate (run cargo bench)");
+        }
+        return 1;
+    }
+    let dirs: Vec<String> = present
+        .iter()
+        .map(|b| criterion_dir.join(b).to_string_lossy().to_string())
+        .collect();
+
+    let path = root.join(STORED_RESULTS);
+    let saved = perf_judge::candidate_means(&dirs).and_then(|means| {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("failed to create {}: {e}", dir.display()))?;
+        }
+        perf_judge::save_stored_means(&path, &means)
+    });
+    if let Err(e) = saved {
+        eprintln!("Cannot save results: {e}");
+        return 1;
+    }
+    println!(
+        "Saved {} benchmark mean(s) to {STORED_RESULTS}.",
+        dirs.len()
+    );
+    0
+}
+
 /// If there are failures, re-bench and re-judge to confirm.
 /// Returns `Some(exit_code)` if regression confirmed or bench failed,
 /// None if all clear (either no failures or noise dismissed).
@@ -133,6 +241,7 @@ fn confirm_failures(
     criterion_dir: &Path,
     root: &Path,
     baseline_name: &str,
+    test: SignificanceTest,
 ) -> Option<i32> {
     let failed: Vec<&BenchResult> = results.iter().filter(|r| r.verdict.is_fail()).collect();
     if failed.is_empty() {
@@ -181,7 +290,7 @@ fn confirm_failures(
         .iter()
         .map(|r| criterion_dir.join(&r.name).to_string_lossy().to_string())
         .collect();
-    let confirm_results = perf_judge::judge(&failed_dirs, baseline_name, criterion_dir);
+    let confirm_results = perf_judge::judge(&failed_dirs, baseline_name, criterion_dir, test);
 
     println!("\nConfirmation results:");
     perf_judge::print_results(&confirm_results);